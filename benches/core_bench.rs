@@ -1,6 +1,8 @@
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::hint::black_box;
 use jigsaw::engine::mask::Mask;
-use jigsaw::engine::rules::{Rule, RuleSet};
+use jigsaw::engine::rules::RuleSet;
+use jigsaw::io::writer::{self, Output, Writer};
 use std::str::FromStr;
 
 fn benchmark_mask_iter(c: &mut Criterion) {
@@ -28,10 +30,23 @@ fn benchmark_mask_nth(c: &mut Criterion) {
     });
 }
 
+fn benchmark_mask_batch(c: &mut Criterion) {
+    // The same 2600 candidates, into one reused batch
+    let mask = Mask::from_str("?l?d?d").unwrap();
+    let mut batch = Vec::new();
+
+    c.bench_function("mask_fill_batch_2600", |b| {
+        b.iter(|| {
+            mask.fill_batch(0, 2600, &mut batch);
+            black_box(&batch);
+        })
+    });
+}
+
 fn benchmark_rule_application(c: &mut Criterion) {
     // Reverse, Upper, Append '!'
     let rs = RuleSet::from_str("ru$!").unwrap();
-    let mut candidate = b"password".to_vec();
+    let candidate = b"password".to_vec();
     
     c.bench_function("rule_apply_ru$!", |b| {
         b.iter(|| {
@@ -45,5 +60,23 @@ fn benchmark_rule_application(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, benchmark_mask_iter, benchmark_mask_nth, benchmark_rule_application);
+fn benchmark_writer(c: &mut Criterion) {
+    // 100 batches of 1000 eight-byte candidates into the null device
+    let batch: Vec<Vec<u8>> = (0..1000u32).map(|i| format!("pass{:04}", i).into_bytes()).collect();
+    let sink = std::path::PathBuf::from(if cfg!(windows) { "NUL" } else { "/dev/null" });
+
+    c.bench_function("writer_100k_candidates", |b| {
+        b.iter(|| {
+            let (sender, receiver) = writer::channel(128);
+            let handle = Writer::new(receiver, Output::File(sink.clone())).start();
+            for _ in 0..100 {
+                sender.send(batch.clone()).unwrap();
+            }
+            drop(sender);
+            black_box(handle.join().unwrap().unwrap());
+        })
+    });
+}
+
+criterion_group!(benches, benchmark_mask_iter, benchmark_mask_nth, benchmark_mask_batch, benchmark_rule_application, benchmark_writer);
 criterion_main!(benches);
@@ -0,0 +1,219 @@
+use actix_web::body::{EitherBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::middleware::Next;
+use actix_web::http::header::{self, HeaderMap, HeaderValue};
+use actix_web::http::StatusCode;
+use actix_web::{web, HttpRequest, HttpResponse};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use crate::cli::args::ServerArgs;
+use super::problem::Problem;
+
+// ═══════════════════════════════════════════════════════════════
+// API KEYS AND QUOTAS
+// ═══════════════════════════════════════════════════════════════
+//
+// With keys configured (--api-key, JIGSAW_API_KEYS), every endpoint but
+// the health checks wants one, as `X-API-Key: KEY` or
+// `Authorization: Bearer KEY`; a missing or unknown key gets 401. Each
+// key then has a request budget per minute and a candidate budget per
+// hour, in fixed windows. Handlers report what they generated with
+// `charge`, after the fact, so the request that crosses the candidate
+// quota still completes and the ones after it get 429 until the window
+// resets. Queued jobs and WebSocket streams run on after their response,
+// so they `reserve` their whole size when submitted instead, and are
+// refused if it doesn't fit in what the key has left. Without keys the
+// API is open, as before.
+
+/// Environment variable holding comma-separated API keys
+pub const KEYS_VAR: &str = "JIGSAW_API_KEYS";
+
+const REQUEST_WINDOW: Duration = Duration::from_secs(60);
+const CANDIDATE_WINDOW: Duration = Duration::from_secs(60 * 60);
+
+/// Candidates a response generated, for the key that asked
+#[derive(Clone, Copy)]
+pub struct Candidates(pub u64);
+
+/// Attach the candidate count to a response so it counts against the quota
+pub fn charge(mut response: HttpResponse, candidates: u64) -> HttpResponse {
+    response.extensions_mut().insert(Candidates(candidates));
+    response
+}
+
+/// Take `candidates` from the quota of the key behind `req` up front; the
+/// error is the 429 to send when they don't fit
+pub fn reserve(req: &HttpRequest, candidates: u64) -> Result<(), HttpResponse> {
+    match req.app_data::<web::Data<Auth>>().filter(|auth| auth.enabled()) {
+        Some(auth) => auth.reserve(request_key(req.headers()), candidates),
+        None => Ok(()),
+    }
+}
+
+#[derive(Debug)]
+struct Window {
+    started: Instant,
+    used: u64,
+}
+
+impl Window {
+    fn new() -> Self {
+        Self { started: Instant::now(), used: 0 }
+    }
+
+    /// Used so far in the current window, starting a new one if it's over
+    fn current(&mut self, length: Duration) -> &mut u64 {
+        if self.started.elapsed() >= length {
+            *self = Window::new();
+        }
+        &mut self.used
+    }
+
+    fn retry_after(&self, length: Duration) -> u64 {
+        length.saturating_sub(self.started.elapsed()).as_secs().max(1)
+    }
+}
+
+#[derive(Debug)]
+struct Usage {
+    requests: Window,
+    candidates: Window,
+}
+
+pub struct Auth {
+    keys: HashMap<String, Mutex<Usage>>,
+    rate_limit: u32,
+    candidate_quota: u64,
+}
+
+impl Auth {
+    pub fn new(args: &ServerArgs) -> Self {
+        let from_env = std::env::var(KEYS_VAR).unwrap_or_default();
+        let keys = args.api_keys.iter().map(String::as_str)
+            .chain(from_env.split(','))
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+            .map(|key| (key.to_string(), Mutex::new(Usage { requests: Window::new(), candidates: Window::new() })))
+            .collect();
+        Self { keys, rate_limit: args.rate_limit, candidate_quota: args.candidate_quota }
+    }
+
+    pub fn enabled(&self) -> bool {
+        !self.keys.is_empty()
+    }
+
+    /// Count a request against `key`; the error is the response to send
+    fn admit(&self, key: Option<&str>) -> Result<&Mutex<Usage>, HttpResponse> {
+        let Some(usage) = key.and_then(|key| self.keys.get(key)) else {
+            let mut response = Problem::new(StatusCode::UNAUTHORIZED, "missing or unknown API key").response();
+            response.headers_mut().insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+            return Err(response);
+        };
+        let mut guard = usage.lock().unwrap();
+        if self.candidate_quota > 0 && *guard.candidates.current(CANDIDATE_WINDOW) >= self.candidate_quota {
+            let retry = guard.candidates.retry_after(CANDIDATE_WINDOW);
+            return Err(too_many(retry, format!("candidate quota of {} per hour used up", self.candidate_quota)));
+        }
+        let requests = guard.requests.current(REQUEST_WINDOW);
+        if self.rate_limit > 0 && *requests >= self.rate_limit as u64 {
+            let retry = guard.requests.retry_after(REQUEST_WINDOW);
+            return Err(too_many(retry, format!("rate limit of {} requests per minute reached", self.rate_limit)));
+        }
+        *requests += 1;
+        Ok(usage)
+    }
+
+    /// Charge `key` for `candidates` now, unless that would pass its quota
+    fn reserve(&self, key: Option<&str>, candidates: u64) -> Result<(), HttpResponse> {
+        let Some(usage) = key.and_then(|key| self.keys.get(key)) else { return Ok(()) };
+        let mut guard = usage.lock().unwrap();
+        let left = self.candidate_quota.saturating_sub(*guard.candidates.current(CANDIDATE_WINDOW));
+        if self.candidate_quota > 0 && candidates > left {
+            let retry = guard.candidates.retry_after(CANDIDATE_WINDOW);
+            return Err(too_many(retry, format!("{} candidates won't fit in the {} left of the hourly quota", candidates, left)));
+        }
+        *guard.candidates.current(CANDIDATE_WINDOW) += candidates;
+        Ok(())
+    }
+}
+
+fn too_many(retry_after: u64, error: String) -> HttpResponse {
+    let mut response = Problem::new(StatusCode::TOO_MANY_REQUESTS, error)
+        .with("retry_after_secs", retry_after)
+        .response();
+    response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+    response
+}
+
+/// The key a request carries, from `X-API-Key` or a bearer token
+fn request_key(headers: &HeaderMap) -> Option<&str> {
+    headers.get("X-API-Key")
+        .and_then(|value| value.to_str().ok())
+        .or_else(|| headers.get("Authorization")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer ")))
+        .map(str::trim)
+}
+
+/// Middleware enforcing keys and quotas; a no-op when no keys are set
+pub async fn check(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, actix_web::Error> {
+    let auth = req.app_data::<web::Data<Auth>>().cloned();
+    let open = req.path().starts_with("/api/health") || req.method() == actix_web::http::Method::OPTIONS;
+    let Some(auth) = auth.filter(|auth| auth.enabled() && !open) else {
+        return Ok(next.call(req).await?.map_into_left_body());
+    };
+
+    let usage = match auth.admit(request_key(req.headers())) {
+        Ok(usage) => usage,
+        Err(response) => return Ok(req.into_response(response).map_into_right_body()),
+    };
+    let response = next.call(req).await?;
+    if let Some(Candidates(n)) = response.response().extensions().get::<Candidates>().copied() {
+        *usage.lock().unwrap().candidates.current(CANDIDATE_WINDOW) += n;
+    }
+    Ok(response.map_into_left_body())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::args::{Commands, JigsawArgs};
+    use clap::Parser;
+
+    fn auth_for(argv: &[&str]) -> Auth {
+        match JigsawArgs::parse_from(argv).command {
+            Some(Commands::Server(args)) => Auth::new(&args),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_admit_enforces_keys_and_quotas() {
+        let auth = auth_for(&["jigsaw", "server", "--api-key", "k1", "--rate-limit", "2", "--candidate-quota", "100"]);
+        assert!(auth.enabled());
+        assert_eq!(auth.admit(None).unwrap_err().status(), 401);
+        assert_eq!(auth.admit(Some("nope")).unwrap_err().status(), 401);
+
+        let usage = auth.admit(Some("k1")).unwrap();
+        *usage.lock().unwrap().candidates.current(CANDIDATE_WINDOW) += 100;
+        let refused = auth.admit(Some("k1")).unwrap_err();
+        assert_eq!(refused.status(), 429);
+        assert!(refused.headers().contains_key("Retry-After"));
+
+        // A job reserves its size up front, and only if it fits
+        let auth = auth_for(&["jigsaw", "server", "--api-key", "k1", "--candidate-quota", "100"]);
+        assert_eq!(auth.reserve(Some("k1"), 101).unwrap_err().status(), 429);
+        assert!(auth.reserve(Some("k1"), 60).is_ok());
+        assert_eq!(auth.reserve(Some("k1"), 41).unwrap_err().status(), 429);
+        assert!(auth.reserve(Some("k1"), 40).is_ok());
+        assert_eq!(auth.admit(Some("k1")).unwrap_err().status(), 429);
+
+        let auth = auth_for(&["jigsaw", "server", "--api-key", "k1", "--rate-limit", "2"]);
+        assert!(auth.admit(Some("k1")).is_ok() && auth.admit(Some("k1")).is_ok());
+        assert_eq!(auth.admit(Some("k1")).unwrap_err().status(), 429);
+    }
+}
@@ -0,0 +1,538 @@
+use actix_web::http::StatusCode;
+use actix_web::{get, post, put, web, HttpResponse, Responder};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use crate::cli::args::ServerArgs;
+use crate::engine::markov::MarkovModel;
+use crate::engine::mask::{CustomCharsets, Mask};
+use super::problem::{self, Problem};
+
+// ═══════════════════════════════════════════════════════════════
+// DISTRIBUTED GENERATION
+// ═══════════════════════════════════════════════════════════════
+//
+// `jigsaw server --coordinator` splits one mask or Markov run into units
+// of --unit-size candidates, each a `skip`/`limit` slice of the whole,
+// and `jigsaw worker`s claim them over the API. A worker fetches the
+// task once, then claims a unit, generates exactly its slice and PUTs
+// the lines back; the coordinator keeps each unit's lines in a temp file
+// and, once every unit is in, joins them in order into --output, so the
+// list is the one a single machine would write. A Markov unit draws from
+// its own ChaCha20 stream of the run's seed, so it comes out the same
+// whichever worker runs it. A claim is a lease: a unit not returned in
+// time goes to the next worker that asks, so a lost worker costs only
+// the unit it held, and whichever copy comes back first is kept.
+
+/// What a distributed run generates; workers fetch it once
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Task {
+    Mask {
+        mask: String,
+        /// What `?1`–`?4` stand for, resolved so workers need no charset files
+        #[serde(default)]
+        charsets: CustomCharsets,
+        /// Iterate charsets likeliest character first
+        #[serde(default)]
+        frequency_order: bool,
+    },
+    Markov {
+        model: MarkovModel,
+        count: u64,
+        min_len: usize,
+        max_len: usize,
+        seed: u64,
+    },
+}
+
+impl Task {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Task::Mask { .. } => "mask",
+            Task::Markov { .. } => "markov",
+        }
+    }
+
+    /// Candidates in the whole run
+    pub fn keyspace(&self) -> anyhow::Result<u64> {
+        match self {
+            Task::Mask { mask, .. } => u64::try_from(self.mask()?.search_space_size())
+                .map_err(|_| anyhow::anyhow!("mask {} has more candidates than a run can index", mask)),
+            Task::Markov { count, .. } => Ok(*count),
+        }
+    }
+
+    /// The mask a mask task iterates
+    fn mask(&self) -> anyhow::Result<Mask> {
+        let Task::Mask { mask, charsets, frequency_order } = self else {
+            anyhow::bail!("a {} task has no mask", self.kind());
+        };
+        let parsed = Mask::with_charsets(mask, charsets)?;
+        Ok(if *frequency_order { parsed.frequency_ordered() } else { parsed })
+    }
+
+    /// Feed candidates `skip..skip + limit` to `emit` until it returns true
+    pub fn run(&self, skip: u64, limit: u64, mut emit: impl FnMut(&[u8]) -> bool) -> anyhow::Result<()> {
+        match self {
+            Task::Mask { .. } => {
+                let mask = self.mask()?;
+                for candidate in mask.iter_from(skip as u128).take(limit as usize) {
+                    if emit(&candidate) {
+                        break;
+                    }
+                }
+            }
+            Task::Markov { model, min_len, max_len, seed, .. } => {
+                let mut rng = crate::engine::memorable::seeded_rng(*seed);
+                rng.set_stream(skip);
+                for _ in 0..limit {
+                    if emit(model.generate(&mut rng, *min_len, *max_len).as_bytes()) {
+                        break;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A claimed unit: candidates `skip..skip + limit`, due back within `lease_secs`
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Unit {
+    pub id: usize,
+    pub lease: String,
+    pub skip: u64,
+    pub limit: u64,
+    pub lease_secs: u64,
+}
+
+#[derive(Deserialize, Default)]
+pub struct ClaimRequest {
+    #[serde(default)]
+    pub worker: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+enum UnitState {
+    Pending,
+    Leased { until: Instant },
+    Done,
+}
+
+/// A lease handed out, kept after it expires so a late result still counts
+struct Lease {
+    unit: usize,
+    worker: String,
+}
+
+pub(super) enum Claim {
+    Unit(Unit),
+    /// Every unit left is leased to another worker
+    Wait,
+    Finished,
+}
+
+#[derive(Debug)]
+pub(super) enum Rejected {
+    UnknownLease,
+    AlreadyDone,
+    WrongCount { expected: u64, got: u64 },
+    Io(std::io::Error),
+}
+
+#[derive(Serialize)]
+pub struct CoordinatorStatus {
+    pub kind: &'static str,
+    pub keyspace: u64,
+    pub units: usize,
+    pub pending: usize,
+    pub leased: usize,
+    pub done: usize,
+    pub generated: u64,
+    pub progress: f64,
+    /// Units each worker has returned
+    pub workers: HashMap<String, u64>,
+    pub finished: bool,
+    pub output: String,
+    pub elapsed_ms: u128,
+}
+
+struct State {
+    units: Vec<UnitState>,
+    leases: HashMap<String, Lease>,
+    generated: u64,
+    workers: HashMap<String, u64>,
+    finished: Option<Instant>,
+}
+
+/// One distributed run and the units it is split into
+pub struct Coordinator {
+    task: Task,
+    /// `task` as workers receive it, serialized once
+    task_json: String,
+    keyspace: u64,
+    unit_size: u64,
+    lease: Duration,
+    dir: PathBuf,
+    output: PathBuf,
+    state: Mutex<State>,
+    started: Instant,
+}
+
+impl Coordinator {
+    pub fn new(task: Task, unit_size: u64, lease: Duration, output: PathBuf) -> anyhow::Result<Self> {
+        let keyspace = task.keyspace()?;
+        let unit_size = unit_size.max(1);
+        let dir = std::env::temp_dir().join(format!("jigsaw-coordinator-{:016x}", rand::random::<u64>()));
+        std::fs::create_dir_all(&dir)?;
+        let units = keyspace.div_ceil(unit_size) as usize;
+        Ok(Self {
+            task_json: serde_json::to_string(&task)?,
+            task,
+            keyspace,
+            unit_size,
+            lease,
+            dir,
+            output,
+            state: Mutex::new(State {
+                units: (0..units).map(|_| UnitState::Pending).collect(),
+                leases: HashMap::new(),
+                generated: 0,
+                workers: HashMap::new(),
+                finished: None,
+            }),
+            started: Instant::now(),
+        })
+    }
+
+    /// The run `--coordinator` and its options describe
+    pub fn from_args(args: &ServerArgs) -> anyhow::Result<Self> {
+        let task = match (&args.mask, &args.model) {
+            (Some(mask), None) => Task::Mask {
+                mask: mask.clone(),
+                charsets: args.charsets.charsets()?,
+                frequency_order: args.charsets.frequency_order,
+            },
+            (None, Some(path)) => {
+                let (min_len, max_len) = (args.markov_min_len, args.markov_max_len);
+                if min_len == 0 || min_len > max_len {
+                    anyhow::bail!("--markov-min-len must be between 1 and --markov-max-len (got {} and {})", min_len, max_len);
+                }
+                let model = MarkovModel::load(path)?;
+                Task::Markov { model, count: args.count, min_len, max_len, seed: args.seed.unwrap_or_else(rand::random) }
+            }
+            _ => anyhow::bail!("--coordinator needs a --mask or a Markov --model to hand out"),
+        };
+        let output = args.output.clone().expect("clap requires --output with --coordinator");
+        Self::new(task, args.unit_size, Duration::from_secs(args.lease), output)
+    }
+
+    fn range(&self, unit: usize) -> (u64, u64) {
+        let skip = unit as u64 * self.unit_size;
+        (skip, self.unit_size.min(self.keyspace - skip))
+    }
+
+    fn part_path(&self, lease: &str) -> PathBuf {
+        self.dir.join(format!("{}.part", lease))
+    }
+
+    fn unit_path(&self, unit: usize) -> PathBuf {
+        self.dir.join(format!("unit-{}.txt", unit))
+    }
+
+    /// Lease the first unit nobody holds, or one whose lease has run out
+    pub(super) fn claim(&self, worker: &str) -> Claim {
+        let mut state = self.state.lock().unwrap();
+        if state.finished.is_some() || state.units.iter().all(|unit| *unit == UnitState::Done) {
+            return Claim::Finished;
+        }
+        let now = Instant::now();
+        let free = state.units.iter().position(|unit| match unit {
+            UnitState::Pending => true,
+            UnitState::Leased { until } => *until <= now,
+            UnitState::Done => false,
+        });
+        let Some(id) = free else { return Claim::Wait };
+        let lease = format!("{:016x}", rand::random::<u64>());
+        state.units[id] = UnitState::Leased { until: now + self.lease };
+        state.leases.insert(lease.clone(), Lease { unit: id, worker: worker.to_string() });
+        let (skip, limit) = self.range(id);
+        Claim::Unit(Unit { id, lease, skip, limit, lease_secs: self.lease.as_secs() })
+    }
+
+    /// The unit a lease is for, unless it has already come back
+    pub(super) fn leased(&self, lease: &str) -> Result<usize, Rejected> {
+        let state = self.state.lock().unwrap();
+        let unit = state.leases.get(lease).ok_or(Rejected::UnknownLease)?.unit;
+        match state.units[unit] {
+            UnitState::Done => Err(Rejected::AlreadyDone),
+            _ => Ok(unit),
+        }
+    }
+
+    /// Accept the lines written to `lease`'s part file. Returns true once
+    /// this was the last unit outstanding.
+    pub(super) fn complete(&self, lease: &str, lines: u64) -> Result<bool, Rejected> {
+        let part = self.part_path(lease);
+        let mut state = self.state.lock().unwrap();
+        let result = (|| {
+            let Lease { unit, worker } = state.leases.get(lease).ok_or(Rejected::UnknownLease)?;
+            let unit = *unit;
+            if state.units[unit] == UnitState::Done {
+                return Err(Rejected::AlreadyDone);
+            }
+            let (_, expected) = self.range(unit);
+            if lines != expected {
+                return Err(Rejected::WrongCount { expected, got: lines });
+            }
+            std::fs::rename(&part, self.unit_path(unit)).map_err(Rejected::Io)?;
+            let worker = worker.clone();
+            state.units[unit] = UnitState::Done;
+            state.generated += lines;
+            *state.workers.entry(worker).or_default() += 1;
+            Ok(state.units.iter().all(|unit| *unit == UnitState::Done))
+        })();
+        if result.is_err() {
+            let _ = std::fs::remove_file(&part);
+        }
+        result
+    }
+
+    /// Join every unit's lines, in order, into the output file
+    pub fn aggregate(&self) -> std::io::Result<()> {
+        let mut out = BufWriter::new(std::fs::File::create(&self.output)?);
+        let units = self.state.lock().unwrap().units.len();
+        for unit in 0..units {
+            std::io::copy(&mut std::fs::File::open(self.unit_path(unit))?, &mut out)?;
+        }
+        out.flush()?;
+        let _ = std::fs::remove_dir_all(&self.dir);
+        self.state.lock().unwrap().finished = Some(Instant::now());
+        Ok(())
+    }
+
+    pub fn status(&self) -> CoordinatorStatus {
+        let state = self.state.lock().unwrap();
+        let count = |wanted: fn(&UnitState) -> bool| state.units.iter().filter(|unit| wanted(unit)).count();
+        let done = count(|unit| *unit == UnitState::Done);
+        let end = state.finished.unwrap_or_else(Instant::now);
+        CoordinatorStatus {
+            kind: self.task.kind(),
+            keyspace: self.keyspace,
+            units: state.units.len(),
+            pending: state.units.len() - done - count(|unit| matches!(unit, UnitState::Leased { .. })),
+            leased: count(|unit| matches!(unit, UnitState::Leased { .. })),
+            done,
+            generated: state.generated,
+            progress: if self.keyspace == 0 { 1.0 } else { state.generated as f64 / self.keyspace as f64 },
+            workers: state.workers.clone(),
+            finished: state.finished.is_some(),
+            output: self.output.display().to_string(),
+            elapsed_ms: end.duration_since(self.started).as_millis(),
+        }
+    }
+
+    /// Where the units returned so far are, when the run is cut short
+    pub fn unfinished(&self) -> Option<(usize, usize, PathBuf)> {
+        let status = self.status();
+        (!status.finished).then(|| (status.done, status.units, self.dir.clone()))
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════
+// ENDPOINTS
+// ═══════════════════════════════════════════════════════════════
+//
+// Always routed; a server started without --coordinator answers 404.
+
+fn not_coordinating() -> HttpResponse {
+    problem::not_found("this server is not a coordinator; start it with --coordinator")
+}
+
+#[get("/api/coordinator")]
+async fn coordinator_status(coordinator: Option<web::Data<Coordinator>>) -> impl Responder {
+    match coordinator {
+        Some(coordinator) => HttpResponse::Ok().json(coordinator.status()),
+        None => not_coordinating(),
+    }
+}
+
+#[get("/api/coordinator/task")]
+async fn coordinator_task(coordinator: Option<web::Data<Coordinator>>) -> impl Responder {
+    match coordinator {
+        Some(coordinator) => HttpResponse::Ok().content_type("application/json").body(coordinator.task_json.clone()),
+        None => not_coordinating(),
+    }
+}
+
+#[post("/api/coordinator/claim")]
+async fn claim_unit(coordinator: Option<web::Data<Coordinator>>, request: Option<web::Json<ClaimRequest>>) -> impl Responder {
+    let Some(coordinator) = coordinator else { return not_coordinating() };
+    let worker = request.and_then(|request| request.into_inner().worker).unwrap_or_else(|| "anonymous".to_string());
+    match coordinator.claim(&worker) {
+        // Charged when handed out, as jobs are when queued
+        Claim::Unit(unit) => {
+            let limit = unit.limit;
+            super::auth::charge(HttpResponse::Ok().json(unit), limit)
+        }
+        Claim::Wait => HttpResponse::NoContent().finish(),
+        Claim::Finished => Problem::new(StatusCode::GONE, "every unit is done")
+            .with("coordinator", coordinator.status())
+            .response(),
+    }
+}
+
+#[put("/api/coordinator/units/{lease}")]
+async fn return_unit(coordinator: Option<web::Data<Coordinator>>, lease: web::Path<String>, mut body: web::Payload) -> impl Responder {
+    let Some(coordinator) = coordinator else { return not_coordinating() };
+    let rejected = |reason: Rejected| match reason {
+        Rejected::UnknownLease => problem::not_found(format!("no lease {}", lease)),
+        Rejected::AlreadyDone => Problem::new(StatusCode::CONFLICT, "another worker already returned this unit").response(),
+        Rejected::WrongCount { expected, got } => Problem::new(StatusCode::UNPROCESSABLE_ENTITY,
+            format!("the unit has {} candidates, but {} lines came back", expected, got)).response(),
+        Rejected::Io(e) => problem::internal(e),
+    };
+    if let Err(reason) = coordinator.leased(&lease) {
+        return rejected(reason);
+    }
+
+    // Each copy of a unit lands in its own part file, so two workers
+    // racing on an expired lease never write into each other
+    let part = coordinator.part_path(&lease);
+    let mut out = match std::fs::File::create(&part) {
+        Ok(file) => BufWriter::new(file),
+        Err(e) => return problem::internal(e),
+    };
+    let (mut lines, mut last) = (0u64, b'\n');
+    while let Some(chunk) = body.next().await {
+        let chunk = match chunk {
+            Ok(chunk) => chunk,
+            Err(e) => {
+                let _ = std::fs::remove_file(&part);
+                return problem::bad_request(e);
+            }
+        };
+        lines += chunk.iter().filter(|&&b| b == b'\n').count() as u64;
+        last = chunk.last().copied().unwrap_or(last);
+        if let Err(e) = out.write_all(&chunk) {
+            let _ = std::fs::remove_file(&part);
+            return problem::internal(e);
+        }
+    }
+    if let Err(e) = out.flush() {
+        let _ = std::fs::remove_file(&part);
+        return problem::internal(e);
+    }
+    drop(out);
+    // An unterminated last line still counts, so a short upload can't pass
+    let lines = lines + u64::from(last != b'\n');
+
+    match coordinator.complete(&lease, lines) {
+        Ok(false) => HttpResponse::NoContent().finish(),
+        Ok(true) => {
+            let joining = coordinator.clone();
+            match web::block(move || joining.aggregate()).await {
+                Ok(Ok(())) => {
+                    let status = coordinator.status();
+                    println!("  [+] distributed run done: {} candidates from {} units in {}", status.generated, status.units, status.output);
+                    HttpResponse::NoContent().finish()
+                }
+                Ok(Err(e)) => problem::internal(format!("joining the units into {}: {}", coordinator.output.display(), e)),
+                Err(e) => problem::internal(e),
+            }
+        }
+        Err(reason) => rejected(reason),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coordinator(mask: &str, unit_size: u64, lease: Duration) -> Coordinator {
+        let output = std::env::temp_dir().join(format!("jigsaw-coordinator-test-{}.txt", rand::random::<u64>()));
+        Coordinator::new(Task::Mask { mask: mask.to_string(), charsets: Default::default(), frequency_order: false }, unit_size, lease, output).unwrap()
+    }
+
+    fn lines(task: &Task, skip: u64, limit: u64) -> Vec<u8> {
+        let mut out = Vec::new();
+        task.run(skip, limit, |candidate| {
+            out.extend_from_slice(candidate);
+            out.push(b'\n');
+            false
+        }).unwrap();
+        out
+    }
+
+    #[test]
+    fn test_units_cover_the_keyspace_in_order() {
+        let coordinator = coordinator("?d?d", 30, Duration::from_secs(60));
+        let mut claimed = Vec::new();
+        while let Claim::Unit(unit) = coordinator.claim("w") {
+            claimed.push(unit);
+        }
+        assert_eq!(claimed.iter().map(|unit| (unit.skip, unit.limit)).collect::<Vec<_>>(), [(0, 30), (30, 30), (60, 30), (90, 10)]);
+        assert!(matches!(coordinator.claim("w"), Claim::Wait));
+
+        // Returned out of order, joined in order
+        for unit in claimed.iter().rev() {
+            std::fs::write(coordinator.part_path(&unit.lease), lines(&coordinator.task, unit.skip, unit.limit)).unwrap();
+            let last = coordinator.complete(&unit.lease, unit.limit).unwrap();
+            assert_eq!(last, unit.id == 0);
+        }
+        coordinator.aggregate().unwrap();
+        let joined = std::fs::read_to_string(&coordinator.output).unwrap();
+        let expected: Vec<String> = (0..100).map(|n| format!("{:02}", n)).collect();
+        assert_eq!(joined.lines().collect::<Vec<_>>(), expected);
+        assert!(matches!(coordinator.claim("w"), Claim::Finished));
+        assert_eq!(coordinator.status().workers["w"], 4);
+        std::fs::remove_file(&coordinator.output).unwrap();
+    }
+
+    #[test]
+    fn test_mask_task_survives_the_trip_to_workers() {
+        let mut charsets = CustomCharsets::default();
+        charsets[0] = Some(b"zab".to_vec());
+        let task = Task::Mask { mask: "?1?d".to_string(), charsets, frequency_order: true };
+        let fetched: Task = serde_json::from_str(&serde_json::to_string(&task).unwrap()).unwrap();
+        assert_eq!(fetched.keyspace().unwrap(), 30);
+        assert_eq!(lines(&fetched, 0, 4), b"a1\na2\na0\na3\n");
+        assert_eq!(lines(&fetched, 0, 30), lines(&task, 0, 30));
+
+        // Tasks from before charsets existed still parse
+        let old: Task = serde_json::from_str(r#"{"kind":"mask","mask":"?d"}"#).unwrap();
+        assert_eq!(old.keyspace().unwrap(), 10);
+    }
+
+    #[test]
+    fn test_expired_leases_are_handed_out_again() {
+        let coordinator = coordinator("?d", 10, Duration::ZERO);
+        let Claim::Unit(first) = coordinator.claim("slow") else { panic!("no unit") };
+        let Claim::Unit(second) = coordinator.claim("fast") else { panic!("lease did not expire") };
+        assert_eq!((first.id, second.id), (0, 0));
+
+        std::fs::write(coordinator.part_path(&second.lease), lines(&coordinator.task, 0, 10)).unwrap();
+        assert!(matches!(coordinator.complete(&second.lease, 9), Err(Rejected::WrongCount { expected: 10, got: 9 })));
+        std::fs::write(coordinator.part_path(&second.lease), lines(&coordinator.task, 0, 10)).unwrap();
+        assert!(coordinator.complete(&second.lease, 10).unwrap());
+        // The first copy, late, is turned away
+        assert!(matches!(coordinator.leased(&first.lease), Err(Rejected::AlreadyDone)));
+        assert!(matches!(coordinator.leased("nope"), Err(Rejected::UnknownLease)));
+        let _ = std::fs::remove_dir_all(&coordinator.dir);
+    }
+
+    #[test]
+    fn test_markov_units_are_reproducible() {
+        let mut model = MarkovModel::new(2);
+        model.train_reader("password\nsunshine\ndragon\nmonkey\n".as_bytes()).unwrap();
+        let task = Task::Markov { model, count: 40, min_len: 4, max_len: 8, seed: 7 };
+        assert_eq!(lines(&task, 20, 20), lines(&task, 20, 20));
+        assert_ne!(lines(&task, 0, 20), lines(&task, 20, 20));
+        assert_eq!(lines(&task, 20, 20).split(|&b| b == b'\n').count(), 21);
+    }
+}
@@ -0,0 +1,119 @@
+use actix_web::http::header;
+use actix_web::{web, HttpRequest, HttpResponse};
+use flate2::write::GzEncoder;
+use serde::Deserialize;
+use std::io::{self, Write};
+use tokio::sync::mpsc;
+use super::server::ChannelBody;
+
+// ═══════════════════════════════════════════════════════════════
+// DOWNLOADS
+// ═══════════════════════════════════════════════════════════════
+//
+// Generation endpoints answer with JSON by default. Given `?download=true`,
+// or an Accept header naming application/gzip, they send the same
+// candidates as a gzipped text attachment instead, one per line,
+// compressed and streamed as they are made so neither side has to hold
+// the list as a JSON array.
+
+#[derive(Deserialize, Default)]
+pub struct DownloadQuery {
+    #[serde(default)]
+    pub download: bool,
+}
+
+/// Whether a request asked for an attachment rather than JSON
+pub fn wanted(req: &HttpRequest, query: &DownloadQuery) -> bool {
+    query.download || accepts_gzip(req)
+}
+
+fn accepts_gzip(req: &HttpRequest) -> bool {
+    req.headers().get_all(header::ACCEPT)
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|range| {
+            let mut params = range.split(';').map(str::trim);
+            params.next().is_some_and(|media| media.eq_ignore_ascii_case("application/gzip"))
+                && params.all(|param| param.replace(' ', "") != "q=0")
+        })
+}
+
+/// `jigsaw-{kind}-{unix time}`, the stem of an attachment's filename
+pub fn stem(kind: &str) -> String {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    format!("jigsaw-{}-{}", kind, secs)
+}
+
+/// Compressed bytes gathered before a chunk is sent
+const CHUNK: usize = 64 * 1024;
+/// Chunks buffered ahead of a slow client before the producer waits
+const BACKLOG: usize = 16;
+
+/// Hands compressed output to the response body in CHUNK-sized pieces
+struct Chunks {
+    sender: mpsc::Sender<web::Bytes>,
+    buffer: Vec<u8>,
+}
+
+impl Write for Chunks {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(data);
+        if self.buffer.len() >= CHUNK {
+            self.flush()?;
+        }
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if !self.buffer.is_empty() {
+            self.sender.blocking_send(std::mem::take(&mut self.buffer).into())
+                .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "client went away"))?;
+        }
+        Ok(())
+    }
+}
+
+/// A gzipped `{stem}.txt.gz` attachment. `produce` runs on its own thread
+/// and feeds it candidates; `emit` returns true once the client has gone.
+pub fn attachment<F>(stem: &str, produce: F) -> HttpResponse
+where
+    F: FnOnce(&mut dyn FnMut(&[u8]) -> bool) + Send + 'static,
+{
+    let (sender, chunks) = mpsc::channel(BACKLOG);
+    std::thread::spawn(move || {
+        let chunks = Chunks { sender, buffer: Vec::with_capacity(CHUNK) };
+        let mut out = GzEncoder::new(chunks, flate2::Compression::default());
+        let mut closed = false;
+        produce(&mut |line| {
+            closed = out.write_all(line).and_then(|_| out.write_all(b"\n")).is_err();
+            closed
+        });
+        if !closed {
+            let _ = out.finish().and_then(|mut chunks| chunks.flush());
+        }
+    });
+    HttpResponse::Ok()
+        .content_type("application/gzip")
+        .insert_header((header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}.txt.gz\"", stem)))
+        .body(ChannelBody::new(chunks))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+
+    #[test]
+    fn test_wanted() {
+        let query = |download| DownloadQuery { download };
+        assert!(wanted(&TestRequest::default().to_http_request(), &query(true)));
+        assert!(!wanted(&TestRequest::default().to_http_request(), &query(false)));
+        let accept = |value: &str| TestRequest::default().insert_header((header::ACCEPT, value)).to_http_request();
+        assert!(wanted(&accept("application/gzip"), &query(false)));
+        assert!(wanted(&accept("application/json, application/gzip;q=0.5"), &query(false)));
+        assert!(!wanted(&accept("application/json"), &query(false)));
+        assert!(!wanted(&accept("application/gzip; q=0"), &query(false)));
+    }
+}
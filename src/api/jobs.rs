@@ -0,0 +1,521 @@
+use actix_web::http::StatusCode;
+use actix_web::{delete, get, post, web, HttpRequest, HttpResponse, Responder};
+use crossbeam_channel::{Receiver, Sender};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use crate::engine::markov::MarkovModel;
+use crate::engine::mask::Mask;
+use crate::engine::personal::Profile;
+use super::download::{self, DownloadQuery};
+use super::problem::{self, Problem};
+use super::server::ChannelBody;
+
+// ═══════════════════════════════════════════════════════════════
+// JOB QUEUE
+// ═══════════════════════════════════════════════════════════════
+//
+// Long generations run here instead of in a request handler. POST
+// /api/jobs queues one and answers at once with its ID; a fixed pool of
+// worker threads takes jobs in order and writes each list to an
+// owner-only file in a fresh temp directory, one candidate per line. Clients poll GET /api/jobs/{id} for
+// progress, download GET /api/jobs/{id}/result once it's done, and
+// DELETE /api/jobs/{id} to cancel a job and drop its result; finished
+// jobs are dropped anyway after the retention time. A job's size must be
+// known and under --max-job-candidates when it's submitted, and it never
+// writes more than that bound. When the server
+// shuts down, queued jobs are dropped and running ones get a grace
+// period to finish; any still running after it stop where they are and
+// keep their partial list on disk.
+
+/// What to generate
+#[derive(Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum JobRequest {
+    Personal {
+        profile: Box<Profile>,
+    },
+    Mask {
+        mask: String,
+    },
+    Markov {
+        /// A trained model; or give `corpus` to train one for the job
+        #[serde(default)]
+        model: Option<MarkovModel>,
+        #[serde(default)]
+        corpus: Vec<String>,
+        #[serde(default = "default_order")]
+        order: usize,
+        #[serde(default = "default_markov_count")]
+        count: usize,
+        #[serde(default = "default_markov_min_len")]
+        min_len: usize,
+        #[serde(default = "default_markov_max_len")]
+        max_len: usize,
+        #[serde(default)]
+        seed: Option<u64>,
+    },
+}
+
+fn default_order() -> usize { 3 }
+fn default_markov_count() -> usize { 10_000 }
+fn default_markov_min_len() -> usize { 6 }
+fn default_markov_max_len() -> usize { 12 }
+
+impl JobRequest {
+    pub(super) fn kind(&self) -> &'static str {
+        match self {
+            JobRequest::Personal { .. } => "personal",
+            JobRequest::Mask { .. } => "mask",
+            JobRequest::Markov { .. } => "markov",
+        }
+    }
+}
+
+/// A request checked and ready to run
+pub(super) enum Work {
+    Personal(Box<Profile>),
+    Mask(Mask),
+    Markov { model: MarkovModel, count: usize, min_len: usize, max_len: usize, seed: Option<u64> },
+}
+
+impl Work {
+    pub(super) fn prepare(request: JobRequest) -> anyhow::Result<Self> {
+        Ok(match request {
+            JobRequest::Personal { profile } if profile.is_empty() => anyhow::bail!("profile {}", super::server::EMPTY_PROFILE),
+            JobRequest::Personal { profile } => Work::Personal(profile),
+            JobRequest::Mask { mask } => Work::Mask(Mask::from_str(&mask)?),
+            JobRequest::Markov { model, corpus, order, count, min_len, max_len, seed } => {
+                if min_len == 0 || min_len > max_len {
+                    anyhow::bail!("min_len must be between 1 and max_len (got {} and {})", min_len, max_len);
+                }
+                let model = match model {
+                    Some(model) => model,
+                    None if corpus.is_empty() => anyhow::bail!("markov jobs need a model or a corpus"),
+                    None => {
+                        let mut model = MarkovModel::new(order.clamp(1, 8));
+                        model.train_reader(corpus.join("\n").as_bytes())?;
+                        model
+                    }
+                };
+                Work::Markov { model, count, min_len, max_len, seed }
+            }
+        })
+    }
+
+    /// Upper bound on the candidates this will write, when it's known
+    pub(super) fn expected(&self) -> Option<u64> {
+        match self {
+            Work::Personal(profile) => Some(profile.estimate().candidates),
+            Work::Mask(mask) => u64::try_from(mask.search_space_size()).ok(),
+            Work::Markov { count, .. } => Some(*count as u64),
+        }
+    }
+
+    /// Feed each candidate to `emit` until it returns true
+    pub(super) fn run(&self, mut emit: impl FnMut(&[u8]) -> bool) {
+        match self {
+            Work::Personal(profile) => profile.for_each_unique(super::server::STREAM_DEDUP, |candidate| emit(candidate.as_bytes())),
+            Work::Mask(mask) => {
+                for candidate in mask.iter() {
+                    if emit(&candidate) {
+                        break;
+                    }
+                }
+            }
+            Work::Markov { model, count, min_len, max_len, seed } => {
+                let mut rng = match seed {
+                    Some(seed) => crate::engine::memorable::seeded_rng(*seed),
+                    None => crate::engine::memorable::seeded_rng(rand::random()),
+                };
+                for _ in 0..*count {
+                    if emit(model.generate(&mut rng, *min_len, *max_len).as_bytes()) {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    Queued,
+    Running,
+    Done,
+    Failed,
+    Cancelled,
+}
+
+pub struct Job {
+    id: String,
+    kind: &'static str,
+    /// Most candidates the job will write
+    expected: u64,
+    generated: AtomicU64,
+    cancel: AtomicBool,
+    state: Mutex<(JobState, Option<String>)>,
+    created: Instant,
+    finished: Mutex<Option<Instant>>,
+    path: PathBuf,
+}
+
+#[derive(Serialize)]
+pub struct JobStatus {
+    pub id: String,
+    pub kind: &'static str,
+    pub state: JobState,
+    pub generated: u64,
+    pub expected: u64,
+    pub progress: Option<f64>,
+    pub error: Option<String>,
+    pub elapsed_ms: u128,
+    pub result_url: Option<String>,
+}
+
+impl Job {
+    fn set_state(&self, state: JobState, error: Option<String>) {
+        if matches!(state, JobState::Done | JobState::Failed | JobState::Cancelled) {
+            *self.finished.lock().unwrap() = Some(Instant::now());
+        }
+        *self.state.lock().unwrap() = (state, error);
+    }
+
+    fn status(&self) -> JobStatus {
+        let (state, error) = self.state.lock().unwrap().clone();
+        let generated = self.generated.load(Ordering::Relaxed);
+        let end = self.finished.lock().unwrap().unwrap_or_else(Instant::now);
+        let progress = match state {
+            JobState::Done => Some(1.0),
+            _ if self.expected > 0 => Some((generated as f64 / self.expected as f64).min(1.0)),
+            _ => None,
+        };
+        JobStatus {
+            id: self.id.clone(),
+            kind: self.kind,
+            result_url: (state == JobState::Done).then(|| format!("/api/jobs/{}/result", self.id)),
+            state,
+            generated,
+            expected: self.expected,
+            progress,
+            error,
+            elapsed_ms: end.duration_since(self.created).as_millis(),
+        }
+    }
+
+    fn execute(&self, work: Work) -> anyhow::Result<()> {
+        // A new owner-only file: results can hold a target's details
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let mut out = BufWriter::new(options.open(&self.path)?);
+        let mut failed = None;
+        work.run(|candidate| {
+            if self.generated.load(Ordering::Relaxed) >= self.expected {
+                return true;
+            }
+            if let Err(e) = out.write_all(candidate).and_then(|_| out.write_all(b"\n")) {
+                failed = Some(e);
+                return true;
+            }
+            self.generated.fetch_add(1, Ordering::Relaxed);
+            self.cancel.load(Ordering::Relaxed)
+        });
+        if let Some(e) = failed {
+            return Err(e.into());
+        }
+        out.flush()?;
+        Ok(())
+    }
+}
+
+/// Drop jobs that finished more than `retention` ago, with their results
+fn expire(jobs: &Table, retention: Duration) {
+    jobs.lock().unwrap().retain(|_, job| {
+        let expired = job.finished.lock().unwrap().is_some_and(|at| at.elapsed() >= retention);
+        if expired {
+            let _ = std::fs::remove_file(&job.path);
+        }
+        !expired
+    });
+}
+
+/// A fresh directory for results that only this user can enter, at a
+/// name nobody could have created first
+fn private_dir() -> std::io::Result<PathBuf> {
+    let dir = std::env::temp_dir().join(format!("jigsaw-jobs-{:016x}", rand::random::<u64>()));
+    let mut builder = std::fs::DirBuilder::new();
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder.create(&dir)?;
+    Ok(dir)
+}
+
+/// A job waiting for a worker, with what it will run
+type Queued = (Arc<Job>, Work);
+
+/// Jobs by ID
+type Table = Arc<Mutex<HashMap<String, Arc<Job>>>>;
+
+/// Every job the server knows about, and the queue feeding the workers
+pub struct Jobs {
+    jobs: Table,
+    queue: Sender<Queued>,
+    /// Where results are written
+    pub(super) dir: PathBuf,
+    closing: Arc<AtomicBool>,
+    max_candidates: u64,
+}
+
+impl Jobs {
+    /// Start `workers` threads taking jobs off the queue. Finished jobs
+    /// are forgotten, and their results deleted, `retention` after they end;
+    /// jobs over `max_candidates` are refused.
+    pub fn start(workers: usize, retention: Option<Duration>, max_candidates: u64) -> std::io::Result<Self> {
+        let dir = private_dir()?;
+        let (queue, work): (Sender<Queued>, Receiver<Queued>) = crossbeam_channel::unbounded();
+        let closing = Arc::new(AtomicBool::new(false));
+        for _ in 0..workers.max(1) {
+            let work = work.clone();
+            let closing = closing.clone();
+            std::thread::spawn(move || {
+                for (job, work) in work {
+                    if closing.load(Ordering::Relaxed) {
+                        job.set_state(JobState::Cancelled, Some("server shut down".to_string()));
+                        continue;
+                    }
+                    if job.cancel.load(Ordering::Relaxed) {
+                        continue;
+                    }
+                    job.set_state(JobState::Running, None);
+                    match job.execute(work) {
+                        // Stopped by shutdown: keep what it made
+                        Ok(()) if job.cancel.load(Ordering::Relaxed) && closing.load(Ordering::Relaxed) => {
+                            job.set_state(JobState::Failed, Some("interrupted by shutdown".to_string()));
+                        }
+                        Ok(()) if job.cancel.load(Ordering::Relaxed) => {
+                            let _ = std::fs::remove_file(&job.path);
+                            job.set_state(JobState::Cancelled, None);
+                        }
+                        Ok(()) => job.set_state(JobState::Done, None),
+                        Err(e) => job.set_state(JobState::Failed, Some(e.to_string())),
+                    }
+                }
+            });
+        }
+        let jobs = Table::default();
+        if let Some(retention) = retention {
+            let jobs = jobs.clone();
+            std::thread::spawn(move || loop {
+                std::thread::sleep(retention.clamp(Duration::from_secs(1), Duration::from_secs(60)));
+                expire(&jobs, retention);
+            });
+        }
+        Ok(Self { jobs, queue, dir, closing, max_candidates })
+    }
+
+    /// Stop for shutdown: drop queued jobs, wait up to `grace` for running
+    /// ones, then stop the rest. Returns the jobs that were cut short, with
+    /// where their partial lists are.
+    pub fn shutdown(&self, grace: Duration) -> Vec<(String, u64, PathBuf)> {
+        self.closing.store(true, Ordering::Relaxed);
+        let running = || -> Vec<Arc<Job>> {
+            self.jobs.lock().unwrap().values()
+                .filter(|job| job.state.lock().unwrap().0 == JobState::Running)
+                .cloned()
+                .collect()
+        };
+        let deadline = Instant::now() + grace;
+        while !running().is_empty() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        // Jobs check the flag after every candidate, so this is quick
+        let mut interrupted = Vec::new();
+        loop {
+            let jobs = running();
+            if jobs.is_empty() {
+                break;
+            }
+            interrupted.extend(jobs.into_iter().filter(|job| !job.cancel.swap(true, Ordering::Relaxed)));
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        interrupted.iter()
+            .map(|job| (job.id.clone(), job.generated.load(Ordering::Relaxed), job.path.clone()))
+            .collect()
+    }
+
+    /// Check a request and size it. Jobs run unwatched, so their size has
+    /// to be known and bounded before they are queued.
+    fn prepare(&self, request: JobRequest) -> anyhow::Result<(&'static str, Work, u64)> {
+        let kind = request.kind();
+        let work = Work::prepare(request)?;
+        let expected = match work.expected() {
+            Some(expected) if expected <= self.max_candidates => expected,
+            Some(expected) => anyhow::bail!("the job would write {} candidates, over this server's limit of {}", expected, self.max_candidates),
+            None => anyhow::bail!("the job would write more candidates than this server's limit of {}", self.max_candidates),
+        };
+        Ok((kind, work, expected))
+    }
+
+    /// Queue prepared work that writes at most `expected` candidates
+    fn submit(&self, kind: &'static str, work: Work, expected: u64) -> Arc<Job> {
+        let id = format!("{:016x}", rand::random::<u64>());
+        let job = Arc::new(Job {
+            path: self.dir.join(format!("{}.txt", id)),
+            id: id.clone(),
+            kind,
+            expected,
+            generated: AtomicU64::new(0),
+            cancel: AtomicBool::new(false),
+            state: Mutex::new((JobState::Queued, None)),
+            created: Instant::now(),
+            finished: Mutex::new(None),
+        });
+        self.jobs.lock().unwrap().insert(id, job.clone());
+        self.queue.send((job.clone(), work)).expect("job workers stopped");
+        job
+    }
+
+    fn get(&self, id: &str) -> Option<Arc<Job>> {
+        self.jobs.lock().unwrap().get(id).cloned()
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════
+// ENDPOINTS
+// ═══════════════════════════════════════════════════════════════
+
+fn not_found(id: &str) -> HttpResponse {
+    problem::not_found(format!("no job {}", id))
+}
+
+/// Check and size a request, then take its size from the quota of the key
+/// behind `req`, before anything runs: POST /api/jobs and the WebSocket
+/// both come through here. The error is the response to send.
+pub(super) async fn accept(req: &HttpRequest, jobs: &web::Data<Jobs>, request: JobRequest) -> Result<(&'static str, Work, u64), HttpResponse> {
+    // Preparing may train a Markov model, so keep it off the async worker
+    let preparing = jobs.clone();
+    let (kind, work, expected) = match web::block(move || preparing.prepare(request)).await {
+        Ok(Ok(prepared)) => prepared,
+        Ok(Err(e)) => return Err(problem::bad_request(e)),
+        Err(e) => return Err(problem::internal(e)),
+    };
+    // Charged its full size now, since nothing stops it short of the end
+    super::auth::reserve(req, expected)?;
+    Ok((kind, work, expected))
+}
+
+#[post("/api/jobs")]
+async fn create_job(req: HttpRequest, jobs: web::Data<Jobs>, request: web::Json<JobRequest>) -> impl Responder {
+    let (kind, work, expected) = match accept(&req, &jobs, request.into_inner()).await {
+        Ok(accepted) => accepted,
+        Err(response) => return response,
+    };
+    let job = jobs.submit(kind, work, expected);
+    HttpResponse::Accepted()
+        .insert_header(("Location", format!("/api/jobs/{}", job.id)))
+        .json(job.status())
+}
+
+#[get("/api/jobs/{id}")]
+async fn job_status(jobs: web::Data<Jobs>, id: web::Path<String>) -> impl Responder {
+    match jobs.get(&id) {
+        Some(job) => HttpResponse::Ok().json(job.status()),
+        None => not_found(&id),
+    }
+}
+
+#[get("/api/jobs/{id}/result")]
+async fn job_result(req: HttpRequest, jobs: web::Data<Jobs>, id: web::Path<String>, download: web::Query<DownloadQuery>) -> impl Responder {
+    let Some(job) = jobs.get(&id) else { return not_found(&id) };
+    let status = job.status();
+    if status.state != JobState::Done {
+        return Problem::new(StatusCode::CONFLICT, format!("job {} has not finished", id))
+            .with("job", status)
+            .response();
+    }
+    let mut file = match std::fs::File::open(&job.path) {
+        Ok(file) => file,
+        Err(e) => return problem::internal(e),
+    };
+    if download::wanted(&req, &download) {
+        return download::attachment(&format!("jigsaw-{}", id), move |emit| {
+            for line in BufReader::new(file).split(b'\n') {
+                if line.map_or(true, |line| emit(&line)) {
+                    break;
+                }
+            }
+        });
+    }
+    let (sender, chunks) = tokio::sync::mpsc::channel(16);
+    std::thread::spawn(move || {
+        let mut buffer = vec![0; 64 * 1024];
+        while let Ok(n @ 1..) = file.read(&mut buffer) {
+            if sender.blocking_send(web::Bytes::copy_from_slice(&buffer[..n])).is_err() {
+                break;
+            }
+        }
+    });
+    HttpResponse::Ok()
+        .content_type("text/plain; charset=utf-8")
+        .insert_header(("Content-Disposition", format!("attachment; filename=\"jigsaw-{}.txt\"", id)))
+        .body(ChannelBody::new(chunks))
+}
+
+#[delete("/api/jobs/{id}")]
+async fn cancel_job(jobs: web::Data<Jobs>, id: web::Path<String>) -> impl Responder {
+    let Some(job) = jobs.jobs.lock().unwrap().remove(id.as_str()) else { return not_found(&id) };
+    job.cancel.store(true, Ordering::Relaxed);
+    // A running job removes its own file when it notices the flag
+    if job.status().state != JobState::Running {
+        let _ = std::fs::remove_file(&job.path);
+    }
+    HttpResponse::NoContent().finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mask(mask: &str) -> JobRequest {
+        JobRequest::Mask { mask: mask.to_string() }
+    }
+
+    #[test]
+    fn test_prepare_bounds_job_size() {
+        let jobs = Jobs::start(1, None, 1000).unwrap();
+        assert_eq!(jobs.prepare(mask("?d?d?d")).unwrap().2, 1000);
+        assert!(jobs.prepare(mask("?d?d?d?d")).is_err());
+        // Past u64, so the size isn't even known
+        assert!(jobs.prepare(mask(&"?a".repeat(12))).is_err());
+        let _ = std::fs::remove_dir_all(&jobs.dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_results_are_private() {
+        use std::os::unix::fs::PermissionsExt;
+        let jobs = Jobs::start(1, None, 1000).unwrap();
+        let other = Jobs::start(1, None, 1000).unwrap();
+        assert_ne!(jobs.dir, other.dir);
+        let _ = std::fs::remove_dir_all(&other.dir);
+        assert_eq!(std::fs::metadata(&jobs.dir).unwrap().permissions().mode() & 0o777, 0o700);
+
+        let (kind, work, expected) = jobs.prepare(mask("?d?d")).unwrap();
+        let job = jobs.submit(kind, work, expected);
+        let start = Instant::now();
+        while job.status().state != JobState::Done && start.elapsed() < Duration::from_secs(5) {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(job.status().generated, 100);
+        assert_eq!(std::fs::metadata(&job.path).unwrap().permissions().mode() & 0o777, 0o600);
+        let _ = std::fs::remove_dir_all(&jobs.dir);
+    }
+}
@@ -0,0 +1,313 @@
+use actix_web::{post, web, HttpMessage, HttpRequest, HttpResponse, Responder};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use crate::engine::markov::MarkovModel;
+use super::download::{self, DownloadQuery};
+use super::problem::{self, Invalid};
+
+// ═══════════════════════════════════════════════════════════════
+// MARKOV MODELS
+// ═══════════════════════════════════════════════════════════════
+//
+// POST /api/markov/train takes a corpus, one word per line, either as
+// the raw body or as the `corpus` field of a multipart form, and keeps
+// the trained model in memory under a new ID. /generate samples
+// candidates from a stored model and /score rates a wordlist against
+// it. The server keeps the --max-models most recently used models and
+// drops the oldest to make room for a new one. Training is charged one
+// candidate per corpus word and scoring one per word scored, and neither
+// a sample nor a wordlist may pass --max-candidates; bigger runs belong
+// in the job queue.
+
+/// Largest corpus upload accepted, in bytes
+pub const MAX_CORPUS: usize = 64 * 1024 * 1024;
+
+/// Trained models by ID, each with when it was last used
+pub struct Models {
+    models: Mutex<HashMap<String, (Arc<MarkovModel>, u64)>>,
+    /// Ticks on every use, to find the least recently used model
+    clock: AtomicU64,
+    max: usize,
+}
+
+impl Models {
+    /// A store holding at most `max` models
+    pub fn new(max: usize) -> Self {
+        Self { models: Mutex::default(), clock: AtomicU64::new(0), max: max.max(1) }
+    }
+
+    fn get(&self, id: &str) -> Option<Arc<MarkovModel>> {
+        let mut models = self.models.lock().unwrap();
+        let (model, used) = models.get_mut(id)?;
+        *used = self.clock.fetch_add(1, Ordering::Relaxed);
+        Some(model.clone())
+    }
+
+    /// Keep `model` under a new ID, dropping the least recently used
+    /// model if the store is full
+    fn insert(&self, model: MarkovModel) -> String {
+        let id = format!("{:016x}", rand::random::<u64>());
+        let mut models = self.models.lock().unwrap();
+        while models.len() >= self.max {
+            let Some(oldest) = models.iter().min_by_key(|(_, (_, used))| *used).map(|(id, _)| id.clone()) else { break };
+            models.remove(&oldest);
+        }
+        models.insert(id.clone(), (Arc::new(model), self.clock.fetch_add(1, Ordering::Relaxed)));
+        id
+    }
+}
+
+#[derive(Deserialize)]
+pub struct TrainQuery {
+    #[serde(default = "default_order")]
+    pub order: usize,
+}
+
+#[derive(Serialize)]
+pub struct TrainResponse {
+    pub model: String,
+    pub order: usize,
+    pub contexts: usize,
+    pub time_taken_ms: u128,
+}
+
+#[derive(Deserialize)]
+pub struct GenerateRequest {
+    pub model: String,
+    #[serde(default = "default_count")]
+    pub count: usize,
+    #[serde(default = "default_min_len")]
+    pub min_len: usize,
+    #[serde(default = "default_max_len")]
+    pub max_len: usize,
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub struct ScoreRequest {
+    pub model: String,
+    pub words: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct Score {
+    pub word: String,
+    /// Natural log of `probability`; null when the model can't produce the word
+    pub log_prob: Option<f64>,
+    pub probability: f64,
+}
+
+fn default_order() -> usize { 3 }
+fn default_count() -> usize { 100 }
+fn default_min_len() -> usize { 6 }
+fn default_max_len() -> usize { 12 }
+
+fn no_model(id: &str) -> HttpResponse {
+    problem::not_found(format!("no model {}", id))
+}
+
+/// The corpus a train request carries: a multipart `corpus` field, or the body itself
+fn corpus<'a>(req: &HttpRequest, body: &'a [u8]) -> Result<&'a [u8], String> {
+    let mime = req.mime_type().map_err(|e| e.to_string())?;
+    match mime {
+        Some(mime) if mime.type_() == "multipart" => {
+            let boundary = mime.get_param("boundary").ok_or("multipart body without a boundary")?;
+            multipart_field(body, boundary.as_str(), "corpus").ok_or_else(|| "no `corpus` field in the form".to_string())
+        }
+        _ => Ok(body),
+    }
+}
+
+/// The contents of form field `name` in a multipart/form-data `body`
+fn multipart_field<'a>(body: &'a [u8], boundary: &str, name: &str) -> Option<&'a [u8]> {
+    let delimiter = format!("--{}", boundary);
+    let disposition = format!("name=\"{}\"", name);
+    let mut rest = &body[find(body, delimiter.as_bytes())? + delimiter.len()..];
+    // Each part: CRLF, headers, blank line, content, CRLF, delimiter
+    while !rest.starts_with(b"--") {
+        let part = rest.strip_prefix(b"\r\n")?;
+        let end = find(part, format!("\r\n{}", delimiter).as_bytes())?;
+        let split = find(&part[..end], b"\r\n\r\n")?;
+        let headers = String::from_utf8_lossy(&part[..split]);
+        let is_field = headers.lines().any(|line| {
+            line.to_ascii_lowercase().starts_with("content-disposition:") && line.contains(&disposition)
+        });
+        if is_field {
+            return Some(&part[split + 4..end]);
+        }
+        rest = &part[end + 2 + delimiter.len()..];
+    }
+    None
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+#[post("/api/markov/train")]
+async fn train(req: HttpRequest, query: web::Query<TrainQuery>, body: web::Bytes, models: web::Data<Models>) -> impl Responder {
+    let start = Instant::now();
+    let order = query.order;
+    if !(1..=8).contains(&order) {
+        return Invalid::one("order", format!("must be between 1 and 8 (got {})", order));
+    }
+    let corpus = match corpus(&req, &body) {
+        Ok(corpus) if !corpus.is_empty() => body.slice_ref(corpus),
+        Ok(_) => return Invalid::one("corpus", "empty corpus"),
+        Err(e) => return problem::bad_request(e),
+    };
+
+    let trained = web::block(move || {
+        let words = corpus.split(|&b| b == b'\n').filter(|line| !line.is_empty()).count();
+        let mut model = MarkovModel::new(order);
+        model.train_reader(&corpus[..]).map(|_| (model, words))
+    }).await;
+    let (model, words) = match trained {
+        Ok(Ok(trained)) => trained,
+        Ok(Err(e)) => return Invalid::one("corpus", e),
+        Err(e) => return problem::internal(e),
+    };
+    if model.transitions.is_empty() {
+        return Invalid::one("corpus", format!("no word is longer than the order ({})", order));
+    }
+
+    let contexts = model.transitions.len();
+    let id = models.insert(model);
+    let response = HttpResponse::Created().json(TrainResponse { model: id, order, contexts, time_taken_ms: start.elapsed().as_millis() });
+    super::auth::charge(response, words as u64)
+}
+
+#[post("/api/markov/generate")]
+async fn generate(
+    req: HttpRequest,
+    data: web::Json<GenerateRequest>,
+    download: web::Query<DownloadQuery>,
+    models: web::Data<Models>,
+    limits: web::Data<super::server::Limits>,
+) -> impl Responder {
+    let start = Instant::now();
+    let Some(model) = models.get(&data.model) else { return no_model(&data.model) };
+    let mut invalid = Invalid::default();
+    invalid.check(data.min_len != 0 && data.min_len <= data.max_len, "min_len",
+        format!("must be between 1 and max_len (got {} and {})", data.min_len, data.max_len));
+    let max = limits.max_candidates;
+    invalid.check(data.count <= max, "count", format!("is limited to {}; queue a markov job for more", max));
+    if let Err(response) = invalid.finish() {
+        return response;
+    }
+
+    let data = data.into_inner();
+    if download::wanted(&req, &download) {
+        let count = data.count;
+        let response = download::attachment(&download::stem("markov"), move |emit| {
+            let mut rng = crate::engine::memorable::seeded_rng(data.seed.unwrap_or_else(rand::random));
+            for _ in 0..data.count {
+                if emit(model.generate(&mut rng, data.min_len, data.max_len).as_bytes()) {
+                    break;
+                }
+            }
+        });
+        return super::auth::charge(response, count as u64);
+    }
+    let candidates = web::block(move || {
+        let mut rng = crate::engine::memorable::seeded_rng(data.seed.unwrap_or_else(rand::random));
+        (0..data.count).map(|_| model.generate(&mut rng, data.min_len, data.max_len)).collect::<Vec<_>>()
+    }).await;
+    let candidates = match candidates {
+        Ok(candidates) => candidates,
+        Err(e) => return problem::internal(e),
+    };
+
+    let total = candidates.len();
+    let response = HttpResponse::Ok().json(super::server::GenerateResponse {
+        candidates,
+        total,
+        time_taken_ms: start.elapsed().as_millis(),
+    });
+    super::auth::charge(response, total as u64)
+}
+
+#[post("/api/markov/score")]
+async fn score(data: web::Json<ScoreRequest>, models: web::Data<Models>, limits: web::Data<super::server::Limits>) -> impl Responder {
+    let Some(model) = models.get(&data.model) else { return no_model(&data.model) };
+    let max = limits.max_candidates;
+    if data.words.len() > max {
+        return Invalid::one("words", format!("is limited to {} per request (got {})", max, data.words.len()));
+    }
+    let words = data.words.len() as u64;
+    let scores: Vec<Score> = data.into_inner().words.into_iter()
+        .map(|word| {
+            let log_prob = model.log_probability(&word);
+            Score { probability: log_prob.map_or(0.0, f64::exp), log_prob, word }
+        })
+        .collect();
+    let response = HttpResponse::Ok().json(serde_json::json!({ "scores": scores }));
+    super::auth::charge(response, words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test as actix, App};
+
+    #[test]
+    fn test_models_drop_the_least_recently_used() {
+        let models = Models::new(2);
+        let trained = || {
+            let mut model = MarkovModel::new(2);
+            model.train_reader(&b"summer\nwinter"[..]).unwrap();
+            model
+        };
+        let first = models.insert(trained());
+        let second = models.insert(trained());
+        assert!(models.get(&first).is_some());
+        let third = models.insert(trained());
+        assert!(models.get(&second).is_none());
+        assert!(models.get(&first).is_some() && models.get(&third).is_some());
+        assert_eq!(models.models.lock().unwrap().len(), 2);
+    }
+
+    #[actix_web::test]
+    async fn test_train_and_score_are_charged_and_bounded() {
+        let app = actix::init_service(App::new()
+            .app_data(web::Data::new(Models::new(4)))
+            .app_data(web::Data::new(super::super::server::Limits { max_candidates: 3 }))
+            .service(train)
+            .service(score)).await;
+        let request = actix::TestRequest::post().uri("/api/markov/train?order=2").set_payload("summer\nwinter\n\nautumn").to_request();
+        let response = actix::call_service(&app, request).await;
+        assert_eq!(response.status(), 201);
+        assert_eq!(response.response().extensions().get::<super::super::auth::Candidates>().map(|charged| charged.0), Some(3));
+        let model: serde_json::Value = actix::read_body_json(response).await;
+
+        let scoring = |words: &[&str]| actix::TestRequest::post()
+            .uri("/api/markov/score")
+            .set_json(serde_json::json!({ "model": model["model"], "words": words }))
+            .to_request();
+        let response = actix::call_service(&app, scoring(&["summer", "winter"])).await;
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.response().extensions().get::<super::super::auth::Candidates>().map(|charged| charged.0), Some(2));
+        let response = actix::call_service(&app, scoring(&["a", "b", "c", "d"])).await;
+        assert_eq!(response.status(), 400);
+    }
+
+    #[test]
+    fn test_multipart_field() {
+        let body = b"--XyZ\r\n\
+            Content-Disposition: form-data; name=\"order\"\r\n\r\n\
+            2\r\n\
+            --XyZ\r\n\
+            Content-Disposition: form-data; name=\"corpus\"; filename=\"words.txt\"\r\n\
+            Content-Type: text/plain\r\n\r\n\
+            alpha\nbeta\r\n\
+            --XyZ--\r\n";
+        assert_eq!(multipart_field(body, "XyZ", "corpus"), Some(&b"alpha\nbeta"[..]));
+        assert_eq!(multipart_field(body, "XyZ", "order"), Some(&b"2"[..]));
+        assert_eq!(multipart_field(body, "XyZ", "missing"), None);
+        assert_eq!(multipart_field(body, "other", "corpus"), None);
+    }
+}
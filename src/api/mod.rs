@@ -1 +1,10 @@
+pub mod auth;
+pub mod coordinator;
+pub mod download;
+pub mod jobs;
+pub mod markov;
+pub mod openapi;
+pub mod problem;
 pub mod server;
+pub mod tls;
+pub mod ws;
@@ -0,0 +1,605 @@
+use actix_web::{get, HttpResponse, Responder};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use crate::engine::mnemonic::{MnemonicCase, MnemonicConfig};
+use crate::engine::personal::{Category, Profile};
+use crate::engine::walks::Layout;
+use super::coordinator::{ClaimRequest, CoordinatorStatus, Unit};
+use super::jobs::{JobState, JobStatus};
+use super::markov;
+use super::problem;
+use super::server::{
+    CheckRequest, CheckResponse, GenerateResponse, MemorableConfigSummary, MemorableRequest, MemorableResponse, PageResponse,
+    PwnedResponse,
+};
+
+// ═══════════════════════════════════════════════════════════════
+// OPENAPI
+// ═══════════════════════════════════════════════════════════════
+//
+// The endpoint table below is the one list of what the server offers:
+// /api/info, the startup banner and GET /api/openapi.json all read it,
+// and the tests check it against `server::routes` both ways.
+// Schemas come from the request/response types through `object!` and
+// `enumeration!`, which destructure the type exhaustively, so adding,
+// removing or retyping a field without updating its schema is a compile
+// error rather than a stale document.
+
+/// A type's JSON schema, or a reference to it in `components`
+pub trait Schema {
+    fn schema() -> Value;
+}
+
+/// A named type listed under `components/schemas`
+trait Component {
+    const NAME: &'static str;
+    fn definition() -> Value;
+}
+
+macro_rules! primitive {
+    ($($ty:ty => $json:tt),* $(,)?) => {$(
+        impl Schema for $ty {
+            fn schema() -> Value {
+                json!($json)
+            }
+        }
+    )*};
+}
+
+primitive!(
+    String => { "type": "string" },
+    &'static str => { "type": "string" },
+    bool => { "type": "boolean" },
+    u32 => { "type": "integer", "minimum": 0 },
+    u64 => { "type": "integer", "minimum": 0 },
+    u128 => { "type": "integer", "minimum": 0 },
+    usize => { "type": "integer", "minimum": 0 },
+    f32 => { "type": "number" },
+    f64 => { "type": "number" },
+);
+
+impl<T: Schema> Schema for Option<T> {
+    fn schema() -> Value {
+        json!({ "anyOf": [T::schema(), { "type": "null" }] })
+    }
+}
+
+impl<T: Schema> Schema for Vec<T> {
+    fn schema() -> Value {
+        json!({ "type": "array", "items": T::schema() })
+    }
+}
+
+impl<T: Schema> Schema for HashMap<String, T> {
+    fn schema() -> Value {
+        json!({ "type": "object", "additionalProperties": T::schema() })
+    }
+}
+
+fn reference<T: Component>() -> Value {
+    json!({ "$ref": format!("#/components/schemas/{}", T::NAME) })
+}
+
+/// A struct as an object schema; `required` lists the fields without a serde default
+macro_rules! object {
+    ($name:literal = $($ty:ident)::+ { $($field:ident: $fty:ty),* $(,)? } $(required [$($required:ident),*])?) => {
+        impl Component for $($ty)::+ {
+            const NAME: &'static str = $name;
+            fn definition() -> Value {
+                // Fails to compile once the field list no longer matches the type
+                let _ = |value: &$($ty)::+| {
+                    let $($ty)::+ { $($field),* } = value;
+                    $(let _: &$fty = $field;)*
+                };
+                let mut properties = Map::new();
+                $(properties.insert(stringify!($field).to_string(), <$fty as Schema>::schema());)*
+                let required: &[&str] = &[$($(stringify!($required)),*)?];
+                json!({ "type": "object", "properties": properties, "required": required })
+            }
+        }
+
+        impl Schema for $($ty)::+ {
+            fn schema() -> Value {
+                reference::<Self>()
+            }
+        }
+    };
+}
+
+/// A unit-variant enum as the strings serde writes for it
+macro_rules! enumeration {
+    ($name:literal = $ty:ident { $($variant:ident),* $(,)? }) => {
+        impl Component for $ty {
+            const NAME: &'static str = $name;
+            fn definition() -> Value {
+                let _ = |value: $ty| match value { $($ty::$variant => ()),* };
+                let values: Vec<Value> = [$($ty::$variant),*].iter().map(|v| serde_json::to_value(v).unwrap()).collect();
+                json!({ "type": "string", "enum": values })
+            }
+        }
+
+        impl Schema for $ty {
+            fn schema() -> Value {
+                reference::<Self>()
+            }
+        }
+    };
+}
+
+object!("Profile" = Profile {
+    first_names: Vec<String>, last_names: Vec<String>, partners: Vec<String>, kids: Vec<String>, pets: Vec<String>,
+    company: Vec<String>, school: Vec<String>, city: Vec<String>, sports: Vec<String>, music: Vec<String>,
+    usernames: Vec<String>, dates: Vec<String>, keywords: Vec<String>, numbers: Vec<String>,
+    email: Vec<String>, parents: Vec<String>, maiden_name: Vec<String>, hobbies: Vec<String>,
+    min_length: Option<usize>, max_length: Option<usize>, typos: bool, keyboard: Layout,
+    nickname_map: HashMap<String, Vec<String>>, blend: Vec<String>,
+    field_weights: HashMap<String, f32>, word_weights: HashMap<String, f32>,
+    word_limit: Option<usize>, skip: Vec<Category>,
+});
+enumeration!("Category" = Category {
+    KeyboardWalks, Leet, Decorative, Idioms, Initials, Combos, Triples, Repetition,
+});
+enumeration!("KeyboardLayout" = Layout { Qwerty, Azerty, Qwertz });
+object!("CheckRequest" = CheckRequest { profile: Profile, password: String } required [profile, password]);
+object!("CheckResponse" = CheckResponse { found: bool, total_candidates: usize, time_taken_ms: u128 } required [found, total_candidates, time_taken_ms]);
+object!("GenerateResponse" = GenerateResponse { candidates: Vec<String>, total: usize, time_taken_ms: u128 } required [candidates, total, time_taken_ms]);
+object!("PageResponse" = PageResponse {
+    candidates: Vec<String>, total: usize, offset: usize, next_cursor: Option<String>, time_taken_ms: u128,
+} required [candidates, total, offset, next_cursor, time_taken_ms]);
+object!("PwnedResponse" = PwnedResponse { pwned: bool, count: u64 } required [pwned, count]);
+object!("MemorableRequest" = MemorableRequest {
+    word_count: usize, separator: String, case_style: String, include_number: bool,
+    number_position: String, number_max: u32, include_special: bool, special_position: String,
+    style: String, count: usize, min_length: usize, max_length: usize, word_pool: String,
+    language: String, template: Option<String>, min_entropy: Option<f64>, policy: Option<String>,
+    leet_probability: f64, seed: Option<u64>, specials: Option<String>,
+    min_word_len: Option<usize>, max_word_len: Option<usize>, number_form: String,
+});
+object!("MemorableResponse" = MemorableResponse {
+    passwords: Vec<String>, count: usize, config_used: MemorableConfigSummary, time_taken_ms: u128,
+} required [passwords, count, config_used, time_taken_ms]);
+object!("MemorableConfigSummary" = MemorableConfigSummary {
+    style: String, word_count: usize, separator: String, case_style: String, include_number: bool, include_special: bool,
+} required [style, word_count, separator, case_style, include_number, include_special]);
+object!("MnemonicConfig" = MnemonicConfig { case: MnemonicCase, leet: bool, number_words: bool, keep_punctuation: bool });
+enumeration!("MnemonicCase" = MnemonicCase { Preserve, Lower, Upper, Alternating });
+object!("JobStatus" = JobStatus {
+    id: String, kind: &'static str, state: JobState, generated: u64, expected: u64,
+    progress: Option<f64>, error: Option<String>, elapsed_ms: u128, result_url: Option<String>,
+} required [id, kind, state, generated, expected, progress, error, elapsed_ms, result_url]);
+enumeration!("JobState" = JobState { Queued, Running, Done, Failed, Cancelled });
+object!("CoordinatorStatus" = CoordinatorStatus {
+    kind: &'static str, keyspace: u64, units: usize, pending: usize, leased: usize, done: usize, generated: u64,
+    progress: f64, workers: HashMap<String, u64>, finished: bool, output: String, elapsed_ms: u128,
+} required [kind, keyspace, units, pending, leased, done, generated, progress, workers, finished, output, elapsed_ms]);
+object!("ClaimRequest" = ClaimRequest { worker: Option<String> });
+object!("Unit" = Unit { id: usize, lease: String, skip: u64, limit: u64, lease_secs: u64 } required [id, lease, skip, limit, lease_secs]);
+object!("MarkovTrainResponse" = markov::TrainResponse { model: String, order: usize, contexts: usize, time_taken_ms: u128 }
+    required [model, order, contexts, time_taken_ms]);
+object!("MarkovGenerateRequest" = markov::GenerateRequest {
+    model: String, count: usize, min_len: usize, max_len: usize, seed: Option<u64>,
+} required [model]);
+object!("MarkovScoreRequest" = markov::ScoreRequest { model: String, words: Vec<String> } required [model, words]);
+object!("MarkovScore" = markov::Score { word: String, log_prob: Option<f64>, probability: f64 } required [word, log_prob, probability]);
+
+/// Every named schema, for `components/schemas`
+fn components() -> Map<String, Value> {
+    macro_rules! all {
+        ($($ty:ty),* $(,)?) => {{
+            let mut schemas = Map::new();
+            $(schemas.insert(<$ty as Component>::NAME.to_string(), <$ty as Component>::definition());)*
+            schemas
+        }};
+    }
+    let mut schemas = all!(
+        Profile, Category, Layout, CheckRequest, CheckResponse, GenerateResponse, PageResponse, PwnedResponse,
+        MemorableRequest, MemorableResponse, MemorableConfigSummary, MnemonicConfig, MnemonicCase,
+        JobStatus, JobState, CoordinatorStatus, ClaimRequest, Unit, markov::TrainResponse, markov::GenerateRequest, markov::ScoreRequest, markov::Score,
+    );
+    schemas.insert("Problem".to_string(), json!({
+        "type": "object",
+        "description": "RFC 7807 problem details",
+        "properties": {
+            "type": { "type": "string" },
+            "title": { "type": "string" },
+            "status": { "type": "integer" },
+            "detail": { "type": "string" },
+            "errors": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": { "field": { "type": "string" }, "message": { "type": "string" } },
+                    "required": ["field", "message"],
+                },
+            },
+        },
+        "required": ["type", "title", "status", "detail"],
+    }));
+    schemas
+}
+
+// ═══════════════════════════════════════════════════════════════
+// ENDPOINTS
+// ═══════════════════════════════════════════════════════════════
+
+/// A request or response body: its media type and schema
+pub struct Body {
+    pub media: &'static str,
+    pub schema: Value,
+}
+
+impl Body {
+    fn json(schema: Value) -> Self {
+        Self { media: "application/json", schema }
+    }
+
+    fn of<T: Schema>() -> Self {
+        Self::json(T::schema())
+    }
+
+    fn text(media: &'static str) -> Self {
+        Self { media, schema: json!({ "type": "string" }) }
+    }
+}
+
+pub struct Endpoint {
+    pub method: &'static str,
+    pub path: &'static str,
+    pub summary: &'static str,
+    /// Query parameters with their schemas
+    pub query: Vec<(&'static str, Value)>,
+    pub request: Vec<Body>,
+    /// Success status and body
+    pub response: (u16, Option<Body>),
+    /// Also answers with a gzipped attachment (`?download=true` or `Accept: application/gzip`)
+    pub download: bool,
+}
+
+impl Endpoint {
+    fn new(method: &'static str, path: &'static str, summary: &'static str) -> Self {
+        Self { method, path, summary, query: Vec::new(), request: Vec::new(), response: (200, None), download: false }
+    }
+
+    fn query<T: Schema>(mut self, name: &'static str) -> Self {
+        self.query.push((name, T::schema()));
+        self
+    }
+
+    fn request(mut self, body: Body) -> Self {
+        self.request.push(body);
+        self
+    }
+
+    fn responds(mut self, status: u16, body: Body) -> Self {
+        self.response = (status, Some(body));
+        self
+    }
+
+    fn download(mut self) -> Self {
+        self.download = true;
+        self.query::<bool>("download")
+    }
+
+    fn empty(mut self, status: u16) -> Self {
+        self.response = (status, None);
+        self
+    }
+}
+
+fn password() -> Value {
+    json!({
+        "type": "object",
+        "properties": { "password": { "type": "string" }, "length": { "type": "integer" } },
+        "required": ["password", "length"],
+    })
+}
+
+/// Everything the server routes, in the order /api/info lists it
+pub fn endpoints() -> Vec<Endpoint> {
+    vec![
+        Endpoint::new("POST", "/api/personal/generate", "Generate wordlist from profile, a page at a time")
+            .query::<usize>("limit")
+            .query::<usize>("offset")
+            .query::<String>("cursor")
+            .download()
+            .request(Body::of::<Profile>())
+            .responds(200, Body::of::<PageResponse>()),
+        Endpoint::new("POST", "/api/personal/stream", "Stream the wordlist as NDJSON")
+            .request(Body::of::<Profile>())
+            .responds(200, Body {
+                media: "application/x-ndjson",
+                schema: json!({ "type": "object", "properties": { "candidate": { "type": "string" } } }),
+            }),
+        Endpoint::new("POST", "/api/personal/check", "Check if password exists")
+            .request(Body::of::<CheckRequest>())
+            .responds(200, Body::of::<CheckResponse>()),
+        Endpoint::new("GET", "/api/pwned", "Breach count from Have I Been Pwned (password or sha1)")
+            .query::<String>("password")
+            .query::<String>("sha1")
+            .responds(200, Body::of::<PwnedResponse>()),
+        Endpoint::new("POST", "/api/memorable/generate", "Generate memorable passwords with config")
+            .download()
+            .request(Body::of::<MemorableRequest>())
+            .responds(200, Body::of::<MemorableResponse>()),
+        Endpoint::new("GET", "/api/memorable", "Quick memorable password (default settings)")
+            .responds(200, Body::json(password())),
+        Endpoint::new("POST", "/api/mnemonic/generate", "Initial-letter password from a sentence")
+            .request(Body::json(json!({
+                "allOf": [
+                    MnemonicConfig::schema(),
+                    { "type": "object", "properties": { "sentence": { "type": "string" } }, "required": ["sentence"] },
+                ],
+            })))
+            .responds(200, Body::json(json!({
+                "allOf": [password(), { "type": "object", "properties": { "variants": Vec::<String>::schema() } }],
+            }))),
+        Endpoint::new("POST", "/api/jobs", "Queue a personal, mask or Markov generation")
+            .request(Body::json(job_request()))
+            .responds(202, Body::of::<JobStatus>()),
+        Endpoint::new("GET", "/api/jobs/{id}", "Job status and progress")
+            .responds(200, Body::of::<JobStatus>()),
+        Endpoint::new("GET", "/api/jobs/{id}/result", "Download a finished job's wordlist")
+            .download()
+            .responds(200, Body::text("text/plain")),
+        Endpoint::new("DELETE", "/api/jobs/{id}", "Cancel a job and drop its result")
+            .empty(204),
+        Endpoint::new("GET", "/api/coordinator", "Progress of the distributed run (servers started with --coordinator)")
+            .responds(200, Body::of::<CoordinatorStatus>()),
+        Endpoint::new("GET", "/api/coordinator/task", "The mask or Markov run workers generate units of")
+            .responds(200, Body::json(coordinator_task())),
+        Endpoint::new("POST", "/api/coordinator/claim", "Lease the next work unit; 204 while all are out, 410 once done")
+            .request(Body::of::<ClaimRequest>())
+            .responds(200, Body::of::<Unit>()),
+        Endpoint::new("PUT", "/api/coordinator/units/{lease}", "Return a unit's candidates, one per line")
+            .request(Body::text("text/plain"))
+            .empty(204),
+        Endpoint::new("GET", "/api/ws/generate", "WebSocket: stream a job request's candidates and progress")
+            .query::<String>("request")
+            .empty(101),
+        Endpoint::new("POST", "/api/markov/train", "Train a Markov model on an uploaded corpus")
+            .query::<usize>("order")
+            .request(Body::text("text/plain"))
+            .request(Body {
+                media: "multipart/form-data",
+                schema: json!({
+                    "type": "object",
+                    "properties": { "corpus": { "type": "string", "format": "binary" } },
+                    "required": ["corpus"],
+                }),
+            })
+            .responds(201, Body::of::<markov::TrainResponse>()),
+        Endpoint::new("POST", "/api/markov/generate", "Sample candidates from a trained model")
+            .download()
+            .request(Body::of::<markov::GenerateRequest>())
+            .responds(200, Body::of::<GenerateResponse>()),
+        Endpoint::new("POST", "/api/markov/score", "Probability of each word under a trained model")
+            .request(Body::of::<markov::ScoreRequest>())
+            .responds(200, Body::json(json!({
+                "type": "object",
+                "properties": { "scores": Vec::<markov::Score>::schema() },
+                "required": ["scores"],
+            }))),
+        Endpoint::new("GET", "/api/openapi.json", "This API as an OpenAPI 3.1 document")
+            .responds(200, Body::json(json!({ "type": "object" }))),
+        Endpoint::new("GET", "/api/health", "Health check")
+            .responds(200, Body::json(json!({ "type": "object" }))),
+        Endpoint::new("GET", "/api/health/live", "Liveness probe: the process is answering")
+            .responds(200, Body::json(json!({ "type": "object" }))),
+        Endpoint::new("GET", "/api/health/ready", "Readiness probe: 503 once shutdown has begun")
+            .responds(200, Body::json(json!({ "type": "object" }))),
+        Endpoint::new("GET", "/api/info", "API info and available endpoints")
+            .responds(200, Body::json(json!({ "type": "object" }))),
+    ]
+}
+
+/// `JobRequest`, one object per `kind`
+fn job_request() -> Value {
+    use super::jobs::JobRequest;
+    // Fails to compile once a kind is added without a schema here
+    let _ = |request: &JobRequest| match request {
+        JobRequest::Personal { profile: _ } => (),
+        JobRequest::Mask { mask: _ } => (),
+        JobRequest::Markov { model: _, corpus: _, order: _, count: _, min_len: _, max_len: _, seed: _ } => (),
+    };
+    let kind = |name: &str, properties: Value, required: &[&str]| {
+        let mut properties = properties;
+        properties["kind"] = json!({ "const": name });
+        let required: Vec<&str> = ["kind"].iter().chain(required).copied().collect();
+        json!({ "type": "object", "properties": properties, "required": required })
+    };
+    json!({
+        "oneOf": [
+            kind("personal", json!({ "profile": Profile::schema() }), &["profile"]),
+            kind("mask", json!({ "mask": String::schema() }), &["mask"]),
+            kind("markov", json!({
+                "model": { "type": "object", "description": "A model saved by `jigsaw --train`" },
+                "corpus": Vec::<String>::schema(),
+                "order": usize::schema(),
+                "count": usize::schema(),
+                "min_len": usize::schema(),
+                "max_len": usize::schema(),
+                "seed": Option::<u64>::schema(),
+            }), &[]),
+        ],
+        "discriminator": { "propertyName": "kind" },
+    })
+}
+
+/// `coordinator::Task`, one object per `kind`
+fn coordinator_task() -> Value {
+    use super::coordinator::Task;
+    // Fails to compile once a kind is added without a schema here
+    let _ = |task: &Task| match task {
+        Task::Mask { mask: _, charsets: _, frequency_order: _ } => (),
+        Task::Markov { model: _, count: _, min_len: _, max_len: _, seed: _ } => (),
+    };
+    json!({
+        "oneOf": [
+            {
+                "type": "object",
+                "properties": {
+                    "kind": { "const": "mask" },
+                    "mask": String::schema(),
+                    "charsets": {
+                        "type": "array",
+                        "description": "The bytes ?1–?4 stand for, null where unset",
+                        "items": { "type": ["array", "null"], "items": { "type": "integer", "minimum": 0, "maximum": 255 } },
+                        "maxItems": 4,
+                    },
+                    "frequency_order": bool::schema(),
+                },
+                "required": ["kind", "mask"],
+            },
+            {
+                "type": "object",
+                "properties": {
+                    "kind": { "const": "markov" },
+                    "model": { "type": "object", "description": "A model saved by `jigsaw --train`" },
+                    "count": u64::schema(),
+                    "min_len": usize::schema(),
+                    "max_len": usize::schema(),
+                    "seed": u64::schema(),
+                },
+                "required": ["kind", "model", "count", "min_len", "max_len", "seed"],
+            },
+        ],
+        "discriminator": { "propertyName": "kind" },
+    })
+}
+
+/// The OpenAPI 3.1 document for `endpoints()`
+pub fn document() -> Value {
+    let mut paths = Map::new();
+    for endpoint in endpoints() {
+        let mut parameters: Vec<Value> = endpoint.path.split('/')
+            .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+            .map(|name| json!({ "name": name, "in": "path", "required": true, "schema": { "type": "string" } }))
+            .collect();
+        parameters.extend(endpoint.query.iter()
+            .map(|(name, schema)| json!({ "name": name, "in": "query", "schema": schema })));
+
+        let (status, body) = &endpoint.response;
+        let mut success = json!({ "description": endpoint.summary });
+        if let Some(body) = body {
+            success["content"] = json!({ body.media: { "schema": body.schema } });
+        }
+        if endpoint.download {
+            success["content"]["application/gzip"] = json!({ "schema": { "type": "string", "format": "binary" } });
+        }
+        let problem = |description: &str| json!({
+            "description": description,
+            "content": { problem::MEDIA: { "schema": { "$ref": "#/components/schemas/Problem" } } },
+        });
+        let mut responses = json!({
+            status.to_string(): success,
+            "401": problem("Missing or unknown API key"),
+            "429": problem("Rate limit or candidate quota reached"),
+        });
+        if !endpoint.request.is_empty() || !parameters.is_empty() {
+            responses["400"] = problem("Invalid request; `errors` names each bad field");
+        }
+        if endpoint.request.iter().any(|body| body.media == "application/json") {
+            responses["413"] = problem("Request body too large");
+        }
+
+        let mut operation = json!({ "summary": endpoint.summary, "responses": responses });
+        if !parameters.is_empty() {
+            operation["parameters"] = Value::Array(parameters);
+        }
+        if !endpoint.request.is_empty() {
+            let content: Map<String, Value> = endpoint.request.iter()
+                .map(|body| (body.media.to_string(), json!({ "schema": body.schema })))
+                .collect();
+            operation["requestBody"] = json!({ "required": true, "content": content });
+        }
+        if endpoint.path.starts_with("/api/health") {
+            operation["security"] = json!([]);
+        }
+
+        let item = paths.entry(endpoint.path).or_insert_with(|| json!({}));
+        item[endpoint.method.to_ascii_lowercase()] = operation;
+    }
+
+    json!({
+        "openapi": "3.1.0",
+        "info": {
+            "title": "JIGSAW",
+            "description": "Intelligent Password Toolkit",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "paths": paths,
+        "components": {
+            "schemas": components(),
+            "securitySchemes": {
+                "apiKey": { "type": "apiKey", "in": "header", "name": "X-API-Key" },
+                "bearer": { "type": "http", "scheme": "bearer" },
+            },
+        },
+        // Keys are only enforced when the server is started with some
+        "security": [{}, { "apiKey": [] }, { "bearer": [] }],
+    })
+}
+
+#[get("/api/openapi.json")]
+async fn openapi() -> impl Responder {
+    HttpResponse::Ok().json(document())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_document_references_resolve() {
+        let document = document();
+        let text = document.to_string();
+        let schemas = document["components"]["schemas"].as_object().unwrap();
+        for reference in text.split("\"#/components/schemas/").skip(1) {
+            let name = &reference[..reference.find('"').unwrap()];
+            assert!(schemas.contains_key(name), "dangling reference to {}", name);
+        }
+        let paths = document["paths"].as_object().unwrap();
+        assert_eq!(paths.values().map(|item| item.as_object().unwrap().len()).sum::<usize>(), endpoints().len());
+        assert_eq!(schemas["Category"]["enum"][0], "keyboard_walks");
+        assert_eq!(document["paths"]["/api/jobs/{id}"]["delete"]["parameters"][0]["name"], "id");
+        assert!(document["paths"]["/api/markov/generate"]["post"]["responses"]["200"]["content"]["application/gzip"].is_object());
+    }
+
+    #[actix_web::test]
+    async fn test_every_endpoint_is_routed() {
+        use actix_web::{http::{Method, StatusCode}, test, web, App, HttpResponse};
+        // Requests `routes` doesn't match land here rather than on problem::no_route
+        let app = test::init_service(App::new()
+            .configure(super::super::server::routes)
+            .default_service(web::to(|| async { HttpResponse::ImATeapot().finish() }))).await;
+        for endpoint in endpoints() {
+            let path: Vec<&str> = endpoint.path.split('/').map(|part| if part.starts_with('{') { "0" } else { part }).collect();
+            let request = test::TestRequest::default()
+                .method(Method::from_bytes(endpoint.method.as_bytes()).unwrap())
+                .uri(&path.join("/"))
+                .to_request();
+            let response = test::call_service(&app, request).await;
+            assert_ne!(response.status(), StatusCode::IM_A_TEAPOT, "{} {} is documented but not routed", endpoint.method, endpoint.path);
+        }
+    }
+
+    #[test]
+    fn test_every_handler_is_documented() {
+        // Every route is a handler attribute somewhere in src/api
+        let documented: std::collections::HashSet<(&str, &str)> =
+            endpoints().iter().map(|endpoint| (endpoint.method, endpoint.path)).collect();
+        let mut handlers = 0;
+        for file in std::fs::read_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/src/api")).unwrap() {
+            let source = std::fs::read_to_string(file.unwrap().path()).unwrap();
+            for method in ["GET", "POST", "PUT", "PATCH", "DELETE"] {
+                let attribute = format!("#[{}(\"", method.to_lowercase());
+                for rest in source.split(attribute.as_str()).skip(1) {
+                    let path = &rest[..rest.find('"').unwrap()];
+                    assert!(documented.contains(&(method, path)), "{} {} is routed but not documented", method, path);
+                    handlers += 1;
+                }
+            }
+        }
+        assert_eq!(handlers, documented.len());
+    }
+}
@@ -0,0 +1,175 @@
+use actix_web::error::{JsonPayloadError, QueryPayloadError};
+use actix_web::http::StatusCode;
+use actix_web::{HttpRequest, HttpResponse};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::fmt::Display;
+
+// ═══════════════════════════════════════════════════════════════
+// PROBLEMS
+// ═══════════════════════════════════════════════════════════════
+//
+// Every error the API returns is an RFC 7807 problem document served as
+// application/problem+json: `type`, `title`, `status` and a `detail`
+// sentence, plus `errors` naming each bad field when a request fails
+// validation. Handlers gather field errors in an `Invalid` and report
+// them together rather than stopping at the first.
+
+pub const MEDIA: &str = "application/problem+json";
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Problem {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldError>,
+    /// Extension members, such as `retry_after_secs`
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Problem {
+    pub fn new(status: StatusCode, detail: impl Display) -> Self {
+        Self {
+            kind: "about:blank".to_string(),
+            title: status.canonical_reason().unwrap_or("Error").to_string(),
+            status: status.as_u16(),
+            detail: detail.to_string(),
+            errors: Vec::new(),
+            extra: Map::new(),
+        }
+    }
+
+    /// Add an extension member
+    pub fn with(mut self, name: &str, value: impl Serialize) -> Self {
+        self.extra.insert(name.to_string(), serde_json::to_value(value).unwrap_or(Value::Null));
+        self
+    }
+
+    pub fn response(self) -> HttpResponse {
+        let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        HttpResponse::build(status).content_type(MEDIA).json(self)
+    }
+}
+
+pub fn bad_request(detail: impl Display) -> HttpResponse {
+    Problem::new(StatusCode::BAD_REQUEST, detail).response()
+}
+
+pub fn not_found(detail: impl Display) -> HttpResponse {
+    Problem::new(StatusCode::NOT_FOUND, detail).response()
+}
+
+pub fn internal(detail: impl Display) -> HttpResponse {
+    Problem::new(StatusCode::INTERNAL_SERVER_ERROR, detail).response()
+}
+
+/// Field errors found so far in one request
+#[derive(Default, Debug)]
+pub struct Invalid(Vec<FieldError>);
+
+impl Invalid {
+    pub fn add(&mut self, field: &str, message: impl Display) {
+        self.0.push(FieldError { field: field.to_string(), message: message.to_string() });
+    }
+
+    /// Record `message` against `field` unless `ok`
+    pub fn check(&mut self, ok: bool, field: &str, message: impl Display) {
+        if !ok {
+            self.add(field, message);
+        }
+    }
+
+    /// `value` isn't one of `expected`
+    pub fn unknown(&mut self, field: &str, value: &str, expected: &[&str]) {
+        self.add(field, format!("unknown value {:?}; expected one of {}", value, expected.join(", ")));
+    }
+
+    /// A single-field problem response
+    pub fn one(field: &str, message: impl Display) -> HttpResponse {
+        let mut invalid = Self::default();
+        invalid.add(field, message);
+        invalid.into_response()
+    }
+
+    /// Ok when nothing was recorded, else the 400 problem to send
+    pub fn finish(self) -> Result<(), HttpResponse> {
+        if self.0.is_empty() { Ok(()) } else { Err(self.into_response()) }
+    }
+
+    fn into_response(self) -> HttpResponse {
+        let detail = match self.0.as_slice() {
+            [only] => format!("{}: {}", only.field, only.message),
+            errors => format!("{} fields are invalid", errors.len()),
+        };
+        let mut problem = Problem::new(StatusCode::BAD_REQUEST, detail);
+        problem.errors = self.0;
+        problem.response()
+    }
+}
+
+/// Problems for JSON bodies that are too big or don't deserialize
+pub fn json_error(error: JsonPayloadError, _req: &HttpRequest) -> actix_web::Error {
+    let status = match &error {
+        JsonPayloadError::Overflow { .. } | JsonPayloadError::OverflowKnownLength { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+        JsonPayloadError::ContentType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+        _ => StatusCode::BAD_REQUEST,
+    };
+    let detail = error.to_string();
+    actix_web::error::InternalError::from_response(error, Problem::new(status, detail).response()).into()
+}
+
+/// Problems for query strings that don't deserialize
+pub fn query_error(error: QueryPayloadError, _req: &HttpRequest) -> actix_web::Error {
+    let detail = error.to_string();
+    actix_web::error::InternalError::from_response(error, bad_request(detail)).into()
+}
+
+/// 404 for paths the server doesn't route
+pub async fn no_route(req: HttpRequest) -> HttpResponse {
+    not_found(format!("no endpoint {} {}", req.method(), req.path()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::body::MessageBody;
+
+    fn body(response: HttpResponse) -> Value {
+        serde_json::from_slice(&response.into_body().try_into_bytes().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_invalid_reports_every_field() {
+        let mut invalid = Invalid::default();
+        invalid.check(true, "count", "fine");
+        assert!(Invalid::default().finish().is_ok());
+        invalid.check(false, "word_count", "must be between 2 and 8");
+        invalid.unknown("style", "epic", &["classic", "story"]);
+        let response = invalid.finish().unwrap_err();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(response.headers().get("content-type").unwrap(), MEDIA);
+        let problem = body(response);
+        assert_eq!(problem["status"], 400);
+        assert_eq!(problem["detail"], "2 fields are invalid");
+        assert_eq!(problem["errors"][0]["field"], "word_count");
+        assert_eq!(problem["errors"][1]["message"], "unknown value \"epic\"; expected one of classic, story");
+    }
+
+    #[test]
+    fn test_extension_members() {
+        let problem = body(Problem::new(StatusCode::TOO_MANY_REQUESTS, "slow down").with("retry_after_secs", 30).response());
+        assert_eq!(problem["title"], "Too Many Requests");
+        assert_eq!(problem["retry_after_secs"], 30);
+        assert!(problem.get("errors").is_none());
+    }
+}
@@ -1,8 +1,26 @@
-use actix_web::{post, get, web, App, HttpServer, HttpResponse, Responder};
+use actix_web::{middleware, post, get, web, App, HttpRequest, HttpServer, HttpResponse, Responder};
+use actix_web::body::{BodySize, MessageBody};
+use actix_web::http::StatusCode;
 use actix_cors::Cors;
 use serde::{Deserialize, Serialize};
-use crate::engine::personal::Profile;
-use crate::engine::memorable::{self, MemorableConfig, MemorableStyle, CaseStyle, Position};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::task::{Context, Poll};
+use tokio::sync::mpsc;
+use crate::engine::personal::{Dedup, Profile};
+use super::auth::{self, Auth};
+use super::coordinator::{self, Coordinator};
+use super::download::{self, DownloadQuery};
+use super::jobs::{self, Jobs};
+use super::markov::{self, Models};
+use super::openapi;
+use super::problem::{self, Invalid};
+use super::tls;
+use super::ws;
+use crate::cli::args::ServerArgs;
+use crate::io;
+use crate::engine::mnemonic::{self, MnemonicConfig};
+use crate::engine::memorable::{self, MemorableConfig, MemorableStyle, CaseStyle, Position, WordPool, Language, NumberForm};
 
 // ═══════════════════════════════════════════════════════════════
 // REQUEST / RESPONSE TYPES
@@ -21,6 +39,22 @@ pub struct CheckResponse {
     pub time_taken_ms: u128,
 }
 
+/// A password to look up, or its SHA-1 so the plaintext never reaches the server
+#[derive(Deserialize)]
+pub struct PwnedQuery {
+    #[serde(default)]
+    pub password: Option<String>,
+    #[serde(default)]
+    pub sha1: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct PwnedResponse {
+    pub pwned: bool,
+    /// Times the password appears in known breaches
+    pub count: u64,
+}
+
 #[derive(Serialize)]
 pub struct GenerateResponse {
     pub candidates: Vec<String>,
@@ -28,6 +62,29 @@ pub struct GenerateResponse {
     pub time_taken_ms: u128,
 }
 
+/// Which page of candidates to return; `cursor` is a `next_cursor` from
+/// an earlier page and takes precedence over `offset`
+#[derive(Deserialize)]
+pub struct PageQuery {
+    #[serde(default)]
+    pub limit: Option<usize>,
+    #[serde(default)]
+    pub offset: Option<usize>,
+    #[serde(default)]
+    pub cursor: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct PageResponse {
+    pub candidates: Vec<String>,
+    /// Unique candidates in the whole list, not just this page
+    pub total: usize,
+    pub offset: usize,
+    /// Pass as `cursor` for the next page; absent on the last one
+    pub next_cursor: Option<String>,
+    pub time_taken_ms: u128,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct MemorableRequest {
     #[serde(default = "default_word_count")]
@@ -54,6 +111,28 @@ pub struct MemorableRequest {
     pub min_length: usize,
     #[serde(default = "default_max_len")]
     pub max_length: usize,
+    #[serde(default)]
+    pub word_pool: String,        // "builtin", "eff-long", "eff-short"
+    #[serde(default)]
+    pub language: String,         // "en", "de", "es", "fr", "it", "pt"
+    #[serde(default)]
+    pub template: Option<String>, // "{adj}-{noun}{num2}{special}"
+    #[serde(default)]
+    pub min_entropy: Option<f64>,
+    #[serde(default)]
+    pub policy: Option<String>,   // "max=16,upper,digit,symbol,no-spaces"
+    #[serde(default)]
+    pub leet_probability: f64,    // 0.0 (off) to 1.0
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub specials: Option<String>, // "!@#_"
+    #[serde(default)]
+    pub min_word_len: Option<usize>,
+    #[serde(default)]
+    pub max_word_len: Option<usize>,
+    #[serde(default)]
+    pub number_form: String,      // "digits", "words", "both"
 }
 
 fn default_word_count() -> usize { 3 }
@@ -88,55 +167,272 @@ pub struct MemorableConfigSummary {
 // ENDPOINTS
 // ═══════════════════════════════════════════════════════════════
 
+/// Page size of /api/personal/generate when no `limit` is given
+pub const DEFAULT_PAGE: usize = 50_000;
+
+/// Per-request limits from the server options
+#[derive(Clone, Copy)]
+pub struct Limits {
+    /// Most candidates one response holds
+    pub max_candidates: usize,
+}
+
+/// Most passwords one /api/memorable/generate request returns
+const MAX_MEMORABLE: usize = 100;
+
+/// Duplicate removal for whole lists sent as they're made (streams,
+/// downloads, jobs): a Bloom filter sized from the profile's estimate, so
+/// memory stays bounded, at the cost of about this share of unique
+/// candidates dropped as false duplicates
+pub const STREAM_DEDUP: Dedup = Dedup::Bloom { fp_rate: 0.001 };
+
+/// Field error for a profile with nothing to build candidates from
+pub const EMPTY_PROFILE: &str = "has no values in any field";
+
 #[post("/api/personal/generate")]
-async fn generate_personal(profile: web::Json<Profile>) -> impl Responder {
+async fn generate_personal(
+    req: HttpRequest,
+    profile: web::Json<Profile>,
+    page: web::Query<PageQuery>,
+    download: web::Query<DownloadQuery>,
+    limits: web::Data<Limits>,
+) -> impl Responder {
     let start = std::time::Instant::now();
-    let candidates = profile.generate();
-    let strings: Vec<String> = candidates.iter()
-        .map(|b| String::from_utf8_lossy(b).to_string())
-        .collect();
-    let total = strings.len();
-    HttpResponse::Ok().json(GenerateResponse {
-        candidates: strings,
+    if profile.is_empty() {
+        return Invalid::one("profile", EMPTY_PROFILE);
+    }
+    // A download is the whole list, not a page
+    if download::wanted(&req, &download) {
+        let profile = profile.into_inner();
+        let expected = profile.estimate().candidates;
+        let response = download::attachment(&download::stem("personal"), move |emit| {
+            profile.for_each_unique(STREAM_DEDUP, |candidate| emit(candidate.as_bytes()));
+        });
+        return auth::charge(response, expected);
+    }
+    let mut invalid = Invalid::default();
+    let offset = match &page.cursor {
+        Some(cursor) => cursor.parse::<usize>().unwrap_or_else(|_| {
+            invalid.add("cursor", "not a cursor from an earlier page");
+            0
+        }),
+        None => page.offset.unwrap_or(0),
+    };
+    let max = limits.max_candidates;
+    let limit = page.limit.unwrap_or(DEFAULT_PAGE.min(max));
+    invalid.check((1..=max).contains(&limit), "limit", format!("must be between 1 and {}", max));
+    if let Err(response) = invalid.finish() {
+        return response;
+    }
+
+    // Generation order is stable for a profile, so pages line up across
+    // requests. Every page walks the whole list, so keep it off the async worker.
+    let walked = web::block(move || {
+        let mut candidates = Vec::new();
+        let mut total = 0;
+        profile.for_each_unique(Dedup::Exact, |candidate| {
+            if total >= offset && candidates.len() < limit {
+                candidates.push(candidate);
+            }
+            total += 1;
+            false
+        });
+        (candidates, total)
+    }).await;
+    let (candidates, total) = match walked {
+        Ok(walked) => walked,
+        Err(e) => return problem::internal(e),
+    };
+    let end = offset + candidates.len();
+    let served = candidates.len() as u64;
+    let response = HttpResponse::Ok().json(PageResponse {
+        candidates,
         total,
+        offset,
+        next_cursor: (end < total).then(|| end.to_string()),
         time_taken_ms: start.elapsed().as_millis(),
-    })
+    });
+    auth::charge(response, served)
+}
+
+/// Bytes of NDJSON gathered before a chunk is sent
+const STREAM_CHUNK: usize = 64 * 1024;
+/// Chunks buffered ahead of a slow client before generation waits
+const STREAM_BACKLOG: usize = 16;
+
+/// A chunked body fed by a producer thread. Dropping it, as actix does
+/// when the client goes away, closes the channel and stops the producer.
+pub struct ChannelBody {
+    chunks: mpsc::Receiver<web::Bytes>,
+}
+
+impl ChannelBody {
+    pub fn new(chunks: mpsc::Receiver<web::Bytes>) -> Self {
+        Self { chunks }
+    }
+}
+
+impl MessageBody for ChannelBody {
+    type Error = std::convert::Infallible;
+
+    fn size(&self) -> BodySize {
+        BodySize::Stream
+    }
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<web::Bytes, Self::Error>>> {
+        self.chunks.poll_recv(cx).map(|chunk| chunk.map(Ok))
+    }
+}
+
+/// Same candidates as /api/personal/generate, one `{"candidate": ...}`
+/// line each, sent as they are made so the list never sits in memory
+#[post("/api/personal/stream")]
+async fn stream_personal(profile: web::Json<Profile>) -> impl Responder {
+    if profile.is_empty() {
+        return Invalid::one("profile", EMPTY_PROFILE);
+    }
+    let profile = profile.into_inner();
+    // Charged up front, at the estimate, since the count isn't known yet
+    let expected = profile.estimate().candidates;
+    let (sender, chunks) = mpsc::channel(STREAM_BACKLOG);
+    std::thread::spawn(move || {
+        let mut buffer = Vec::with_capacity(STREAM_CHUNK);
+        let mut closed = false;
+        profile.for_each_unique(STREAM_DEDUP, |candidate| {
+            serde_json::to_writer(&mut buffer, &serde_json::json!({ "candidate": candidate }))
+                .expect("writing JSON to memory");
+            buffer.push(b'\n');
+            if buffer.len() >= STREAM_CHUNK {
+                closed = sender.blocking_send(std::mem::take(&mut buffer).into()).is_err();
+            }
+            closed
+        });
+        if !closed && !buffer.is_empty() {
+            let _ = sender.blocking_send(buffer.into());
+        }
+    });
+    let response = HttpResponse::Ok()
+        .content_type("application/x-ndjson")
+        .body(ChannelBody::new(chunks));
+    auth::charge(response, expected)
 }
 
 #[post("/api/personal/check")]
 async fn check_password(data: web::Json<CheckRequest>) -> impl Responder {
     let start = std::time::Instant::now();
+    if data.profile.is_empty() {
+        return Invalid::one("profile", EMPTY_PROFILE);
+    }
     let found = data.profile.check_password(&data.password);
     let candidates_count = data.profile.generate().len();
-    HttpResponse::Ok().json(CheckResponse {
+    let response = HttpResponse::Ok().json(CheckResponse {
         found,
         total_candidates: candidates_count,
         time_taken_ms: start.elapsed().as_millis(),
-    })
+    });
+    auth::charge(response, candidates_count as u64)
+}
+
+#[get("/api/pwned")]
+async fn pwned(query: web::Query<PwnedQuery>) -> impl Responder {
+    let query = query.into_inner();
+    let sha1 = match (query.sha1, query.password) {
+        (Some(sha1), _) => sha1,
+        (None, Some(password)) => io::pwned::sha1_hex(&password),
+        (None, None) => return Invalid::one("password", "give password or sha1"),
+    };
+    if sha1.len() != 40 || !sha1.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Invalid::one("sha1", "must be 40 hex digits");
+    }
+    match web::block(move || io::pwned::Client::new().hash_count(&sha1)).await {
+        Ok(Ok(count)) => HttpResponse::Ok().json(PwnedResponse { pwned: count > 0, count }),
+        Ok(Err(e)) => problem::Problem::new(StatusCode::BAD_GATEWAY, format!("{:#}", e)).response(),
+        Err(e) => problem::internal(e),
+    }
 }
 
 #[post("/api/memorable/generate")]
-async fn generate_memorable(data: web::Json<MemorableRequest>) -> impl Responder {
+async fn generate_memorable(req: HttpRequest, data: web::Json<MemorableRequest>, download: web::Query<DownloadQuery>) -> impl Responder {
     let start = std::time::Instant::now();
 
-    let config = MemorableConfig {
-        word_count: data.word_count.clamp(2, 8),
+    let mut invalid = Invalid::default();
+    if let Some(template) = &data.template {
+        if let Err(e) = memorable::compile_template(template) {
+            invalid.add("template", e);
+        }
+    }
+    invalid.check(!data.specials.as_ref().is_some_and(|set| set.trim().is_empty()),
+        "specials", "needs at least one character");
+    invalid.check((2..=8).contains(&data.word_count), "word_count", "must be between 2 and 8");
+    invalid.check((1..=MAX_MEMORABLE).contains(&data.count), "count", format!("must be between 1 and {}", MAX_MEMORABLE));
+    invalid.check(data.min_length <= data.max_length, "min_length", "must not exceed max_length");
+    invalid.check((0.0..=1.0).contains(&data.leet_probability), "leet_probability", "must be between 0 and 1");
+
+    let mut config = MemorableConfig {
+        word_count: data.word_count,
         separator: data.separator.clone(),
-        case_style: parse_case_style(&data.case_style),
+        case_style: parse_case_style(&data.case_style, &mut invalid),
         include_number: data.include_number,
-        number_position: parse_position(&data.number_position),
+        number_position: parse_position("number_position", &data.number_position, &mut invalid),
         number_max: data.number_max,
         include_special: data.include_special,
-        special_position: parse_position(&data.special_position),
-        style: parse_style(&data.style),
-        count: data.count.clamp(1, 100),
+        special_position: parse_position("special_position", &data.special_position, &mut invalid),
+        style: parse_style(&data.style, &mut invalid),
+        count: data.count,
         min_length: data.min_length,
         max_length: data.max_length,
+        word_pool: parse_word_pool(&data.word_pool, &mut invalid),
+        language: parse_language(&data.language, &mut invalid),
+        template: data.template.clone(),
+        leet_probability: data.leet_probability,
+        seed: data.seed,
+        specials: data.specials.clone(),
+        min_word_len: data.min_word_len,
+        max_word_len: data.max_word_len,
+        number_form: parse_number_form(&data.number_form, &mut invalid),
+        ..Default::default()
     };
+    if let Err(e) = memorable::check_word_lengths(&config) {
+        invalid.add("min_word_len", e);
+    }
+    // The checks below build on a config that is valid so far
+    if let Err(response) = invalid.finish() {
+        return response;
+    }
+
+    if let Some(spec) = &data.policy {
+        let applied = memorable::Policy::parse(spec)
+            .and_then(|policy| memorable::apply_policy(&mut config, policy));
+        if let Err(e) = applied {
+            return Invalid::one("policy", e);
+        }
+    }
 
-    let passwords = memorable::generate_batch(&config);
+    if let Some(bits) = data.min_entropy {
+        let met = memorable::meet_min_entropy(&mut config, bits)
+            .and_then(|_| memorable::check_policy(&config));
+        if let Err(e) = met {
+            return Invalid::one("min_entropy", e);
+        }
+    }
+
+    let passwords = match memorable::generate_batch(&config) {
+        Ok(passwords) => passwords,
+        Err(e) => return problem::bad_request(e),
+    };
 
-    HttpResponse::Ok().json(MemorableResponse {
+    let count = passwords.len() as u64;
+    if download::wanted(&req, &download) {
+        let response = download::attachment(&download::stem("memorable"), move |emit| {
+            for password in &passwords {
+                if emit(password.as_bytes()) {
+                    break;
+                }
+            }
+        });
+        return auth::charge(response, count);
+    }
+    let response = HttpResponse::Ok().json(MemorableResponse {
         count: passwords.len(),
         passwords,
         config_used: MemorableConfigSummary {
@@ -148,7 +444,28 @@ async fn generate_memorable(data: web::Json<MemorableRequest>) -> impl Responder
             include_special: config.include_special,
         },
         time_taken_ms: start.elapsed().as_millis(),
-    })
+    });
+    auth::charge(response, count)
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct MnemonicRequest {
+    pub sentence: String,
+    #[serde(flatten)]
+    pub config: MnemonicConfig,
+}
+
+#[post("/api/mnemonic/generate")]
+async fn generate_mnemonic(data: web::Json<MnemonicRequest>) -> impl Responder {
+    let password = mnemonic::acronym(&data.sentence, &data.config);
+    if password.is_empty() {
+        return Invalid::one("sentence", "no words found");
+    }
+    HttpResponse::Ok().json(serde_json::json!({
+        "length": password.len(),
+        "password": password,
+        "variants": mnemonic::variants(&data.sentence, &data.config),
+    }))
 }
 
 #[get("/api/memorable")]
@@ -160,6 +477,10 @@ async fn generate_memorable_get() -> impl Responder {
     }))
 }
 
+/// Cleared once shutdown begins, so /api/health/ready turns traffic away
+/// while open requests drain
+pub struct Ready(AtomicBool);
+
 #[get("/api/health")]
 async fn health() -> impl Responder {
     HttpResponse::Ok().json(serde_json::json!({
@@ -169,20 +490,60 @@ async fn health() -> impl Responder {
     }))
 }
 
+/// The process is up and answering
+#[get("/api/health/live")]
+async fn health_live() -> impl Responder {
+    HttpResponse::Ok().json(serde_json::json!({ "status": "live" }))
+}
+
+/// The server takes new work; 503 once it is shutting down
+#[get("/api/health/ready")]
+async fn health_ready(ready: web::Data<Ready>) -> impl Responder {
+    if !ready.0.load(Ordering::SeqCst) {
+        return problem::Problem::new(StatusCode::SERVICE_UNAVAILABLE, "shutting down").response();
+    }
+    HttpResponse::Ok().json(serde_json::json!({ "status": "ready" }))
+}
+
+/// Every endpoint the server answers; `openapi::endpoints` documents each
+pub fn routes(config: &mut web::ServiceConfig) {
+    config
+        .service(generate_personal)
+        .service(stream_personal)
+        .service(check_password)
+        .service(pwned)
+        .service(generate_memorable)
+        .service(generate_memorable_get)
+        .service(generate_mnemonic)
+        .service(jobs::create_job)
+        .service(jobs::job_status)
+        .service(jobs::job_result)
+        .service(jobs::cancel_job)
+        .service(coordinator::coordinator_status)
+        .service(coordinator::coordinator_task)
+        .service(coordinator::claim_unit)
+        .service(coordinator::return_unit)
+        .service(markov::train)
+        .service(markov::generate)
+        .service(markov::score)
+        .service(ws::ws_generate)
+        .service(openapi::openapi)
+        .service(health)
+        .service(health_live)
+        .service(health_ready)
+        .service(info);
+}
+
 #[get("/api/info")]
 async fn info() -> impl Responder {
     HttpResponse::Ok().json(serde_json::json!({
         "name": "JIGSAW",
         "description": "Intelligent Password Toolkit",
         "version": env!("CARGO_PKG_VERSION"),
-        "endpoints": [
-            {"method": "POST", "path": "/api/personal/generate", "description": "Generate wordlist from profile"},
-            {"method": "POST", "path": "/api/personal/check", "description": "Check if password exists"},
-            {"method": "POST", "path": "/api/memorable/generate", "description": "Generate memorable passwords with config"},
-            {"method": "GET",  "path": "/api/memorable", "description": "Quick memorable password (default settings)"},
-            {"method": "GET",  "path": "/api/health", "description": "Health check"},
-            {"method": "GET",  "path": "/api/info", "description": "API info and available endpoints"},
-        ],
+        "openapi": "/api/openapi.json",
+        "endpoints": openapi::endpoints().iter()
+            .map(|e| serde_json::json!({ "method": e.method, "path": e.path, "description": e.summary }))
+            .collect::<Vec<_>>(),
     }))
 }
 
@@ -190,65 +551,327 @@ async fn info() -> impl Responder {
 // SERVER STARTUP
 // ═══════════════════════════════════════════════════════════════
 
-pub async fn run_server(port: u16) -> std::io::Result<()> {
+/// CORS for the --cors-origin list: any origin given `*`, else just those
+fn cors(origins: &[String]) -> Cors {
+    let cors = Cors::default().allow_any_method().allow_any_header().expose_any_header().max_age(3600);
+    if origins.iter().any(|origin| origin == "*") {
+        return cors.allow_any_origin();
+    }
+    origins.iter().fold(cors, |cors, origin| cors.allowed_origin(origin))
+}
+
+/// Origins look like `https://host[:port]`, which `Cors` insists on
+fn check_origins(origins: &[String]) -> std::io::Result<()> {
+    for origin in origins.iter().filter(|origin| *origin != "*") {
+        let valid = origin.parse::<actix_web::http::Uri>()
+            .is_ok_and(|uri| uri.scheme().is_some() && uri.host().is_some() && uri.path() == "/" && !origin.ends_with('/'));
+        if !valid {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput,
+                format!("--cors-origin {:?} is not an origin like https://example.com", origin)));
+        }
+    }
+    Ok(())
+}
+
+pub async fn run_server(args: &ServerArgs) -> std::io::Result<()> {
+    let port = args.port;
+    let auth = web::Data::new(Auth::new(args));
+    let tls = match (&args.tls_cert, &args.tls_key) {
+        (Some(cert), Some(key)) => Some(tls::config(cert, key)?),
+        _ => None,
+    };
+    let coordinator = match args.coordinator {
+        true => Some(web::Data::new(Coordinator::from_args(args).map_err(std::io::Error::other)?)),
+        false => None,
+    };
     println!();
     println!("  ╔═══════════════════════════════════════════╗");
     println!("  ║     JIGSAW API Server                      ║");
     println!("  ╚═══════════════════════════════════════════╝");
     println!();
-    println!("  Listening on: http://0.0.0.0:{}", port);
+    match &args.unix_socket {
+        Some(path) => println!("  Listening on: unix:{}", path.display()),
+        None => println!("  Listening on: {}://{}:{}", if tls.is_some() { "https" } else { "http" }, args.bind, port),
+    }
+    if auth.enabled() {
+        println!("  Auth:         API key required (X-API-Key or Authorization: Bearer)");
+    } else {
+        println!("  Auth:         none — set --api-key or {} before exposing this", auth::KEYS_VAR);
+    }
+    println!("  CORS origins: {}", args.cors_origins.join(", "));
+    println!("  Limits:       {} candidates per response, {} per job, {} byte bodies, jobs kept {}s, {} models",
+        args.max_candidates, args.max_job_candidates, args.max_body, args.job_retention, args.max_models);
+    if let Some(coordinator) = &coordinator {
+        let status = coordinator.status();
+        println!("  Coordinator:  {} run of {} candidates in {} units, joined into {}",
+            status.kind, status.keyspace, status.units, status.output);
+    }
     println!("  Endpoints:");
-    println!("    POST /api/personal/generate");
-    println!("    POST /api/personal/check");
-    println!("    POST /api/memorable/generate");
-    println!("    GET  /api/memorable");
-    println!("    GET  /api/health");
-    println!("    GET  /api/info");
+    for endpoint in openapi::endpoints() {
+        println!("    {:<6} {}", endpoint.method, endpoint.path);
+    }
     println!();
 
-    HttpServer::new(|| {
-        let cors = Cors::permissive();
-        App::new()
-            .wrap(cors)
-            .service(generate_personal)
-            .service(check_password)
-            .service(generate_memorable)
-            .service(generate_memorable_get)
-            .service(health)
-            .service(info)
-    })
-    .bind(("0.0.0.0", port))?
-    .run()
-    .await
+    check_origins(&args.cors_origins)?;
+    let retention = (args.job_retention > 0).then(|| std::time::Duration::from_secs(args.job_retention));
+    let jobs = web::Data::new(Jobs::start(args.job_workers, retention, args.max_job_candidates)?);
+    let limits = web::Data::new(Limits { max_candidates: args.max_candidates.max(1) });
+    let origins = args.cors_origins.clone();
+    let max_body = args.max_body;
+    let models = web::Data::new(Models::new(args.max_models));
+    let ready = web::Data::new(Ready(AtomicBool::new(true)));
+    let app_jobs = jobs.clone();
+    let app_ready = ready.clone();
+    let app_coordinator = coordinator.clone();
+    let server = HttpServer::new(move || {
+        let app = App::new();
+        // Without --coordinator the endpoints find no run and answer 404
+        let app = match &app_coordinator {
+            Some(coordinator) => app.app_data(coordinator.clone()),
+            None => app,
+        };
+        app
+            .wrap(middleware::from_fn(auth::check))
+            .wrap(cors(&origins))
+            .app_data(auth.clone())
+            .app_data(app_jobs.clone())
+            .app_data(models.clone())
+            .app_data(app_ready.clone())
+            .app_data(limits.clone())
+            .app_data(web::PayloadConfig::new(markov::MAX_CORPUS))
+            .app_data(web::JsonConfig::default().limit(max_body).error_handler(problem::json_error))
+            .app_data(web::QueryConfig::default().error_handler(problem::query_error))
+            .configure(routes)
+            .default_service(web::to(problem::no_route))
+    });
+
+    // Signals are ours, so readiness can drop before the listener closes
+    let server = server.disable_signals()
+        .shutdown_timeout(args.shutdown_timeout)
+        .client_request_timeout(std::time::Duration::from_secs(args.request_timeout));
+    let server = match args.workers {
+        0 => server,
+        workers => server.workers(workers),
+    };
+    #[cfg(not(unix))]
+    if args.unix_socket.is_some() {
+        return Err(std::io::Error::new(std::io::ErrorKind::Unsupported, "--unix-socket needs a Unix system"));
+    }
+    let server = match tls {
+        #[cfg(unix)]
+        _ if args.unix_socket.is_some() => {
+            let path = args.unix_socket.as_deref().unwrap();
+            clear_stale_socket(path)?;
+            server.bind_uds(path)?
+        }
+        None => server.bind((args.bind.as_str(), port))?,
+        Some(config) => server.bind_rustls_0_23((args.bind.as_str(), port), config)?,
+    };
+    let server = server.run();
+
+    let (stop, mut stopped) = mpsc::unbounded_channel();
+    let signalled = ready.clone();
+    ctrlc::set_handler(move || {
+        if !signalled.0.swap(false, Ordering::SeqCst) {
+            std::process::exit(crate::cli::exit::INTERRUPTED);
+        }
+        println!("\n  [!] shutting down; waiting for open requests and jobs (interrupt again to quit now)");
+        let _ = stop.send(());
+    }).map_err(std::io::Error::other)?;
+    let handle = server.handle();
+    actix_web::rt::spawn(async move {
+        if stopped.recv().await.is_some() {
+            handle.stop(true).await;
+        }
+    });
+    server.await?;
+
+    let grace = std::time::Duration::from_secs(args.shutdown_timeout);
+    for (id, generated, path) in web::block(move || jobs.shutdown(grace)).await.map_err(std::io::Error::other)? {
+        println!("  [!] job {} stopped after {} candidates; partial list kept at {}", id, generated, path.display());
+    }
+    if let Some((done, units, dir)) = coordinator.as_ref().and_then(|coordinator| coordinator.unfinished()) {
+        println!("  [!] distributed run stopped with {} of {} units done; they are kept in {}", done, units, dir.display());
+    }
+    if let Some(path) = &args.unix_socket {
+        let _ = std::fs::remove_file(path);
+    }
+    Ok(())
+}
+
+/// Clear the way for a Unix socket at `path`: remove a stale socket left
+/// by an earlier run, but never any other kind of file or a live socket
+#[cfg(unix)]
+fn clear_stale_socket(path: &std::path::Path) -> std::io::Result<()> {
+    use std::os::unix::fs::FileTypeExt;
+    let Ok(meta) = std::fs::symlink_metadata(path) else { return Ok(()) };
+    if !meta.file_type().is_socket() {
+        return Err(std::io::Error::new(std::io::ErrorKind::AlreadyExists,
+            format!("{} exists and is not a socket", path.display())));
+    }
+    if std::os::unix::net::UnixStream::connect(path).is_ok() {
+        return Err(std::io::Error::new(std::io::ErrorKind::AddrInUse,
+            format!("another server is listening on {}", path.display())));
+    }
+    std::fs::remove_file(path)
 }
 
 // ═══════════════════════════════════════════════════════════════
 // HELPERS
 // ═══════════════════════════════════════════════════════════════
 
-fn parse_case_style(s: &str) -> CaseStyle {
+fn parse_case_style(s: &str, invalid: &mut Invalid) -> CaseStyle {
     match s.to_lowercase().as_str() {
+        "" | "title" => CaseStyle::Title,
         "lower" => CaseStyle::Lower,
         "upper" => CaseStyle::Upper,
         "random" => CaseStyle::Random,
         "alternating" => CaseStyle::Alternating,
-        _ => CaseStyle::Title,
+        _ => {
+            invalid.unknown("case_style", s, &["title", "lower", "upper", "random", "alternating"]);
+            CaseStyle::Title
+        }
     }
 }
 
-fn parse_position(s: &str) -> Position {
+fn parse_position(field: &str, s: &str, invalid: &mut Invalid) -> Position {
     match s.to_lowercase().as_str() {
         "start" => Position::Start,
         "between" => Position::Between,
-        _ => Position::End,
+        "" | "end" => Position::End,
+        _ => {
+            invalid.unknown(field, s, &["start", "between", "end"]);
+            Position::End
+        }
+    }
+}
+
+fn parse_word_pool(s: &str, invalid: &mut Invalid) -> WordPool {
+    match s.to_lowercase().replace('_', "-").as_str() {
+        "eff-long" => WordPool::EffLong,
+        "eff-short" => WordPool::EffShort,
+        "" | "builtin" => WordPool::Builtin,
+        _ => {
+            invalid.unknown("word_pool", s, &["builtin", "eff-long", "eff-short"]);
+            WordPool::Builtin
+        }
+    }
+}
+
+fn parse_language(s: &str, invalid: &mut Invalid) -> Language {
+    match s.to_lowercase().as_str() {
+        "de" | "german" => Language::German,
+        "es" | "spanish" => Language::Spanish,
+        "fr" | "french" => Language::French,
+        "it" | "italian" => Language::Italian,
+        "pt" | "portuguese" => Language::Portuguese,
+        "" | "en" | "english" => Language::English,
+        _ => {
+            invalid.unknown("language", s, &["en", "de", "es", "fr", "it", "pt"]);
+            Language::English
+        }
+    }
+}
+
+fn parse_number_form(s: &str, invalid: &mut Invalid) -> NumberForm {
+    match s.to_lowercase().as_str() {
+        "words" => NumberForm::Words,
+        "both" => NumberForm::WordsAndDigits,
+        "" | "digits" => NumberForm::Digits,
+        _ => {
+            invalid.unknown("number_form", s, &["digits", "words", "both"]);
+            NumberForm::Digits
+        }
     }
 }
 
-fn parse_style(s: &str) -> MemorableStyle {
+fn parse_style(s: &str, invalid: &mut Invalid) -> MemorableStyle {
     match s.to_lowercase().as_str() {
         "passphrase" => MemorableStyle::Passphrase,
         "story" => MemorableStyle::Story,
         "alliterative" => MemorableStyle::Alliterative,
-        _ => MemorableStyle::Classic,
+        "" | "classic" => MemorableStyle::Classic,
+        _ => {
+            invalid.unknown("style", s, &["classic", "passphrase", "story", "alliterative"]);
+            MemorableStyle::Classic
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, App};
+    use serde_json::{json, Value};
+    use std::collections::HashSet;
+
+    /// Status, body and candidates charged for a /api/personal/generate page
+    async fn page(query: &str) -> (StatusCode, Value, Option<u64>) {
+        let app = test::init_service(App::new()
+            .app_data(web::Data::new(Limits { max_candidates: 1_000_000 }))
+            .service(generate_personal)).await;
+        let request = test::TestRequest::post()
+            .uri(&format!("/api/personal/generate?{}", query))
+            .set_json(json!({ "pets": ["rex"] }))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        let charged = response.response().extensions().get::<auth::Candidates>().map(|charged| charged.0);
+        let status = response.status();
+        (status, test::read_body_json(response).await, charged)
+    }
+
+    #[actix_web::test]
+    async fn test_personal_stream() {
+        let (_, all, _) = page("limit=1000000").await;
+        let app = test::init_service(App::new().service(stream_personal)).await;
+        let request = test::TestRequest::post()
+            .uri("/api/personal/stream")
+            .set_json(json!({ "pets": ["rex"] }))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.headers().get("content-type").unwrap(), "application/x-ndjson");
+        let body = test::read_body(response).await;
+
+        // One object per line; the Bloom filter may drop a rare unique one
+        let streamed: Vec<Value> = body.split(|&b| b == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(|line| serde_json::from_slice::<Value>(line).unwrap()["candidate"].clone())
+            .collect();
+        let paged: HashSet<&str> = all["candidates"].as_array().unwrap().iter().filter_map(Value::as_str).collect();
+        assert!(streamed.len() * 100 >= paged.len() * 99, "{} of {}", streamed.len(), paged.len());
+        assert!(streamed.iter().all(|candidate| paged.contains(candidate.as_str().unwrap())));
+        assert_eq!(streamed.iter().filter_map(Value::as_str).collect::<HashSet<_>>().len(), streamed.len());
+    }
+
+    #[actix_web::test]
+    async fn test_personal_pages() {
+        let (status, all, _) = page("limit=1000000").await;
+        assert_eq!(status, 200);
+        let total = all["total"].as_u64().unwrap() as usize;
+        assert!(total > 20, "{}", total);
+        assert_eq!(all["candidates"].as_array().unwrap().len(), total);
+        assert!(all["next_cursor"].is_null());
+
+        // Pages are slices of the whole list, each charged only its own size
+        let (_, first, charged) = page("limit=10").await;
+        assert_eq!(first["candidates"], json!(all["candidates"].as_array().unwrap()[..10]));
+        assert_eq!(charged, Some(10));
+        assert_eq!(first["next_cursor"], "10");
+        let (_, second, _) = page("limit=10&cursor=10").await;
+        assert_eq!(second["candidates"], json!(all["candidates"].as_array().unwrap()[10..20]));
+        let (_, by_offset, _) = page("limit=10&offset=10").await;
+        assert_eq!(by_offset["candidates"], second["candidates"]);
+
+        // The last page is short and has no cursor after it
+        let (_, last, charged) = page(&format!("limit=10&offset={}", total - 3)).await;
+        assert_eq!(last["candidates"].as_array().unwrap().len(), 3);
+        assert!(last["next_cursor"].is_null());
+        assert_eq!(charged, Some(3));
+
+        let (status, invalid, _) = page("cursor=nope").await;
+        assert_eq!(status, 400);
+        assert!(invalid.to_string().contains("cursor"), "{}", invalid);
+        assert_eq!(page("limit=0").await.0, 400);
     }
 }
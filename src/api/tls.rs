@@ -0,0 +1,34 @@
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::ServerConfig;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+// ═══════════════════════════════════════════════════════════════
+// TLS
+// ═══════════════════════════════════════════════════════════════
+//
+// With --tls-cert/--tls-key the server binds its address through
+// actix-web's rustls support, so TLS connections get the same worker
+// pool, handshake and keep-alive timeouts as plain ones, and handlers
+// see the real peer address. This only loads the certificate and key,
+// with ring, the provider ureq already uses.
+
+/// Server config for the certificate chain and key in `cert` and `key`
+pub fn config(cert: &Path, key: &Path) -> io::Result<ServerConfig> {
+    let invalid = |path: &Path, e: &dyn std::fmt::Display| {
+        io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", path.display(), e))
+    };
+    let certs = CertificateDer::pem_file_iter(cert)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| invalid(cert, &e))?;
+    if certs.is_empty() {
+        return Err(invalid(cert, &"no certificate found"));
+    }
+    let key_der = PrivateKeyDer::from_pem_file(key).map_err(|e| invalid(key, &e))?;
+    ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .and_then(|builder| builder.with_no_client_auth().with_single_cert(certs, key_der))
+        .map_err(|e| invalid(key, &e))
+}
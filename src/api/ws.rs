@@ -0,0 +1,219 @@
+use actix_http::ws::{hash_key, verify_handshake, CloseCode, CloseReason, OpCode, Parser};
+use actix_web::http::header;
+use actix_web::{get, web, HttpRequest, HttpResponse};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use super::jobs::{self, JobRequest, Jobs, Work};
+use super::problem;
+use super::server::ChannelBody;
+
+// ═══════════════════════════════════════════════════════════════
+// WEBSOCKET GENERATION
+// ═══════════════════════════════════════════════════════════════
+//
+// GET /api/ws/generate?request=JSON upgrades to a WebSocket. `request`
+// is a job request (the same JSON POST /api/jobs takes) and goes through
+// the same checks: it's refused with a plain HTTP error, before any
+// upgrade, when it's over --max-job-candidates or won't fit in the key's
+// quota, and otherwise charged its full size. The server then sends JSON
+// events: `started`, then `candidates` batches interleaved with
+// `progress` every PROGRESS_EVERY, then `done`, and closes. Closing from
+// the client side stops generation. Generation runs on its own thread; a
+// full socket blocks it, the same backpressure as /api/personal/stream.
+
+/// Candidates per `candidates` event
+const BATCH: usize = 1000;
+
+/// How often `progress` events go out
+const PROGRESS_EVERY: Duration = Duration::from_millis(250);
+
+/// Frames buffered ahead of a slow client
+const BACKLOG: usize = 16;
+
+/// Largest frame accepted from the client
+const MAX_FRAME: usize = 1024 * 1024;
+
+/// The job to stream
+#[derive(Deserialize)]
+pub struct WsQuery {
+    request: String,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Event<'a> {
+    Started { kind: &'a str, expected: u64 },
+    Candidates { candidates: &'a [String] },
+    Progress { generated: u64, expected: u64, progress: f64 },
+    Done { generated: u64, cancelled: bool, time_taken_ms: u128 },
+}
+
+fn text(event: &Event) -> web::Bytes {
+    let mut frame = web::BytesMut::new();
+    Parser::write_message(&mut frame, serde_json::to_vec(event).unwrap(), OpCode::Text, true, false);
+    frame.freeze()
+}
+
+fn pong(data: &[u8]) -> web::Bytes {
+    let mut frame = web::BytesMut::new();
+    Parser::write_message(&mut frame, data, OpCode::Pong, true, false);
+    frame.freeze()
+}
+
+fn close(code: CloseCode) -> web::Bytes {
+    let mut frame = web::BytesMut::new();
+    Parser::write_close(&mut frame, Some(CloseReason { code, description: None }), false);
+    frame.freeze()
+}
+
+/// The next complete frame from the client; `None` once the connection
+/// is gone or sends garbage
+async fn next_frame(payload: &mut web::Payload, buffer: &mut web::BytesMut) -> Option<(OpCode, web::BytesMut)> {
+    loop {
+        match Parser::parse(buffer, true, MAX_FRAME) {
+            Ok(Some((_, opcode, data))) => return Some((opcode, data.unwrap_or_default())),
+            Ok(None) => {}
+            Err(_) => return None,
+        }
+        buffer.extend_from_slice(&payload.next().await?.ok()?);
+    }
+}
+
+#[get("/api/ws/generate")]
+async fn ws_generate(
+    req: HttpRequest,
+    payload: web::Payload,
+    jobs: web::Data<Jobs>,
+    query: web::Query<WsQuery>,
+) -> actix_web::Result<HttpResponse> {
+    verify_handshake(req.head())?;
+    let request: JobRequest = match serde_json::from_str(&query.request) {
+        Ok(request) => request,
+        Err(e) => return Ok(problem::bad_request(format!("request: {}", e))),
+    };
+    let (kind, work, expected) = match jobs::accept(&req, &jobs, request).await {
+        Ok(accepted) => accepted,
+        Err(response) => return Ok(response),
+    };
+    let key = req.headers().get(header::SEC_WEBSOCKET_KEY).map(|key| hash_key(key.as_bytes())).unwrap_or_default();
+    let (frames, body) = mpsc::channel(BACKLOG);
+    actix_web::rt::spawn(session(payload, frames, kind, work, expected));
+    Ok(HttpResponse::SwitchingProtocols()
+        .upgrade("websocket")
+        .insert_header((header::SEC_WEBSOCKET_ACCEPT, &key[..]))
+        .body(ChannelBody::new(body)))
+}
+
+async fn session(mut payload: web::Payload, frames: mpsc::Sender<web::Bytes>, kind: &'static str, work: Work, expected: u64) {
+    let mut buffer = web::BytesMut::new();
+    let cancel = Arc::new(AtomicBool::new(false));
+    let (stop, sender) = (cancel.clone(), frames.clone());
+    std::thread::spawn(move || generate(work, kind, expected, sender, &stop));
+
+    // Keep reading so a close or a dropped connection stops the generator
+    loop {
+        match next_frame(&mut payload, &mut buffer).await {
+            Some((OpCode::Ping, data)) => {
+                let _ = frames.send(pong(&data)).await;
+            }
+            Some((OpCode::Close, _)) | None => break,
+            Some(_) => {}
+        }
+    }
+    cancel.store(true, Ordering::Relaxed);
+}
+
+/// Run `work`, sending its events until done, cancelled, disconnected or
+/// at the `expected` candidates it was charged for
+fn generate(work: Work, kind: &str, expected: u64, frames: mpsc::Sender<web::Bytes>, cancel: &AtomicBool) {
+    let start = Instant::now();
+    let send = |event: &Event| frames.blocking_send(text(event)).is_ok();
+    if !send(&Event::Started { kind, expected }) {
+        return;
+    }
+
+    let mut batch = Vec::with_capacity(BATCH);
+    let mut generated = 0u64;
+    let mut reported = Instant::now();
+    let mut gone = false;
+    work.run(|candidate| {
+        if generated >= expected {
+            return true;
+        }
+        batch.push(String::from_utf8_lossy(candidate).into_owned());
+        generated += 1;
+        if batch.len() == BATCH {
+            gone = !send(&Event::Candidates { candidates: &batch });
+            batch.clear();
+        }
+        if !gone && reported.elapsed() >= PROGRESS_EVERY {
+            let progress = (generated as f64 / expected.max(1) as f64).min(1.0);
+            gone = !send(&Event::Progress { generated, expected, progress });
+            reported = Instant::now();
+        }
+        gone || cancel.load(Ordering::Relaxed)
+    });
+    if gone {
+        return;
+    }
+
+    let cancelled = cancel.load(Ordering::Relaxed);
+    if !batch.is_empty() && !cancelled {
+        send(&Event::Candidates { candidates: &batch });
+    }
+    send(&Event::Done { generated, cancelled, time_taken_ms: start.elapsed().as_millis() });
+    let _ = frames.blocking_send(close(CloseCode::Normal));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, App};
+    use crate::api::auth::Auth;
+    use crate::cli::args::{Commands, JigsawArgs};
+    use clap::Parser;
+
+    fn upgrade(mask: &str) -> test::TestRequest {
+        let request = serde_json::json!({ "kind": "mask", "mask": mask }).to_string();
+        let query: String = request.bytes().map(|b| format!("%{:02X}", b)).collect();
+        test::TestRequest::get()
+            .uri(&format!("/api/ws/generate?request={}", query))
+            .insert_header((header::UPGRADE, "websocket"))
+            .insert_header((header::CONNECTION, "Upgrade"))
+            .insert_header((header::SEC_WEBSOCKET_VERSION, "13"))
+            .insert_header((header::SEC_WEBSOCKET_KEY, "dGhlIHNhbXBsZSBub25jZQ=="))
+            .insert_header(("X-API-Key", "k1"))
+    }
+
+    #[actix_web::test]
+    async fn test_refuses_before_upgrading() {
+        let auth = match JigsawArgs::parse_from(["jigsaw", "server", "--api-key", "k1", "--candidate-quota", "50"]).command {
+            Some(Commands::Server(args)) => Auth::new(&args),
+            _ => unreachable!(),
+        };
+        let jobs = web::Data::new(Jobs::start(1, None, 1000).unwrap());
+        let app = test::init_service(App::new()
+            .app_data(jobs.clone())
+            .app_data(web::Data::new(auth))
+            .service(ws_generate)).await;
+
+        // Over --max-job-candidates, then over what the key has left
+        let response = test::call_service(&app, upgrade("?d?d?d?d").to_request()).await;
+        assert_eq!(response.status(), 400);
+        let response = test::call_service(&app, upgrade("?d?d").to_request()).await;
+        assert_eq!(response.status(), 429);
+
+        // A stream that fits is charged its whole size when it starts
+        let response = test::call_service(&app, upgrade("?l").to_request()).await;
+        assert_eq!(response.status(), 101);
+        let response = test::call_service(&app, upgrade("?l").to_request()).await;
+        assert_eq!(response.status(), 429);
+        let response = test::call_service(&app, upgrade("?d").to_request()).await;
+        assert_eq!(response.status(), 101);
+        let _ = std::fs::remove_dir_all(&jobs.dir);
+    }
+}
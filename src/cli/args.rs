@@ -1,4 +1,8 @@
-use clap::{Parser, Subcommand, ValueEnum};
+use crate::engine::filter::{Class, Filter};
+use crate::engine::mask::{self, CustomCharsets, Mask};
+use crate::engine::personal::Category;
+use crate::invalid;
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 
 #[derive(Copy, Clone, Debug, ValueEnum)]
@@ -13,12 +17,149 @@ pub enum GenerationLevel {
     Insane,
 }
 
+impl GenerationLevel {
+    /// How many of the highest-weighted values a weighted profile keeps
+    pub fn word_limit(self) -> Option<usize> {
+        match self {
+            GenerationLevel::Quick => Some(10),
+            GenerationLevel::Standard => Some(40),
+            GenerationLevel::Deep | GenerationLevel::Insane => None,
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, ValueEnum)]
 pub enum OutputFormat {
     /// One password per line
     Plain,
     /// JSON array
     Json,
+    /// JSON Lines, one object per candidate (streamed; non-UTF-8 candidates are hex with "encoding":"hex")
+    Ndjson,
+    /// Parquet file with candidate, length and category columns, for DuckDB/Spark
+    Parquet,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum CompressFormat {
+    /// gzip (.gz)
+    Gz,
+    /// Zstandard (.zst)
+    Zst,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum LineEndingArg {
+    /// \n (Unix)
+    Lf,
+    /// \r\n (Windows)
+    Crlf,
+    /// NUL byte, for xargs -0
+    #[value(name = "nul", alias = "\\0", alias = "null")]
+    Nul,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum RollArg {
+    /// New file every hour (name-2026-10-16T13.txt)
+    Hourly,
+    /// New file every day (name-2026-10-16.txt)
+    Daily,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum DedupMode {
+    /// Exact — remembers every candidate
+    Exact,
+    /// Bloom filter — bounded memory, rare false drops (see --fp-rate)
+    Bloom,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum ModelType {
+    /// Character Markov chain — fast to train, the default
+    Markov,
+    /// Recurrent (GRU) character model (needs a build with `--features neural`)
+    Neural,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, ValueEnum)]
+pub enum CharClass {
+    /// a-z
+    Lower,
+    /// A-Z
+    Upper,
+    /// 0-9
+    Digit,
+    /// ASCII punctuation and space
+    Special,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum SkipCategory {
+    /// qwerty/asdf/1qaz… suffixes
+    KeyboardWalks,
+    /// p@ssw0rd substitutions
+    Leet,
+    /// xXwordXx, _word_ wraps
+    Decorative,
+    /// ilove…, …4ever, my+pet
+    Idioms,
+    /// Initials of names
+    Initials,
+    /// Two-word combinations
+    Combos,
+    /// Three-word combinations
+    Triples,
+    /// johnjohn, johnnhoj
+    Repetition,
+}
+
+impl SkipCategory {
+    pub fn category(self) -> Category {
+        match self {
+            SkipCategory::KeyboardWalks => Category::KeyboardWalks,
+            SkipCategory::Leet => Category::Leet,
+            SkipCategory::Decorative => Category::Decorative,
+            SkipCategory::Idioms => Category::Idioms,
+            SkipCategory::Initials => Category::Initials,
+            SkipCategory::Combos => Category::Combos,
+            SkipCategory::Triples => Category::Triples,
+            SkipCategory::Repetition => Category::Repetition,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum KeyboardLayout {
+    /// US/UK
+    Qwerty,
+    /// French
+    Azerty,
+    /// German
+    Qwertz,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum WalkDirection {
+    /// Along a row to the right (qwer)
+    Right,
+    /// Along a row to the left (rewq)
+    Left,
+    /// Down to the next row, either side (1qaz, 4esz)
+    Down,
+    /// Up to the previous row, either side (zaq1, zse4)
+    Up,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum ProfileTemplate {
+    /// Employee: company, work email, seasons, 8+ chars
+    Corporate,
+    /// Gaming handles, clan tags, 1337 numbers
+    Gamer,
+    /// Partner, kids, pets, parents, anniversaries
+    Family,
 }
 
 #[derive(Copy, Clone, Debug, ValueEnum)]
@@ -27,12 +168,38 @@ pub enum MemStyle {
     Classic,
     /// Random word chain (correct-horse-battery)
     Passphrase,
-    /// Subject-Verb-Object (TigerEatsFish)
+    /// Tiny sentence (TheBraveTigerRunsQuickly)
     Story,
     /// Same starting letter (BraveBearBounces)
     Alliterative,
 }
 
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum MemWordPool {
+    /// Built-in adjective/noun/verb pools
+    Builtin,
+    /// EFF long diceware list (7776 words)
+    EffLong,
+    /// EFF short diceware list (1296 words)
+    EffShort,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum MemLanguage {
+    /// English
+    En,
+    /// German (Deutsch)
+    De,
+    /// Spanish (Español)
+    Es,
+    /// French (Français)
+    Fr,
+    /// Italian (Italiano)
+    It,
+    /// Portuguese (Português)
+    Pt,
+}
+
 #[derive(Copy, Clone, Debug, ValueEnum)]
 pub enum MemCase {
     Title,
@@ -42,6 +209,25 @@ pub enum MemCase {
     Alternating,
 }
 
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum NumForm {
+    /// 42
+    Digits,
+    /// FortyTwo
+    Words,
+    /// FortyTwo42
+    Both,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum MnemonicCaseArg {
+    /// Keep the sentence's capitalisation (MdRwbi)
+    Preserve,
+    Lower,
+    Upper,
+    Alternating,
+}
+
 #[derive(Copy, Clone, Debug, ValueEnum)]
 pub enum NumPosition {
     Start,
@@ -64,14 +250,94 @@ pub struct JigsawArgs {
     // GLOBAL OPTIONS
     // ═══════════════════════════════════════════════
 
-    /// Output file path (default: stdout)
+    /// Read option defaults from this TOML file; flags on the command line win (default: $XDG_CONFIG_HOME/jigsaw/config.toml)
+    #[arg(long, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+
+    /// Don't read the default config file
+    #[arg(long, conflicts_with = "config")]
+    pub no_config: bool,
+
+    /// Suppress status messages (they go to stderr; results stay on stdout)
+    #[arg(short, long)]
+    pub quiet: bool,
+
+    /// Output file path, or an http(s):// / s3:// URL to upload to (default: stdout)
     #[arg(short, long)]
     pub output: Option<PathBuf>,
 
+    /// Stream candidates into this command's stdin instead, e.g. "hashcat -m 0 hashes.txt"
+    #[arg(long, value_name = "COMMAND", conflicts_with = "output")]
+    pub pipe_to: Option<String>,
+
+    /// Candidates per batch handed to the writer thread
+    #[arg(long, default_value_t = crate::io::writer::DEFAULT_BATCH_SIZE, value_name = "N")]
+    pub batch_size: usize,
+
+    /// Batches that may queue for the writer before generators wait
+    #[arg(long, default_value_t = crate::io::writer::DEFAULT_CHANNEL_CAPACITY, value_name = "N")]
+    pub channel_capacity: usize,
+
     /// Output format
     #[arg(long, value_enum, default_value_t = OutputFormat::Plain)]
     pub format: OutputFormat,
 
+    /// Drop duplicate candidates before writing (any mode)
+    #[arg(long, value_enum, value_name = "MODE")]
+    pub unique: Option<DedupMode>,
+
+    /// Terminator after each candidate
+    #[arg(long, value_enum, default_value_t = LineEndingArg::Lf, value_name = "ENDING")]
+    pub line_ending: LineEndingArg,
+
+    /// Report writer throughput (candidates/s, MB written) to stderr
+    #[arg(long)]
+    pub stats: bool,
+
+    /// Seconds between --stats reports
+    #[arg(long, default_value_t = 5, value_name = "SECS")]
+    pub stats_interval: u64,
+
+    /// Write a JSON run summary (mode, parameters, candidates, duration, output files, exit status) to PATH
+    #[arg(long, value_name = "PATH")]
+    pub stats_json: Option<PathBuf>,
+
+    /// Continue --output from its .ckpt sidecar instead of overwriting it; the run is replayed and candidates already written are skipped (mask mode runs single-threaded, Markov mode needs --seed)
+    #[arg(long, requires = "output")]
+    pub append: bool,
+
+    /// Stop after N candidates (after --unique drops repeats)
+    #[arg(long, value_name = "N")]
+    pub limit: Option<u64>,
+
+    /// Emit a uniform random sample of N candidates instead of the whole run, in generation order (--seed makes it repeatable)
+    #[arg(long, value_name = "N", conflicts_with_all = ["limit", "append", "sort_unique", "roll", "session"])]
+    pub sample: Option<u64>,
+
+    /// Record this run as a named session in ~/.jigsaw/sessions, resumable with `jigsaw restore NAME` (mask, Markov, PRINCE and personal wordlists)
+    #[arg(long, value_name = "NAME", requires = "output", conflicts_with_all = ["roll", "sort_unique", "pipe_to", "interactive"])]
+    pub session: Option<String>,
+
+    /// Write the output sorted and deduplicated once generation ends (spills to temp files past --sort-memory)
+    #[arg(long, conflicts_with = "append")]
+    pub sort_unique: bool,
+
+    /// Memory budget in MB for --sort-unique before sorted runs spill to disk
+    #[arg(long, default_value_t = 256, value_name = "MB")]
+    pub sort_memory: usize,
+
+    /// Rotate --output into UTC time-stamped files for long-running jobs
+    #[arg(long, value_enum, requires = "output", conflicts_with_all = ["append", "sort_unique"])]
+    pub roll: Option<RollArg>,
+
+    /// Write <output>.manifest.json with the SHA-256, candidate count, command line and parameters
+    #[arg(long, requires = "output", conflicts_with = "pipe_to")]
+    pub manifest: bool,
+
+    /// Compress the output stream (inferred from a .gz/.zst --output otherwise)
+    #[arg(long, value_enum, value_name = "FORMAT")]
+    pub compress: Option<CompressFormat>,
+
     /// Number of threads (default: auto)
     #[arg(short, long)]
     pub threads: Option<usize>,
@@ -80,14 +346,36 @@ pub struct JigsawArgs {
     #[arg(short, long)]
     pub interactive: bool,
 
+    /// Answer the wizard's questions from a TOML answers file
+    #[arg(long, value_name = "FILE", requires = "interactive", conflicts_with = "record_answers")]
+    pub answers: Option<PathBuf>,
+
+    /// Save the answers given in the wizard to a TOML answers file
+    #[arg(long, value_name = "FILE", requires = "interactive")]
+    pub record_answers: Option<PathBuf>,
+
+    /// Directory the wizard lists profiles from when loading or editing one
+    #[arg(long, value_name = "DIR", default_value = ".")]
+    pub profiles_dir: PathBuf,
+
+    // ═══════════════════════════════════════════════
+    // CANDIDATE FILTERS
+    // ═══════════════════════════════════════════════
+
+    #[command(flatten)]
+    pub filter: FilterArgs,
+
     // ═══════════════════════════════════════════════
     // MASK ATTACK
     // ═══════════════════════════════════════════════
 
-    /// Mask pattern (e.g. ?u?l?l?d?d)
+    /// Mask pattern (e.g. ?u?l?l?d?d, ?1?1?d with --custom-charset1)
     #[arg(short, long)]
     pub mask: Option<String>,
 
+    #[command(flatten)]
+    pub charsets: CharsetArgs,
+
     /// Rule file path
     #[arg(short, long)]
     pub rules: Option<PathBuf>,
@@ -96,11 +384,11 @@ pub struct JigsawArgs {
     // MARKOV ENGINE
     // ═══════════════════════════════════════════════
 
-    /// Train a Markov model from this wordlist
+    /// Train a model (see --model-type) from this wordlist, or - for stdin (.gz/.zst/.xz/.zip are read directly)
     #[arg(long, value_name = "WORDLIST")]
     pub train: Option<PathBuf>,
 
-    /// Path to Markov model file
+    /// Path to the model file
     #[arg(long, value_name = "MODEL_PATH")]
     pub model: Option<PathBuf>,
 
@@ -112,6 +400,86 @@ pub struct JigsawArgs {
     #[arg(long, default_value_t = 10000)]
     pub count: usize,
 
+    /// Characters of context a Markov model conditions on when training with --train
+    #[arg(long, default_value_t = 3, value_name = "N")]
+    pub order: usize,
+
+    /// Kind of model --train builds and --markov loads
+    #[arg(long, value_enum, default_value_t = ModelType::Markov)]
+    pub model_type: ModelType,
+
+    /// Passes over the corpus when training a neural model
+    #[arg(long, default_value_t = 10, value_name = "N")]
+    pub epochs: usize,
+
+    /// Units in a neural model's recurrent state; more learns more, slower
+    #[arg(long, default_value_t = 128, value_name = "N")]
+    pub hidden: usize,
+
+    /// Minimum Markov candidate length
+    #[arg(long, default_value_t = 6, value_name = "N")]
+    pub markov_min_len: usize,
+
+    /// Maximum Markov candidate length
+    #[arg(long, default_value_t = 12, value_name = "N")]
+    pub markov_max_len: usize,
+
+    // ═══════════════════════════════════════════════
+    // PRINCE ATTACK
+    // ═══════════════════════════════════════════════
+
+    /// Chain elements of this wordlist, best first, into candidates (PRINCE); - for stdin
+    #[arg(long, value_name = "WORDLIST")]
+    pub prince: Option<PathBuf>,
+
+    /// Minimum PRINCE candidate length
+    #[arg(long, default_value_t = 1, value_name = "N")]
+    pub prince_min_len: usize,
+
+    /// Maximum PRINCE candidate length
+    #[arg(long, default_value_t = 16, value_name = "N")]
+    pub prince_max_len: usize,
+
+    /// Fewest elements in a PRINCE chain
+    #[arg(long, default_value_t = 1, value_name = "N")]
+    pub prince_min_elems: usize,
+
+    /// Most elements in a PRINCE chain
+    #[arg(long, default_value_t = 8, value_name = "N")]
+    pub prince_max_elems: usize,
+
+    // ═══════════════════════════════════════════════
+    // KEYBOARD WALKS
+    // ═══════════════════════════════════════════════
+
+    /// Generate keyboard walks (qwerty, 1qaz, zse4…), straightest and shortest first
+    #[arg(long)]
+    pub walks: bool,
+
+    /// Keyboard layout for --walks and the personal engine's walk suffixes [default: qwerty]
+    #[arg(long, value_enum, value_name = "LAYOUT")]
+    pub walk_layout: Option<KeyboardLayout>,
+
+    /// Shortest walk, in keys
+    #[arg(long, default_value_t = 4, value_name = "N")]
+    pub walk_min_len: usize,
+
+    /// Longest walk, in keys
+    #[arg(long, default_value_t = 8, value_name = "N")]
+    pub walk_max_len: usize,
+
+    /// Most changes of direction in one walk
+    #[arg(long, default_value_t = 1, value_name = "N")]
+    pub walk_turns: usize,
+
+    /// Directions a walk may step in (comma-separated)
+    #[arg(long, value_enum, value_delimiter = ',', value_name = "DIRECTION", default_values_t = [WalkDirection::Right, WalkDirection::Left, WalkDirection::Down, WalkDirection::Up])]
+    pub walk_directions: Vec<WalkDirection>,
+
+    /// Also emit each walk held on shift (!QAZ) and with only its first key shifted (!qaz)
+    #[arg(long)]
+    pub walk_shift: bool,
+
     // ═══════════════════════════════════════════════
     // PERSONAL ATTACK
     // ═══════════════════════════════════════════════
@@ -128,18 +496,47 @@ pub struct JigsawArgs {
     #[arg(long, value_enum, default_value_t = GenerationLevel::Standard)]
     pub level: GenerationLevel,
 
-    /// Minimum password length filter
+    /// Treat profile warnings (unknown keys, malformed dates…) as errors
     #[arg(long)]
-    pub min_length: Option<usize>,
-
-    /// Maximum password length filter
-    #[arg(long)]
-    pub max_length: Option<usize>,
+    pub strict: bool,
 
     /// Check if this password exists in generated wordlist
     #[arg(long, value_name = "PASSWORD")]
     pub check: Option<String>,
 
+    /// Print the keyspace size, output size and expected runtime, then exit without generating. Exact for masks, analytic for personal profiles (before dedup), the configured count for Markov and memorable; runtime comes from timing a small sample
+    #[arg(long)]
+    pub estimate: bool,
+
+    /// Duplicate removal strategy for generated candidates
+    #[arg(long, value_enum, default_value_t = DedupMode::Exact)]
+    pub dedup: DedupMode,
+
+    /// False-positive rate for --dedup bloom and --unique bloom
+    #[arg(long, default_value_t = 0.001)]
+    pub fp_rate: f64,
+
+    /// Write a hashcat rule file with the profile's mangling; the output then
+    /// gets only the base words (use with hashcat -r)
+    #[arg(long, value_name = "RULE_FILE")]
+    pub emit_rules: Option<PathBuf>,
+
+    /// Candidate families to leave out (comma-separated)
+    #[arg(long, value_enum, value_delimiter = ',', value_name = "CATEGORY")]
+    pub skip: Vec<SkipCategory>,
+
+    /// Add keyboard-proximity typo variants of profile words (jphn, joohn)
+    #[arg(long)]
+    pub typos: bool,
+
+    /// Common-password list to mangle with profile suffixes (e.g. rockyou-top10k.txt.gz), or - for stdin
+    #[arg(long, value_name = "FILE")]
+    pub blend: Option<PathBuf>,
+
+    /// Extra nickname mappings, one "name: nick1, nick2" per line
+    #[arg(long, value_name = "FILE")]
+    pub nicknames: Option<PathBuf>,
+
     // ═══════════════════════════════════════════════
     // MEMORABLE PASSWORD
     // ═══════════════════════════════════════════════
@@ -180,6 +577,10 @@ pub struct JigsawArgs {
     #[arg(long, default_value_t = 99)]
     pub num_max: u32,
 
+    /// Write the number as digits, English words, or both
+    #[arg(long, value_enum, default_value_t = NumForm::Digits)]
+    pub num_form: NumForm,
+
     /// Include special character  
     #[arg(long, default_value_t = true)]
     pub mem_special: bool,
@@ -203,14 +604,931 @@ pub struct JigsawArgs {
     /// Maximum memorable password length
     #[arg(long, default_value_t = 32)]
     pub mem_max_len: usize,
+
+    /// Word pool for memorable passwords
+    #[arg(long, value_enum, default_value_t = MemWordPool::Builtin)]
+    pub word_pool: MemWordPool,
+
+    /// Memorable pattern, e.g. "{adj}-{noun}{num2}{special}" (overrides --mem-style)
+    #[arg(long, value_name = "PATTERN")]
+    pub template: Option<String>,
+
+    /// Require at least this many bits of entropy, adding words or digits as needed
+    #[arg(long, value_name = "BITS")]
+    pub min_entropy: Option<f64>,
+
+    /// Apply one or two random leet substitutions to memorable words
+    #[arg(long)]
+    pub mem_leet: bool,
+
+    /// Chance each word is leeted when --mem-leet is set (0.0-1.0)
+    #[arg(long, default_value_t = 0.5, value_name = "P")]
+    pub leet_prob: f64,
+
+    /// Seed for reproducible memorable passwords (and Markov runs with --append)
+    #[arg(long, value_name = "U64")]
+    pub seed: Option<u64>,
+
+    /// Copy the password to the clipboard instead of printing it
+    #[arg(long)]
+    pub copy: bool,
+
+    /// Seconds before the copied password is cleared (0 = keep it)
+    #[arg(long, default_value_t = 45, value_name = "SECS")]
+    pub clear_after: u64,
+
+    /// Show the password as a terminal QR code
+    #[arg(long)]
+    pub qr: bool,
+
+    /// Also save the QR code as a PNG image
+    #[arg(long, value_name = "FILE")]
+    pub qr_out: Option<PathBuf>,
+
+    /// Look each password up in Have I Been Pwned (only a hash prefix leaves the machine)
+    #[arg(long)]
+    pub check_pwned: bool,
+
+    /// Only use words with at least this many letters
+    #[arg(long, value_name = "N")]
+    pub min_word_len: Option<usize>,
+
+    /// Only use words with at most this many letters
+    #[arg(long, value_name = "N")]
+    pub max_word_len: Option<usize>,
+
+    /// Symbols that may be inserted, e.g. "!@#_" (default: !@#$%&*?+=^~)
+    #[arg(long, value_name = "CHARS")]
+    pub specials: Option<String>,
+
+    /// Site password policy, e.g. "max=16,upper,digit,symbol,no-spaces,disallow=&%"
+    #[arg(long, value_name = "SPEC")]
+    pub policy: Option<String>,
+
+    /// Language of the built-in memorable word pools
+    #[arg(long, value_enum, default_value_t = MemLanguage::En)]
+    pub language: MemLanguage,
+
+    /// JSON file with "adjectives", "nouns", "verbs", "adverbs", "colors" lists
+    #[arg(long, value_name = "FILE")]
+    pub language_file: Option<PathBuf>,
+
+    /// Draw memorable words from this file instead, or - for stdin (one word per line; may be compressed)
+    #[arg(long, value_name = "FILE")]
+    pub wordlist_file: Option<PathBuf>,
+
+    // ═══════════════════════════════════════════════
+    // MNEMONIC PASSWORD
+    // ═══════════════════════════════════════════════
+
+    /// Build an initial-letter password from a sentence ("My dog Rex was born in 2015!" → MdRwbi2015!)
+    #[arg(long, value_name = "SENTENCE")]
+    pub mnemonic: Option<String>,
+
+    /// Case of the initial letters
+    #[arg(long, value_enum, default_value_t = MnemonicCaseArg::Preserve)]
+    pub mnemonic_case: MnemonicCaseArg,
+
+    /// Leetspeak the initial letters (a→@, e→3, i→1, o→0, s→$)
+    #[arg(long)]
+    pub mnemonic_leet: bool,
+
+    /// Turn sound-alike words into digits (to→2, for→4, ate→8)
+    #[arg(long)]
+    pub number_words: bool,
+
+    /// Drop punctuation from the sentence
+    #[arg(long)]
+    pub no_punctuation: bool,
+}
+
+impl Default for JigsawArgs {
+    /// The values clap fills in when no flags are given
+    fn default() -> Self {
+        Self::parse_from(["jigsaw"])
+    }
 }
 
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     /// Start the REST API server
-    Server {
-        /// Port to listen on
-        #[arg(short, long, default_value_t = 8080)]
-        port: u16,
+    Server(ServerArgs),
+
+    /// Full-screen profile editor with a live preview, keyspace estimate and generation progress
+    Tui {
+        /// Profile to edit (created on save if it doesn't exist)
+        #[arg(default_value = "target.json")]
+        profile: PathBuf,
+
+        /// Wordlist to write when generating
+        #[arg(short, long, default_value = "wordlist.txt", value_name = "FILE")]
+        output: PathBuf,
+    },
+
+    /// Resume an interrupted --session run, or list sessions without a NAME
+    Restore {
+        /// Session to resume
+        name: Option<String>,
+    },
+
+    /// Create and manage personal attack profiles
+    Profile {
+        #[command(subcommand)]
+        action: ProfileCommand,
+    },
+
+    /// Crawl a website and harvest words (CeWL-style)
+    Scrape(ScrapeArgs),
+
+    /// Measure candidates/sec for each engine and the writer on this machine
+    Bench(BenchArgs),
+
+    /// Join every word of one list to every word of another (combinator attack)
+    Combine(CombineArgs),
+
+    /// Report lengths, character classes, masks and affixes of a password list (PACK-style)
+    Analyze(AnalyzeArgs),
+
+    /// Estimate how many guesses a password takes (zxcvbn-style) and how long cracking it would be
+    Strength(StrengthArgs),
+
+    /// Report how each attack would fare against a known password, with suggestions to improve it
+    Audit(AuditArgs),
+
+    /// Build an offline breached-password filter from the Pwned Passwords corpus or any list
+    PwnedFilter(PwnedFilterArgs),
+
+    /// Check passwords against a filter from `pwned-filter`, without network access
+    PwnedCheck(PwnedCheckArgs),
+
+    /// Sort, deduplicate, merge and diff wordlists larger than memory
+    Wordlist {
+        #[command(subcommand)]
+        action: WordlistCommand,
+    },
+
+    /// Write every date in a year range as tokens (24121989, 1224, Dec89…), for combine or hybrid attacks
+    Dates(DatesArgs),
+
+    /// Generate work units for a `jigsaw server --coordinator` until its run is done
+    Worker(WorkerArgs),
+
+    /// Reorder a wordlist likeliest first, by a trained model and optionally a target's profile
+    Rank(RankArgs),
+
+    /// Run every word of a list through the personal attack's case, leet and suffix forms
+    Mangle(MangleArgs),
+
+    /// Write every leet spelling of each word of a list, up to a number of swapped characters
+    Leet(LeetArgs),
+
+    /// Write every upper/lower-case spelling of each word of a list (pAsSwOrd…), for words of up to N letters
+    Case(CaseArgs),
+
+    /// Try hashcat rule chains on words outside an attack
+    Rules {
+        #[command(subcommand)]
+        action: RulesCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ProfileCommand {
+    /// Create a profile pre-filled from a template, ready to edit
+    New {
+        /// Scenario to start from
+        #[arg(long, value_enum)]
+        template: ProfileTemplate,
+
+        /// Where to write the profile (default: <template>.json)
+        #[arg(short, long, value_name = "FILE")]
+        out: Option<PathBuf>,
+
+        /// Overwrite an existing file
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Create profile JSON files from a vCard (.vcf) or CSV contacts export
+    Import {
+        /// Contacts file to import
+        #[arg(long, value_name = "FILE")]
+        from: PathBuf,
+
+        /// Directory to write the generated profiles into
+        #[arg(long, value_name = "DIR", default_value = ".")]
+        out_dir: PathBuf,
+
+        /// CSV column mapping (e.g. "Given Name=first_names,Org=company")
+        #[arg(long, value_name = "MAPPING")]
+        map: Option<String>,
+    },
+
+    /// Check profile JSON files for unknown keys and suspicious values
+    Validate {
+        /// Profile files to check
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+    },
+}
+
+#[derive(Args, Debug)]
+pub struct ServerArgs {
+    /// Port to listen on
+    #[arg(short, long, default_value_t = 8080, env = "JIGSAW_PORT")]
+    pub port: u16,
+
+    /// Address to listen on (127.0.0.1 keeps the API local)
+    #[arg(long, default_value = "0.0.0.0", value_name = "ADDR", env = "JIGSAW_BIND")]
+    pub bind: String,
+
+    /// Serve HTTPS with this PEM certificate chain
+    #[arg(long, requires = "tls_key", value_name = "FILE", env = "JIGSAW_TLS_CERT")]
+    pub tls_cert: Option<PathBuf>,
+
+    /// PEM private key for --tls-cert
+    #[arg(long, requires = "tls_cert", value_name = "FILE", env = "JIGSAW_TLS_KEY")]
+    pub tls_key: Option<PathBuf>,
+
+    /// Listen on this Unix domain socket instead of a TCP port
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["tls_cert", "port", "bind"], env = "JIGSAW_UNIX_SOCKET")]
+    pub unix_socket: Option<PathBuf>,
+
+    /// Accept requests carrying this key (repeatable; also JIGSAW_API_KEYS, comma separated).
+    /// Without any key the API is open
+    #[arg(long = "api-key", value_name = "KEY")]
+    pub api_keys: Vec<String>,
+
+    /// Requests each key may make per minute (0 = unlimited)
+    #[arg(long, default_value_t = 0, value_name = "N", env = "JIGSAW_RATE_LIMIT")]
+    pub rate_limit: u32,
+
+    /// Candidates each key may generate per hour (0 = unlimited)
+    #[arg(long, default_value_t = 0, value_name = "N", env = "JIGSAW_CANDIDATE_QUOTA")]
+    pub candidate_quota: u64,
+
+    /// On SIGTERM or Ctrl-C, seconds to let open requests and running jobs finish
+    #[arg(long, default_value_t = 30, value_name = "SECS", env = "JIGSAW_SHUTDOWN_TIMEOUT")]
+    pub shutdown_timeout: u64,
+
+    /// Browser origin allowed to call the API, like https://app.example.com
+    /// (repeatable or comma separated; * allows any)
+    #[arg(long = "cors-origin", default_value = "*", value_delimiter = ',', value_name = "ORIGIN", env = "JIGSAW_CORS_ORIGINS")]
+    pub cors_origins: Vec<String>,
+
+    /// Threads answering HTTP requests (0 = one per CPU)
+    #[arg(long, default_value_t = 0, value_name = "N", env = "JIGSAW_WORKERS")]
+    pub workers: usize,
+
+    /// Threads running queued jobs
+    #[arg(long, default_value_t = 2, value_name = "N", env = "JIGSAW_JOB_WORKERS")]
+    pub job_workers: usize,
+
+    /// Most candidates one request returns: a personal page, a Markov sample.
+    /// Larger lists go through the job queue
+    #[arg(long, default_value_t = 100_000, value_name = "N", env = "JIGSAW_MAX_CANDIDATES")]
+    pub max_candidates: usize,
+
+    /// Most candidates one queued job may write; bigger jobs are refused
+    /// when submitted
+    #[arg(long, default_value_t = 100_000_000, value_name = "N", env = "JIGSAW_MAX_JOB_CANDIDATES")]
+    pub max_job_candidates: u64,
+
+    /// Largest JSON request body, in bytes
+    #[arg(long, default_value_t = 4 * 1024 * 1024, value_name = "BYTES", env = "JIGSAW_MAX_BODY")]
+    pub max_body: usize,
+
+    /// Trained Markov models kept in memory; training another drops the
+    /// least recently used
+    #[arg(long, default_value_t = 32, value_name = "N", env = "JIGSAW_MAX_MODELS")]
+    pub max_models: usize,
+
+    /// Seconds a finished job and its result are kept (0 = until shutdown)
+    #[arg(long, default_value_t = 3600, value_name = "SECS", env = "JIGSAW_JOB_RETENTION")]
+    pub job_retention: u64,
+
+    /// Seconds a client gets to send a request's headers
+    #[arg(long, default_value_t = 5, value_name = "SECS", env = "JIGSAW_REQUEST_TIMEOUT")]
+    pub request_timeout: u64,
+
+    /// Split a --mask or Markov --model run into units for `jigsaw worker`s and join their lists into --output
+    #[arg(long, requires = "output")]
+    pub coordinator: bool,
+
+    /// Mask the coordinator hands out
+    #[arg(short, long, requires = "coordinator", conflicts_with = "model", value_name = "MASK")]
+    pub mask: Option<String>,
+
+    #[command(flatten)]
+    pub charsets: CharsetArgs,
+
+    /// Markov model the coordinator hands out (from `jigsaw --train`)
+    #[arg(long, requires = "coordinator", value_name = "MODEL_PATH")]
+    pub model: Option<PathBuf>,
+
+    /// Candidates the Markov run draws in all
+    #[arg(long, default_value_t = 1_000_000, value_name = "N", requires = "model")]
+    pub count: u64,
+
+    /// Minimum Markov candidate length
+    #[arg(long, default_value_t = 6, value_name = "N", requires = "model")]
+    pub markov_min_len: usize,
+
+    /// Maximum Markov candidate length
+    #[arg(long, default_value_t = 12, value_name = "N", requires = "model")]
+    pub markov_max_len: usize,
+
+    /// Seed for the Markov run, so it can be repeated (random if not given)
+    #[arg(long, value_name = "N", requires = "model")]
+    pub seed: Option<u64>,
+
+    /// Candidates in each work unit
+    #[arg(long, default_value_t = 1_000_000, value_name = "N", requires = "coordinator")]
+    pub unit_size: u64,
+
+    /// Seconds a worker has to return a unit before it is handed to another
+    #[arg(long, default_value_t = 600, value_name = "SECS", requires = "coordinator")]
+    pub lease: u64,
+
+    /// Where the coordinator writes the joined wordlist
+    #[arg(short, long, value_name = "FILE", requires = "coordinator")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct BenchArgs {
+    /// Seconds to run each measurement
+    #[arg(long, default_value_t = 1.0, value_name = "SECS")]
+    pub seconds: f64,
+
+    /// Thread counts to compare (default: 1, 2, 4, ... up to the CPU count)
+    #[arg(long, value_delimiter = ',', value_name = "N,N,...")]
+    pub threads: Vec<usize>,
+
+    /// Batch sizes to compare for the writer
+    #[arg(long, value_delimiter = ',', value_name = "N,N,...", default_values_t = [100, 1_000, 10_000])]
+    pub batch_sizes: Vec<usize>,
+
+    /// Markov model to time (default: one trained on generated passphrases)
+    #[arg(long, value_name = "FILE")]
+    pub model: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct ScrapeArgs {
+    /// Start URL
+    pub url: String,
+
+    /// Link depth to follow from the start page
+    #[arg(long, default_value_t = 1)]
+    pub depth: usize,
+
+    /// Maximum number of pages to fetch
+    #[arg(long, default_value_t = 50)]
+    pub max_pages: usize,
+
+    /// Minimum word length
+    #[arg(long, default_value_t = 3)]
+    pub min_word_len: usize,
+
+    /// Maximum word length
+    #[arg(long, default_value_t = 24)]
+    pub max_word_len: usize,
+
+    /// Only keep words seen at least this many times
+    #[arg(long, default_value_t = 1)]
+    pub min_count: usize,
+
+    /// Keep only the N most frequent words
+    #[arg(long, value_name = "N")]
+    pub top: Option<usize>,
+
+    /// Follow links to other hosts
+    #[arg(long)]
+    pub offsite: bool,
+
+    /// Lowercase all harvested words
+    #[arg(long)]
+    pub lowercase: bool,
+
+    /// Add the words to this profile's keywords (created if missing)
+    #[arg(long, value_name = "PROFILE_PATH")]
+    pub profile: Option<PathBuf>,
+
+    /// Write the wordlist to this file (default: stdout)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct CombineArgs {
+    /// Left wordlist, or - for stdin
+    pub left: PathBuf,
+
+    /// Right wordlist, or - for stdin
+    pub right: PathBuf,
+
+    /// Put SEP between the words; repeat for more (default: none)
+    #[arg(short, long = "separator", value_name = "SEP")]
+    pub separators: Vec<String>,
+
+    /// Rule chain applied to each left word (e.g. u)
+    #[arg(short = 'j', long, value_name = "RULE")]
+    pub rule_left: Option<String>,
+
+    /// Rule chain applied to each right word (e.g. $!)
+    #[arg(short = 'k', long, value_name = "RULE")]
+    pub rule_right: Option<String>,
+
+    /// Write the candidates to this file (default: stdout)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Stop after N candidates, taken in order
+    #[arg(long, value_name = "N")]
+    pub limit: Option<u64>,
+
+    /// Worker threads (default: one per core)
+    #[arg(short, long)]
+    pub threads: Option<usize>,
+
+    #[command(flatten)]
+    pub filter: FilterArgs,
+}
+
+#[derive(Args, Debug)]
+pub struct MangleArgs {
+    /// Base words, one per line, or - for stdin (.gz/.zst/.xz/.zip are read directly)
+    #[arg(short, long, value_name = "WORDLIST")]
+    pub input: PathBuf,
+
+    /// Take extra suffixes (dates, numbers) and skipped categories from this profile
+    #[arg(long, value_name = "FILE")]
+    pub profile: Option<PathBuf>,
+
+    /// Variant families to leave out (comma-separated)
+    #[arg(long, value_enum, value_delimiter = ',', value_name = "CATEGORY")]
+    pub skip: Vec<SkipCategory>,
+
+    /// Write the candidates to this file (default: stdout)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Stop after N candidates, taken in order
+    #[arg(long, value_name = "N")]
+    pub limit: Option<u64>,
+
+    /// Worker threads (default: one per core)
+    #[arg(short, long)]
+    pub threads: Option<usize>,
+
+    #[command(flatten)]
+    pub filter: FilterArgs,
+}
+
+#[derive(Args, Debug)]
+pub struct LeetArgs {
+    /// Words, one per line, or - for stdin (.gz/.zst/.xz/.zip are read directly)
+    #[arg(short, long, value_name = "WORDLIST")]
+    pub input: PathBuf,
+
+    /// Most characters swapped in one variant
+    #[arg(long, default_value_t = 3, value_name = "N")]
+    pub max_subs: usize,
+
+    /// Substitutions to use instead of the built-in ones, as `a: @ 4` lines
+    #[arg(long, value_name = "FILE")]
+    pub map: Option<PathBuf>,
+
+    /// Write the candidates to this file (default: stdout)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Stop after N candidates, taken in order
+    #[arg(long, value_name = "N")]
+    pub limit: Option<u64>,
+
+    /// Worker threads (default: one per core)
+    #[arg(short, long)]
+    pub threads: Option<usize>,
+
+    #[command(flatten)]
+    pub filter: FilterArgs,
+}
+
+#[derive(Args, Debug)]
+pub struct CaseArgs {
+    /// Words, one per line, or - for stdin (.gz/.zst/.xz/.zip are read directly)
+    #[arg(short, long, value_name = "WORDLIST")]
+    pub input: PathBuf,
+
+    /// Skip words with more letters than this; each has 2^letters spellings
+    #[arg(long, default_value_t = 12, value_name = "N")]
+    pub max_letters: usize,
+
+    /// Write the candidates to this file (default: stdout)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Stop after N candidates, taken in order
+    #[arg(long, value_name = "N")]
+    pub limit: Option<u64>,
+
+    /// Worker threads (default: one per core)
+    #[arg(short, long)]
+    pub threads: Option<usize>,
+
+    #[command(flatten)]
+    pub filter: FilterArgs,
+}
+
+#[derive(Args, Debug)]
+pub struct AnalyzeArgs {
+    /// Wordlist of known passwords, or - for stdin (.gz/.zst/.xz/.zip are read directly)
+    pub wordlist: PathBuf,
+
+    /// Rows to show in each table
+    #[arg(long, default_value_t = 10, value_name = "N")]
+    pub top: usize,
+
+    /// Write the most common masks to FILE, one per line, for --mask
+    #[arg(long, value_name = "FILE")]
+    pub masks: Option<PathBuf>,
+
+    /// Write append/prepend rules for the most common affixes to FILE
+    #[arg(long, value_name = "FILE")]
+    pub rules: Option<PathBuf>,
+
+    /// Masks/rules to write with --masks and --rules
+    #[arg(long, default_value_t = 100, value_name = "N")]
+    pub emit: usize,
+
+    /// Print the report as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct StrengthArgs {
+    /// Password to rate
+    #[arg(required_unless_present = "stdin")]
+    pub password: Option<String>,
+
+    /// Rate each line of stdin instead, one result per line
+    #[arg(long, conflicts_with = "password")]
+    pub stdin: bool,
+
+    /// Also match words of this wordlist, most likely first; repeat for more
+    #[arg(long = "dictionary", value_name = "FILE")]
+    pub dictionaries: Vec<PathBuf>,
+
+    /// Treat every value in this profile as a likely word
+    #[arg(long, value_name = "PROFILE_PATH")]
+    pub profile: Option<PathBuf>,
+
+    /// Print results as JSON (one object per line with --stdin)
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct AuditArgs {
+    /// Password to audit (read from stdin if left out, which keeps it out of shell history)
+    #[arg(long)]
+    pub password: Option<String>,
+
+    /// Owner's profile: find the password's rank in a personal attack on it
+    #[arg(long, value_name = "PROFILE_PATH")]
+    pub profile: Option<PathBuf>,
+
+    /// Model from `--train` to give the password's probability
+    #[arg(long, value_name = "MODEL_PATH")]
+    pub model: Option<PathBuf>,
+
+    /// Kind of model --model is
+    #[arg(long, value_enum, default_value_t = ModelType::Markov)]
+    pub model_type: ModelType,
+
+    /// Also match words of this wordlist, most likely first; repeat for more
+    #[arg(long = "dictionary", value_name = "FILE")]
+    pub dictionaries: Vec<PathBuf>,
+
+    /// Print the report as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct PwnedFilterArgs {
+    /// Pwned Passwords SHA-1 corpus (SHA1:COUNT lines) or a list of plain passwords; - for stdin
+    #[arg(long, value_name = "FILE")]
+    pub from: PathBuf,
+
+    /// Where to write the filter
+    #[arg(short, long, value_name = "FILE")]
+    pub out: PathBuf,
+
+    /// Share of clean passwords the filter may report as breached
+    #[arg(long, default_value_t = 0.001, value_name = "P")]
+    pub fp_rate: f64,
+
+    /// Lines in the corpus, to size the filter (default: count them first; required for stdin)
+    #[arg(long, value_name = "N")]
+    pub expected: Option<u64>,
+
+    /// Hash every line as a password, even ones that look like SHA-1 hashes
+    #[arg(long)]
+    pub plain: bool,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum PwnedOnly {
+    /// Print only passwords found in the filter
+    Pwned,
+    /// Print only passwords not in the filter
+    Clean,
+}
+
+#[derive(Args, Debug)]
+pub struct PwnedCheckArgs {
+    /// Filter built by `jigsaw pwned-filter`
+    #[arg(short, long, value_name = "FILE")]
+    pub filter: PathBuf,
+
+    /// Passwords to check
+    #[arg(required_unless_present = "stdin")]
+    pub passwords: Vec<String>,
+
+    /// Check each line of stdin instead (a candidate list, say)
+    #[arg(long, conflicts_with = "passwords")]
+    pub stdin: bool,
+
+    /// Print just the passwords in one state, one per line, to filter a list
+    #[arg(long, value_enum, value_name = "STATE")]
+    pub only: Option<PwnedOnly>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum WordlistCommand {
+    /// Sort the lines of one or more lists in byte order (`LC_ALL=C sort`)
+    Sort {
+        /// Lists to sort together (`-` for stdin)
+        #[arg(required = true)]
+        inputs: Vec<PathBuf>,
+
+        /// Drop repeated lines
+        #[arg(short, long)]
+        unique: bool,
+
+        #[command(flatten)]
+        output: WordlistOutput,
     },
+
+    /// Drop repeated lines, keeping each where it first appears (ranked lists stay ranked)
+    Unique {
+        /// Lists to deduplicate together, in order (`-` for stdin)
+        #[arg(required = true)]
+        inputs: Vec<PathBuf>,
+
+        #[command(flatten)]
+        output: WordlistOutput,
+    },
+
+    /// Merge lists that are already sorted into one sorted, deduplicated list, without re-sorting
+    Merge {
+        /// Sorted lists to merge
+        #[arg(required = true, num_args = 2..)]
+        inputs: Vec<PathBuf>,
+
+        /// Write to a file instead of stdout
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+
+    /// Lines of LEFT that are not in RIGHT, in LEFT's order (e.g. strip already-tried candidates)
+    Diff {
+        /// List to filter
+        left: PathBuf,
+
+        /// Lines to remove from LEFT
+        right: PathBuf,
+
+        /// Print the lines in both lists instead
+        #[arg(long)]
+        common: bool,
+
+        #[command(flatten)]
+        output: WordlistOutput,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum RulesCommand {
+    /// Print what each rule chain makes of each word, one line per word and chain
+    Apply {
+        /// Rule chains, separated by spaces (`:$1 u$!` is two chains)
+        #[arg(short, long, required = true, value_name = "CHAINS")]
+        rules: Vec<String>,
+
+        /// Words to transform
+        #[arg(short, long, required_unless_present = "stdin", value_name = "WORD")]
+        word: Vec<String>,
+
+        /// Transform each line of stdin instead
+        #[arg(long, conflicts_with = "word")]
+        stdin: bool,
+    },
+}
+
+#[derive(Args, Debug)]
+pub struct WordlistOutput {
+    /// Write to a file instead of stdout
+    #[arg(short, long, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+
+    /// Memory budget in MB before sorted runs spill to disk
+    #[arg(long, default_value_t = 256, value_name = "MB")]
+    pub memory: usize,
+}
+
+#[derive(Args, Debug)]
+pub struct DatesArgs {
+    /// First year
+    #[arg(long, default_value_t = 1950, value_name = "YEAR")]
+    pub from: u32,
+
+    /// Last year [default: this year]
+    #[arg(long, value_name = "YEAR")]
+    pub to: Option<u32>,
+
+    /// Date formats, comma-separated: DD/D day, MM/M month, YYYY/YY year,
+    /// Month/MONTH/month and Mon/MON/mon names; anything else is literal (DD.MM.YY)
+    #[arg(long, value_delimiter = ',', value_name = "FORMATS", default_value = crate::engine::dates::DEFAULT_FORMATS)]
+    pub formats: Vec<String>,
+
+    /// Languages for month names (comma-separated)
+    #[arg(long, value_enum, value_delimiter = ',', value_name = "LANG", default_value = "en")]
+    pub locales: Vec<MemLanguage>,
+
+    /// Write to a file instead of stdout
+    #[arg(short, long, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct WorkerArgs {
+    /// Coordinator to take units from, like http://10.0.0.5:8080
+    #[arg(long, value_name = "URL")]
+    pub join: String,
+
+    /// API key, if the coordinator wants one
+    #[arg(long, value_name = "KEY", env = "JIGSAW_API_KEY")]
+    pub api_key: Option<String>,
+
+    /// Name the coordinator reports this worker under (default: host and process ID)
+    #[arg(long, value_name = "NAME")]
+    pub name: Option<String>,
+
+    /// Seconds to wait before asking again when every unit is out with other workers
+    #[arg(long, default_value_t = 5, value_name = "SECS")]
+    pub poll: u64,
+}
+
+#[derive(Args, Debug)]
+pub struct RankArgs {
+    /// Wordlist to reorder, or - for stdin (.gz/.zst/.xz/.zip are read directly)
+    pub wordlist: PathBuf,
+
+    /// Model from `--train` to score candidates with
+    #[arg(long, value_name = "MODEL_PATH")]
+    pub model: PathBuf,
+
+    /// Kind of model --model is
+    #[arg(long, value_enum, default_value_t = ModelType::Markov)]
+    pub model_type: ModelType,
+
+    /// Rank candidates containing this target's names, dates and other values higher
+    #[arg(long, value_name = "PROFILE_PATH")]
+    pub profile: Option<PathBuf>,
+
+    /// How many times likelier a profile value makes a candidate (raised to the value's weight)
+    #[arg(long, default_value_t = 1000.0, value_name = "FACTOR")]
+    pub boost: f64,
+
+    /// Write each score (natural log probability) and a tab before the candidate
+    #[arg(long)]
+    pub scores: bool,
+
+    #[command(flatten)]
+    pub output: WordlistOutput,
+}
+
+/// Candidate filters shared by every mode that writes a wordlist
+#[derive(Args, Debug, Default)]
+pub struct FilterArgs {
+    /// Drop candidates shorter than N bytes, in every mode
+    #[arg(long, value_name = "N")]
+    pub min_length: Option<usize>,
+
+    /// Drop candidates longer than N bytes, in every mode
+    #[arg(long, value_name = "N")]
+    pub max_length: Option<usize>,
+
+    /// Keep only candidates with at least one character of each class (comma-separated)
+    #[arg(long, value_enum, value_delimiter = ',', value_name = "CLASS")]
+    pub require: Vec<CharClass>,
+
+    /// Drop candidates with any character of these classes (comma-separated)
+    #[arg(long, value_enum, value_delimiter = ',', value_name = "CLASS")]
+    pub forbid: Vec<CharClass>,
+
+    /// Keep only candidates made of these characters; ?l ?u ?d ?s stand for their classes (?? for a literal ?)
+    #[arg(long, value_name = "SET")]
+    pub allowed_chars: Option<String>,
+}
+
+impl FilterArgs {
+    /// The filter these flags describe
+    pub fn filter(&self) -> anyhow::Result<Filter> {
+        if let (Some(min), Some(max)) = (self.min_length, self.max_length) {
+            if min > max {
+                return Err(invalid!("--min-length {} is greater than --max-length {}; nothing would be written", min, max));
+            }
+        }
+        if let Some(class) = self.require.iter().find(|class| self.forbid.contains(class)) {
+            let name = class.to_possible_value().map(|v| v.get_name().to_string()).unwrap_or_default();
+            return Err(invalid!("--require and --forbid both name {}; nothing would be written", name));
+        }
+        let class = |class: &CharClass| match class {
+            CharClass::Lower => Class::Lower,
+            CharClass::Upper => Class::Upper,
+            CharClass::Digit => Class::Digit,
+            CharClass::Special => Class::Special,
+        };
+        let mut filter = Filter::new();
+        filter.min_len = self.min_length;
+        filter.max_len = self.max_length;
+        filter.require = self.require.iter().map(class).collect();
+        filter.forbid = self.forbid.iter().map(class).collect();
+        match &self.allowed_chars {
+            Some(set) => filter.allowed_chars(set),
+            None => Ok(filter),
+        }
+    }
+}
+
+/// Charsets for the mask placeholders ?1–?4, and the order masks iterate charsets in
+#[derive(Args, Debug, Default)]
+pub struct CharsetArgs {
+    /// Charset for ?1: characters and ?l ?u ?d ?s, or @FILE to read one (a hashcat .hcchr file)
+    #[arg(short = '1', long, value_name = "CHARSET")]
+    pub custom_charset1: Option<String>,
+
+    /// Charset for ?2; may use ?1
+    #[arg(short = '2', long, value_name = "CHARSET")]
+    pub custom_charset2: Option<String>,
+
+    /// Charset for ?3; may use ?1 and ?2
+    #[arg(short = '3', long, value_name = "CHARSET")]
+    pub custom_charset3: Option<String>,
+
+    /// Charset for ?4; may use ?1–?3
+    #[arg(short = '4', long, value_name = "CHARSET")]
+    pub custom_charset4: Option<String>,
+
+    /// Try each position's likeliest characters first (e t a o i n…, 1 2 0…) instead of a–z, 0–9
+    #[arg(long)]
+    pub frequency_order: bool,
+}
+
+impl CharsetArgs {
+    /// The charsets these flags set, with @FILE references read. Files are
+    /// taken byte for byte, as hashcat reads .hcchr files, so one saved in
+    /// a legacy encoding keeps its single-byte characters.
+    pub fn charsets(&self) -> anyhow::Result<CustomCharsets> {
+        let mut charsets = CustomCharsets::default();
+        let flags = [&self.custom_charset1, &self.custom_charset2, &self.custom_charset3, &self.custom_charset4];
+        for (i, flag) in flags.into_iter().enumerate() {
+            let Some(spec) = flag else { continue };
+            let chars = match spec.strip_prefix('@') {
+                Some(path) => {
+                    let bytes = std::fs::read(path).map_err(|e| invalid!("--custom-charset{} {}: {}", i + 1, path, e))?;
+                    let len = bytes.len() - bytes.iter().rev().take_while(|&&b| b == b'\n' || b == b'\r').count();
+                    mask::dedup(bytes[..len].to_vec())
+                }
+                None => mask::expand_charset(spec, &charsets).map_err(|e| invalid!("--custom-charset{}: {}", i + 1, e))?,
+            };
+            if chars.is_empty() {
+                return Err(invalid!("--custom-charset{} is empty", i + 1));
+            }
+            charsets[i] = Some(chars);
+        }
+        Ok(charsets)
+    }
+
+    /// `pattern` with these charsets, in the order these flags ask for
+    pub fn mask(&self, pattern: &str) -> anyhow::Result<Mask> {
+        let mask = Mask::with_charsets(pattern, &self.charsets()?)?;
+        Ok(if self.frequency_order { mask.frequency_ordered() } else { mask })
+    }
 }
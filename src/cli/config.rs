@@ -0,0 +1,206 @@
+use crate::cli::args::JigsawArgs;
+use crate::invalid;
+use anyhow::{Context, Result};
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory, Parser};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+// ═══════════════════════════════════════════════════════════════
+// CONFIG FILE
+// ═══════════════════════════════════════════════════════════════
+//
+// A TOML file of option defaults, keyed by flag name:
+//
+//     threads = 8
+//     output = "lists/out.txt.gz"
+//     words = 5
+//     mem-sep = "."
+//     skip = ["leet", "combos"]
+//
+//     [server]
+//     port = 9000
+//
+// Top-level keys are the global flags; a table named after a subcommand
+// applies when that subcommand runs. Every value is turned back into a
+// flag and parsed by clap with the rest of the command line, so the file
+// is validated exactly like flags are, and anything given on the command
+// line or in an option's environment variable (or conflicting with
+// either) takes precedence.
+
+/// `$XDG_CONFIG_HOME/jigsaw/config.toml`, falling back to `~/.config`
+/// (`%APPDATA%\jigsaw\config.toml` on Windows)
+pub fn default_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()).map(PathBuf::from);
+    #[cfg(windows)]
+    let base = base.or_else(|| std::env::var_os("APPDATA").map(PathBuf::from));
+    #[cfg(not(windows))]
+    let base = base.or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")));
+    base.map(|dir| dir.join("jigsaw").join("config.toml"))
+}
+
+/// Parse the process arguments with the config file's defaults applied
+pub fn parse() -> Result<JigsawArgs> {
+    parse_from(std::env::args_os().collect())
+}
+
+pub fn parse_from(argv: Vec<OsString>) -> Result<JigsawArgs> {
+    Ok(JigsawArgs::parse_from(resolve(argv)?))
+}
+
+/// `argv` with the config file's defaults added as flags, ready for clap
+pub fn resolve(argv: Vec<OsString>) -> Result<Vec<OsString>> {
+    // A lenient first pass, to find --config and see which flags were given;
+    // --help and --version still stop it, and clap handles those as usual
+    let Ok(probe) = JigsawArgs::command().ignore_errors(true).try_get_matches_from(&argv) else {
+        return Ok(argv);
+    };
+    let path = match probe.get_one::<PathBuf>("config") {
+        Some(path) => path.clone(),
+        None if probe.get_flag("no_config") => return Ok(argv),
+        None => match default_path().filter(|path| path.is_file()) {
+            Some(path) => path,
+            None => return Ok(argv),
+        },
+    };
+    let text = std::fs::read_to_string(&path).with_context(|| format!("can't read config {}", path.display()))?;
+    with_config(argv, &probe, &text, &path)
+}
+
+/// `argv` with a flag added for each config value the command line
+/// doesn't already set. Global flags go first; the running subcommand's
+/// flags go last, after its own arguments.
+fn with_config(argv: Vec<OsString>, probe: &ArgMatches, text: &str, path: &Path) -> Result<Vec<OsString>> {
+    let table: toml::Table = text.parse().map_err(|e| invalid!("{}: {}", path.display(), e))?;
+    let command = JigsawArgs::command();
+
+    let mut global = Vec::new();
+    let mut trailing = Vec::new();
+    for (key, value) in &table {
+        match value {
+            toml::Value::Table(section) => {
+                let Some(subcommand) = command.find_subcommand(key) else {
+                    return Err(invalid!("{}: no subcommand '{}' for [{}]", path.display(), key, key));
+                };
+                if let Some((_, matches)) = probe.subcommand().filter(|(name, _)| name == key) {
+                    for (key, value) in section {
+                        trailing.extend(flags(subcommand, matches, key, value, path)?);
+                    }
+                }
+            }
+            _ => global.extend(flags(&command, probe, key, value, path)?),
+        }
+    }
+
+    let mut argv = argv.into_iter();
+    Ok(argv.next().into_iter().chain(global).chain(argv).chain(trailing).collect())
+}
+
+/// The flags that set `key = value`, or none if the command line already
+/// decides it
+fn flags(command: &clap::Command, matches: &ArgMatches, key: &str, value: &toml::Value, path: &Path) -> Result<Vec<OsString>> {
+    let id = key.replace('-', "_");
+    let Some(arg) = command.get_arguments().find(|arg| arg.get_id() == id.as_str() || arg.get_long() == Some(key)) else {
+        return Err(invalid!("{}: unknown option '{}'", path.display(), key));
+    };
+    let id = arg.get_id().as_str();
+    let long = match arg.get_long() {
+        Some(long) if !matches!(id, "config" | "no_config" | "help" | "version") => long,
+        _ => return Err(invalid!("{}: '{}' can't be set from a config file", path.display(), key)),
+    };
+
+    let given = |id: &str| matches.try_contains_id(id).unwrap_or(false)
+        && matches!(matches.value_source(id), Some(ValueSource::CommandLine | ValueSource::EnvVariable));
+    // Conflicts may be declared on either side
+    let conflicts = |a: &clap::Arg, b: &clap::Arg| command.get_arg_conflicts_with(a).iter().any(|c| c.get_id() == b.get_id());
+    if given(id) || command.get_arguments().any(|other| given(other.get_id().as_str()) && (conflicts(arg, other) || conflicts(other, arg))) {
+        return Ok(Vec::new());
+    }
+
+    if !arg.get_action().takes_values() {
+        return match value {
+            toml::Value::Boolean(true) => Ok(vec![format!("--{}", long).into()]),
+            toml::Value::Boolean(false) => Ok(Vec::new()),
+            _ => return Err(invalid!("{}: '{}' is a switch; use true or false", path.display(), key)),
+        };
+    }
+    let values = match value {
+        toml::Value::Array(items) => items.iter().collect(),
+        value => vec![value],
+    };
+    values.into_iter()
+        .map(|value| {
+            let text = match value {
+                toml::Value::String(text) => text.clone(),
+                toml::Value::Integer(n) => n.to_string(),
+                toml::Value::Float(x) => x.to_string(),
+                toml::Value::Boolean(b) => b.to_string(),
+                toml::Value::Datetime(d) => d.to_string(),
+                toml::Value::Array(_) | toml::Value::Table(_) => return Err(invalid!("{}: '{}' takes plain values", path.display(), key)),
+            };
+            Ok(format!("--{}={}", long, text).into())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::args::{Commands, ServerArgs, SkipCategory};
+
+    fn parse_with(argv: &[&str], config: &str) -> Result<JigsawArgs> {
+        let argv: Vec<OsString> = argv.iter().map(OsString::from).collect();
+        let probe = JigsawArgs::command().ignore_errors(true).try_get_matches_from(&argv)?;
+        let argv = with_config(argv, &probe, config, Path::new("jigsaw.toml"))?;
+        Ok(JigsawArgs::try_parse_from(argv)?)
+    }
+
+    #[test]
+    fn test_config_defaults_and_overrides() {
+        let config = r#"
+            threads = 8
+            output = "out.txt"
+            mem-sep = "."
+            words = 5
+            quiet = true
+            typos = false
+            skip = ["leet", "combos"]
+
+            [server]
+            port = 9000
+            cors-origin = ["https://a.example", "https://b.example"]
+        "#;
+
+        let args = parse_with(&["jigsaw", "--memorable"], config).unwrap();
+        assert_eq!((args.threads, args.words, args.mem_sep.as_str()), (Some(8), 5, "."));
+        assert_eq!(args.output, Some(PathBuf::from("out.txt")));
+        assert!(args.quiet && !args.typos);
+        assert!(matches!(args.skip[..], [SkipCategory::Leet, SkipCategory::Combos]));
+
+        // The command line wins, including over options it conflicts with
+        let args = parse_with(&["jigsaw", "--words", "3", "--pipe-to", "cat"], config).unwrap();
+        assert_eq!((args.words, args.output), (3, None));
+
+        let args = parse_with(&["jigsaw", "server"], config).unwrap();
+        assert!(matches!(args.command, Some(Commands::Server(ServerArgs { port: 9000, .. }))));
+        let args = parse_with(&["jigsaw", "server", "--port", "1234"], config).unwrap();
+        assert!(matches!(args.command, Some(Commands::Server(ServerArgs { port: 1234, .. }))));
+        let Some(Commands::Server(server)) = parse_with(&["jigsaw", "server"], config).unwrap().command else { panic!() };
+        assert_eq!(server.cors_origins, ["https://a.example", "https://b.example"]);
+
+        assert!(parse_with(&["jigsaw"], "colour = true").unwrap_err().to_string().contains("unknown option 'colour'"));
+        assert!(parse_with(&["jigsaw"], "threads = \"many\"").is_err());
+        assert!(parse_with(&["jigsaw"], "quiet = 1").is_err());
+    }
+
+    #[test]
+    fn test_environment_beats_config() {
+        let config = "[server]\nrequest-timeout = 9\n";
+        std::env::set_var("JIGSAW_REQUEST_TIMEOUT", "12");
+        let args = parse_with(&["jigsaw", "server"], config);
+        std::env::remove_var("JIGSAW_REQUEST_TIMEOUT");
+        assert!(matches!(args.unwrap().command, Some(Commands::Server(ServerArgs { request_timeout: 12, .. }))));
+        let args = parse_with(&["jigsaw", "server"], config).unwrap();
+        assert!(matches!(args.command, Some(Commands::Server(ServerArgs { request_timeout: 9, .. }))));
+    }
+}
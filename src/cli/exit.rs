@@ -0,0 +1,116 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+// ═══════════════════════════════════════════════════════════════
+// EXIT CODES
+// ═══════════════════════════════════════════════════════════════
+//
+//   0    success (for --check and pwned-check: a password is in the list)
+//   1    --check, pwned-check: no password is in the list
+//   2    invalid input: flags, mask, profile, config or input files
+//   3    the run failed: disk, network, a --pipe-to command, or any error
+//        not known to be the caller's
+//   130  interrupted (Ctrl-C, SIGTERM, SIGHUP)
+//
+// clap already exits with 2 for bad flags; checks of our own return an
+// `engine::InvalidInput` (the `invalid!` macro) to get 2. An interrupt stops the writer
+// between batches, so the output is flushed and its checkpoint saved
+// before jigsaw exits; a second interrupt exits at once.
+
+pub const SUCCESS: i32 = 0;
+pub const NOT_FOUND: i32 = 1;
+pub const INVALID_INPUT: i32 = 2;
+pub const FAILED: i32 = 3;
+pub const INTERRUPTED: i32 = 130;
+
+/// How long a run gets to wind down after an interrupt
+const GRACE: Duration = Duration::from_secs(5);
+
+static INTERRUPT: AtomicBool = AtomicBool::new(false);
+
+/// Install the interrupt handler for a generation run
+pub fn catch_interrupts() -> anyhow::Result<()> {
+    ctrlc::set_handler(|| {
+        if INTERRUPT.swap(true, Ordering::SeqCst) {
+            std::process::exit(INTERRUPTED);
+        }
+        eprintln!("\n  [!] interrupted; finishing the output (interrupt again to quit now)");
+        // Engines that don't stream through the writer never look at the flag
+        std::thread::spawn(|| {
+            std::thread::sleep(GRACE);
+            std::process::exit(INTERRUPTED);
+        });
+    })?;
+    Ok(())
+}
+
+/// Whether the run was asked to stop
+pub fn interrupted() -> bool {
+    INTERRUPT.load(Ordering::SeqCst)
+}
+
+/// The exit code for a run that ended in `error`: INVALID_INPUT only for
+/// errors known to be the caller's (bad flags, unparsable files, failed
+/// validation, missing inputs), FAILED for anything else
+pub fn code_for(error: &anyhow::Error) -> i32 {
+    if interrupted() {
+        return INTERRUPTED;
+    }
+    for cause in error.chain() {
+        if let Some(io) = cause.downcast_ref::<std::io::Error>() {
+            use std::io::ErrorKind::*;
+            return match io.kind() {
+                // A missing or unreadable input is the caller's to fix
+                NotFound | InvalidData | InvalidInput | UnexpectedEof => INVALID_INPUT,
+                _ => FAILED,
+            };
+        }
+        if let Some(json) = cause.downcast_ref::<serde_json::Error>() {
+            return if json.is_io() { FAILED } else { INVALID_INPUT };
+        }
+        if cause.is::<crate::engine::InvalidInput>()
+            || cause.is::<clap::Error>()
+            || cause.is::<toml::de::Error>()
+            || cause.is::<std::num::ParseIntError>()
+            || cause.is::<std::num::ParseFloatError>()
+        {
+            return INVALID_INPUT;
+        }
+    }
+    FAILED
+}
+
+/// The `status` field of a --stats-json summary
+pub fn describe(code: i32) -> &'static str {
+    match code {
+        SUCCESS => "ok",
+        NOT_FOUND => "not_found",
+        INVALID_INPUT => "invalid_input",
+        INTERRUPTED => "interrupted",
+        _ => "failed",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_exit_codes() {
+        let missing = std::fs::read("/nonexistent/jigsaw").context("reading profile").unwrap_err();
+        assert_eq!(code_for(&missing), INVALID_INPUT);
+        let broken: anyhow::Error = std::io::Error::from(std::io::ErrorKind::StorageFull).into();
+        assert_eq!(code_for(&broken.context("writing output")), FAILED);
+        assert_eq!(code_for(&"?z".parse::<crate::engine::mask::Mask>().unwrap_err()), INVALID_INPUT);
+        assert_eq!(code_for(&crate::invalid!("--order must be at least 1").context("training")), INVALID_INPUT);
+        assert_eq!(code_for(&serde_json::from_str::<u8>("x").unwrap_err().into()), INVALID_INPUT);
+        assert_eq!(code_for(&"x".parse::<usize>().unwrap_err().into()), INVALID_INPUT);
+        let clap = <crate::cli::args::JigsawArgs as clap::Parser>::try_parse_from(["jigsaw", "--no-such-flag"]).unwrap_err();
+        assert_eq!(code_for(&clap.into()), INVALID_INPUT);
+
+        // Anything not known to be the caller's is a failure
+        assert_eq!(code_for(&anyhow::anyhow!("upload thread panicked")), FAILED);
+        assert_eq!([SUCCESS, NOT_FOUND, FAILED, INTERRUPTED].map(describe), ["ok", "not_found", "failed", "interrupted"]);
+    }
+}
@@ -1 +1,6 @@
 pub mod args;
+pub mod status;
+pub mod config;
+pub mod session;
+pub mod exit;
+pub mod summary;
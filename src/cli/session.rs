@@ -0,0 +1,152 @@
+use crate::io::checkpoint;
+use crate::io::writer::Checkpoint;
+use crate::invalid;
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// ═══════════════════════════════════════════════════════════════
+// SESSIONS
+// ═══════════════════════════════════════════════════════════════
+//
+// `--session NAME` records a wordlist run in `~/.jigsaw/sessions/NAME` so
+// `jigsaw restore NAME` can pick it up after a crash or Ctrl-C. A session
+// is the resolved command line (config file applied, random seed pinned)
+// plus the output file's append checkpoint: restoring re-runs the command
+// with --append, which replays the run and skips what's already on disk.
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Session {
+    /// Checkpoint format version; sessions from before versioning have none
+    #[serde(default)]
+    pub version: u32,
+    pub name: String,
+    /// mask, markov or personal
+    pub mode: String,
+    pub output: PathBuf,
+    /// The command line to re-run, config defaults included
+    pub command: Vec<String>,
+    /// Resolved generation parameters, as --manifest records them
+    pub parameters: serde_json::Value,
+    /// Lines in the output as of the last update
+    pub lines: u64,
+    pub finished: bool,
+    /// Unix timestamps, seconds
+    pub created: u64,
+    pub updated: u64,
+}
+
+/// `~/.jigsaw` (`%USERPROFILE%\.jigsaw` on Windows), the user's own state
+pub fn state_dir() -> Result<PathBuf> {
+    #[cfg(windows)]
+    let home = std::env::var_os("USERPROFILE");
+    #[cfg(not(windows))]
+    let home = std::env::var_os("HOME");
+    let home = home.filter(|home| !home.is_empty()).ok_or_else(|| anyhow!("no home directory for ~/.jigsaw"))?;
+    Ok(PathBuf::from(home).join(".jigsaw"))
+}
+
+/// `~/.jigsaw/sessions`
+pub fn dir() -> Result<PathBuf> {
+    Ok(state_dir()?.join("sessions"))
+}
+
+/// Session names become file names, so keep them to one plain component
+fn check_name(name: &str) -> Result<()> {
+    let plain = name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if name.is_empty() || name.starts_with('.') || !plain {
+        return Err(invalid!("session names use letters, digits, '-', '_' and '.' (got '{}')", name));
+    }
+    Ok(())
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+impl Session {
+    pub fn new(name: &str, mode: &str, output: PathBuf, command: Vec<String>, parameters: serde_json::Value) -> Result<Self> {
+        check_name(name)?;
+        Ok(Self {
+            version: checkpoint::VERSION,
+            name: name.to_string(),
+            mode: mode.to_string(),
+            output,
+            command,
+            parameters,
+            lines: 0,
+            finished: false,
+            created: now(),
+            updated: now(),
+        })
+    }
+
+    pub fn load(dir: &Path, name: &str) -> Result<Self> {
+        check_name(name)?;
+        let path = dir.join(name);
+        let text = std::fs::read_to_string(&path).with_context(|| format!("no session '{}' ({})", name, path.display()))?;
+        let session: Self = serde_json::from_str(&text).map_err(|e| invalid!("{}: {}", path.display(), e))?;
+        checkpoint::check_version(session.version, &path)?;
+        Ok(session)
+    }
+
+    /// Every saved session, oldest first
+    pub fn list(dir: &Path) -> Result<Vec<Self>> {
+        let mut sessions = Vec::new();
+        let entries = match std::fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(sessions),
+            Err(e) => return Err(e.into()),
+        };
+        for entry in entries {
+            let name = entry?.file_name();
+            if let Some(session) = name.to_str().and_then(|name| Self::load(dir, name).ok()) {
+                sessions.push(session);
+            }
+        }
+        sessions.sort_by_key(|session| session.created);
+        Ok(sessions)
+    }
+
+    pub fn save(&mut self, dir: &Path) -> Result<()> {
+        self.updated = now();
+        std::fs::create_dir_all(dir)?;
+        self.version = checkpoint::VERSION;
+        checkpoint::write_atomic(&dir.join(&self.name), (serde_json::to_string_pretty(self)? + "\n").as_bytes())?;
+        Ok(())
+    }
+
+    /// Lines on disk, from the output's checkpoint when there is one
+    pub fn progress(&self) -> u64 {
+        Checkpoint::load(&self.output).map_or(self.lines, |checkpoint| checkpoint.lines)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("jigsaw_sessions_{}", std::process::id()));
+        let output = dir.join("out.txt");
+        let command = vec!["jigsaw".to_string(), "--mask".to_string(), "?d?d".to_string()];
+        let mut session = Session::new("nightly-1", "mask", output.clone(), command, serde_json::json!({ "mode": "mask" })).unwrap();
+        session.save(&dir).unwrap();
+        assert_eq!(Session::load(&dir, "nightly-1").unwrap(), session);
+        assert_eq!(Session::list(&dir).unwrap(), vec![session.clone()]);
+
+        // Progress follows the output's checkpoint once there is one
+        assert_eq!(session.progress(), 0);
+        std::fs::write(crate::io::writer::checkpoint_path(&output), "consumed=12\nlines=10\nbytes=30\n").unwrap();
+        assert_eq!(session.progress(), 10);
+
+        for name in ["", "../x", ".hidden", "a/b"] {
+            assert!(Session::load(&dir, name).is_err(), "{}", name);
+        }
+        assert!(Session::load(&dir, "missing").unwrap_err().to_string().contains("no session 'missing'"));
+        assert!(Session::list(&dir.join("none")).unwrap().is_empty());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,30 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// ═══════════════════════════════════════════════════════════════
+// STATUS OUTPUT
+// ═══════════════════════════════════════════════════════════════
+//
+// Banners, progress and timings are for the person at the terminal, so
+// they go to stderr and never mix into a wordlist written to stdout.
+// Results (the wordlist, JSON documents, --check answers) stay on stdout.
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Silence `status!` for the rest of the run (--quiet)
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// `eprintln!` for human-facing status, muted by --quiet
+#[macro_export]
+macro_rules! status {
+    ($($arg:tt)*) => {
+        if !$crate::cli::status::is_quiet() {
+            eprintln!($($arg)*);
+        }
+    };
+}
@@ -0,0 +1,111 @@
+use super::exit;
+use crate::io::writer::Stats;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+// ═══════════════════════════════════════════════════════════════
+// RUN SUMMARY
+// ═══════════════════════════════════════════════════════════════
+//
+// `--stats-json PATH` writes one JSON document per run, success or not,
+// for CI and orchestration scripts: what ran, how it ended (the same
+// status as the exit code), how much it produced and where it went.
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub mode: String,
+    /// Resolved generation parameters, as --manifest records them
+    pub parameters: serde_json::Value,
+    /// ok, not_found, invalid_input, failed or interrupted
+    pub status: String,
+    pub exit_code: i32,
+    pub error: Option<String>,
+    /// Candidates written (or printed, for password modes)
+    pub candidates: u64,
+    /// Candidates dropped as repeats
+    pub duplicates: u64,
+    /// Candidates dropped by the length, class and character filters
+    pub filtered: u64,
+    /// Bytes written to the output (after compression)
+    pub bytes: u64,
+    pub duration_ms: u128,
+    /// Files the run wrote: outputs, rolled files, sidecars
+    pub outputs: Vec<PathBuf>,
+    /// Unix timestamp, seconds
+    pub started: u64,
+    #[serde(skip)]
+    clock: Instant,
+}
+
+impl Summary {
+    pub fn new(mode: &str, parameters: serde_json::Value) -> Self {
+        Self {
+            mode: mode.to_string(),
+            parameters,
+            status: exit::describe(exit::SUCCESS).to_string(),
+            exit_code: exit::SUCCESS,
+            error: None,
+            candidates: 0,
+            duplicates: 0,
+            filtered: 0,
+            bytes: 0,
+            duration_ms: 0,
+            outputs: Vec::new(),
+            started: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+            clock: Instant::now(),
+        }
+    }
+
+    /// Count what a writer thread got through
+    pub fn add(&mut self, stats: &Stats) {
+        self.candidates += stats.candidates;
+        self.duplicates += stats.duplicates;
+        self.filtered += stats.filtered;
+        self.bytes += stats.bytes;
+        for file in &stats.files {
+            self.output(file);
+        }
+    }
+
+    pub fn output(&mut self, path: &Path) {
+        if !self.outputs.iter().any(|known| known == path) {
+            self.outputs.push(path.to_path_buf());
+        }
+    }
+
+    /// Set how the run ended
+    pub fn finish(&mut self, code: i32, error: Option<&anyhow::Error>) {
+        self.exit_code = code;
+        self.status = exit::describe(code).to_string();
+        self.error = error.map(|e| format!("{:#}", e));
+        self.duration_ms = self.clock.elapsed().as_millis();
+    }
+
+    pub fn write(&self, path: &Path) -> anyhow::Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)? + "\n")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary() {
+        let mut summary = Summary::new("mask", serde_json::json!({ "mode": "mask", "mask": "?d" }));
+        let stats = Stats { candidates: 10, duplicates: 2, bytes: 20, files: vec![PathBuf::from("out.txt")], ..Default::default() };
+        summary.add(&stats);
+        summary.add(&stats);
+        summary.finish(exit::FAILED, Some(&anyhow::anyhow!("disk full").context("writing out.txt")));
+
+        let json: serde_json::Value = serde_json::to_value(&summary).unwrap();
+        assert_eq!((json["candidates"].as_u64(), json["duplicates"].as_u64()), (Some(20), Some(4)));
+        assert_eq!(json["outputs"], serde_json::json!(["out.txt"]));
+        assert_eq!((json["status"].as_str(), json["exit_code"].as_i64()), (Some("failed"), Some(3)));
+        assert_eq!(json["error"], "writing out.txt: disk full");
+        assert_eq!(json["parameters"]["mask"], "?d");
+        assert!(json.get("clock").is_none());
+    }
+}
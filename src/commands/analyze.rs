@@ -0,0 +1,93 @@
+use crate::cli::args::AnalyzeArgs;
+use crate::engine::analyze::{self, Analysis, Count};
+use crate::engine::mask::Mask;
+use crate::io::reader;
+use crate::invalid;
+use std::io::BufRead;
+use std::str::FromStr;
+
+pub fn run(args: &AnalyzeArgs) -> anyhow::Result<()> {
+    let start_time = std::time::Instant::now();
+    let mut analysis = Analysis::new();
+    for line in reader::open(&args.wordlist)?.split(b'\n') {
+        let line = line?;
+        let word = line.strip_suffix(b"\r").unwrap_or(&line);
+        if !word.is_empty() {
+            analysis.add(word);
+        }
+    }
+    if analysis.words == 0 {
+        return Err(invalid!("{:?} has no passwords to analyze", args.wordlist));
+    }
+
+    let lengths: Vec<Count> = analysis.lengths.iter()
+        .map(|(len, &count)| Count { value: len.to_string(), count, percent: analysis.percent(count) })
+        .collect();
+    let charsets = analysis.top(&analysis.charsets, args.top);
+    let masks = analysis.top(&analysis.masks, args.top.max(args.emit));
+    let prefixes = analysis.top(&analysis.prefixes, args.top.max(args.emit));
+    let suffixes = analysis.top(&analysis.suffixes, args.top.max(args.emit));
+
+    if let Some(path) = &args.masks {
+        let lines: String = masks.iter().take(args.emit).map(|row| format!("{}\n", row.value)).collect();
+        std::fs::write(path, lines)?;
+        eprintln!("  ✓ Wrote {} masks to {:?}", masks.len().min(args.emit), path);
+    }
+    if let Some(path) = &args.rules {
+        let mut rules: Vec<(u64, String)> = suffixes.iter().map(|row| (row.count, analyze::append_rule(&row.value)))
+            .chain(prefixes.iter().map(|row| (row.count, analyze::prepend_rule(&row.value))))
+            .collect();
+        rules.sort_by_key(|(count, _)| std::cmp::Reverse(*count));
+        rules.truncate(args.emit);
+        let lines: String = rules.iter().map(|(_, rule)| format!("{}\n", rule)).collect();
+        std::fs::write(path, lines)?;
+        eprintln!("  ✓ Wrote {} rules to {:?}", rules.len(), path);
+    }
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&serde_json::json!({
+            "words": analysis.words,
+            "unmaskable": analysis.unmaskable,
+            "lengths": lengths,
+            "charsets": charsets,
+            "masks": &masks[..masks.len().min(args.top)],
+            "prefixes": &prefixes[..prefixes.len().min(args.top)],
+            "suffixes": &suffixes[..suffixes.len().min(args.top)],
+        }))?);
+        return Ok(());
+    }
+
+    println!("\n  ── Wordlist Analysis ──\n");
+    println!("  Source:   {:?}", args.wordlist);
+    println!("  Words:    {}", analysis.words);
+    if analysis.unmaskable > 0 {
+        println!("  Skipped:  {} with characters no mask covers (left out of charsets, masks and affixes)", analysis.unmaskable);
+    }
+    print_table("LENGTH", &lengths, false);
+    print_table("CHARSET", &charsets, false);
+    print_table("MASK", &masks[..masks.len().min(args.top)], true);
+    print_table("PREFIX", &prefixes[..prefixes.len().min(args.top)], false);
+    print_table("SUFFIX", &suffixes[..suffixes.len().min(args.top)], false);
+    println!("\n  Analyzed in {}ms\n", start_time.elapsed().as_millis());
+    Ok(())
+}
+
+/// One ranked table; rows that are masks can show their keyspace
+fn print_table(title: &str, rows: &[Count], masks: bool) {
+    if rows.is_empty() {
+        return;
+    }
+    println!();
+    if masks {
+        println!("  {:<28} {:>10} {:>8} {:>16}", title, "COUNT", "%", "KEYSPACE");
+    } else {
+        println!("  {:<28} {:>10} {:>8}", title, "COUNT", "%");
+    }
+    for row in rows {
+        let line = format!("  {:<28} {:>10} {:>7.2}%", row.value, row.count, row.percent);
+        match Mask::from_str(&row.value).ok().filter(|_| masks) {
+            Some(mask) => println!("{} {:>16}", line, mask.search_space_size()),
+            None => println!("{}", line),
+        }
+    }
+}
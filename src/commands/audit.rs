@@ -0,0 +1,92 @@
+use super::strength::{describe, SCORES};
+use crate::cli::args::AuditArgs;
+use crate::engine::audit::{self, Audit};
+use crate::engine::personal::Profile;
+use crate::engine::strength::{Estimator, SCENARIOS};
+use crate::io::reader;
+use crate::invalid;
+use std::io::BufRead;
+
+pub fn run(args: &AuditArgs) -> anyhow::Result<()> {
+    let password = match &args.password {
+        Some(password) => password.clone(),
+        None => {
+            let mut line = String::new();
+            std::io::stdin().lock().read_line(&mut line)?;
+            line.trim_end_matches(['\r', '\n']).to_string()
+        }
+    };
+    if password.is_empty() {
+        return Err(invalid!("no password to audit (pass --password or one line on stdin)"));
+    }
+
+    let mut estimator = Estimator::new();
+    for path in &args.dictionaries {
+        let name = path.file_stem().map_or_else(|| "custom".to_string(), |stem| stem.to_string_lossy().into_owned());
+        estimator = estimator.with_dictionary(&name, reader::read_words(path)?);
+    }
+    let profile = args.profile.as_deref().map(Profile::load).transpose()?;
+    if let Some(profile) = &profile {
+        estimator = estimator.with_profile(profile);
+    }
+    let log_probability = match &args.model {
+        Some(path) => Some(crate::CandidateModel::load(args.model_type, path)?.log_probability(&password)),
+        None => None,
+    };
+
+    let report = audit::audit(&password, &estimator, profile.as_ref(), log_probability);
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+    print_report(&report);
+    Ok(())
+}
+
+fn print_report(report: &Audit) {
+    let strength = &report.strength;
+    println!("\n  ── Password Audit ──\n");
+    println!("  Length:   {} characters", report.length);
+    println!("  Score:    {}/4 ({})", strength.score, SCORES[strength.score as usize]);
+    println!("  Entropy:  {:.1} bits (10^{:.1} guesses)", strength.bits(), strength.guesses_log10);
+
+    println!("\n  Attacks:");
+    match &report.personal {
+        Some(hit) => match hit.rank {
+            Some(rank) => println!("    {:<16} guess #{} of up to {}", "personal", rank, hit.keyspace),
+            None => println!("    {:<16} not among the profile's {} candidates", "personal", hit.keyspace),
+        },
+        None => println!("    {:<16} (no --profile)", "personal"),
+    }
+    match &report.mask {
+        Some(cover) => println!("    {:<16} {} covers it: {:.2e} candidates, {} {}", "mask", cover.mask, cover.keyspace,
+            crate::human_duration(cover.keyspace / SCENARIOS[SCENARIOS.len() - 1].1), SCENARIOS[SCENARIOS.len() - 1].0),
+        None => println!("    {:<16} no ?l?u?d?s mask covers it", "mask"),
+    }
+    match report.model.as_ref().map(|model| (model.log_probability, model.expected_draws)) {
+        Some((Some(log_probability), Some(draws))) =>
+            println!("    {:<16} probability e^{:.1}, about {:.2e} draws", "model", log_probability, draws),
+        Some(_) => println!("    {:<16} the model can't write it", "model"),
+        None => println!("    {:<16} (no --model)", "model"),
+    }
+
+    println!("\n  Time to crack:");
+    for (scenario, rate) in SCENARIOS {
+        println!("    {:<30} {}", scenario, crate::human_duration(strength.crack_seconds(rate)));
+    }
+
+    if !strength.matches.is_empty() {
+        println!("\n  {:<20} {:<36} {:>10}", "PART", "PATTERN", "GUESSES");
+        for m in &strength.matches {
+            println!("  {:<20} {:<36} {:>10}", m.token, describe(m), format!("10^{:.1}", m.guesses.log10()));
+        }
+    }
+
+    if !report.suggestions.is_empty() {
+        println!("\n  Suggestions:");
+        for suggestion in &report.suggestions {
+            println!("  [!] {}", suggestion);
+        }
+    }
+    println!();
+}
@@ -0,0 +1,169 @@
+use crate::cli::args::BenchArgs;
+use crate::engine::markov::MarkovModel;
+use crate::engine::mask::Mask;
+use crate::engine::memorable::{self, MemorableConfig};
+use crate::engine::rules::RuleSet;
+use crate::io::writer::{self, BatchSender, Output as WriterOutput, Writer};
+use crate::invalid;
+use rayon::prelude::*;
+use std::hint::black_box;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// Candidates generated per parallel round between clock checks
+const ROUND: u64 = 50_000;
+
+pub fn run(args: &BenchArgs) -> anyhow::Result<()> {
+    if !args.seconds.is_finite() || args.seconds <= 0.0 {
+        return Err(invalid!("--seconds must be positive (got {})", args.seconds));
+    }
+    let budget = Duration::from_secs_f64(args.seconds);
+    let threads = if args.threads.is_empty() { default_threads() } else { args.threads.clone() };
+    if threads.contains(&0) || args.batch_sizes.contains(&0) {
+        return Err(invalid!("thread counts and batch sizes start at 1"));
+    }
+
+    let mask = Mask::from_str("?l?l?l?l?l?l?d?d")?;
+    let rules = RuleSet::from_str("u$1$!")?;
+    let model = match &args.model {
+        Some(path) => MarkovModel::load(path)?,
+        None => sample_model()?,
+    };
+
+    eprintln!("  Benchmarking {:.1}s per case...\n", args.seconds);
+    println!("  {:<18} {:>8} {:>8} {:>16}", "BENCHMARK", "THREADS", "BATCH", "CANDIDATES/S");
+
+    let mut rows = Vec::new();
+    for &n in &threads {
+        rows.push(("mask iteration", n, measure(n, budget, |i| {
+            black_box(mask.nth_candidate(i as u128 % mask.search_space_size()));
+        })?));
+    }
+    // Mask runs build whole batches, a writer batch at a time
+    let batch = writer::DEFAULT_BATCH_SIZE;
+    for &n in &threads {
+        let batches = measure(n, budget, |i| {
+            let mut candidates = Vec::with_capacity(batch);
+            mask.fill_batch(i as u128 * batch as u128 % mask.search_space_size(), batch, &mut candidates);
+            black_box(candidates);
+        })?;
+        rows.push(("mask batches", n, batches * batch as f64));
+    }
+    // --append runs masks in order on one thread, refilling the batches
+    // the writer hands back
+    let start = Instant::now();
+    let mut done = 0u64;
+    let spare = std::cell::Cell::new(Vec::new());
+    for candidates in mask.batches_from(0, batch, || spare.take()) {
+        done += black_box(&candidates).len() as u64;
+        spare.set(candidates);
+        if done.is_multiple_of(ROUND) && start.elapsed() >= budget {
+            break;
+        }
+    }
+    rows.push(("mask (sequential)", 1, done as f64 / start.elapsed().as_secs_f64()));
+    print_rows(&mut rows, false);
+
+    let words: Vec<Vec<u8>> = (0..1000u32).map(|i| format!("pass{:04}", i).into_bytes()).collect();
+    for &n in &threads {
+        rows.push(("rule application", n, measure(n, budget, |i| {
+            let mut candidate = words[i as usize % words.len()].clone();
+            rules.apply(&mut candidate);
+            black_box(candidate);
+        })?));
+    }
+    print_rows(&mut rows, false);
+
+    for &n in &threads {
+        rows.push(("markov generation", n, measure(n, budget, |_| {
+            black_box(model.generate(&mut rand::rng(), 6, 12));
+        })?));
+    }
+    print_rows(&mut rows, false);
+
+    // The writer is one thread whatever --threads says; batch size is its knob
+    let mut batches = Vec::new();
+    for &size in &args.batch_sizes {
+        batches.push(("writer (plain)", size, writer_rate(&words, size, budget)?));
+    }
+    print_rows(&mut batches, true);
+
+    println!();
+    println!("  Use the fastest --threads for your mode, and a --batch-size near the writer's best.");
+    Ok(())
+}
+
+/// 1, 2, 4, ... and the CPU count
+fn default_threads() -> Vec<usize> {
+    let cpus = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let mut threads: Vec<usize> = std::iter::successors(Some(1), |n| Some(n * 2)).take_while(|&n| n < cpus).collect();
+    threads.push(cpus);
+    threads
+}
+
+/// Candidates per second from running `generate(index)` on `threads`
+/// threads in rounds until `budget` is spent
+fn measure(threads: usize, budget: Duration, generate: impl Fn(u64) + Sync) -> anyhow::Result<f64> {
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build()?;
+    Ok(pool.install(|| {
+        let start = Instant::now();
+        let mut done = 0;
+        while start.elapsed() < budget {
+            (done..done + ROUND).into_par_iter().for_each(&generate);
+            done += ROUND;
+        }
+        done as f64 / start.elapsed().as_secs_f64()
+    }))
+}
+
+/// Candidates per second through a writer into the null device
+fn writer_rate(words: &[Vec<u8>], batch_size: usize, budget: Duration) -> anyhow::Result<f64> {
+    let sink = std::path::PathBuf::from(if cfg!(windows) { "NUL" } else { "/dev/null" });
+    let (sender, receiver) = writer::channel(writer::DEFAULT_CHANNEL_CAPACITY);
+    let handle = Writer::new(receiver, WriterOutput::File(sink)).start();
+    let mut batcher = BatchSender::new(sender, batch_size);
+    let start = Instant::now();
+    'timed: while start.elapsed() < budget {
+        for word in words {
+            if batcher.push(word.clone()).is_err() {
+                break 'timed;
+            }
+        }
+    }
+    drop(batcher);
+    let stats = handle.join().expect("Writer panic")?;
+    Ok(stats.candidates as f64 / start.elapsed().as_secs_f64())
+}
+
+/// A model trained on generated passphrases, for machines without one
+fn sample_model() -> anyhow::Result<MarkovModel> {
+    let config = MemorableConfig::default();
+    let mut rng = memorable::seeded_rng(0);
+    let mut corpus = String::new();
+    for _ in 0..5_000 {
+        corpus.push_str(&memorable::generate_with_rng(&mut rng, &config)?);
+        corpus.push('\n');
+    }
+    let mut model = MarkovModel::new(3);
+    model.train_reader(corpus.as_bytes())?;
+    Ok(model)
+}
+
+/// Print and clear one benchmark's rows, starring the fastest
+fn print_rows(rows: &mut Vec<(&str, usize, f64)>, by_batch: bool) {
+    let best = rows.iter().map(|row| row.2).fold(0.0, f64::max);
+    for (name, n, rate) in rows.drain(..) {
+        let (threads, batch) = if by_batch { ("1".to_string(), n.to_string()) } else { (n.to_string(), "-".to_string()) };
+        let star = if rate == best { " *" } else { "" };
+        println!("  {:<18} {:>8} {:>8} {:>16}{}", name, threads, batch, human_rate(rate), star);
+    }
+}
+
+fn human_rate(rate: f64) -> String {
+    match rate {
+        r if r >= 1e9 => format!("{:.2}G", r / 1e9),
+        r if r >= 1e6 => format!("{:.2}M", r / 1e6),
+        r if r >= 1e3 => format!("{:.1}K", r / 1e3),
+        r => format!("{:.0}", r),
+    }
+}
@@ -0,0 +1,21 @@
+use crate::cli::args::CaseArgs;
+use crate::engine::case;
+use crate::io::reader;
+use crate::invalid;
+
+pub fn run(args: &CaseArgs) -> anyhow::Result<()> {
+    let max_letters = args.max_letters;
+    if max_letters > case::MAX_LETTERS {
+        return Err(invalid!("--max-letters can be at most {} (got {})", case::MAX_LETTERS, max_letters));
+    }
+    let (words, skipped): (Vec<String>, Vec<String>) = reader::read_words(&args.input)?
+        .into_iter()
+        .partition(|word| case::letters(word) <= max_letters);
+    let keyspace = words.iter().fold(0u64, |total, word| total.saturating_add(1 << case::letters(word)));
+    eprintln!("  Permuting the case of {} words from {:?}: {} candidates", words.len(), args.input, keyspace);
+    if !skipped.is_empty() {
+        eprintln!("  [!] Skipped {} words with more than {} letters (raise --max-letters to include them)", skipped.len(), max_letters);
+    }
+
+    super::run_words(&words, &args.filter, args.limit, args.threads, &args.output, |word, emit| case::permutations(word, emit))
+}
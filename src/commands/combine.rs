@@ -0,0 +1,37 @@
+use crate::cli::args::CombineArgs;
+use crate::engine::combinator::Combinator;
+use crate::engine::rules::RuleSet;
+use crate::io::reader;
+use crate::invalid;
+use rayon::prelude::*;
+use std::str::FromStr;
+
+pub fn run(args: &CombineArgs) -> anyhow::Result<()> {
+    let start_time = std::time::Instant::now();
+    if reader::is_stdin(&args.left) && reader::is_stdin(&args.right) {
+        return Err(invalid!("only one side of combine can read stdin"));
+    }
+    let rule = |chain: &Option<String>| chain.as_deref().map(RuleSet::from_str).transpose();
+    let (rule_left, rule_right) = (rule(&args.rule_left)?, rule(&args.rule_right)?);
+    let words = |path| -> anyhow::Result<Vec<Vec<u8>>> {
+        Ok(reader::read_words(path)?.into_iter().map(String::into_bytes).collect())
+    };
+    let separators = args.separators.iter().map(|s| s.clone().into_bytes()).collect();
+    let combinator = Combinator::new(words(&args.left)?, words(&args.right)?, separators)
+        .with_rules(rule_left.as_ref(), rule_right.as_ref());
+    eprintln!("  Combining {:?} × {:?}: {} candidates", args.left, args.right, combinator.keyspace());
+
+    super::write_candidates(&args.filter, args.limit, args.threads, &args.output, start_time, |batcher| {
+        // A failed push means the writer is done; write_candidates says why
+        let _ = if args.limit.is_some() {
+            // Sequential, so --limit keeps the start of the keyspace
+            let mut batcher = batcher;
+            combinator.iter().try_for_each(|candidate| batcher.push(candidate))
+        } else {
+            (0..combinator.rows()).into_par_iter().try_for_each_init(
+                || batcher.clone(),
+                |batcher, row| combinator.row(row).try_for_each(|candidate| batcher.push(candidate)),
+            )
+        };
+    })
+}
@@ -0,0 +1,35 @@
+use crate::cli::args::DatesArgs;
+use crate::engine::dates::{Dates, Format};
+use crate::engine::personal::CURRENT_YEAR;
+use crate::io::writer::{self, BatchSender, Output as WriterOutput, Writer};
+use crate::invalid;
+use std::str::FromStr;
+
+pub fn run(args: &DatesArgs) -> anyhow::Result<()> {
+    let start_time = std::time::Instant::now();
+    let to = args.to.unwrap_or(CURRENT_YEAR);
+    if args.from > to || to > 9999 {
+        return Err(invalid!("--from must be at most --to, and years at most 9999 (got {} and {})", args.from, to));
+    }
+    let formats = args.formats.iter()
+        .filter(|format| !format.is_empty())
+        .map(|format| Format::from_str(format))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let languages = args.locales.iter().map(|&locale| crate::language(locale)).collect();
+    let dates = Dates::new(args.from, to, formats, languages);
+
+    let (sender, receiver) = writer::channel(writer::DEFAULT_CHANNEL_CAPACITY);
+    let output = match &args.output {
+        Some(path) => WriterOutput::File(path.clone()),
+        None => WriterOutput::Stdout,
+    };
+    let writer_thread = Writer::new(receiver, output).start();
+    let mut batcher = BatchSender::new(sender, writer::DEFAULT_BATCH_SIZE);
+    // A closed channel means the writer failed; joining it says why
+    dates.for_each(|token| batcher.push(token.as_bytes().to_vec()).is_err());
+
+    drop(batcher);
+    let stats = writer_thread.join().expect("Writer panic")?;
+    eprintln!("  Wrote {} date tokens for {}–{} in {}ms", stats.candidates, args.from, to, start_time.elapsed().as_millis());
+    Ok(())
+}
@@ -0,0 +1,15 @@
+use crate::cli::args::LeetArgs;
+use crate::engine::leet::LeetMap;
+use crate::io::reader;
+
+pub fn run(args: &LeetArgs) -> anyhow::Result<()> {
+    let map = match &args.map {
+        Some(path) => LeetMap::load(path)?,
+        None => LeetMap::default(),
+    };
+    let words = reader::read_words(&args.input)?;
+    let keyspace = words.iter().fold(0u64, |total, word| total.saturating_add(map.count(word, args.max_subs)));
+    eprintln!("  Expanding {} words from {:?}, up to {} swaps each: {} candidates", words.len(), args.input, args.max_subs, keyspace);
+
+    super::run_words(&words, &args.filter, args.limit, args.threads, &args.output, |word, emit| map.expand(word, args.max_subs, emit))
+}
@@ -0,0 +1,16 @@
+use crate::cli::args::MangleArgs;
+use crate::engine::personal::{Mangler, Profile};
+use crate::io::reader;
+
+pub fn run(args: &MangleArgs) -> anyhow::Result<()> {
+    let mut profile = match &args.profile {
+        Some(path) => Profile::load(path)?,
+        None => Profile::new(),
+    };
+    profile.skip.extend(args.skip.iter().map(|category| category.category()));
+    let words = reader::read_words(&args.input)?;
+    let mangler = Mangler::new(profile);
+    eprintln!("  Mangling {} words from {:?} with {} suffixes", words.len(), args.input, mangler.suffixes());
+
+    super::run_words(&words, &args.filter, args.limit, args.threads, &args.output, |word, emit| mangler.mangle(word, emit))
+}
@@ -0,0 +1,92 @@
+pub mod analyze;
+pub mod audit;
+pub mod bench;
+pub mod case;
+pub mod combine;
+pub mod dates;
+pub mod leet;
+pub mod mangle;
+pub mod profile;
+pub mod pwned;
+pub mod rank;
+pub mod rules;
+pub mod scrape;
+pub mod strength;
+pub mod wordlist;
+pub mod worker;
+
+use crate::cli::args::FilterArgs;
+use crate::io::writer::{self, BatchSender, Output as WriterOutput, Writer};
+use rayon::prelude::*;
+use std::path::PathBuf;
+use std::time::Instant;
+
+/// Shared driver for the word-expansion subcommands (case, leet, mangle):
+/// streams each word's candidates from `expand` to `output`. `expand`
+/// hands every candidate to its callback and returns true once the
+/// callback asks it to stop.
+pub fn run_words<F>(
+    words: &[String],
+    filter: &FilterArgs,
+    limit: Option<u64>,
+    threads: Option<usize>,
+    output: &Option<PathBuf>,
+    expand: F,
+) -> anyhow::Result<()>
+where
+    F: Fn(&str, &mut dyn FnMut(String) -> bool) -> bool + Sync,
+{
+    write_candidates(filter, limit, threads, output, Instant::now(), |batcher| {
+        if limit.is_some() {
+            // Sequential, so --limit keeps the first words' variants
+            let mut batcher = batcher;
+            for word in words {
+                if expand(word, &mut |s| batcher.push(s.into_bytes()).is_err()) {
+                    break;
+                }
+            }
+        } else {
+            words.par_iter().for_each_init(
+                || batcher.clone(),
+                |batcher, word| { expand(word, &mut |s| batcher.push(s.into_bytes()).is_err()); },
+            );
+        }
+    })
+}
+
+/// The writer side of the streaming subcommands: sizes the thread pool,
+/// starts a writer on `output` with the filter and --limit, hands `produce`
+/// a batcher to push candidates to, then reports what was written (timed
+/// from `started`). A push fails once the writer hits --limit or fails, so
+/// `produce` can just stop; joining the writer says which it was.
+pub fn write_candidates(
+    filter: &FilterArgs,
+    limit: Option<u64>,
+    threads: Option<usize>,
+    output: &Option<PathBuf>,
+    started: Instant,
+    produce: impl FnOnce(BatchSender),
+) -> anyhow::Result<()> {
+    if let Some(threads) = threads {
+        rayon::ThreadPoolBuilder::new().num_threads(threads).build_global()?;
+    }
+
+    let (sender, receiver) = writer::channel(writer::DEFAULT_CHANNEL_CAPACITY);
+    let output = match output {
+        Some(path) => WriterOutput::File(path.clone()),
+        None => WriterOutput::Stdout,
+    };
+    let mut writer = Writer::new(receiver, output).filter(filter.filter()?);
+    if let Some(n) = limit {
+        writer = writer.limit(n);
+    }
+    let writer_thread = writer.start();
+    produce(BatchSender::new(sender, writer::DEFAULT_BATCH_SIZE));
+
+    let stats = writer_thread.join().expect("Writer panic")?;
+    eprintln!("  Wrote {} candidates in {}ms", stats.candidates, started.elapsed().as_millis());
+    if stats.filtered > 0 {
+        eprintln!("  Filtered out {} candidates", stats.filtered);
+    }
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use crate::cli::args::{ProfileCommand, ProfileTemplate};
+use crate::engine::templates::{self, Template};
+use crate::engine::validate;
+use crate::io::import;
+use crate::invalid;
+use std::collections::HashSet;
+
+pub fn run(action: &ProfileCommand) -> anyhow::Result<()> {
+    match action {
+        ProfileCommand::New { template, out, force } => {
+            let template = match template {
+                ProfileTemplate::Corporate => Template::Corporate,
+                ProfileTemplate::Gamer => Template::Gamer,
+                ProfileTemplate::Family => Template::Family,
+            };
+            let path = out.clone().unwrap_or_else(|| {
+                format!("{:?}.json", template).to_lowercase().into()
+            });
+            if path.exists() && !force {
+                return Err(invalid!("{:?} already exists (use --force to overwrite)", path));
+            }
+
+            templates::build(template).save(&path)?;
+            println!("  ✓ Created {:?} from the {:?} template.", path, template);
+            println!("    Replace the example values, then run: jigsaw --profile {:?}", path);
+            Ok(())
+        }
+        ProfileCommand::Import { from, out_dir, map } => {
+            println!("\n  ── Profile Import ──\n");
+            println!("  Source:   {:?}", from);
+
+            let profiles = import::import_file(from, map.as_deref())?;
+            if profiles.is_empty() {
+                println!("  [-] No contacts with usable fields found.");
+                return Ok(());
+            }
+
+            std::fs::create_dir_all(out_dir)?;
+            let mut used: HashSet<String> = HashSet::new();
+            for profile in &profiles {
+                let stem = import::profile_file_stem(profile);
+                let mut name = format!("{}.json", stem);
+                let mut n = 2;
+                while used.contains(&name) || out_dir.join(&name).exists() {
+                    name = format!("{}_{}.json", stem, n);
+                    n += 1;
+                }
+                let path = out_dir.join(&name);
+                profile.save(&path)?;
+                used.insert(name);
+
+                let filled = profile.fields().iter().filter(|(_, v)| !v.is_empty()).count();
+                println!("  ✓ {:?} ({} fields)", path, filled);
+            }
+
+            println!("\n  Imported {} profile(s).\n", profiles.len());
+            Ok(())
+        }
+        ProfileCommand::Validate { paths } => {
+            let mut problems = 0;
+            for path in paths {
+                let text = std::fs::read_to_string(path)?;
+                match validate::validate_profile(&text) {
+                    Ok(diags) if diags.is_empty() => println!("  ✓ {:?}", path),
+                    Ok(diags) => {
+                        println!("  ✗ {:?}", path);
+                        for diag in &diags {
+                            println!("      {}", diag);
+                        }
+                        problems += diags.len();
+                    }
+                    Err(e) => {
+                        println!("  ✗ {:?}\n      {}", path, e);
+                        problems += 1;
+                    }
+                }
+            }
+            if problems > 0 {
+                return Err(invalid!("{} problem(s) found", problems));
+            }
+            Ok(())
+        }
+    }
+}
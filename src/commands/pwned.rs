@@ -0,0 +1,57 @@
+use crate::cli::args::{PwnedCheckArgs, PwnedFilterArgs, PwnedOnly};
+use crate::cli::exit;
+use crate::io::pwned::Filter;
+use crate::io::reader;
+use crate::invalid;
+use std::io::BufRead;
+
+pub fn build(args: &PwnedFilterArgs) -> anyhow::Result<()> {
+    if !(args.fp_rate > 0.0 && args.fp_rate < 1.0) {
+        return Err(invalid!("--fp-rate must be between 0 and 1 (got {})", args.fp_rate));
+    }
+    let start_time = std::time::Instant::now();
+    let expected = match args.expected {
+        Some(n) => n,
+        None if reader::is_stdin(&args.from) => return Err(invalid!("give --expected when reading the corpus from stdin")),
+        None => {
+            eprintln!("  Counting lines in {:?}...", args.from);
+            let mut lines = 0u64;
+            for line in reader::open(&args.from)?.split(b'\n') {
+                lines += u64::from(line?.iter().any(|&b| b != b'\r'));
+            }
+            lines
+        }
+    };
+    eprintln!("  Building a filter for {} passwords (fp rate {})...", expected, args.fp_rate);
+    let (filter, added) = Filter::build(reader::open(&args.from)?, expected, args.fp_rate, args.plain)?;
+    if added > expected {
+        eprintln!("  [!] {} lines is more than --expected {}; false positives will be above --fp-rate", added, expected);
+    }
+    filter.save(&args.out)?;
+    println!("  ✓ {} passwords in {:?} ({:.1} MB) in {}ms", added, args.out, filter.size_bytes() as f64 / (1024.0 * 1024.0), start_time.elapsed().as_millis());
+    Ok(())
+}
+
+/// Exit code: SUCCESS if any password is in the filter, else NOT_FOUND
+pub fn check(args: &PwnedCheckArgs) -> anyhow::Result<i32> {
+    let filter = Filter::load(&args.filter)?;
+    let mut found = false;
+    let mut report = |password: &str| {
+        let pwned = filter.contains(password);
+        found |= pwned;
+        match (args.only, pwned) {
+            (Some(PwnedOnly::Pwned), true) | (Some(PwnedOnly::Clean), false) => println!("{}", password),
+            (Some(_), _) => {}
+            (None, true) => println!("pwned\t{}", password),
+            (None, false) => println!("clean\t{}", password),
+        }
+    };
+    if args.stdin {
+        for line in std::io::stdin().lock().lines() {
+            report(line?.trim_end_matches('\r'));
+        }
+    } else {
+        args.passwords.iter().for_each(|password| report(password));
+    }
+    Ok(if found { exit::SUCCESS } else { exit::NOT_FOUND })
+}
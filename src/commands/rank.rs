@@ -0,0 +1,40 @@
+use crate::cli::args::RankArgs;
+use crate::engine::personal::Profile;
+use crate::engine::rank::Ranker;
+use crate::io::{reader, wordlist};
+
+/// Score every line of the wordlist and write them best first
+pub fn run(args: &RankArgs) -> anyhow::Result<()> {
+    let start_time = std::time::Instant::now();
+    let model = crate::CandidateModel::load(args.model_type, &args.model)?;
+    let mut ranker = Ranker::new();
+    if let Some(path) = &args.profile {
+        ranker = ranker.with_profile(&Profile::load(path)?, args.boost);
+        if ranker.terms() == 0 {
+            eprintln!("  [!] {:?} has no values of 3 or more characters to boost", path);
+        }
+    }
+
+    let input = reader::open(&args.wordlist)?;
+    let memory_limit = args.output.memory.max(1) * 1024 * 1024;
+    let score = |line: &[u8]| {
+        let word = String::from_utf8_lossy(line);
+        ranker.score(&word, model.log_probability(&word))
+    };
+    let lines = super::wordlist::write_lines(&args.output.output, |emit| {
+        let mut scored = Vec::new();
+        wordlist::rank(vec![input], memory_limit, score, |score, line| {
+            if !args.scores {
+                return emit(line);
+            }
+            scored.clear();
+            scored.extend_from_slice(format!("{:.4}\t", score).as_bytes());
+            scored.extend_from_slice(line);
+            emit(&scored)
+        })
+    })?;
+    if let Some(path) = &args.output.output {
+        eprintln!("  ✓ Ranked {} lines into {:?} in {}ms", lines, path, start_time.elapsed().as_millis());
+    }
+    Ok(())
+}
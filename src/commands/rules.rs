@@ -0,0 +1,39 @@
+use crate::cli::args::RulesCommand;
+use crate::engine::rules::{self, RuleSet};
+use crate::invalid;
+use std::io::{BufRead, BufWriter, Write};
+
+pub fn run(action: &RulesCommand) -> anyhow::Result<()> {
+    let RulesCommand::Apply { rules: lines, word, stdin } = action;
+    let mut chains: Vec<RuleSet> = Vec::new();
+    for line in lines {
+        chains.extend(rules::chains(line).map_err(|e| invalid!("--rules {:?}: {}", line, e))?);
+    }
+    if chains.is_empty() {
+        return Err(invalid!("--rules has no rule chains"));
+    }
+
+    let mut out = BufWriter::new(std::io::stdout().lock());
+    let mut apply = |word: &[u8]| -> std::io::Result<()> {
+        for chain in &chains {
+            let mut candidate = word.to_vec();
+            chain.apply(&mut candidate);
+            candidate.push(b'\n');
+            out.write_all(&candidate)?;
+        }
+        Ok(())
+    };
+    if *stdin {
+        for line in std::io::stdin().lock().split(b'\n') {
+            let mut line = line?;
+            if line.last() == Some(&b'\r') {
+                line.pop();
+            }
+            apply(&line)?;
+        }
+    } else {
+        word.iter().try_for_each(|word| apply(word.as_bytes()))?;
+    }
+    out.flush()?;
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use crate::cli::args::ScrapeArgs;
+use crate::engine::personal::Profile;
+use crate::io::scrape::{self, ScrapeConfig};
+use crate::io::writer::{Writer, Output as WriterOutput};
+use crossbeam_channel::bounded;
+
+pub fn run(args: &ScrapeArgs) -> anyhow::Result<()> {
+    let start_time = std::time::Instant::now();
+    let config = ScrapeConfig {
+        depth: args.depth,
+        max_pages: args.max_pages,
+        min_word_len: args.min_word_len,
+        max_word_len: args.max_word_len,
+        min_count: args.min_count,
+        offsite: args.offsite,
+        lowercase: args.lowercase,
+    };
+
+    eprintln!("  Crawling {} (depth {})...", args.url, config.depth);
+    let mut words = scrape::crawl(&args.url, &config)?;
+    if let Some(n) = args.top {
+        words.truncate(n);
+    }
+    eprintln!("  Harvested {} unique words in {}ms", words.len(), start_time.elapsed().as_millis());
+
+    if let Some(path) = &args.profile {
+        let mut target = if path.exists() { Profile::load(path)? } else { Profile::new() };
+        let before = target.keywords.len();
+        for (word, _) in &words {
+            if !target.keywords.contains(word) {
+                target.keywords.push(word.clone());
+            }
+        }
+        target.save(path)?;
+        eprintln!("  ✓ Added {} keywords to {:?}", target.keywords.len() - before, path);
+        if args.output.is_none() {
+            return Ok(());
+        }
+    }
+
+    let (sender, receiver) = bounded::<Vec<Vec<u8>>>(100);
+    let writer_output = match &args.output {
+        Some(path) => WriterOutput::File(path.clone()),
+        None => WriterOutput::Stdout,
+    };
+    let writer_thread = Writer::new(receiver, writer_output).start();
+    let batch: Vec<Vec<u8>> = words.into_iter().map(|(w, _)| w.into_bytes()).collect();
+    sender.send(batch).expect("Channel closed");
+    drop(sender);
+    writer_thread.join().expect("Writer panic")?;
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use crate::cli::args::StrengthArgs;
+use crate::engine::personal::Profile;
+use crate::engine::strength::{Estimator, Match, Pattern, Strength, SCENARIOS};
+use crate::io::reader;
+use std::io::BufRead;
+
+pub const SCORES: [&str; 5] = ["very weak", "weak", "fair", "strong", "very strong"];
+
+pub fn run(args: &StrengthArgs) -> anyhow::Result<()> {
+    let mut estimator = Estimator::new();
+    for path in &args.dictionaries {
+        let name = path.file_stem().map_or_else(|| "custom".to_string(), |stem| stem.to_string_lossy().into_owned());
+        estimator = estimator.with_dictionary(&name, reader::read_words(path)?);
+    }
+    if let Some(path) = &args.profile {
+        estimator = estimator.with_profile(&Profile::load(path)?);
+    }
+
+    let Some(password) = &args.password else {
+        for line in std::io::stdin().lock().lines() {
+            let line = line?;
+            let password = line.trim_end_matches('\r');
+            let strength = estimator.estimate(password);
+            if args.json {
+                let mut record = serde_json::to_value(&strength)?;
+                record["password"] = password.into();
+                println!("{}", record);
+            } else {
+                println!("{}\t{:.1}\t{}", strength.score, strength.bits(), password);
+            }
+        }
+        return Ok(());
+    };
+
+    let strength = estimator.estimate(password);
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&strength)?);
+        return Ok(());
+    }
+    print_report(&strength);
+    Ok(())
+}
+
+fn print_report(strength: &Strength) {
+    println!("\n  ── Password Strength ──\n");
+    println!("  Score:    {}/4 ({})", strength.score, SCORES[strength.score as usize]);
+    println!("  Guesses:  10^{:.1} ({:.1} bits)", strength.guesses_log10, strength.bits());
+
+    println!("\n  Time to crack:");
+    for (scenario, rate) in SCENARIOS {
+        println!("    {:<30} {}", scenario, crate::human_duration(strength.crack_seconds(rate)));
+    }
+
+    if !strength.matches.is_empty() {
+        println!("\n  {:<20} {:<36} {:>10}", "PART", "PATTERN", "GUESSES");
+        for m in &strength.matches {
+            println!("  {:<20} {:<36} {:>10}", m.token, describe(m), format!("10^{:.1}", m.guesses.log10()));
+        }
+    }
+
+    let feedback = strength.feedback();
+    if !feedback.is_empty() {
+        println!();
+        for note in feedback {
+            println!("  [!] {}", note);
+        }
+    }
+    println!();
+}
+
+pub fn describe(m: &Match) -> String {
+    match &m.pattern {
+        Pattern::Dictionary { dictionary, word, rank, reversed, leet } => {
+            let mut text = format!("{} word #{} ({})", dictionary, rank, word);
+            if *reversed {
+                text.push_str(", reversed");
+            }
+            if *leet {
+                text.push_str(", leet");
+            }
+            text
+        }
+        Pattern::Spatial { turns, shifted } => {
+            format!("keyboard walk, {} turn{}{}", turns, if *turns == 1 { "" } else { "s" }, if *shifted { ", shifted" } else { "" })
+        }
+        Pattern::Repeat { base, count } => format!("\"{}\" × {}", base, count),
+        Pattern::Sequence { ascending: true } => "sequence".to_string(),
+        Pattern::Sequence { ascending: false } => "sequence, descending".to_string(),
+        Pattern::Date { year, .. } => format!("date ({})", year),
+        Pattern::Year { year } => format!("year ({})", year),
+        Pattern::BruteForce => "brute force".to_string(),
+    }
+}
@@ -0,0 +1,76 @@
+use crate::cli::args::WordlistCommand;
+use crate::io::{reader, wordlist};
+use crate::invalid;
+use std::fs::File;
+use std::io::{self, BufRead, BufWriter, Write};
+use std::path::PathBuf;
+
+pub fn run(action: &WordlistCommand) -> anyhow::Result<()> {
+    let start_time = std::time::Instant::now();
+    let open_all = |paths: &[PathBuf]| -> anyhow::Result<Vec<Box<dyn BufRead + Send>>> {
+        if paths.iter().filter(|path| reader::is_stdin(path)).count() > 1 {
+            return Err(invalid!("only one input can read stdin"));
+        }
+        paths.iter().map(|path| reader::open(path)).collect()
+    };
+    let mb = |memory: usize| memory.max(1) * 1024 * 1024;
+
+    let (output, lines) = match action {
+        WordlistCommand::Sort { inputs, unique, output } => {
+            let inputs = open_all(inputs)?;
+            (&output.output, write_lines(&output.output, |emit| wordlist::sort(inputs, mb(output.memory), *unique, emit))?)
+        }
+        WordlistCommand::Unique { inputs, output } => {
+            let inputs = open_all(inputs)?;
+            (&output.output, write_lines(&output.output, |emit| wordlist::unique(inputs, mb(output.memory), emit))?)
+        }
+        WordlistCommand::Merge { inputs, output } => {
+            // Merging streams, so the output can't overwrite an input still being read
+            if output.as_ref().is_some_and(|out| inputs.contains(out)) {
+                return Err(invalid!("--output can't be one of the lists being merged"));
+            }
+            let inputs = open_all(inputs)?;
+            (output, write_lines(output, |emit| wordlist::merge(inputs, emit))?)
+        }
+        WordlistCommand::Diff { left, right, common, output } => {
+            let mut inputs = open_all(&[left.clone(), right.clone()])?.into_iter();
+            let (left, right) = (inputs.next().unwrap(), inputs.next().unwrap());
+            (&output.output, write_lines(&output.output, |emit| wordlist::diff(left, right, mb(output.memory), *common, emit))?)
+        }
+    };
+    if let Some(path) = output {
+        eprintln!("  ✓ Wrote {} lines to {:?} in {}ms", lines, path, start_time.elapsed().as_millis());
+    }
+    Ok(())
+}
+
+/// Run `operation` with a sink writing lines to `output` (stdout if None); returns how many were written
+pub fn write_lines(output: &Option<PathBuf>, operation: impl FnOnce(&mut dyn FnMut(&[u8]) -> io::Result<()>) -> io::Result<()>) -> anyhow::Result<u64> {
+    // Created on the first line: sorting reads all input before emitting,
+    // so `-o` may name one of the inputs, as with `sort -o`
+    let open = || -> io::Result<Box<dyn Write>> {
+        Ok(match output {
+            Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+            None => Box::new(BufWriter::new(io::stdout().lock())),
+        })
+    };
+    let mut out: Option<Box<dyn Write>> = None;
+    let mut lines = 0u64;
+    let result = operation(&mut |line| {
+        let out = match &mut out {
+            Some(out) => out,
+            None => out.insert(open()?),
+        };
+        lines += 1;
+        out.write_all(line)?;
+        out.write_all(b"\n")
+    }).and_then(|_| match out {
+        Some(mut out) => out.flush(),
+        None => open().map(|_| ()),
+    });
+    match result {
+        // Piped into head or similar: stop quietly
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(lines),
+        result => result.map(|_| lines).map_err(Into::into),
+    }
+}
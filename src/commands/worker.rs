@@ -0,0 +1,91 @@
+use crate::api::coordinator::{Task, Unit};
+use crate::cli::args::WorkerArgs;
+use anyhow::Context;
+use std::io::{BufWriter, Write};
+use std::time::{Duration, Instant};
+use ureq::SendBody;
+
+/// Claim units from the coordinator and return their candidates until it has them all
+pub fn run(args: &WorkerArgs) -> anyhow::Result<()> {
+    let base = args.join.trim_end_matches('/');
+    let name = args.name.clone().unwrap_or_else(|| {
+        let host = std::env::var("HOSTNAME").unwrap_or_else(|_| "worker".to_string());
+        format!("{}-{}", host, std::process::id())
+    });
+    // Statuses are answers here (204 wait, 409 beaten to it, 410 done)
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .http_status_as_error(false)
+        .build()
+        .into();
+
+    let mut response = authorized(agent.get(&format!("{}/api/coordinator/task", base)), &args.api_key).call()
+        .with_context(|| format!("can't reach the coordinator at {}", base))?;
+    if response.status() != 200 {
+        anyhow::bail!("{} is not a coordinator (HTTP {}): {}", base, response.status(), response.body_mut().read_to_string()?);
+    }
+    let task: Task = serde_json::from_str(&response.body_mut().read_to_string()?)?;
+    eprintln!("  [+] joined {} as {}: {} run of {} candidates", base, name, task.kind(), task.keyspace()?);
+
+    let (mut units, mut candidates) = (0u64, 0u64);
+    let start_time = Instant::now();
+    loop {
+        let mut response = authorized(agent.post(&format!("{}/api/coordinator/claim", base)), &args.api_key)
+            .header("Content-Type", "application/json")
+            .send(serde_json::json!({ "worker": name }).to_string())?;
+        let unit: Unit = match response.status().as_u16() {
+            200 => serde_json::from_str(&response.body_mut().read_to_string()?)?,
+            204 => {
+                std::thread::sleep(Duration::from_secs(args.poll.max(1)));
+                continue;
+            }
+            410 => break,
+            status => anyhow::bail!("claiming a unit failed (HTTP {}): {}", status, response.body_mut().read_to_string()?),
+        };
+
+        let unit_start = Instant::now();
+        let (reader, writer) = std::io::pipe()?;
+        let url = format!("{}/api/coordinator/units/{}", base, unit.lease);
+        let upload = {
+            let request = authorized(agent.put(&url), &args.api_key).header("Content-Type", "text/plain; charset=utf-8");
+            std::thread::spawn(move || request.send(SendBody::from_owned_reader(reader)))
+        };
+        let mut out = BufWriter::new(writer);
+        let mut failed = None;
+        task.run(unit.skip, unit.limit, |candidate| {
+            if let Err(e) = out.write_all(candidate).and_then(|_| out.write_all(b"\n")) {
+                failed = Some(e);
+                return true;
+            }
+            false
+        })?;
+        let flushed = out.flush();
+        drop(out);
+        // A failed upload closes the pipe; report the upload's error first
+        let mut response = upload.join().map_err(|_| anyhow::anyhow!("upload thread panicked"))?
+            .with_context(|| format!("returning unit {} failed", unit.id))?;
+        if let Some(e) = failed {
+            return Err(e.into());
+        }
+        flushed?;
+        match response.status().as_u16() {
+            204 => {
+                units += 1;
+                candidates += unit.limit;
+                eprintln!("  [+] unit {}: {} candidates in {}ms", unit.id, unit.limit, unit_start.elapsed().as_millis());
+            }
+            // The lease ran out and another worker returned it first
+            409 => eprintln!("  [!] unit {} was already returned by another worker", unit.id),
+            status => anyhow::bail!("returning unit {} failed (HTTP {}): {}", unit.id, status, response.body_mut().read_to_string()?),
+        }
+    }
+    eprintln!("  ✓ Coordinator has every unit; this worker returned {} units ({} candidates) in {}",
+        units, candidates, crate::human_duration(start_time.elapsed().as_secs_f64()));
+    Ok(())
+}
+
+fn authorized<B>(request: ureq::RequestBuilder<B>, key: &Option<String>) -> ureq::RequestBuilder<B> {
+    match key {
+        Some(key) => request.header("X-API-Key", key),
+        None => request,
+    }
+}
@@ -0,0 +1,137 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+// ═══════════════════════════════════════════════════════════════
+// WORDLIST ANALYSIS
+// ═══════════════════════════════════════════════════════════════
+//
+// PACK-style statistics over a list of known passwords: how long they
+// are, which character classes they mix, which masks describe them and
+// what non-letter runs they start and end with. The masks feed straight
+// back into --mask, and the affixes become append/prepend rules, so a
+// leaked list from one site shapes the attack on the next.
+
+/// Character classes a password uses, as its mask spells them
+const CLASSES: [(u8, &str); 4] = [(b'l', "lower"), (b'u', "upper"), (b'd', "digit"), (b's', "special")];
+
+/// Running counts over the words seen so far
+#[derive(Debug, Default)]
+pub struct Analysis {
+    pub words: u64,
+    /// Words with bytes no mask can produce (non-ASCII, control characters)
+    pub unmaskable: u64,
+    pub lengths: BTreeMap<usize, u64>,
+    pub charsets: HashMap<String, u64>,
+    pub masks: HashMap<String, u64>,
+    pub prefixes: HashMap<String, u64>,
+    pub suffixes: HashMap<String, u64>,
+}
+
+/// One row of a ranked table
+#[derive(Debug, Serialize, PartialEq)]
+pub struct Count {
+    pub value: String,
+    pub count: u64,
+    pub percent: f64,
+}
+
+impl Analysis {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, word: &[u8]) {
+        self.words += 1;
+        *self.lengths.entry(String::from_utf8_lossy(word).chars().count()).or_default() += 1;
+
+        let Some(mask) = mask_of(word) else {
+            self.unmaskable += 1;
+            return;
+        };
+        let classes: Vec<&str> = CLASSES.iter()
+            .filter(|(class, _)| mask.as_bytes().chunks(2).any(|pair| pair[1] == *class))
+            .map(|(_, name)| *name)
+            .collect();
+        *self.charsets.entry(classes.join("+")).or_default() += 1;
+        *self.masks.entry(mask).or_default() += 1;
+
+        // The runs of digits and symbols around the letters; a word with no
+        // letters is all affix, which says nothing about decoration
+        if let (Some(first), Some(last)) = (word.iter().position(u8::is_ascii_alphabetic), word.iter().rposition(u8::is_ascii_alphabetic)) {
+            if first > 0 {
+                *self.prefixes.entry(String::from_utf8_lossy(&word[..first]).into_owned()).or_default() += 1;
+            }
+            if last + 1 < word.len() {
+                *self.suffixes.entry(String::from_utf8_lossy(&word[last + 1..]).into_owned()).or_default() += 1;
+            }
+        }
+    }
+
+    /// The `n` most common entries of `table`, ties in value order
+    pub fn top(&self, table: &HashMap<String, u64>, n: usize) -> Vec<Count> {
+        let mut rows: Vec<(&String, &u64)> = table.iter().collect();
+        rows.sort_unstable_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        rows.into_iter().take(n).map(|(value, &count)| Count { value: value.clone(), count, percent: self.percent(count) }).collect()
+    }
+
+    /// Share of all words, 0–100
+    pub fn percent(&self, count: u64) -> f64 {
+        count as f64 * 100.0 / self.words.max(1) as f64
+    }
+}
+
+/// `?l?u?d?s` mask matching `word`, or None if a byte is outside them all
+pub fn mask_of(word: &[u8]) -> Option<String> {
+    let mut mask = String::with_capacity(word.len() * 2);
+    for &byte in word {
+        mask.push_str(match byte {
+            b'a'..=b'z' => "?l",
+            b'A'..=b'Z' => "?u",
+            b'0'..=b'9' => "?d",
+            byte if crate::engine::mask::Charset::Special.chars().contains(&byte) => "?s",
+            _ => return None,
+        });
+    }
+    Some(mask)
+}
+
+/// Rule chain that appends `suffix`
+pub fn append_rule(suffix: &str) -> String {
+    suffix.chars().map(|c| format!("${}", c)).collect()
+}
+
+/// Rule chain that prepends `prefix`: `^` pushes to the front, so last character first
+pub fn prepend_rule(prefix: &str) -> String {
+    prefix.chars().rev().map(|c| format!("^{}", c)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::rules::RuleSet;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_counts() {
+        let mut analysis = Analysis::new();
+        for word in ["password1", "Summer2024!", "monkey1", "123456", "!!love", "café"] {
+            analysis.add(word.as_bytes());
+        }
+        assert_eq!(analysis.words, 6);
+        assert_eq!(analysis.unmaskable, 1);
+        assert_eq!(analysis.lengths[&6], 2);
+        assert_eq!(analysis.top(&analysis.charsets, 1)[0], Count { value: "lower+digit".into(), count: 2, percent: 100.0 / 3.0 });
+        assert_eq!(analysis.top(&analysis.suffixes, 1)[0].value, "1");
+        assert_eq!(analysis.prefixes.get("!!"), Some(&1));
+        assert!(!analysis.suffixes.contains_key("123456"));
+        assert_eq!(analysis.masks.get("?u?l?l?l?l?l?d?d?d?d?s"), Some(&1));
+    }
+
+    #[test]
+    fn test_affix_rules() {
+        let mut word = b"love".to_vec();
+        RuleSet::from_str(&append_rule("12")).unwrap().apply(&mut word);
+        RuleSet::from_str(&prepend_rule("#1")).unwrap().apply(&mut word);
+        assert_eq!(word, b"#1love12");
+    }
+}
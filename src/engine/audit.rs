@@ -0,0 +1,141 @@
+use crate::engine::analyze::mask_of;
+use crate::engine::mask::Charset;
+use crate::engine::personal::{Dedup, Profile};
+use crate::engine::strength::{Estimator, Strength};
+use serde::Serialize;
+
+// ═══════════════════════════════════════════════════════════════
+// PASSWORD AUDIT
+// ═══════════════════════════════════════════════════════════════
+//
+// One known password measured against each attack jigsaw runs: where it
+// falls in a personal attack on its owner's profile, the smallest mask
+// that covers it and that mask's size, how likely a trained model is to
+// write it, and the zxcvbn-style estimate. Each weakness found becomes a
+// concrete suggestion, most damaging first.
+
+/// Guesses a fast offline attack makes in a year, the line for "brute-forceable"
+const YEAR_OF_GUESSES: f64 = 1e10 * 365.25 * 86_400.0;
+
+/// Where the password falls in a personal attack
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct PersonalHit {
+    /// 1-based position among the profile's unique candidates, if generated
+    pub rank: Option<u64>,
+    /// Candidates the profile generates, duplicates included
+    pub keyspace: u64,
+}
+
+/// The smallest `?l?u?d?s` mask that produces the password
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct MaskCover {
+    pub mask: String,
+    /// Candidates it generates; a float, as guesses are, since long masks pass u128
+    pub keyspace: f64,
+}
+
+/// How likely a trained model is to write the password
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ModelOdds {
+    /// Natural log; None when the model can't write it
+    pub log_probability: Option<f64>,
+    /// Draws a sampler takes on average before writing it
+    pub expected_draws: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Audit {
+    pub length: usize,
+    /// None without a profile
+    pub personal: Option<PersonalHit>,
+    /// None when a character is outside the built-in charsets
+    pub mask: Option<MaskCover>,
+    /// None without a model
+    pub model: Option<ModelOdds>,
+    pub strength: Strength,
+    pub suggestions: Vec<String>,
+}
+
+/// Audit `password`. `profile` is the owner's, and `log_probability`
+/// the outer `Some` when a model was given.
+pub fn audit(password: &str, estimator: &Estimator, profile: Option<&Profile>, log_probability: Option<Option<f64>>) -> Audit {
+    let personal = profile.map(|profile| {
+        let (mut position, mut rank) = (0u64, None);
+        profile.for_each_unique(Dedup::Exact, |candidate| {
+            position += 1;
+            if candidate == password {
+                rank = Some(position);
+            }
+            rank.is_some()
+        });
+        PersonalHit { rank, keyspace: profile.estimate().candidates }
+    });
+    let mask = mask_of(password.as_bytes()).map(|mask| {
+        let keyspace = mask.as_bytes().chunks(2).map(|pair| match pair[1] {
+            b'd' => 10.0,
+            b's' => Charset::Special.chars().len() as f64,
+            _ => 26.0,
+        }).product();
+        MaskCover { mask, keyspace }
+    });
+    let model = log_probability.map(|log_probability| ModelOdds {
+        log_probability,
+        expected_draws: log_probability.map(|p| (-p).exp()),
+    });
+    let strength = estimator.estimate(password);
+
+    let mut suggestions = Vec::new();
+    if let Some(PersonalHit { rank: Some(rank), .. }) = &personal {
+        suggestions.push(format!(
+            "A personal attack from this profile tries it as guess #{}; leave out names, dates and other details about yourself", rank));
+    }
+    if let Some(draws) = model.as_ref().and_then(|model| model.expected_draws).filter(|&draws| draws < YEAR_OF_GUESSES) {
+        suggestions.push(format!(
+            "A model trained on leaked passwords writes it within about {:.0e} guesses; it looks like what people usually choose", draws));
+    }
+    if let Some(cover) = mask.as_ref().filter(|cover| cover.keyspace < YEAR_OF_GUESSES) {
+        suggestions.push(format!(
+            "Brute force over {} reaches it within {:.0e} guesses, less than a year for a fast offline attack; make it longer",
+            cover.mask, cover.keyspace));
+    }
+    let classes = ["?l", "?u", "?d", "?s"].iter()
+        .filter(|class| mask.as_ref().is_some_and(|cover| cover.mask.contains(*class)))
+        .count();
+    if mask.is_some() && classes < 3 && password.chars().count() < 16 {
+        suggestions.push(format!("It uses {} of 4 character classes; a longer password or a mix of classes widens the search", classes));
+    }
+    for note in strength.feedback() {
+        if !suggestions.contains(&note) {
+            suggestions.push(note);
+        }
+    }
+    Audit { length: password.chars().count(), personal, mask, model, strength, suggestions }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audit() {
+        let mut profile = Profile::new();
+        profile.pets = vec!["rex".to_string()];
+        profile.dates = vec!["1990".to_string()];
+        let estimator = Estimator::new().with_profile(&profile);
+
+        let report = audit("Rex1990", &estimator, Some(&profile), Some(Some(-12.0)));
+        let hit = report.personal.clone().unwrap();
+        assert!(hit.rank.is_some_and(|rank| rank <= hit.keyspace));
+        assert_eq!(report.mask, Some(MaskCover { mask: "?u?l?l?d?d?d?d".to_string(), keyspace: 26f64.powi(3) * 1e4 }));
+        assert_eq!(report.model.as_ref().unwrap().expected_draws, Some(12f64.exp()));
+        assert!(report.suggestions[0].contains("personal attack"));
+        assert!(report.suggestions.iter().any(|s| s.contains("make it longer")));
+
+        // A long random passphrase draws no mask or model complaints
+        let report = audit("correct-horse-battery-staple-quartz", &estimator, Some(&profile), Some(None));
+        assert_eq!(report.personal.unwrap().rank, None);
+        assert!(report.mask.is_some_and(|cover| cover.keyspace > 1e40));
+        assert_eq!(report.model.unwrap().expected_draws, None);
+        assert!(!report.suggestions.iter().any(|s| s.contains("make it longer") || s.contains("classes")));
+    }
+}
@@ -0,0 +1,166 @@
+use std::io::{self, Read, Write};
+
+// ═══════════════════════════════════════════════════════════════
+// BLOOM FILTER
+// ═══════════════════════════════════════════════════════════════
+
+/// First bytes of a saved filter, then a format version
+const MAGIC: &[u8; 8] = b"JIGSAWBF";
+const VERSION: u32 = 1;
+
+/// Fixed-size probabilistic set. Never reports a seen item as new, but may
+/// report a new item as seen with roughly the configured probability.
+#[derive(Debug, Clone)]
+pub struct BloomFilter {
+    bits: Vec<u64>,
+    num_bits: u64,
+    num_hashes: u32,
+}
+
+impl BloomFilter {
+    /// Size the filter for `expected` items at the given false-positive rate
+    pub fn with_rate(expected: u64, fp_rate: f64) -> Self {
+        let n = expected.max(1) as f64;
+        let p = fp_rate.clamp(1e-12, 0.5);
+        let ln2 = std::f64::consts::LN_2;
+
+        let num_bits = ((-n * p.ln()) / (ln2 * ln2)).ceil().max(64.0) as u64;
+        let num_hashes = ((num_bits as f64 / n) * ln2).round().clamp(1.0, 32.0) as u32;
+
+        Self {
+            bits: vec![0; num_bits.div_ceil(64) as usize],
+            num_bits,
+            num_hashes,
+        }
+    }
+
+    /// Memory held by the bit array
+    pub fn size_bytes(&self) -> usize {
+        self.bits.len() * 8
+    }
+
+    /// Save as MAGIC, version, bit count and hash count, then the bits,
+    /// all little-endian, for `read_from`
+    pub fn write_to(&self, mut out: impl Write) -> io::Result<()> {
+        out.write_all(MAGIC)?;
+        out.write_all(&VERSION.to_le_bytes())?;
+        out.write_all(&self.num_bits.to_le_bytes())?;
+        out.write_all(&self.num_hashes.to_le_bytes())?;
+        for word in &self.bits {
+            out.write_all(&word.to_le_bytes())?;
+        }
+        out.flush()
+    }
+
+    /// Load a filter saved by `write_to`
+    pub fn read_from(mut input: impl Read) -> io::Result<Self> {
+        let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+        let mut magic = [0u8; 8];
+        input.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid("not a jigsaw bloom filter"));
+        }
+        let mut word = [0u8; 8];
+        let mut half = [0u8; 4];
+        input.read_exact(&mut half)?;
+        if u32::from_le_bytes(half) != VERSION {
+            return Err(invalid("bloom filter from an unsupported version of jigsaw"));
+        }
+        input.read_exact(&mut word)?;
+        let num_bits = u64::from_le_bytes(word);
+        input.read_exact(&mut half)?;
+        let num_hashes = u32::from_le_bytes(half);
+        if num_bits == 0 || !(1..=32).contains(&num_hashes) {
+            return Err(invalid("corrupt bloom filter header"));
+        }
+        let mut bits = Vec::with_capacity(num_bits.div_ceil(64) as usize);
+        for _ in 0..num_bits.div_ceil(64) {
+            input.read_exact(&mut word)?;
+            bits.push(u64::from_le_bytes(word));
+        }
+        Ok(Self { bits, num_bits, num_hashes })
+    }
+
+    pub fn contains(&self, item: &[u8]) -> bool {
+        let (h1, h2) = hash_pair(item);
+        (0..self.num_hashes as u64).all(|i| {
+            let bit = h1.wrapping_add(i.wrapping_mul(h2)) % self.num_bits;
+            self.bits[(bit / 64) as usize] & (1 << (bit % 64)) != 0
+        })
+    }
+
+    /// Add `item`, returning true if it was not (probably) present before
+    pub fn insert(&mut self, item: &[u8]) -> bool {
+        let (h1, h2) = hash_pair(item);
+        let mut new = false;
+        for i in 0..self.num_hashes as u64 {
+            let bit = h1.wrapping_add(i.wrapping_mul(h2)) % self.num_bits;
+            let (word, mask) = ((bit / 64) as usize, 1u64 << (bit % 64));
+            if self.bits[word] & mask == 0 {
+                self.bits[word] |= mask;
+                new = true;
+            }
+        }
+        new
+    }
+}
+
+/// Two independent 64-bit hashes (FNV-1a, then splitmix64 for the second)
+/// for Kirsch–Mitzenmacher double hashing. Stable across builds.
+fn hash_pair(item: &[u8]) -> (u64, u64) {
+    let mut h: u64 = 0xcbf2_9ce4_8422_2325;
+    for &b in item {
+        h ^= b as u64;
+        h = h.wrapping_mul(0x0100_0000_01b3);
+    }
+
+    let mut z = h.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^= z >> 31;
+
+    // Odd step so every probe lands on a distinct bit
+    (h, z | 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_false_negatives() {
+        let mut filter = BloomFilter::with_rate(1000, 0.01);
+        for i in 0..1000 {
+            filter.insert(format!("word{}", i).as_bytes());
+        }
+        for i in 0..1000 {
+            assert!(filter.contains(format!("word{}", i).as_bytes()));
+            assert!(!filter.insert(format!("word{}", i).as_bytes()));
+        }
+    }
+
+    #[test]
+    fn test_false_positive_rate() {
+        let mut filter = BloomFilter::with_rate(10_000, 0.01);
+        for i in 0..10_000 {
+            filter.insert(format!("in{}", i).as_bytes());
+        }
+        let false_hits = (0..10_000)
+            .filter(|i| filter.contains(format!("out{}", i).as_bytes()))
+            .count();
+        assert!(false_hits < 300, "{} false positives", false_hits);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let mut filter = BloomFilter::with_rate(100, 0.01);
+        filter.insert(b"hunter2");
+        let mut saved = Vec::new();
+        filter.write_to(&mut saved).unwrap();
+        let loaded = BloomFilter::read_from(saved.as_slice()).unwrap();
+        assert!(loaded.contains(b"hunter2") && !loaded.contains(b"hunter3"));
+        assert_eq!(loaded.size_bytes(), filter.size_bytes());
+        assert!(BloomFilter::read_from(&saved[..saved.len() - 1]).is_err());
+        assert!(BloomFilter::read_from(&b"not a filter at all"[..]).is_err());
+    }
+}
@@ -0,0 +1,91 @@
+// ═══════════════════════════════════════════════════════════════
+// CASE PERMUTATIONS
+// ═══════════════════════════════════════════════════════════════
+//
+// Every upper/lower-case spelling of a word: 2^n of them for n letters,
+// fewest capitals first, so pAssword comes out long before PaSsWoRd. The
+// personal attack only tries a handful of fixed forms (lower, UPPER,
+// Title…); this is for targets known to capitalise oddly, and callers
+// bound n since the count doubles with every letter.
+
+/// Most letters `permutations` handles; 2^32 spellings is already past any real run
+pub const MAX_LETTERS: usize = 32;
+
+/// Characters whose case can flip, counted the way `permutations` counts them
+pub fn letters(word: &str) -> usize {
+    word.chars().filter(|&c| flips(c)).count()
+}
+
+/// A character with one-character upper and lower forms that differ
+fn flips(c: char) -> bool {
+    let (mut upper, mut lower) = (c.to_uppercase(), c.to_lowercase());
+    match (upper.next(), upper.next(), lower.next(), lower.next()) {
+        (Some(u), None, Some(l), None) => u != l,
+        _ => false,
+    }
+}
+
+/// Each case spelling of `word`, starting all lower-case, then with one
+/// capital (first letter first), two, and so on. Characters that have no
+/// case stay as they are. Returns true once `callback` does, to stop early.
+pub fn permutations<F>(word: &str, mut callback: F) -> bool
+where F: FnMut(String) -> bool
+{
+    let lower: Vec<char> = word.chars().map(|c| if flips(c) { c.to_lowercase().next().unwrap_or(c) } else { c }).collect();
+    // The mask bit each character's case follows, if it has one
+    let mut n = 0;
+    let bits: Vec<Option<usize>> = lower.iter().map(|&c| flips(c).then(|| { n += 1; n - 1 })).collect();
+    assert!(n <= MAX_LETTERS, "{} letters is more than case::MAX_LETTERS", n);
+    let end = 1u64 << n;
+
+    for k in 0..=n {
+        // Every n-bit mask with k bits set, in increasing order (Gosper's hack)
+        let mut mask = (1u64 << k) - 1;
+        while mask < end {
+            let spelling: String = lower.iter().zip(&bits).map(|(&c, bit)| match bit {
+                Some(bit) if mask >> bit & 1 == 1 => c.to_uppercase().next().unwrap_or(c),
+                _ => c,
+            }).collect();
+            if callback(spelling) {
+                return true;
+            }
+            if mask == 0 {
+                break;
+            }
+            let lowest = mask & mask.wrapping_neg();
+            let ripple = mask + lowest;
+            mask = (((ripple ^ mask) >> 2) / lowest) | ripple;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn permutations_of(word: &str) -> Vec<String> {
+        let mut out = Vec::new();
+        permutations(word, |s| { out.push(s); false });
+        out
+    }
+
+    #[test]
+    fn test_permutations() {
+        assert_eq!(permutations_of("aB1"), ["ab1", "Ab1", "aB1", "AB1"]);
+        assert_eq!(permutations_of("abc")[..5], ["abc", "Abc", "aBc", "abC", "ABc"]);
+
+        let all = permutations_of("Pass_w0rd");
+        assert_eq!(letters("Pass_w0rd"), 7);
+        assert_eq!(all.len(), 1 << 7);
+        assert_eq!(all.iter().collect::<std::collections::HashSet<_>>().len(), all.len());
+        assert_eq!(all[0], "pass_w0rd");
+        assert_eq!(all[all.len() - 1], "PASS_W0RD");
+
+        assert_eq!(permutations_of("123"), ["123"]);
+        assert_eq!(permutations_of("straße").len(), 1 << 5, "ß has no one-character capital");
+
+        let mut seen = 0;
+        assert!(permutations("password", |_| { seen += 1; seen == 3 }));
+    }
+}
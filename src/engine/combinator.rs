@@ -0,0 +1,92 @@
+use crate::engine::rules::RuleSet;
+
+// ═══════════════════════════════════════════════════════════════
+// COMBINATOR
+// ═══════════════════════════════════════════════════════════════
+//
+// The classic two-list attack: every left word joined to every right
+// word, with a separator between them. Rule chains run over each side
+// once, before joining (hashcat's -j/-k), so a rule is never applied to
+// the combined candidate. Rows are indexed by left word, which lets
+// callers split the work across threads a row at a time.
+
+/// Every `left + separator + right`, left word varying slowest
+#[derive(Debug, Clone)]
+pub struct Combinator {
+    left: Vec<Vec<u8>>,
+    right: Vec<Vec<u8>>,
+    separators: Vec<Vec<u8>>,
+}
+
+impl Combinator {
+    /// No separators means the words are joined directly
+    pub fn new(left: Vec<Vec<u8>>, right: Vec<Vec<u8>>, separators: Vec<Vec<u8>>) -> Self {
+        let separators = if separators.is_empty() { vec![Vec::new()] } else { separators };
+        Self { left, right, separators }
+    }
+
+    /// Run `left` over each left word and `right` over each right word
+    pub fn with_rules(mut self, left: Option<&RuleSet>, right: Option<&RuleSet>) -> Self {
+        for (words, rules) in [(&mut self.left, left), (&mut self.right, right)] {
+            if let Some(rules) = rules {
+                words.iter_mut().for_each(|word| rules.apply(word));
+            }
+        }
+        self
+    }
+
+    pub fn keyspace(&self) -> u128 {
+        self.left.len() as u128 * self.separators.len() as u128 * self.right.len() as u128
+    }
+
+    /// Rows in the keyspace, one per left word
+    pub fn rows(&self) -> usize {
+        self.left.len()
+    }
+
+    /// The candidates built on left word `row`
+    pub fn row(&self, row: usize) -> impl Iterator<Item = Vec<u8>> + '_ {
+        let left = &self.left[row];
+        self.separators.iter().flat_map(move |separator| {
+            self.right.iter().map(move |right| {
+                let mut candidate = Vec::with_capacity(left.len() + separator.len() + right.len());
+                candidate.extend_from_slice(left);
+                candidate.extend_from_slice(separator);
+                candidate.extend_from_slice(right);
+                candidate
+            })
+        })
+    }
+
+    /// Every candidate, in order
+    pub fn iter(&self) -> impl Iterator<Item = Vec<u8>> + '_ {
+        (0..self.rows()).flat_map(|row| self.row(row))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn bytes(words: &[&str]) -> Vec<Vec<u8>> {
+        words.iter().map(|w| w.as_bytes().to_vec()).collect()
+    }
+
+    #[test]
+    fn test_cross_product_with_separators() {
+        let combinator = Combinator::new(bytes(&["john", "jane"]), bytes(&["1990", "!"]), bytes(&["", "_"]));
+        assert_eq!(combinator.keyspace(), 8);
+        let all: Vec<Vec<u8>> = combinator.iter().collect();
+        assert_eq!(all, bytes(&["john1990", "john!", "john_1990", "john_!", "jane1990", "jane!", "jane_1990", "jane_!"]));
+        assert_eq!(combinator.row(1).count(), 4);
+    }
+
+    #[test]
+    fn test_rules_run_on_each_side() {
+        let left = RuleSet::from_str("u").unwrap();
+        let right = RuleSet::from_str("$!").unwrap();
+        let combinator = Combinator::new(bytes(&["summer"]), bytes(&["2024"]), Vec::new()).with_rules(Some(&left), Some(&right));
+        assert_eq!(combinator.iter().collect::<Vec<_>>(), bytes(&["SUMMER2024!"]));
+    }
+}
@@ -0,0 +1,238 @@
+use crate::engine::memorable::Language;
+use crate::invalid;
+use anyhow::Result;
+use std::collections::HashSet;
+use std::str::FromStr;
+
+// ═══════════════════════════════════════════════════════════════
+// DATE TOKENS
+// ═══════════════════════════════════════════════════════════════
+//
+// Birthdays, anniversaries and years are the most common thing stuck on
+// the end of a password, in whatever shape the owner writes dates. A
+// format spells that shape with DD/D, MM/M, YYYY/YY and month names
+// (Month, MONTH, month; Mon, MON, mon), anything else being taken
+// literally, so "DD.MM.YY" gives 24.12.89. Every real calendar date in
+// the year range is written in every format, format by format and in
+// date order, each token once. A format without a year is written for
+// a leap year, so 2902 is there. Month names are transliterated like
+// the word pools (märz → maerz).
+
+/// Formats `jigsaw dates` writes when none are given
+pub const DEFAULT_FORMATS: &str = "DDMMYYYY,MMDDYYYY,YYYYMMDD,DDMMYY,MMDDYY,DDMM,MMDD,YYYY,YY";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Case {
+    Title,
+    Upper,
+    Lower,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Day { padded: bool },
+    Month { padded: bool },
+    MonthName { long: bool, case: Case },
+    Year { digits: usize },
+    Literal(char),
+}
+
+/// One way of writing a date
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Format {
+    parts: Vec<Part>,
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        // Longest first, so MONTH isn't read as MON + TH
+        const TOKENS: [(&str, Part); 12] = [
+            ("MONTH", Part::MonthName { long: true, case: Case::Upper }),
+            ("Month", Part::MonthName { long: true, case: Case::Title }),
+            ("month", Part::MonthName { long: true, case: Case::Lower }),
+            ("YYYY", Part::Year { digits: 4 }),
+            ("MON", Part::MonthName { long: false, case: Case::Upper }),
+            ("Mon", Part::MonthName { long: false, case: Case::Title }),
+            ("mon", Part::MonthName { long: false, case: Case::Lower }),
+            ("YY", Part::Year { digits: 2 }),
+            ("DD", Part::Day { padded: true }),
+            ("MM", Part::Month { padded: true }),
+            ("D", Part::Day { padded: false }),
+            ("M", Part::Month { padded: false }),
+        ];
+        let mut parts = Vec::new();
+        let mut rest = s;
+        while let Some(c) = rest.chars().next() {
+            if let Some((token, part)) = TOKENS.iter().find(|(token, _)| rest.starts_with(token)) {
+                parts.push(part.clone());
+                rest = &rest[token.len()..];
+            } else if c.is_alphabetic() {
+                return Err(invalid!("unknown field at '{}' in date format '{}' (use DD, D, MM, M, YYYY, YY, Month or Mon)", rest, s));
+            } else {
+                parts.push(Part::Literal(c));
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+        if !parts.iter().any(|part| !matches!(part, Part::Literal(_))) {
+            return Err(invalid!("date format '{}' has no day, month or year", s));
+        }
+        Ok(Self { parts })
+    }
+}
+
+impl Format {
+    fn has(&self, wanted: fn(&Part) -> bool) -> bool {
+        self.parts.iter().any(wanted)
+    }
+
+    /// Write `year-month-day` in this format, month names in `language`
+    fn write(&self, out: &mut String, year: u32, month: u32, day: u32, language: Language) {
+        out.clear();
+        for part in &self.parts {
+            match *part {
+                Part::Day { padded: true } => out.push_str(&format!("{:02}", day)),
+                Part::Day { padded: false } => out.push_str(&day.to_string()),
+                Part::Month { padded: true } => out.push_str(&format!("{:02}", month)),
+                Part::Month { padded: false } => out.push_str(&month.to_string()),
+                Part::Year { digits: 2 } => out.push_str(&format!("{:02}", year % 100)),
+                Part::Year { .. } => out.push_str(&format!("{:04}", year)),
+                Part::MonthName { long, case } => {
+                    let (short, full) = month_names(language);
+                    let name = if long { full } else { short }[month as usize - 1];
+                    match case {
+                        Case::Lower => out.push_str(name),
+                        Case::Upper => out.push_str(&name.to_uppercase()),
+                        Case::Title => {
+                            let mut chars = name.chars();
+                            out.extend(chars.next().map(|c| c.to_ascii_uppercase()));
+                            out.push_str(chars.as_str());
+                        }
+                    }
+                }
+                Part::Literal(c) => out.push(c),
+            }
+        }
+    }
+}
+
+/// Every date from January 1 of `from` to December 31 of `to`, in each format
+#[derive(Debug, Clone)]
+pub struct Dates {
+    pub from: u32,
+    pub to: u32,
+    formats: Vec<Format>,
+    languages: Vec<Language>,
+}
+
+impl Dates {
+    /// Month names are written once per language, in the order given
+    pub fn new(from: u32, to: u32, formats: Vec<Format>, languages: Vec<Language>) -> Self {
+        let languages = if languages.is_empty() { vec![Language::English] } else { languages };
+        Self { from, to, formats, languages }
+    }
+
+    /// Each distinct token once, format by format in date order. Return true to stop.
+    pub fn for_each(&self, mut emit: impl FnMut(&str) -> bool) {
+        let mut seen = HashSet::new();
+        let mut token = String::new();
+        for format in &self.formats {
+            let years = if format.has(|part| matches!(part, Part::Year { .. })) { self.from..=self.to } else { 2000..=2000 };
+            let languages = if format.has(|part| matches!(part, Part::MonthName { .. })) { &self.languages[..] } else { &self.languages[..1] };
+            for year in years {
+                for month in 1..=12 {
+                    for day in 1..=days_in_month(year, month) {
+                        for &language in languages {
+                            format.write(&mut token, year, month, day, language);
+                            if !seen.contains(token.as_str()) {
+                                seen.insert(token.clone());
+                                if emit(&token) {
+                                    return;
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Short and full month names, lowercase
+fn month_names(language: Language) -> ([&'static str; 12], [&'static str; 12]) {
+    match language {
+        Language::English => (
+            ["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"],
+            ["january", "february", "march", "april", "may", "june", "july", "august", "september", "october", "november", "december"],
+        ),
+        Language::German => (
+            ["jan", "feb", "maer", "apr", "mai", "jun", "jul", "aug", "sep", "okt", "nov", "dez"],
+            ["januar", "februar", "maerz", "april", "mai", "juni", "juli", "august", "september", "oktober", "november", "dezember"],
+        ),
+        Language::Spanish => (
+            ["ene", "feb", "mar", "abr", "may", "jun", "jul", "ago", "sep", "oct", "nov", "dic"],
+            ["enero", "febrero", "marzo", "abril", "mayo", "junio", "julio", "agosto", "septiembre", "octubre", "noviembre", "diciembre"],
+        ),
+        Language::French => (
+            ["janv", "fevr", "mars", "avr", "mai", "juin", "juil", "aout", "sept", "oct", "nov", "dec"],
+            ["janvier", "fevrier", "mars", "avril", "mai", "juin", "juillet", "aout", "septembre", "octobre", "novembre", "decembre"],
+        ),
+        Language::Italian => (
+            ["gen", "feb", "mar", "apr", "mag", "giu", "lug", "ago", "set", "ott", "nov", "dic"],
+            ["gennaio", "febbraio", "marzo", "aprile", "maggio", "giugno", "luglio", "agosto", "settembre", "ottobre", "novembre", "dicembre"],
+        ),
+        Language::Portuguese => (
+            ["jan", "fev", "mar", "abr", "mai", "jun", "jul", "ago", "set", "out", "nov", "dez"],
+            ["janeiro", "fevereiro", "marco", "abril", "maio", "junho", "julho", "agosto", "setembro", "outubro", "novembro", "dezembro"],
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(from: u32, to: u32, formats: &str, languages: Vec<Language>) -> Vec<String> {
+        let formats = formats.split(',').map(|f| Format::from_str(f).unwrap()).collect();
+        let mut out = Vec::new();
+        Dates::new(from, to, formats, languages).for_each(|token| { out.push(token.to_string()); false });
+        out
+    }
+
+    #[test]
+    fn test_formats() {
+        let out = tokens(1999, 2000, "DDMMYYYY,DD.MM.YY,D/M", vec![]);
+        assert_eq!(out[0], "01011999");
+        assert_eq!(out.iter().filter(|t| t.len() == 8 && !t.contains('.')).count(), 365 + 366);
+        assert!(out.contains(&"29022000".to_string()) && !out.contains(&"29021999".to_string()));
+        assert!(out.contains(&"24.12.99".to_string()) && out.contains(&"7/4".to_string()));
+        // D/M has no year, so it comes out once, leap day included
+        assert_eq!(out.iter().filter(|t| t.contains('/')).count(), 366);
+    }
+
+    #[test]
+    fn test_month_names() {
+        let out = tokens(1990, 1990, "DDmonthYYYY,MonYY", vec![Language::English, Language::German]);
+        assert!(out.contains(&"15march1990".to_string()) && out.contains(&"15maerz1990".to_string()));
+        // Names the languages share come out once: 12 English, plus Maer, Mai, Okt, Dez
+        assert_eq!(out.iter().filter(|t| t.starts_with(char::is_alphabetic)).count(), 16);
+        assert!(out.contains(&"Dec90".to_string()) && out.contains(&"Dez90".to_string()));
+    }
+
+    #[test]
+    fn test_bad_formats() {
+        assert!(Format::from_str("DDMMYYY").is_err());
+        assert!(Format::from_str("--").is_err());
+        assert!(Format::from_str("YYYY-MM-DD").is_ok());
+    }
+}
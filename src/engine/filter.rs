@@ -0,0 +1,131 @@
+use crate::engine::mask::Charset;
+use crate::invalid;
+use anyhow::Result;
+
+// ═══════════════════════════════════════════════════════════════
+// CANDIDATE FILTERS
+// ═══════════════════════════════════════════════════════════════
+//
+// One set of rules every mode's output passes through on its way to the
+// writer: a length range, character classes a candidate must or must not
+// contain, and the characters it may be made of. Engines that can prune
+// by length early (the personal attack) still do, which saves work, but
+// this is what decides what reaches the output, so a mask, Markov and
+// personal run with the same flags keep exactly the same kinds of
+// candidates. Lengths are in bytes, as hashcat counts them.
+
+/// Character class a filter can require or forbid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Class {
+    Lower,
+    Upper,
+    Digit,
+    /// ASCII punctuation and space
+    Special,
+}
+
+impl Class {
+    pub fn contains(self, byte: u8) -> bool {
+        match self {
+            Class::Lower => byte.is_ascii_lowercase(),
+            Class::Upper => byte.is_ascii_uppercase(),
+            Class::Digit => byte.is_ascii_digit(),
+            Class::Special => byte.is_ascii_punctuation() || byte == b' ',
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Filter {
+    pub min_len: Option<usize>,
+    pub max_len: Option<usize>,
+    /// Classes every candidate needs at least one character of
+    pub require: Vec<Class>,
+    /// Classes no candidate may contain
+    pub forbid: Vec<Class>,
+    /// Bytes candidates may use; None allows any
+    allowed: Option<Box<[bool; 256]>>,
+}
+
+impl Filter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep only candidates made of `set`: literal characters and the
+    /// mask classes `?l ?u ?d ?s` (`??` for a literal `?`)
+    pub fn allowed_chars(mut self, set: &str) -> Result<Self> {
+        let mut allowed = Box::new([false; 256]);
+        let bytes = set.as_bytes();
+        let mut i = 0;
+        while i < bytes.len() {
+            let chars: &[u8] = if bytes[i] == b'?' {
+                i += 1;
+                match bytes.get(i) {
+                    Some(b'l') => Charset::Lower.chars(),
+                    Some(b'u') => Charset::Upper.chars(),
+                    Some(b'd') => Charset::Digit.chars(),
+                    Some(b's') => Charset::Special.chars(),
+                    Some(b'?') => b"?",
+                    Some(&other) => return Err(invalid!("unknown class ?{} in allowed characters (use ?l ?u ?d ?s or ??)", other as char)),
+                    None => return Err(invalid!("allowed characters end in a lone '?' (use ?? for a literal one)")),
+                }
+            } else {
+                std::slice::from_ref(&bytes[i])
+            };
+            for &byte in chars {
+                allowed[byte as usize] = true;
+            }
+            i += 1;
+        }
+        if !allowed.contains(&true) {
+            return Err(invalid!("the allowed character set is empty"));
+        }
+        self.allowed = Some(allowed);
+        Ok(self)
+    }
+
+    /// True when the filter lets everything through
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn accepts(&self, candidate: &[u8]) -> bool {
+        if self.min_len.is_some_and(|min| candidate.len() < min) || self.max_len.is_some_and(|max| candidate.len() > max) {
+            return false;
+        }
+        if let Some(allowed) = &self.allowed {
+            if !candidate.iter().all(|&byte| allowed[byte as usize]) {
+                return false;
+            }
+        }
+        self.require.iter().all(|&class| candidate.iter().any(|&byte| class.contains(byte)))
+            && !self.forbid.iter().any(|&class| candidate.iter().any(|&byte| class.contains(byte)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter() {
+        assert!(Filter::new().is_empty() && Filter::new().accepts(b""));
+
+        let filter = Filter { min_len: Some(6), max_len: Some(8), require: vec![Class::Digit], forbid: vec![Class::Special], ..Filter::new() };
+        assert!(!filter.is_empty());
+        assert!(filter.accepts(b"summer19"));
+        assert!(!filter.accepts(b"summer"), "needs a digit");
+        assert!(!filter.accepts(b"summer1!"), "special forbidden");
+        assert!(!filter.accepts(b"sum19"), "too short");
+        assert!(!filter.accepts(b"summer2019"), "too long");
+
+        let filter = Filter::new().allowed_chars("?l?d_").unwrap();
+        assert!(filter.accepts(b"pass_123"));
+        assert!(!filter.accepts(b"Pass_123"));
+        assert!(!filter.accepts("café".as_bytes()));
+        assert!(Filter::new().allowed_chars("??").unwrap().accepts(b"??"));
+        assert!(Filter::new().allowed_chars("?x").is_err());
+        assert!(Filter::new().allowed_chars("").is_err());
+    }
+}
@@ -0,0 +1,183 @@
+// ═══════════════════════════════════════════════════════════════
+// LOCALIZED WORD POOLS
+// ═══════════════════════════════════════════════════════════════
+//
+// Same shape as the English pools in memorable.rs. Accents and umlauts
+// are transliterated (ü → ue, ñ → n) so every word types on any keyboard.
+
+/// Adjectives, nouns, verbs, adverbs and colors for one language
+pub struct Pools {
+    pub adjectives: &'static [&'static str],
+    pub nouns: &'static [&'static str],
+    pub verbs: &'static [&'static str],
+    pub adverbs: &'static [&'static str],
+    pub colors: &'static [&'static str],
+}
+
+pub const GERMAN: Pools = Pools {
+    adjectives: &[
+        "schnell", "mutig", "ruhig", "klug", "stark", "wild", "frei", "hell",
+        "dunkel", "kalt", "warm", "gross", "klein", "leise", "laut", "froh",
+        "treu", "stolz", "sanft", "wach", "flink", "kuehn", "weise", "edel",
+        "frech", "lustig", "tapfer", "eisern", "ewig", "geheim",
+    ],
+    nouns: &[
+        "tiger", "adler", "loewe", "baer", "wolf", "fuchs", "falke", "eule",
+        "hirsch", "katze", "hund", "fisch", "vogel", "frosch", "hai", "wal",
+        "drache", "rabe", "pferd", "burg", "krone", "sturm", "blitz", "stern",
+        "mond", "sonne", "berg", "fluss", "wald", "meer", "insel", "turm",
+        "schild", "pfeil", "funke", "flamme", "welle", "anker", "komet",
+    ],
+    verbs: &[
+        "laufen", "springen", "schwimmen", "fliegen", "singen", "tanzen", "lesen",
+        "schreiben", "malen", "kochen", "essen", "schlafen", "traeumen", "jagen",
+        "klettern", "tauchen", "gleiten", "rennen", "bauen", "reiten", "leuchten",
+        "brennen", "spielen", "lachen", "rufen", "wandern", "segeln", "fangen",
+    ],
+    adverbs: &[
+        "immer", "nie", "oft", "langsam", "gern", "heute", "morgen", "bald",
+        "sehr", "ganz", "fast", "wieder", "hier", "dort", "jetzt", "weit",
+    ],
+    colors: &[
+        "rot", "blau", "gruen", "gold", "schwarz", "weiss", "silber", "lila",
+        "rosa", "grau", "braun", "gelb", "orange", "tuerkis", "bronze",
+    ],
+};
+
+pub const SPANISH: Pools = Pools {
+    adjectives: &[
+        "rapido", "valiente", "tranquilo", "listo", "fuerte", "salvaje", "libre",
+        "claro", "oscuro", "frio", "calido", "grande", "feliz", "alegre", "noble",
+        "fiel", "orgulloso", "suave", "astuto", "sabio", "dulce", "loco", "bravo",
+        "eterno", "secreto", "magico", "veloz", "brillante",
+    ],
+    nouns: &[
+        "tigre", "aguila", "leon", "oso", "lobo", "zorro", "halcon", "buho",
+        "ciervo", "gato", "perro", "pez", "pajaro", "rana", "tiburon", "ballena",
+        "dragon", "cuervo", "caballo", "castillo", "corona", "tormenta", "rayo",
+        "estrella", "luna", "sol", "montana", "rio", "bosque", "mar", "isla",
+        "torre", "escudo", "flecha", "chispa", "llama", "ola", "ancla", "cometa",
+    ],
+    verbs: &[
+        "correr", "saltar", "nadar", "volar", "cantar", "bailar", "leer",
+        "escribir", "pintar", "cocinar", "comer", "dormir", "sonar", "cazar",
+        "escalar", "bucear", "brillar", "construir", "montar", "jugar", "reir",
+        "viajar", "ganar", "luchar", "navegar", "soplar",
+    ],
+    adverbs: &[
+        "siempre", "nunca", "mucho", "poco", "lento", "hoy", "manana", "pronto",
+        "muy", "casi", "aqui", "ahora", "lejos", "bien", "tarde", "temprano",
+    ],
+    colors: &[
+        "rojo", "azul", "verde", "oro", "negro", "blanco", "plata", "morado",
+        "rosa", "gris", "marron", "amarillo", "naranja", "bronce", "turquesa",
+    ],
+};
+
+pub const FRENCH: Pools = Pools {
+    adjectives: &[
+        "rapide", "brave", "calme", "malin", "fort", "sauvage", "libre", "clair",
+        "sombre", "froid", "chaud", "grand", "petit", "heureux", "joyeux", "noble",
+        "fidele", "fier", "doux", "sage", "vif", "fou", "eternel", "secret",
+        "magique", "brillant", "agile",
+    ],
+    nouns: &[
+        "tigre", "aigle", "lion", "ours", "loup", "renard", "faucon", "hibou",
+        "cerf", "chat", "chien", "poisson", "oiseau", "grenouille", "requin",
+        "baleine", "dragon", "corbeau", "cheval", "chateau", "couronne", "tempete",
+        "eclair", "etoile", "lune", "soleil", "montagne", "riviere", "foret", "mer",
+        "ile", "tour", "bouclier", "fleche", "etincelle", "flamme", "vague",
+        "ancre", "comete",
+    ],
+    verbs: &[
+        "courir", "sauter", "nager", "voler", "chanter", "danser", "lire", "ecrire",
+        "peindre", "cuisiner", "manger", "dormir", "rever", "chasser", "grimper",
+        "plonger", "briller", "construire", "monter", "jouer", "rire", "voyager",
+        "gagner", "lutter", "naviguer", "glisser",
+    ],
+    adverbs: &[
+        "toujours", "jamais", "souvent", "vite", "lentement", "demain", "bientot",
+        "tres", "presque", "encore", "ici", "maintenant", "loin", "bien", "hier",
+    ],
+    colors: &[
+        "rouge", "bleu", "vert", "or", "noir", "blanc", "argent", "violet",
+        "rose", "gris", "brun", "jaune", "orange", "bronze", "turquoise",
+    ],
+};
+
+pub const ITALIAN: Pools = Pools {
+    adjectives: &[
+        "veloce", "coraggioso", "calmo", "furbo", "forte", "selvaggio", "libero",
+        "chiaro", "scuro", "freddo", "caldo", "grande", "piccolo", "felice",
+        "allegro", "nobile", "fedele", "fiero", "dolce", "saggio", "vivace",
+        "pazzo", "eterno", "segreto", "magico", "brillante", "agile",
+    ],
+    nouns: &[
+        "tigre", "aquila", "leone", "orso", "lupo", "volpe", "falco", "gufo",
+        "cervo", "gatto", "cane", "pesce", "uccello", "rana", "squalo", "balena",
+        "drago", "corvo", "cavallo", "castello", "corona", "tempesta", "fulmine",
+        "stella", "luna", "sole", "montagna", "fiume", "bosco", "mare", "isola",
+        "torre", "scudo", "freccia", "scintilla", "fiamma", "onda", "ancora",
+        "cometa",
+    ],
+    verbs: &[
+        "correre", "saltare", "nuotare", "volare", "cantare", "ballare", "leggere",
+        "scrivere", "dipingere", "cucinare", "mangiare", "dormire", "sognare",
+        "cacciare", "scalare", "tuffare", "brillare", "costruire", "cavalcare",
+        "giocare", "ridere", "viaggiare", "vincere", "lottare", "navigare",
+    ],
+    adverbs: &[
+        "sempre", "mai", "spesso", "presto", "piano", "oggi", "domani", "subito",
+        "molto", "quasi", "qui", "adesso", "lontano", "bene", "tardi",
+    ],
+    colors: &[
+        "rosso", "blu", "verde", "oro", "nero", "bianco", "argento", "viola",
+        "rosa", "grigio", "marrone", "giallo", "arancio", "bronzo", "turchese",
+    ],
+};
+
+pub const PORTUGUESE: Pools = Pools {
+    adjectives: &[
+        "rapido", "valente", "calmo", "esperto", "forte", "selvagem", "livre",
+        "claro", "escuro", "frio", "quente", "grande", "pequeno", "feliz",
+        "alegre", "nobre", "fiel", "orgulhoso", "suave", "sabio", "doce", "louco",
+        "bravo", "eterno", "secreto", "magico", "brilhante",
+    ],
+    nouns: &[
+        "tigre", "aguia", "leao", "urso", "lobo", "raposa", "falcao", "coruja",
+        "cervo", "gato", "cachorro", "peixe", "passaro", "sapo", "tubarao",
+        "baleia", "dragao", "corvo", "cavalo", "castelo", "coroa", "tempestade",
+        "raio", "estrela", "lua", "sol", "montanha", "rio", "floresta", "mar",
+        "ilha", "torre", "escudo", "flecha", "faisca", "chama", "onda", "ancora",
+        "cometa",
+    ],
+    verbs: &[
+        "correr", "saltar", "nadar", "voar", "cantar", "dancar", "ler", "escrever",
+        "pintar", "cozinhar", "comer", "dormir", "sonhar", "cacar", "escalar",
+        "mergulhar", "brilhar", "construir", "montar", "jogar", "rir", "viajar",
+        "ganhar", "lutar", "navegar",
+    ],
+    adverbs: &[
+        "sempre", "nunca", "muito", "pouco", "hoje", "amanha", "logo", "quase",
+        "aqui", "agora", "longe", "bem", "cedo", "tarde",
+    ],
+    colors: &[
+        "vermelho", "azul", "verde", "ouro", "preto", "branco", "prata", "roxo",
+        "rosa", "cinza", "marrom", "amarelo", "laranja", "bronze", "turquesa",
+    ],
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pools_are_ascii_and_filled() {
+        for pools in [GERMAN, SPANISH, FRENCH, ITALIAN, PORTUGUESE] {
+            for pool in [pools.adjectives, pools.nouns, pools.verbs, pools.adverbs, pools.colors] {
+                assert!(pool.len() >= 10);
+                assert!(pool.iter().all(|w| w.chars().all(|c| c.is_ascii_lowercase())), "{:?}", pool);
+            }
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use std::path::Path;
+use anyhow::Result;
+use crate::invalid;
+
+// ═══════════════════════════════════════════════════════════════
+// LEET EXPANSION
+// ═══════════════════════════════════════════════════════════════
+//
+// Every way of writing a word with up to `max_subs` of its characters
+// swapped for look-alikes, fewest swaps first. The personal attack keeps
+// its own capped handful (full leet plus a few single swaps); this is the
+// exhaustive version, for when a budget is a better limit than a cap.
+
+/// Look-alikes for each character, used by `jigsaw leet` unless `--map` replaces them
+const DEFAULT_MAP: &[(char, &[&str])] = &[
+    ('a', &["@", "4"]),
+    ('b', &["8"]),
+    ('e', &["3"]),
+    ('g', &["9"]),
+    ('i', &["1", "!"]),
+    ('l', &["1"]),
+    ('o', &["0"]),
+    ('s', &["$", "5"]),
+    ('t', &["7", "+"]),
+    ('z', &["2"]),
+];
+
+/// Replacements per character; lookups ignore case, so `a` covers `A`
+#[derive(Debug, Clone)]
+pub struct LeetMap {
+    entries: Vec<(char, Vec<String>)>,
+}
+
+impl Default for LeetMap {
+    fn default() -> Self {
+        Self {
+            entries: DEFAULT_MAP.iter()
+                .map(|(c, subs)| (*c, subs.iter().map(|s| s.to_string()).collect()))
+                .collect(),
+        }
+    }
+}
+
+impl LeetMap {
+    /// Read a map of `char: sub sub…` lines (`a: @ 4`); lines starting
+    /// with `#` are comments. Replacements are split on whitespace, so
+    /// they may be several characters (`h: |-| #`).
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        Self::parse(&text).map_err(|e| invalid!("{}: {}", path.display(), e))
+    }
+
+    fn parse(text: &str) -> Result<Self> {
+        let mut entries: Vec<(char, Vec<String>)> = Vec::new();
+        for (lineno, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut key = line.chars();
+            let (Some(c), Some(':')) = (key.next(), key.next()) else {
+                return Err(invalid!("line {}: expected 'a: @ 4'", lineno + 1));
+            };
+            let c = c.to_lowercase().next().unwrap_or(c);
+            let subs = key.as_str().split_whitespace()
+                .filter(|sub| sub.to_lowercase() != c.to_string())
+                .map(str::to_string);
+            match entries.iter_mut().find(|(key, _)| *key == c) {
+                Some((_, existing)) => existing.extend(subs),
+                None => entries.push((c, subs.collect())),
+            }
+        }
+        for (_, subs) in &mut entries {
+            let mut seen = std::collections::HashSet::new();
+            subs.retain(|sub| seen.insert(sub.clone()));
+        }
+        entries.retain(|(_, subs)| !subs.is_empty());
+        if entries.is_empty() {
+            return Err(invalid!("no substitutions"));
+        }
+        Ok(Self { entries })
+    }
+
+    fn subs(&self, c: char) -> &[String] {
+        let lower = c.to_lowercase().next().unwrap_or(c);
+        self.entries.iter().find(|(key, _)| *key == lower).map_or(&[], |(_, subs)| subs)
+    }
+
+    /// How many strings `expand` gives `word`, the word itself included
+    pub fn count(&self, word: &str, max_subs: usize) -> u64 {
+        // ways[k]: variants with exactly k positions swapped
+        let mut ways = vec![0u64; max_subs + 1];
+        ways[0] = 1;
+        for c in word.chars() {
+            let options = self.subs(c).len() as u64;
+            if options == 0 {
+                continue;
+            }
+            for k in (1..=max_subs).rev() {
+                ways[k] = ways[k].saturating_add(ways[k - 1].saturating_mul(options));
+            }
+        }
+        ways.iter().fold(0u64, |total, &n| total.saturating_add(n))
+    }
+
+    /// `word` and each variant with 1 to `max_subs` characters swapped,
+    /// fewest swaps first. Returns true once `callback` does, to stop early.
+    pub fn expand<F>(&self, word: &str, max_subs: usize, mut callback: F) -> bool
+    where F: FnMut(String) -> bool
+    {
+        let chars: Vec<char> = word.chars().collect();
+        let positions: Vec<usize> = (0..chars.len()).filter(|&i| !self.subs(chars[i]).is_empty()).collect();
+        let mut parts: Vec<String> = chars.iter().map(|c| c.to_string()).collect();
+        for k in 0..=max_subs.min(positions.len()) {
+            if self.swap(&chars, &positions, k, &mut parts, &mut callback) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Every way to swap `k` more of `positions`, each choosing one of its
+    /// replacements, with `parts` holding the swaps made so far
+    fn swap(&self, chars: &[char], positions: &[usize], k: usize, parts: &mut [String], callback: &mut dyn FnMut(String) -> bool) -> bool {
+        if k == 0 {
+            return callback(parts.concat());
+        }
+        for (n, &i) in positions.iter().enumerate().take(positions.len() + 1 - k) {
+            for sub in self.subs(chars[i]) {
+                parts[i].clone_from(sub);
+                if self.swap(chars, &positions[n + 1..], k - 1, parts, callback) {
+                    parts[i] = chars[i].to_string();
+                    return true;
+                }
+            }
+            parts[i] = chars[i].to_string();
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand(map: &LeetMap, word: &str, max_subs: usize) -> Vec<String> {
+        let mut out = Vec::new();
+        map.expand(word, max_subs, |s| { out.push(s); false });
+        out
+    }
+
+    #[test]
+    fn test_expand() {
+        let map = LeetMap::default();
+        assert_eq!(expand(&map, "Sea", 1), ["Sea", "$ea", "5ea", "S3a", "Se@", "Se4"]);
+        let all = expand(&map, "password", 8);
+        assert_eq!(all.len() as u64, map.count("password", 8));
+        assert_eq!(all.len(), 3 * 3 * 3 * 2);
+        assert!(all.contains(&"p@$$w0rd".to_string()));
+        assert_eq!(expand(&map, "password", 2).len() as u64, map.count("password", 2));
+        assert!(!expand(&map, "password", 2).contains(&"p@$$word".to_string()));
+        assert_eq!(expand(&map, "xyx", 3), ["xyx"]);
+
+        let mut seen = 0;
+        assert!(map.expand("password", 3, |_| { seen += 1; seen == 10 }));
+
+        let map = LeetMap::parse("# custom\nh: |-| #\nH: # 4\no: o 0\n").unwrap();
+        assert_eq!(expand(&map, "Ho", 2), ["Ho", "|-|o", "#o", "4o", "H0", "|-|0", "#0", "40"]);
+        assert!(LeetMap::parse("ab @").is_err());
+        assert!(LeetMap::parse("a: a").is_err());
+    }
+}
@@ -3,10 +3,15 @@ use rand::Rng;
 use rand::RngExt;
 use serde::{Serialize, Deserialize};
 use std::fs::File;
-use std::io::{self, BufRead, BufReader};
+use std::io::BufRead;
 use std::path::Path;
 use anyhow::Result;
 
+/// Walks `generate` tries before settling for one shorter than asked
+const MAX_ATTEMPTS: usize = 1000;
+
+/// Character transitions learned from a corpus: each `order`-character
+/// context maps to the characters seen after it
 #[derive(Serialize, Deserialize, Debug)]
 pub struct MarkovModel {
     pub order: usize,
@@ -22,14 +27,20 @@ impl MarkovModel {
         }
     }
 
+    /// Train on a corpus file, one word per line (compressed files work too)
+    #[cfg(feature = "cli")]
     pub fn train(&mut self, corpus_path: &Path) -> Result<()> {
-        let file = File::open(corpus_path)?;
-        let reader = BufReader::new(file);
+        self.train_reader(crate::io::reader::open(corpus_path)?)
+    }
 
+    /// Train on a corpus already open, one word per line
+    pub fn train_reader(&mut self, reader: impl BufRead) -> Result<()> {
         let mut counts: HashMap<String, HashMap<char, usize>> = HashMap::new();
 
-        for line in reader.lines() {
-            let word = line?;
+        for line in reader.split(b'\n') {
+            // Leaked corpora are full of non-UTF-8 lines; skip them
+            let Ok(line) = String::from_utf8(line?) else { continue };
+            let word = line.trim_end_matches('\r');
             if word.len() < self.order {
                 continue;
             }
@@ -82,7 +93,22 @@ impl MarkovModel {
         Ok(())
     }
 
+    /// One candidate of `min_len` to `max_len` characters, drawn from `rng`
     pub fn generate(&self, rng: &mut impl Rng, min_len: usize, max_len: usize) -> String {
+        // Retry short walks, but give up eventually: a model whose chains all
+        // dead-end early can never reach min_len
+        let mut result = self.walk(rng, max_len);
+        for _ in 1..MAX_ATTEMPTS {
+            if result.len() >= min_len {
+                break;
+            }
+            result = self.walk(rng, max_len);
+        }
+        result
+    }
+
+    /// One walk from a random start until max_len or a dead end
+    fn walk(&self, rng: &mut impl Rng, max_len: usize) -> String {
         // Without start/end tokens, we need a random starting point.
         // A better model would have a special START node.
         // For this implementation, we pick a random context from the map to start.
@@ -122,16 +148,29 @@ impl MarkovModel {
                 break;
             }
         }
-        
-        // Ensure min length (simple retry or truncation? simple truncation doesn't help if too short)
-        if result.len() < min_len {
-            // Recurse or loop? Loop protection needed.
-            return self.generate(rng, min_len, max_len); 
-        }
-        
+
         result
     }
 
+    /// Natural log of the chance `generate` picks `word`'s first `order`
+    /// characters as its start and then each following character in turn;
+    /// `None` when the model can't produce it at all
+    pub fn log_probability(&self, word: &str) -> Option<f64> {
+        let chars: Vec<char> = word.chars().collect();
+        if chars.len() < self.order || !self.transitions.contains_key(&chars[..self.order].iter().collect::<String>()) {
+            return None;
+        }
+        let mut log_prob = -(self.transitions.len() as f64).ln();
+        for i in self.order..chars.len() {
+            let context: String = chars[i - self.order..i].iter().collect();
+            let trans = self.transitions.get(&context)?;
+            let at = trans.iter().position(|(c, _)| *c == chars[i])?;
+            let below = if at == 0 { 0.0 } else { trans[at - 1].1 };
+            log_prob += (trans[at].1 - below).ln();
+        }
+        Some(log_prob)
+    }
+
     pub fn save(&self, path: &Path) -> Result<()> {
         let file = File::create(path)?;
         serde_json::to_writer(file, self)?;
@@ -144,3 +183,30 @@ impl MarkovModel {
         Ok(model)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_probability() {
+        let mut model = MarkovModel::new(2);
+        model.train_reader("abc\nabd\nbcd\n".as_bytes()).unwrap();
+        // Contexts "ab" and "bc"; "ab" goes to c or d evenly
+        let p = model.log_probability("abc").unwrap().exp();
+        assert!((p - 0.25).abs() < 1e-9, "{}", p);
+        let p = model.log_probability("abcd").unwrap().exp();
+        assert!((p - 0.25).abs() < 1e-9, "{}", p);
+        assert!(model.log_probability("abx").is_none());
+        assert!(model.log_probability("zz").is_none());
+        assert!(model.log_probability("a").is_none());
+    }
+
+    #[test]
+    fn test_generate_gives_up_on_unreachable_min_len() {
+        let mut model = MarkovModel::new(2);
+        model.train_reader("abc\n".as_bytes()).unwrap();
+        let mut rng = crate::engine::memorable::seeded_rng(1);
+        assert_eq!(model.generate(&mut rng, 10, 20), "abc");
+    }
+}
@@ -1,7 +1,8 @@
 use std::str::FromStr;
-use anyhow::{anyhow, Result};
-use rayon::prelude::*;
+use anyhow::Result;
+use crate::invalid;
 
+/// The characters one mask position ranges over
 #[derive(Debug, Clone, PartialEq)]
 pub enum Charset {
     Lower,
@@ -13,6 +14,7 @@ pub enum Charset {
 }
 
 impl Charset {
+    /// The characters, in the order masks iterate them
     pub fn chars(&self) -> &[u8] {
         match self {
             Charset::Lower => b"abcdefghijklmnopqrstuvwxyz",
@@ -25,6 +27,13 @@ impl Charset {
     }
 }
 
+/// Characters likeliest first: letters by English frequency (either case),
+/// then the digits and symbols that lead in leaked passwords. Characters
+/// not listed follow these, in their usual order.
+const FREQUENCY: &[u8] = b"etaoinshrdlcumwfgypbvkjxqz1203!@#$._*-&?";
+
+/// A fixed-length pattern, one `Charset` per position; parse one from
+/// hashcat syntax (`?l?u?d?s`, `??` for a literal `?`) with `FromStr`
 #[derive(Debug, Clone)]
 pub struct Mask {
     pub components: Vec<Charset>,
@@ -36,42 +45,135 @@ impl Mask {
         Self { components }
     }
 
+    /// Iterate every charset likeliest character first (`e t a o i n…`, see
+    /// `FREQUENCY`) instead of a–z, so a partial run covers likelier
+    /// candidates first. The keyspace is the same, only its order changes.
+    pub fn frequency_ordered(mut self) -> Self {
+        let rank = |c: &u8| FREQUENCY.iter().position(|f| *f == c.to_ascii_lowercase()).unwrap_or(FREQUENCY.len());
+        for component in &mut self.components {
+            if !matches!(component, Charset::Literal(_)) {
+                let mut chars = component.chars().to_vec();
+                chars.sort_by_key(rank);
+                *component = Charset::Custom(chars);
+            }
+        }
+        self
+    }
+
     /// Calculate the total size of the search space for this mask
     pub fn search_space_size(&self) -> u128 {
         self.components.iter().map(|c| c.chars().len() as u128).product()
     }
 
+    /// Every candidate, first position varying slowest
     pub fn iter(&self) -> MaskIterator<'_> {
         MaskIterator::new(self)
     }
 
-    pub fn nth_candidate(&self, mut index: u128) -> Option<Vec<u8>> {
-        let total = self.search_space_size();
-        if index >= total {
-            return None;
+    /// `iter` from `index` on, without walking there
+    pub fn iter_from(&self, index: u128) -> MaskIterator<'_> {
+        let mut iter = MaskIterator::new(self);
+        if index >= self.search_space_size() {
+            iter.done = true;
+            return iter;
         }
-
-        let mut candidate = Vec::with_capacity(self.components.len());
-        
-        let mut divisors = Vec::with_capacity(self.components.len());
-        let mut current_div = total;
-        
-        for component in &self.components {
+        let mut rest = index;
+        for (i, component) in self.components.iter().enumerate().rev() {
             let len = component.chars().len() as u128;
-            current_div /= len;
-            divisors.push((current_div, len));
+            iter.indices[i] = (rest % len) as usize;
+            rest /= len;
         }
-        
-        for (i, component) in self.components.iter().enumerate() {
-            let (divisor, len) = divisors[i];
+        iter
+    }
+
+    /// The candidate `iter` yields at `index`, without walking there
+    pub fn nth_candidate(&self, index: u128) -> Option<Vec<u8>> {
+        if index >= self.search_space_size() {
+            return None;
+        }
+        // Last position varies fastest, so peel positions off the low end
+        let mut candidate = vec![0; self.components.len()];
+        let mut rest = index;
+        for (slot, component) in candidate.iter_mut().zip(&self.components).rev() {
             let chars = component.chars();
-            let char_idx = (index / divisor) % len;
-            candidate.push(chars[char_idx as usize]);
+            *slot = chars[(rest % chars.len() as u128) as usize];
+            rest /= chars.len() as u128;
         }
-        
         Some(candidate)
     }
 
+    /// Candidates `start..start + count` in `iter` order, cut off at the
+    /// end of the keyspace, written over `batch`. Entries already in
+    /// `batch` keep their allocations, so a batch reused across calls
+    /// allocates nothing. Only `start` is divided out; every candidate
+    /// after it is one step of an odometer.
+    pub fn fill_batch(&self, start: u128, count: usize, batch: &mut Vec<Vec<u8>>) {
+        let total = self.search_space_size();
+        let count = if start >= total { 0 } else { (count as u128).min(total - start) as usize };
+        let width = self.components.len();
+        batch.resize_with(count, || Vec::with_capacity(width));
+        if count == 0 {
+            return;
+        }
+        let sets: Vec<&[u8]> = self.components.iter().map(Charset::chars).collect();
+        let mut digits = vec![0; width];
+        let mut rest = start;
+        for (digit, chars) in digits.iter_mut().zip(&sets).rev() {
+            *digit = (rest % chars.len() as u128) as usize;
+            rest /= chars.len() as u128;
+        }
+        for candidate in batch.iter_mut() {
+            candidate.clear();
+            candidate.extend(sets.iter().zip(&digits).map(|(chars, &digit)| chars[digit]));
+            for (digit, chars) in digits.iter_mut().zip(&sets).rev() {
+                *digit += 1;
+                if *digit < chars.len() {
+                    break;
+                }
+                *digit = 0;
+            }
+        }
+    }
+
+    /// The keyspace from `start` on, `size` candidates at a time, in order.
+    /// Each batch is one from `spare` refilled by `fill_batch`, so batches
+    /// handed back by the consumer (`BatchSender::spare`) cost no allocation.
+    pub fn batches_from<'a>(
+        &'a self,
+        start: u128,
+        size: usize,
+        mut spare: impl FnMut() -> Vec<Vec<u8>> + 'a,
+    ) -> impl Iterator<Item = Vec<Vec<u8>>> + 'a {
+        let size = size.max(1);
+        let mut next = start;
+        std::iter::from_fn(move || {
+            let mut batch = spare();
+            self.fill_batch(next, size, &mut batch);
+            next += batch.len() as u128;
+            (!batch.is_empty()).then_some(batch)
+        })
+    }
+
+    /// The keyspace as batches of `size` candidates, each one from `spare`
+    /// refilled in parallel by `fill_batch`. Each batch is in order; batches
+    /// arrive in any order.
+    #[cfg(feature = "parallel")]
+    pub fn par_batches<'a>(
+        &'a self,
+        size: usize,
+        spare: impl Fn() -> Vec<Vec<u8>> + Sync + Send + 'a,
+    ) -> impl rayon::iter::ParallelIterator<Item = Vec<Vec<u8>>> + 'a {
+        use rayon::prelude::*;
+        let size = size.max(1);
+        (0..self.search_space_size().div_ceil(size as u128)).into_par_iter().map(move |i| {
+            let mut batch = spare();
+            self.fill_batch(i * size as u128, size, &mut batch);
+            batch
+        })
+    }
+
+    /// `iter` as a rayon parallel iterator, in the same order
+    #[cfg(feature = "parallel")]
     pub fn par_iter(&self) -> rayon::iter::Map<rayon::range::Iter<u128>, impl Fn(u128) -> Vec<u8> + '_> {
         use rayon::prelude::*;
         let size = self.search_space_size();
@@ -79,10 +181,54 @@ impl Mask {
     }
 }
 
-impl FromStr for Mask {
-    type Err = anyhow::Error;
+/// Charsets the `?1`–`?4` placeholders stand for, as hashcat's `-1`–`-4` set them
+pub type CustomCharsets = [Option<Vec<u8>>; 4];
 
-    fn from_str(s: &str) -> Result<Self> {
+/// The charset `?<class>` names
+fn placeholder(class: u8, custom: &CustomCharsets) -> Result<Charset> {
+    Ok(match class {
+        b'l' => Charset::Lower,
+        b'u' => Charset::Upper,
+        b'd' => Charset::Digit,
+        b's' => Charset::Special,
+        b'?' => Charset::Literal(b'?'),
+        b'1'..=b'4' => match &custom[(class - b'1') as usize] {
+            Some(chars) => Charset::Custom(chars.clone()),
+            None => return Err(invalid!("?{} is used but custom charset {} isn't set", class as char, class as char)),
+        },
+        c => return Err(invalid!("Unknown mask pattern: ?{}", c as char)),
+    })
+}
+
+/// Expand a custom charset written inline, hashcat style: literal characters,
+/// `?l ?u ?d ?s`, `??`, and `?1`–`?4` from `custom`. Repeats are dropped so
+/// each character counts toward the keyspace once.
+pub fn expand_charset(spec: &str, custom: &CustomCharsets) -> Result<Vec<u8>> {
+    let bytes = spec.as_bytes();
+    let mut chars = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'?' {
+            let class = *bytes.get(i + 1).ok_or_else(|| invalid!("Invalid charset: ends with ?"))?;
+            chars.extend_from_slice(placeholder(class, custom)?.chars());
+            i += 2;
+        } else {
+            chars.push(bytes[i]);
+            i += 1;
+        }
+    }
+    Ok(dedup(chars))
+}
+
+/// Drop repeated bytes, keeping the first of each
+pub fn dedup(chars: Vec<u8>) -> Vec<u8> {
+    let mut seen = [false; 256];
+    chars.into_iter().filter(|&c| !std::mem::replace(&mut seen[c as usize], true)).collect()
+}
+
+impl Mask {
+    /// Parse `s`, resolving `?1`–`?4` to `custom`
+    pub fn with_charsets(s: &str, custom: &CustomCharsets) -> Result<Self> {
         let mut components = Vec::new();
         let bytes = s.as_bytes();
         let mut i = 0;
@@ -90,16 +236,9 @@ impl FromStr for Mask {
         while i < bytes.len() {
             if bytes[i] == b'?' {
                 if i + 1 >= bytes.len() {
-                    return Err(anyhow!("Invalid mask: ends with ?"));
-                }
-                match bytes[i + 1] {
-                    b'l' => components.push(Charset::Lower),
-                    b'u' => components.push(Charset::Upper),
-                    b'd' => components.push(Charset::Digit),
-                    b's' => components.push(Charset::Special),
-                    b'?' => components.push(Charset::Literal(b'?')),
-                    c => return Err(anyhow!("Unknown mask pattern: ?{}", c as char)),
+                    return Err(invalid!("Invalid mask: ends with ?"));
                 }
+                components.push(placeholder(bytes[i + 1], custom)?);
                 i += 2;
             } else {
                 components.push(Charset::Literal(bytes[i]));
@@ -111,6 +250,14 @@ impl FromStr for Mask {
     }
 }
 
+impl FromStr for Mask {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Mask::with_charsets(s, &CustomCharsets::default())
+    }
+}
+
 pub struct MaskIterator<'a> {
     mask: &'a Mask,
     indices: Vec<usize>,
@@ -191,6 +338,41 @@ mod tests {
         assert!(mask.nth_candidate(100).is_none());
     }
 
+    #[test]
+    fn test_batches() {
+        let mask = Mask::from_str("a?d?l").unwrap();
+        let all: Vec<Vec<u8>> = mask.iter().collect();
+        let mut batch = vec![b"leftover".to_vec(); 50];
+        mask.fill_batch(255, 10, &mut batch);
+        assert_eq!(batch, all[255..]);
+        mask.fill_batch(9, 40, &mut batch);
+        assert_eq!(batch, all[9..49]);
+        mask.fill_batch(260, 10, &mut batch);
+        assert!(batch.is_empty());
+
+        let batched: Vec<Vec<u8>> = mask.batches_from(0, 7, Vec::new).flatten().collect();
+        assert_eq!(batched, all);
+        assert_eq!(mask.batches_from(250, 7, Vec::new).map(|batch| batch.len()).collect::<Vec<_>>(), [7, 3]);
+
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+            let mut parallel: Vec<Vec<u8>> = mask.par_batches(7, Vec::new).flatten_iter().collect();
+            parallel.sort();
+            assert_eq!(parallel, all);
+        }
+    }
+
+    #[test]
+    fn test_iter_from() {
+        let mask = Mask::from_str("?d?l").unwrap();
+        let tail: Vec<Vec<u8>> = mask.iter_from(27).collect();
+        assert_eq!(tail.len(), 260 - 27);
+        assert_eq!(tail[0], mask.nth_candidate(27).unwrap());
+        assert_eq!(tail, mask.iter().skip(27).collect::<Vec<_>>());
+        assert_eq!(mask.iter_from(260).count(), 0);
+    }
+
     #[test]
     fn test_mask_parsing() {
         let mask = Mask::from_str("?d").unwrap();
@@ -204,6 +386,43 @@ mod tests {
         assert_eq!(mask.components.len(), 4);
     }
 
+    #[test]
+    fn test_frequency_ordered() {
+        let mask = Mask::from_str("?l?u?d?s-").unwrap();
+        let ordered = mask.clone().frequency_ordered();
+        assert_eq!(ordered.search_space_size(), mask.search_space_size());
+        assert_eq!(&ordered.components[0].chars()[..6], b"etaoin");
+        assert_eq!(&ordered.components[1].chars()[..6], b"ETAOIN");
+        assert_eq!(ordered.components[2].chars(), b"1203456789");
+        assert_eq!(&ordered.components[3].chars()[..4], b"!@#$");
+        assert_eq!(ordered.components[4], Charset::Literal(b'-'));
+        for (a, b) in mask.components.iter().zip(&ordered.components) {
+            let (mut a, mut b) = (a.chars().to_vec(), b.chars().to_vec());
+            a.sort();
+            b.sort();
+            assert_eq!(a, b);
+        }
+        assert_eq!(ordered.nth_candidate(0).unwrap(), b"eE1!-");
+    }
+
+    #[test]
+    fn test_custom_charsets() {
+        let mut custom = CustomCharsets::default();
+        custom[0] = Some(expand_charset("?dabca", &custom).unwrap());
+        assert_eq!(custom[0].as_deref(), Some(&b"0123456789abc"[..]));
+        custom[1] = Some(expand_charset("?1?u", &custom).unwrap());
+        assert_eq!(custom[1].as_ref().unwrap().len(), 13 + 26);
+
+        let mask = Mask::with_charsets("x?1?2", &custom).unwrap();
+        assert_eq!(mask.search_space_size(), 13 * 39);
+        assert_eq!(mask.nth_candidate(0).unwrap(), b"x00");
+        assert_eq!(mask.nth_candidate(13 * 39 - 1).unwrap(), b"xcZ");
+
+        assert!(Mask::with_charsets("?3", &custom).is_err());
+        assert!(Mask::from_str("?1").is_err());
+        assert!(expand_charset("ab?", &custom).is_err());
+    }
+
     #[test]
     fn test_generation_small() {
         let mask = Mask::from_str("?d").unwrap();
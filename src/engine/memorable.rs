@@ -1,7 +1,12 @@
+use chacha20::ChaCha20Rng;
 use rand::seq::IndexedRandom;
-use rand::Rng;
-use rand::RngExt;
+use rand::{Rng, RngExt, SeedableRng};
 use serde::{Serialize, Deserialize};
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::sync::OnceLock;
+use crate::engine::languages;
+use crate::invalid;
 
 // ═══════════════════════════════════════════════════════════════
 // CONFIGURATION
@@ -27,10 +32,63 @@ pub enum Position {
 pub enum MemorableStyle {
     Classic,      // Adjective-Noun-Verb-Number (HappyTiger42!)
     Passphrase,   // word-word-word-word (correct-horse-battery-staple)
-    Story,        // Subject-Verb-Object (TigerEatsFish)
+    Story,        // Tiny sentence (TheBraveTigerRunsQuickly)
     Alliterative, // Same starting letter (BraveBearBounces)
 }
 
+/// How the inserted number is written
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NumberForm {
+    /// 42
+    #[default]
+    Digits,
+    /// FortyTwo (English)
+    Words,
+    /// FortyTwo42
+    WordsAndDigits,
+}
+
+/// Where words come from
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WordPool {
+    /// Small built-in adjective/noun/verb pools (drives the styles)
+    #[default]
+    Builtin,
+    /// EFF long diceware list: 7776 words, ~12.9 bits each
+    EffLong,
+    /// EFF short diceware list: 1296 words, ~10.3 bits each
+    EffShort,
+}
+
+/// Language of the built-in parts-of-speech pools
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+    Spanish,
+    French,
+    Italian,
+    Portuguese,
+}
+
+/// Loadable pools, e.g. from a JSON file with these five keys. Lists left
+/// empty fall back to the selected language's built-in pool.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CustomPools {
+    #[serde(default)]
+    pub adjectives: Vec<String>,
+    #[serde(default)]
+    pub nouns: Vec<String>,
+    #[serde(default)]
+    pub verbs: Vec<String>,
+    #[serde(default)]
+    pub adverbs: Vec<String>,
+    #[serde(default)]
+    pub colors: Vec<String>,
+}
+
+/// Everything that shapes a memorable password; `Default` is the CLI's defaults
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct MemorableConfig {
     pub word_count: usize,
@@ -45,8 +103,45 @@ pub struct MemorableConfig {
     pub count: usize,
     pub min_length: usize,
     pub max_length: usize,
+    #[serde(default)]
+    pub word_pool: WordPool,
+    /// Organisation-supplied words; overrides `word_pool` when non-empty
+    #[serde(default)]
+    pub custom_words: Vec<String>,
+    #[serde(default)]
+    pub language: Language,
+    #[serde(default)]
+    pub custom_pools: Option<CustomPools>,
+    /// Pattern like "{adj}-{noun}{num2}{special}"; overrides style, word
+    /// count, separator and number/special placement when set
+    #[serde(default)]
+    pub template: Option<String>,
+    /// Site rules every generated password must satisfy
+    #[serde(default)]
+    pub policy: Option<Policy>,
+    /// Chance each word gets one or two leet substitutions (0 = off)
+    #[serde(default)]
+    pub leet_probability: f64,
+    /// Makes a batch reproducible; unset draws from the thread RNG. The
+    /// sequence for a given seed may change between rand releases.
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Symbols to insert instead of the built-in set, e.g. "!@#_"
+    #[serde(default)]
+    pub specials: Option<String>,
+    /// Only pick words with at least this many letters
+    #[serde(default)]
+    pub min_word_len: Option<usize>,
+    /// Only pick words with at most this many letters
+    #[serde(default)]
+    pub max_word_len: Option<usize>,
+    #[serde(default)]
+    pub number_form: NumberForm,
 }
 
+/// Most words `meet_min_entropy` will grow a password to
+const MAX_ENTROPY_WORDS: usize = 12;
+
 impl Default for MemorableConfig {
     fn default() -> Self {
         Self {
@@ -62,6 +157,18 @@ impl Default for MemorableConfig {
             count: 1,
             min_length: 12,
             max_length: 32,
+            word_pool: WordPool::Builtin,
+            custom_words: Vec::new(),
+            language: Language::English,
+            custom_pools: None,
+            template: None,
+            policy: None,
+            leet_probability: 0.0,
+            seed: None,
+            specials: None,
+            min_word_len: None,
+            max_word_len: None,
+            number_form: NumberForm::Digits,
         }
     }
 }
@@ -125,6 +232,121 @@ const COLORS: &[&str] = &[
     "copper", "pearl", "cobalt", "emerald", "slate", "grey", "rose",
 ];
 
+const ENGLISH: languages::Pools = languages::Pools {
+    adjectives: ADJECTIVES,
+    nouns: NOUNS,
+    verbs: VERBS,
+    adverbs: ADVERBS,
+    colors: COLORS,
+};
+
+/// Pools in effect for one config: custom lists over the chosen language
+struct ActivePools<'a> {
+    adjectives: Vec<&'a str>,
+    nouns: Vec<&'a str>,
+    verbs: Vec<&'a str>,
+    adverbs: Vec<&'a str>,
+    colors: Vec<&'a str>,
+}
+
+fn active_pools(config: &MemorableConfig) -> ActivePools<'_> {
+    let base = match config.language {
+        Language::English => ENGLISH,
+        Language::German => languages::GERMAN,
+        Language::Spanish => languages::SPANISH,
+        Language::French => languages::FRENCH,
+        Language::Italian => languages::ITALIAN,
+        Language::Portuguese => languages::PORTUGUESE,
+    };
+    let custom = config.custom_pools.as_ref();
+
+    ActivePools {
+        adjectives: merge_pool(config, custom.map(|c| &c.adjectives), base.adjectives),
+        nouns: merge_pool(config, custom.map(|c| &c.nouns), base.nouns),
+        verbs: merge_pool(config, custom.map(|c| &c.verbs), base.verbs),
+        adverbs: merge_pool(config, custom.map(|c| &c.adverbs), base.adverbs),
+        colors: merge_pool(config, custom.map(|c| &c.colors), base.colors),
+    }
+}
+
+fn merge_pool<'a>(config: &MemorableConfig, list: Option<&'a Vec<String>>, builtin: &'static [&'static str]) -> Vec<&'a str> {
+    let words: Vec<&str> = match list {
+        Some(words) if !words.is_empty() => words.iter().map(String::as_str).collect(),
+        _ => builtin.to_vec(),
+    };
+    words.into_iter().filter(|w| word_fits(config, w)).collect()
+}
+
+/// Whether `word` satisfies the per-word length limits
+fn word_fits(config: &MemorableConfig, word: &str) -> bool {
+    let len = word.chars().count();
+    config.min_word_len.is_none_or(|min| len >= min) && config.max_word_len.is_none_or(|max| len <= max)
+}
+
+/// Fail if the per-word length limits leave a pool the config draws from empty
+pub fn check_word_lengths(config: &MemorableConfig) -> anyhow::Result<()> {
+    if let (Some(min), Some(max)) = (config.min_word_len, config.max_word_len) {
+        if min > max {
+            return Err(invalid!("minimum word length {} is greater than maximum {}", min, max));
+        }
+    }
+    if config.min_word_len.is_none() && config.max_word_len.is_none() {
+        return Ok(());
+    }
+
+    if let Some(pool) = external_pool(config) {
+        if pool.is_empty() {
+            return Err(invalid!("no words in the word list fit the word length limits"));
+        }
+        return Ok(());
+    }
+    let pools = active_pools(config);
+    for (name, pool) in [
+        ("adjective", &pools.adjectives), ("noun", &pools.nouns), ("verb", &pools.verbs),
+        ("adverb", &pools.adverbs), ("color", &pools.colors),
+    ] {
+        if pool.is_empty() {
+            return Err(invalid!("no {} fits the word length limits; widen them", name));
+        }
+    }
+    Ok(())
+}
+
+// EFF diceware lists (https://www.eff.org/dice, CC BY 3.0 US)
+const EFF_LONG: &str = include_str!("wordlists/eff_large_wordlist.txt");
+const EFF_SHORT: &str = include_str!("wordlists/eff_short_wordlist_1.txt");
+
+/// Words of a "<dice>\t<word>" diceware list, parsed once
+fn diceware(list: &'static str, cache: &'static OnceLock<Vec<&'static str>>) -> &'static [&'static str] {
+    cache.get_or_init(|| {
+        list.lines()
+            .filter_map(|line| line.split_whitespace().nth(1))
+            .collect()
+    })
+}
+
+/// The EFF long list's 7776 words, in dice order
+pub fn eff_long_words() -> &'static [&'static str] {
+    static LONG: OnceLock<Vec<&'static str>> = OnceLock::new();
+    diceware(EFF_LONG, &LONG)
+}
+
+/// The EFF short list's 1296 words, in dice order
+pub fn eff_short_words() -> &'static [&'static str] {
+    static SHORT: OnceLock<Vec<&'static str>> = OnceLock::new();
+    diceware(EFF_SHORT, &SHORT)
+}
+
+/// Substitutions for `leet_probability`; the first is the most common
+const LEET: &[(char, &[char])] = &[
+    ('a', &['@', '4']),
+    ('e', &['3']),
+    ('i', &['1', '!']),
+    ('o', &['0']),
+    ('s', &['$', '5']),
+    ('t', &['7']),
+];
+
 const SPECIALS: &[char] = &[
     '!', '@', '#', '$', '%', '&', '*', '?', '+', '=', '^', '~',
 ];
@@ -133,33 +355,73 @@ const SPECIALS: &[char] = &[
 // GENERATION ENGINE
 // ═══════════════════════════════════════════════════════════════
 
+/// RNG for `--seed`: ChaCha20 keyed with the seed's low bytes. The stream
+/// itself is fixed, but word and digit picks go through `rand`'s sampling,
+/// so a seed is only guaranteed to repeat its passwords within this build.
+pub fn seeded_rng(seed: u64) -> ChaCha20Rng {
+    let mut key = [0u8; 32];
+    key[..8].copy_from_slice(&seed.to_le_bytes());
+    ChaCha20Rng::from_seed(key)
+}
+
+/// One password with the default settings
 pub fn generate_memorable_password() -> String {
-    generate_with_config(&MemorableConfig::default())
+    generate_with_config(&MemorableConfig::default()).expect("default config has no policy")
 }
 
-pub fn generate_with_config(config: &MemorableConfig) -> String {
-    let mut rng = rand::rng();
-    // Retry loop to satisfy length constraints
-    for _ in 0..100 {
-        let result = build_password(&mut rng, config);
-        if result.len() >= config.min_length && result.len() <= config.max_length {
-            return result;
+/// Fails only when a policy is set and no compliant password turns up
+pub fn generate_with_config(config: &MemorableConfig) -> anyhow::Result<String> {
+    match config.seed {
+        Some(seed) => generate_with_rng(&mut seeded_rng(seed), config),
+        None => generate_with_rng(&mut rand::rng(), config),
+    }
+}
+
+/// One password drawn from `rng`; `config.seed` is ignored
+pub fn generate_with_rng(rng: &mut impl Rng, config: &MemorableConfig) -> anyhow::Result<String> {
+    // Retry loop to satisfy length and policy constraints
+    let attempts = if config.policy.is_some() { POLICY_ATTEMPTS } else { 100 };
+    for _ in 0..attempts {
+        let result = build_password(rng, config);
+        if result.len() >= config.min_length && result.len() <= config.max_length
+            && config.policy.as_ref().is_none_or(|p| p.allows(&result))
+        {
+            return Ok(result);
         }
     }
-    // Fallback: return whatever we get
-    build_password(&mut rng, config)
+    // A policy is a hard requirement; otherwise settle for any length
+    if config.policy.is_some() {
+        return Err(invalid!("no password meeting the policy turned up in {} tries", attempts));
+    }
+    Ok(build_password(rng, config))
 }
 
-pub fn generate_batch(config: &MemorableConfig) -> Vec<String> {
-    (0..config.count)
-        .map(|_| generate_with_config(config))
-        .collect()
+/// `config.count` passwords, reproducible when `config.seed` is set
+pub fn generate_batch(config: &MemorableConfig) -> anyhow::Result<Vec<String>> {
+    match config.seed {
+        Some(seed) => {
+            let mut rng = seeded_rng(seed);
+            (0..config.count).map(|_| generate_with_rng(&mut rng, config)).collect()
+        }
+        None => (0..config.count).map(|_| generate_with_config(config)).collect(),
+    }
 }
 
 fn build_password(rng: &mut impl Rng, config: &MemorableConfig) -> String {
+    if let Some(template) = &config.template {
+        // Invalid templates are rejected up front by callers; render the
+        // raw text rather than panic if one slips through
+        let plan = compile_template(template)
+            .unwrap_or_else(|_| vec![TemplateToken::Literal(template.clone())]);
+        return render_template(rng, config, &plan);
+    }
+
     let words = pick_words(rng, config);
     let styled: Vec<String> = words.iter()
-        .map(|w| apply_case(w, &config.case_style, rng))
+        .map(|w| {
+            let cased = apply_case(w, &config.case_style, rng);
+            apply_leet(&cased, config.leet_probability, rng)
+        })
         .collect();
 
     let mut parts: Vec<String> = styled;
@@ -175,6 +437,7 @@ fn build_password(rng: &mut impl Rng, config: &MemorableConfig) -> String {
         } else {
             rng.random_range(0..=config.number_max).to_string()
         };
+        let num = write_number(&num, config, rng);
 
         match config.number_position {
             Position::Start => parts.insert(0, num),
@@ -188,7 +451,7 @@ fn build_password(rng: &mut impl Rng, config: &MemorableConfig) -> String {
 
     // Insert special
     if config.include_special {
-        let sym = SPECIALS.choose(rng).unwrap().to_string();
+        let sym = pick_special(rng, config).to_string();
         match config.special_position {
             Position::Start => parts.insert(0, sym),
             Position::End => parts.push(sym),
@@ -202,18 +465,484 @@ fn build_password(rng: &mut impl Rng, config: &MemorableConfig) -> String {
     parts.join(&config.separator)
 }
 
+/// Symbols that may be inserted: the configured set (or the built-in
+/// one) minus anything the policy disallows, without duplicates
+fn special_set(config: &MemorableConfig) -> Vec<char> {
+    let base: Vec<char> = match &config.specials {
+        Some(set) => set.chars().filter(|c| !c.is_whitespace()).collect(),
+        None => SPECIALS.to_vec(),
+    };
+    let mut allowed: Vec<char> = Vec::new();
+    for c in base {
+        if !allowed.contains(&c) && config.policy.as_ref().is_none_or(|p| !p.disallowed.contains(c)) {
+            allowed.push(c);
+        }
+    }
+    allowed
+}
+
+fn pick_special(rng: &mut impl Rng, config: &MemorableConfig) -> char {
+    special_set(config).choose(rng).copied().unwrap_or('!')
+}
+
+/// Flat word list replacing the built-in pools, if one is configured
+fn external_pool(config: &MemorableConfig) -> Option<Cow<'_, [&str]>> {
+    let pool: Cow<'_, [&str]> = if !config.custom_words.is_empty() {
+        // Repeated lines (in any case) would skew both picks and entropy
+        let mut seen = HashSet::new();
+        Cow::Owned(config.custom_words.iter()
+            .filter(|w| seen.insert(w.to_lowercase()))
+            .map(String::as_str)
+            .collect())
+    } else {
+        match config.word_pool {
+            WordPool::Builtin => return None,
+            WordPool::EffLong => Cow::Borrowed(eff_long_words()),
+            WordPool::EffShort => Cow::Borrowed(eff_short_words()),
+        }
+    };
+    if config.min_word_len.is_none() && config.max_word_len.is_none() {
+        return Some(pool);
+    }
+    Some(Cow::Owned(pool.iter().copied().filter(|w| word_fits(config, w)).collect()))
+}
+
+// ═══════════════════════════════════════════════════════════════
+// ENTROPY
+// ═══════════════════════════════════════════════════════════════
+
+/// Bits of entropy against an attacker who knows the generation scheme
+/// (pools, style, case, number and special settings) but not the random
+/// choices. Length filtering is ignored, so this is a slight overestimate
+/// when `min_length`/`max_length` reject many candidates.
+pub fn entropy_bits(config: &MemorableConfig) -> f64 {
+    let pools = active_pools(config);
+    let external = external_pool(config);
+    let log2 = |n: usize| (n.max(1) as f64).log2();
+    let merged = pools.adjectives.len() + pools.nouns.len() + pools.verbs.len()
+        + pools.colors.len() + pools.adverbs.len();
+    let case_bits = |pool_avg_len: f64| match config.case_style {
+        CaseStyle::Random => pool_avg_len,
+        _ => 0.0,
+    };
+    let avg_len = |pool: &[&str]| {
+        pool.iter().map(|w| w.len()).sum::<usize>() as f64 / pool.len().max(1) as f64
+    };
+
+    if let Some(template) = &config.template {
+        let Ok(plan) = compile_template(template) else { return 0.0 };
+        return plan.iter().map(|token| match token {
+            TemplateToken::Literal(_) => 0.0,
+            TemplateToken::Word(kind) => {
+                let pool: &[&str] = match (kind, &external) {
+                    (_, Some(list)) => list,
+                    (WordKind::Adjective, None) => &pools.adjectives,
+                    (WordKind::Noun, None) => &pools.nouns,
+                    (WordKind::Verb, None) => &pools.verbs,
+                    (WordKind::Adverb, None) => &pools.adverbs,
+                    (WordKind::Color, None) => &pools.colors,
+                    (WordKind::Any, None) => return log2(merged) + case_bits(6.0),
+                };
+                log2(pool.len()) + case_bits(avg_len(pool))
+            }
+            TemplateToken::Number(None) => log2(config.number_max as usize + 1),
+            TemplateToken::Number(Some(digits)) => *digits as f64 * 10f64.log2(),
+            TemplateToken::Special => log2(special_set(config).len()),
+        }).sum();
+    }
+
+    let count = config.word_count;
+    let mut bits = match (&external, &config.style) {
+        (Some(list), MemorableStyle::Alliterative) => 26f64.log2() + count as f64 * log2(list.len() / 26),
+        (Some(list), _) => count as f64 * log2(list.len()),
+        (None, MemorableStyle::Classic) => {
+            let cycle = [&pools.adjectives, &pools.nouns, &pools.verbs, &pools.colors, &pools.adverbs, &pools.adjectives];
+            (0..count).map(|i| log2(cycle[i % cycle.len()].len())).sum()
+        }
+        (None, MemorableStyle::Story) if config.language == Language::English => story_bits(&pools, count),
+        (None, MemorableStyle::Story) => {
+            let cycle = [&pools.nouns, &pools.verbs, &pools.nouns, &pools.adverbs, &pools.adjectives, &pools.nouns];
+            (0..count).map(|i| log2(cycle[i % cycle.len()].len())).sum()
+        }
+        (None, MemorableStyle::Passphrase) => count as f64 * log2(merged),
+        (None, MemorableStyle::Alliterative) => {
+            let letter_pool = pools.adjectives.len() + pools.nouns.len() + pools.verbs.len() + pools.colors.len();
+            26f64.log2() + count as f64 * log2(letter_pool / 26)
+        }
+    };
+    bits += count as f64 * case_bits(6.0);
+
+    let position_bits = |position: &Position, parts: usize| match position {
+        Position::Between => log2(parts.saturating_sub(1)),
+        _ => 0.0,
+    };
+    let mut parts = count;
+    if config.include_number {
+        bits += log2(config.number_max as usize + 1) + position_bits(&config.number_position, parts);
+        parts += 1;
+    }
+    if config.include_special {
+        bits += log2(special_set(config).len()) + position_bits(&config.special_position, parts);
+    }
+    bits
+}
+
+/// Grow `config` until it reaches `min_bits`: add words (up to
+/// `MAX_ENTROPY_WORDS`), then widen the number, or append digits to a
+/// template. Errors if the configuration can't get there.
+pub fn meet_min_entropy(config: &mut MemorableConfig, min_bits: f64) -> anyhow::Result<f64> {
+    loop {
+        let bits = entropy_bits(config);
+        if bits >= min_bits {
+            return Ok(bits);
+        }
+
+        if let Some(template) = &mut config.template {
+            if template.matches("{digit}").count() >= 8 {
+                return Err(invalid!("template reaches only {:.1} bits; {:.1} required. Add more placeholders", bits, min_bits));
+            }
+            template.push_str("{digit}");
+        } else if config.word_count < MAX_ENTROPY_WORDS {
+            config.word_count += 1;
+        } else if config.number_max < 999_999 {
+            config.include_number = true;
+            config.number_max = config.number_max.max(9) * 10 + 9;
+        } else {
+            return Err(invalid!(
+                "configuration tops out at {:.1} bits with {} words; {:.1} required. Use a larger word pool (--word-pool eff-long)",
+                bits, config.word_count, min_bits
+            ));
+        }
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════
+// SITE POLICY
+// ═══════════════════════════════════════════════════════════════
+
+/// Candidates tried per password when a policy is set
+const POLICY_ATTEMPTS: usize = 1000;
+
+/// A site's password rules, e.g. parsed from
+/// `"max=16,upper,digit,symbol,no-spaces,disallow=&%"`
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Policy {
+    #[serde(default)]
+    pub min_length: Option<usize>,
+    #[serde(default)]
+    pub max_length: Option<usize>,
+    #[serde(default)]
+    pub require_upper: bool,
+    #[serde(default)]
+    pub require_lower: bool,
+    #[serde(default)]
+    pub require_digit: bool,
+    #[serde(default)]
+    pub require_symbol: bool,
+    #[serde(default)]
+    pub no_spaces: bool,
+    /// Characters the site rejects
+    #[serde(default)]
+    pub disallowed: String,
+}
+
+impl Policy {
+    /// Parse a comma-separated spec: `min=N`, `max=N`, `upper`, `lower`,
+    /// `digit`, `symbol`, `no-spaces` and `disallow=CHARS` (repeatable;
+    /// commas can't be disallowed this way)
+    pub fn parse(spec: &str) -> anyhow::Result<Self> {
+        let mut policy = Policy::default();
+        for rule in spec.split(',').map(str::trim).filter(|r| !r.is_empty()) {
+            let (key, value) = match rule.split_once('=') {
+                Some((k, v)) => (k.trim().to_lowercase(), Some(v)),
+                None => (rule.to_lowercase(), None),
+            };
+            let number = |v: Option<&str>| -> anyhow::Result<usize> {
+                v.and_then(|v| v.trim().parse().ok())
+                    .ok_or_else(|| invalid!("policy rule '{}' needs a number", rule))
+            };
+            match key.as_str() {
+                "min" | "min-length" => policy.min_length = Some(number(value)?),
+                "max" | "max-length" => policy.max_length = Some(number(value)?),
+                "upper" | "uppercase" => policy.require_upper = true,
+                "lower" | "lowercase" => policy.require_lower = true,
+                "digit" | "number" => policy.require_digit = true,
+                "symbol" | "special" => policy.require_symbol = true,
+                "no-spaces" | "nospaces" => policy.no_spaces = true,
+                "disallow" => policy.disallowed.push_str(value.unwrap_or_default()),
+                _ => return Err(invalid!(
+                    "unknown policy rule '{}' (expected min=N, max=N, upper, lower, digit, symbol, no-spaces, disallow=CHARS)",
+                    rule
+                )),
+            }
+        }
+        if let (Some(min), Some(max)) = (policy.min_length, policy.max_length) {
+            if min > max {
+                return Err(invalid!("policy min length {} is greater than max length {}", min, max));
+            }
+        }
+        Ok(policy)
+    }
+
+    /// Character-class, space and disallowed-character rules (length is
+    /// enforced through the config's bounds)
+    pub fn allows(&self, password: &str) -> bool {
+        (!self.require_upper || password.chars().any(|c| c.is_uppercase()))
+            && (!self.require_lower || password.chars().any(|c| c.is_lowercase()))
+            && (!self.require_digit || password.chars().any(|c| c.is_ascii_digit()))
+            && (!self.require_symbol || password.chars().any(|c| !c.is_alphanumeric() && !c.is_whitespace()))
+            && (!self.no_spaces || !password.contains(char::is_whitespace))
+            && !password.chars().any(|c| self.disallowed.contains(c))
+    }
+}
+
+/// Adjust `config` so it can satisfy `policy`: tighten length bounds, fix
+/// the case style and separator, turn on the number and special, and drop
+/// words until the expected length fits. Errors if no compliant password
+/// turns up in a trial run.
+pub fn apply_policy(config: &mut MemorableConfig, policy: Policy) -> anyhow::Result<()> {
+    if let Some(min) = policy.min_length {
+        config.min_length = config.min_length.max(min);
+    }
+    if let Some(max) = policy.max_length {
+        config.max_length = config.max_length.min(max);
+        config.min_length = config.min_length.min(max).max(policy.min_length.unwrap_or(0));
+    }
+
+    config.case_style = match config.case_style {
+        CaseStyle::Lower if policy.require_upper => CaseStyle::Title,
+        CaseStyle::Upper if policy.require_lower => CaseStyle::Title,
+        ref other => other.clone(),
+    };
+    if policy.no_spaces {
+        config.separator.retain(|c| !c.is_whitespace());
+    }
+    config.separator.retain(|c| !policy.disallowed.contains(c));
+    let symbols = special_set(&MemorableConfig { policy: Some(policy.clone()), ..config.clone() });
+    if policy.require_symbol && symbols.is_empty() {
+        return Err(invalid!("policy disallows every special character but requires a symbol"));
+    }
+
+    if let Some(template) = &mut config.template {
+        let plan = compile_template(template)?;
+        if policy.require_digit && !plan.iter().any(|t| matches!(t, TemplateToken::Number(_))) {
+            template.push_str("{digit}");
+        }
+        if policy.require_symbol && !plan.iter().any(|t| matches!(t, TemplateToken::Special)) {
+            template.push_str("{special}");
+        }
+    } else {
+        config.include_number |= policy.require_digit;
+        config.include_special |= policy.require_symbol;
+
+        // Shed words while the typical password overshoots the maximum
+        let pools = active_pools(config);
+        let external = external_pool(config);
+        let pool: Vec<&str> = match &external {
+            Some(list) => list.to_vec(),
+            None => [&pools.adjectives, &pools.nouns, &pools.verbs, &pools.colors]
+                .iter().flat_map(|p| p.iter().copied()).collect(),
+        };
+        let avg_word = pool.iter().map(|w| w.len()).sum::<usize>() as f64 / pool.len().max(1) as f64;
+        let expected = |words: usize| {
+            let extras = config.include_number as usize + config.include_special as usize;
+            let number = if config.include_number { config.number_max.to_string().len() } else { 0 };
+            words as f64 * avg_word + number as f64 + config.include_special as usize as f64
+                + (config.separator.len() * (words + extras).saturating_sub(1)) as f64
+        };
+        while config.word_count > 1 && expected(config.word_count) > config.max_length as f64 {
+            config.word_count -= 1;
+        }
+    }
+
+    config.policy = Some(policy);
+    check_policy(config)
+}
+
+/// Confirm the configuration produces compliant passwords at a usable rate
+pub fn check_policy(config: &MemorableConfig) -> anyhow::Result<()> {
+    let Some(policy) = &config.policy else { return Ok(()) };
+    let mut rng = rand::rng();
+    let compliant = (0..POLICY_ATTEMPTS).any(|_| {
+        let pw = build_password(&mut rng, config);
+        pw.len() >= config.min_length && pw.len() <= config.max_length && policy.allows(&pw)
+    });
+    if !compliant {
+        return Err(invalid!(
+            "no password meeting the policy turned up in {} tries with {} words and length {}-{}; relax the policy or change the word pool",
+            POLICY_ATTEMPTS, config.word_count, config.min_length, config.max_length
+        ));
+    }
+    Ok(())
+}
+
+// ═══════════════════════════════════════════════════════════════
+// TEMPLATES
+// ═══════════════════════════════════════════════════════════════
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WordKind {
+    Adjective,
+    Noun,
+    Verb,
+    Adverb,
+    Color,
+    /// Any word (the merged pool, or the EFF/custom list when selected)
+    Any,
+}
+
+/// One step of a compiled template
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateToken {
+    Literal(String),
+    Word(WordKind),
+    /// `{num}` uses `number_max`; `{numN}` is exactly N digits
+    Number(Option<usize>),
+    Special,
+}
+
+/// Compile a pattern such as `{adj}-{noun}{num2}{special}`. Placeholders:
+/// adj, noun, verb, adv, color, word, num, num1–num9, digit, special.
+/// `{{` and `}}` produce literal braces.
+pub fn compile_template(template: &str) -> anyhow::Result<Vec<TemplateToken>> {
+    let mut tokens = Vec::new();
+    let mut literal = String::new();
+    let mut chars = template.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(ch) => name.push(ch),
+                        None => return Err(invalid!("unclosed placeholder '{{{}' in template", name)),
+                    }
+                }
+                if !literal.is_empty() {
+                    tokens.push(TemplateToken::Literal(std::mem::take(&mut literal)));
+                }
+                tokens.push(parse_placeholder(&name)?);
+            }
+            '}' => return Err(invalid!("unmatched '}}' in template (use '}}}}' for a literal brace)")),
+            _ => literal.push(c),
+        }
+    }
+    if !literal.is_empty() {
+        tokens.push(TemplateToken::Literal(literal));
+    }
+    Ok(tokens)
+}
+
+fn parse_placeholder(name: &str) -> anyhow::Result<TemplateToken> {
+    let token = match name.trim().to_lowercase().as_str() {
+        "adj" | "adjective" => TemplateToken::Word(WordKind::Adjective),
+        "noun" => TemplateToken::Word(WordKind::Noun),
+        "verb" => TemplateToken::Word(WordKind::Verb),
+        "adv" | "adverb" => TemplateToken::Word(WordKind::Adverb),
+        "color" | "colour" => TemplateToken::Word(WordKind::Color),
+        "word" => TemplateToken::Word(WordKind::Any),
+        "num" => TemplateToken::Number(None),
+        "digit" => TemplateToken::Number(Some(1)),
+        "special" => TemplateToken::Special,
+        other => match other.strip_prefix("num").and_then(|n| n.parse::<usize>().ok()) {
+            Some(digits @ 1..=9) => TemplateToken::Number(Some(digits)),
+            _ => return Err(invalid!(
+                "unknown placeholder '{{{}}}' (expected adj, noun, verb, adv, color, word, num, num1-num9, digit, special)",
+                name
+            )),
+        },
+    };
+    Ok(token)
+}
+
+fn render_template(rng: &mut impl Rng, config: &MemorableConfig, plan: &[TemplateToken]) -> String {
+    let pools = active_pools(config);
+    let external = external_pool(config);
+    let mut out = String::new();
+
+    for token in plan {
+        match token {
+            TemplateToken::Literal(text) => out.push_str(text),
+            TemplateToken::Word(kind) => {
+                let pool: &[&str] = match (kind, &external) {
+                    (_, Some(list)) => list,
+                    (WordKind::Adjective, None) => &pools.adjectives,
+                    (WordKind::Noun, None) => &pools.nouns,
+                    (WordKind::Verb, None) => &pools.verbs,
+                    (WordKind::Adverb, None) => &pools.adverbs,
+                    (WordKind::Color, None) => &pools.colors,
+                    (WordKind::Any, None) => {
+                        let word = pick_passphrase(rng, &pools, 1).pop().unwrap_or_default();
+                        let word = apply_case(&word, &config.case_style, rng);
+                        out.push_str(&apply_leet(&word, config.leet_probability, rng));
+                        continue;
+                    }
+                };
+                if let Some(word) = pool.choose(rng) {
+                    let word = apply_case(word, &config.case_style, rng);
+                    out.push_str(&apply_leet(&word, config.leet_probability, rng));
+                }
+            }
+            TemplateToken::Number(None) => {
+                let num = rng.random_range(0..=config.number_max).to_string();
+                out.push_str(&write_number(&num, config, rng));
+            }
+            TemplateToken::Number(Some(digits)) => {
+                for _ in 0..*digits {
+                    out.push(char::from(b'0' + rng.random_range(0..10u8)));
+                }
+            }
+            TemplateToken::Special => out.push(pick_special(rng, config)),
+        }
+    }
+    out
+}
+
 fn pick_words(rng: &mut impl Rng, config: &MemorableConfig) -> Vec<String> {
+    // External lists have no parts of speech, so only alliteration survives
+    if let Some(pool) = external_pool(config) {
+        if let MemorableStyle::Alliterative = config.style {
+            let letter = pool.choose(rng).and_then(|w| w.chars().next());
+            let same: Vec<&str> = pool.iter().copied()
+                .filter(|w| w.chars().next() == letter)
+                .collect();
+            if same.len() >= config.word_count {
+                return pick_from(rng, &same, config.word_count);
+            }
+        }
+        return pick_from(rng, &pool, config.word_count);
+    }
+
+    let pools = active_pools(config);
     match config.style {
-        MemorableStyle::Classic => pick_classic(rng, config.word_count),
-        MemorableStyle::Passphrase => pick_passphrase(rng, config.word_count),
-        MemorableStyle::Story => pick_story(rng, config.word_count),
-        MemorableStyle::Alliterative => pick_alliterative(rng, config.word_count),
+        MemorableStyle::Classic => pick_classic(rng, &pools, config.word_count),
+        MemorableStyle::Passphrase => pick_passphrase(rng, &pools, config.word_count),
+        // The grammar's agreement rules are English-only
+        MemorableStyle::Story if config.language == Language::English => pick_story(rng, &pools, config.word_count),
+        MemorableStyle::Story => pick_story_rotation(rng, &pools, config.word_count),
+        MemorableStyle::Alliterative => pick_alliterative(rng, &pools, config.word_count),
     }
 }
 
-fn pick_classic(rng: &mut impl Rng, count: usize) -> Vec<String> {
+fn pick_from(rng: &mut impl Rng, pool: &[&str], count: usize) -> Vec<String> {
+    (0..count)
+        .filter_map(|_| pool.choose(rng).map(|w| w.to_string()))
+        .collect()
+}
+
+fn pick_classic(rng: &mut impl Rng, p: &ActivePools, count: usize) -> Vec<String> {
     // Pattern: Adj Noun (Verb) (Adj) ...
-    let pools: &[&[&str]] = &[ADJECTIVES, NOUNS, VERBS, COLORS, ADVERBS, ADJECTIVES];
+    let pools: &[&[&str]] = &[&p.adjectives, &p.nouns, &p.verbs, &p.colors, &p.adverbs, &p.adjectives];
     let mut words = Vec::new();
     for i in 0..count {
         let pool = pools[i % pools.len()];
@@ -222,14 +951,14 @@ fn pick_classic(rng: &mut impl Rng, count: usize) -> Vec<String> {
     words
 }
 
-fn pick_passphrase(rng: &mut impl Rng, count: usize) -> Vec<String> {
+fn pick_passphrase(rng: &mut impl Rng, p: &ActivePools, count: usize) -> Vec<String> {
     // All from a merged pool for maximum entropy
     let mut all: Vec<&str> = Vec::new();
-    all.extend_from_slice(ADJECTIVES);
-    all.extend_from_slice(NOUNS);
-    all.extend_from_slice(VERBS);
-    all.extend_from_slice(COLORS);
-    all.extend_from_slice(ADVERBS);
+    all.extend_from_slice(&p.adjectives);
+    all.extend_from_slice(&p.nouns);
+    all.extend_from_slice(&p.verbs);
+    all.extend_from_slice(&p.colors);
+    all.extend_from_slice(&p.adverbs);
 
     let mut words = Vec::new();
     for _ in 0..count {
@@ -238,10 +967,10 @@ fn pick_passphrase(rng: &mut impl Rng, count: usize) -> Vec<String> {
     words
 }
 
-fn pick_story(rng: &mut impl Rng, count: usize) -> Vec<String> {
+fn pick_story_rotation(rng: &mut impl Rng, p: &ActivePools, count: usize) -> Vec<String> {
     // Pattern: Subject Verb Object ...
     let mut words = Vec::new();
-    let patterns: &[&[&str]] = &[NOUNS, VERBS, NOUNS, ADVERBS, ADJECTIVES, NOUNS];
+    let patterns: &[&[&str]] = &[&p.nouns, &p.verbs, &p.nouns, &p.adverbs, &p.adjectives, &p.nouns];
     for i in 0..count {
         let pool = patterns[i % patterns.len()];
         words.push(pool.choose(rng).unwrap().to_string());
@@ -249,15 +978,228 @@ fn pick_story(rng: &mut impl Rng, count: usize) -> Vec<String> {
     words
 }
 
-fn pick_alliterative(rng: &mut impl Rng, count: usize) -> Vec<String> {
+// ═══════════════════════════════════════════════════════════════
+// STORY GRAMMAR
+// ═══════════════════════════════════════════════════════════════
+
+#[derive(Debug, Clone, Copy)]
+enum Slot {
+    /// "a"/"an" (matching the next word) or "the"
+    Article,
+    Adjective,
+    Color,
+    Noun,
+    /// Plural noun (tigers, wolves)
+    Nouns,
+    /// Base form, after a plural subject or "do"/"does" (run)
+    Verb,
+    /// Third person singular, after a singular subject (runs)
+    Verbs,
+    Adverb,
+    Word(&'static str),
+}
+
+use Slot::*;
+
+/// Sentence shapes, grouped by word count
+const STORIES: &[&[Slot]] = &[
+    &[Noun],
+    &[Noun, Verbs],
+    &[Nouns, Verb],
+    &[Adjective, Nouns],
+    &[Adjective, Noun, Verbs],
+    &[Article, Noun, Verbs],
+    &[Nouns, Verb, Adverb],
+    &[Color, Nouns, Verb],
+    &[Article, Adjective, Noun, Verbs],
+    &[Adjective, Noun, Verbs, Adverb],
+    &[Word("why"), Word("do"), Nouns, Verb],
+    &[Word("does"), Article, Noun, Verb],
+    &[Article, Adjective, Noun, Verbs, Adverb],
+    &[Article, Color, Noun, Verbs, Adverb],
+    &[Word("why"), Word("do"), Adjective, Nouns, Verb],
+    &[Word("does"), Article, Adjective, Noun, Verb],
+    &[Word("when"), Word("do"), Color, Nouns, Verb],
+    &[Article, Adjective, Noun, Verbs, Article, Noun],
+    &[Word("does"), Article, Adjective, Noun, Verb, Adverb],
+    &[Word("why"), Word("does"), Article, Noun, Verb, Adverb],
+    &[Adjective, Nouns, Verb, Word("and"), Verb, Adverb],
+    &[Article, Noun, Verbs, Word("with"), Article, Noun],
+];
+
+/// Longest single sentence; longer stories chain several
+const MAX_SENTENCE: usize = 6;
+
+/// Split `count` words into sentences of at most `MAX_SENTENCE` words,
+/// as even as possible so no sentence is a lone word
+fn story_chunks(count: usize) -> Vec<usize> {
+    let sentences = count.div_ceil(MAX_SENTENCE);
+    (0..sentences)
+        .map(|i| count / sentences + usize::from(i < count % sentences))
+        .collect()
+}
+
+fn sentence_shapes(len: usize) -> Vec<&'static [Slot]> {
+    STORIES.iter().copied().filter(|shape| shape.len() == len).collect()
+}
+
+fn pick_story(rng: &mut impl Rng, p: &ActivePools, count: usize) -> Vec<String> {
+    let mut words = Vec::new();
+    for len in story_chunks(count) {
+        let shape = *sentence_shapes(len).choose(rng).unwrap();
+        let start = words.len();
+        for slot in shape {
+            let word = match slot {
+                // Filled once the following word is known
+                Article => String::new(),
+                Adjective => p.adjectives.choose(rng).unwrap().to_string(),
+                Color => p.colors.choose(rng).unwrap().to_string(),
+                Noun => p.nouns.choose(rng).unwrap().to_string(),
+                Nouns => pluralize(p.nouns.choose(rng).unwrap()),
+                Verb => p.verbs.choose(rng).unwrap().to_string(),
+                Verbs => third_person(p.verbs.choose(rng).unwrap()),
+                Adverb => p.adverbs.choose(rng).unwrap().to_string(),
+                Word(word) => word.to_string(),
+            };
+            words.push(word);
+        }
+        for i in (start..words.len()).rev() {
+            if matches!(shape[i - start], Article) {
+                words[i] = article(rng, words.get(i + 1).map(String::as_str).unwrap_or(""));
+            }
+        }
+    }
+    words
+}
+
+/// Entropy of `pick_story`, taking the weakest shape of each length
+fn story_bits(p: &ActivePools, count: usize) -> f64 {
+    let log2 = |n: usize| (n.max(1) as f64).log2();
+    story_chunks(count).into_iter().map(|len| {
+        let shapes = sentence_shapes(len);
+        let weakest = shapes.iter().map(|shape| shape.iter().map(|slot| match slot {
+            Article => 1.0,
+            Adjective => log2(p.adjectives.len()),
+            Color => log2(p.colors.len()),
+            Noun | Nouns => log2(p.nouns.len()),
+            Verb | Verbs => log2(p.verbs.len()),
+            Adverb => log2(p.adverbs.len()),
+            Word(_) => 0.0,
+        }).sum::<f64>()).fold(f64::INFINITY, f64::min);
+        log2(shapes.len()) + weakest
+    }).sum()
+}
+
+fn article(rng: &mut impl Rng, next: &str) -> String {
+    if rng.random_bool(0.5) {
+        "the".to_string()
+    } else if next.starts_with(['a', 'e', 'i', 'o', 'u']) {
+        "an".to_string()
+    } else {
+        "a".to_string()
+    }
+}
+
+/// Irregular plurals among common nouns
+const IRREGULAR_PLURALS: &[(&str, &str)] = &[
+    ("wolf", "wolves"), ("deer", "deer"), ("fish", "fish"), ("sheep", "sheep"),
+    ("mouse", "mice"), ("goose", "geese"), ("moose", "moose"), ("leaf", "leaves"),
+    ("knife", "knives"), ("thief", "thieves"), ("man", "men"), ("woman", "women"),
+    ("child", "children"), ("foot", "feet"), ("tooth", "teeth"), ("ox", "oxen"),
+];
+
+fn pluralize(noun: &str) -> String {
+    match IRREGULAR_PLURALS.iter().find(|(one, _)| *one == noun) {
+        Some((_, many)) => many.to_string(),
+        None => add_s(noun),
+    }
+}
+
+fn third_person(verb: &str) -> String {
+    match verb {
+        "be" => "is".to_string(),
+        "have" => "has".to_string(),
+        _ => add_s(verb),
+    }
+}
+
+/// Regular English -s/-es/-ies suffix (tiger → tigers, fox → foxes,
+/// fly → flies, echo → echoes)
+fn add_s(word: &str) -> String {
+    let consonant_y = word.len() > 1 && word.ends_with('y')
+        && !word[..word.len() - 1].ends_with(['a', 'e', 'i', 'o', 'u']);
+    if consonant_y {
+        format!("{}ies", &word[..word.len() - 1])
+    } else if ["s", "sh", "ch", "x", "z", "o"].iter().any(|end| word.ends_with(end)) {
+        format!("{}es", word)
+    } else {
+        format!("{}s", word)
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════
+// NUMBERS AS WORDS
+// ═══════════════════════════════════════════════════════════════
+
+const ONES: &[&str] = &[
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+    "ten", "eleven", "twelve", "thirteen", "fourteen", "fifteen", "sixteen",
+    "seventeen", "eighteen", "nineteen",
+];
+
+const TENS: &[&str] = &[
+    "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+
+/// English words for `n` (42 → ["forty", "two"])
+fn spell_number(n: u64) -> Vec<&'static str> {
+    const SCALES: &[(u64, &str)] = &[(1_000_000_000, "billion"), (1_000_000, "million"), (1000, "thousand")];
+    if n < 20 {
+        return vec![ONES[n as usize]];
+    }
+    if n < 100 {
+        let mut words = vec![TENS[(n / 10) as usize]];
+        if !n.is_multiple_of(10) {
+            words.push(ONES[(n % 10) as usize]);
+        }
+        return words;
+    }
+    let (scale, name) = SCALES.iter().copied()
+        .find(|(scale, _)| n >= *scale)
+        .unwrap_or((100, "hundred"));
+    let mut words = spell_number(n / scale);
+    words.push(name);
+    if !n.is_multiple_of(scale) {
+        words.extend(spell_number(n % scale));
+    }
+    words
+}
+
+/// Render a generated number per `config.number_form`, casing each word
+/// like the password's words. Leading zeros survive only in the digits.
+fn write_number(digits: &str, config: &MemorableConfig, rng: &mut impl Rng) -> String {
+    if config.number_form == NumberForm::Digits {
+        return digits.to_string();
+    }
+    let value: u64 = digits.parse().unwrap_or(0);
+    let mut out: String = spell_number(value).iter()
+        .map(|word| apply_case(word, &config.case_style, rng))
+        .collect();
+    if config.number_form == NumberForm::WordsAndDigits {
+        out.push_str(digits);
+    }
+    out
+}
+
+fn pick_alliterative(rng: &mut impl Rng, p: &ActivePools, count: usize) -> Vec<String> {
     // All words start with the same letter
     let letter_idx = rng.random_range(b'a'..=b'z') as char;
 
     let mut all: Vec<&str> = Vec::new();
-    all.extend_from_slice(ADJECTIVES);
-    all.extend_from_slice(NOUNS);
-    all.extend_from_slice(VERBS);
-    all.extend_from_slice(COLORS);
+    all.extend_from_slice(&p.adjectives);
+    all.extend_from_slice(&p.nouns);
+    all.extend_from_slice(&p.verbs);
+    all.extend_from_slice(&p.colors);
 
     let filtered: Vec<&&str> = all.iter()
         .filter(|w| w.starts_with(letter_idx))
@@ -265,7 +1207,7 @@ fn pick_alliterative(rng: &mut impl Rng, count: usize) -> Vec<String> {
 
     if filtered.len() < count {
         // Fallback to classic if not enough words for this letter
-        return pick_classic(rng, count);
+        return pick_classic(rng, p, count);
     }
 
     let mut words = Vec::new();
@@ -305,6 +1247,29 @@ fn apply_case(word: &str, style: &CaseStyle, rng: &mut impl Rng) -> String {
     }
 }
 
+/// With chance `probability`, swap one or two letters of `word` for leet
+/// look-alikes ("tiger" → "t1ger", "7ig3r")
+fn apply_leet(word: &str, probability: f64, rng: &mut impl Rng) -> String {
+    if probability <= 0.0 || !rng.random_bool(probability.min(1.0)) {
+        return word.to_string();
+    }
+    let mut chars: Vec<char> = word.chars().collect();
+    let mut candidates: Vec<usize> = (0..chars.len())
+        .filter(|&i| LEET.iter().any(|(from, _)| chars[i].eq_ignore_ascii_case(from)))
+        .collect();
+
+    for _ in 0..rng.random_range(1..=2) {
+        if candidates.is_empty() {
+            break;
+        }
+        let i = candidates.swap_remove(rng.random_range(0..candidates.len()));
+        if let Some((_, to)) = LEET.iter().find(|(from, _)| chars[i].eq_ignore_ascii_case(from)) {
+            chars[i] = *to.choose(rng).unwrap();
+        }
+    }
+    chars.into_iter().collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -326,7 +1291,7 @@ mod tests {
             max_length: 100,
             ..Default::default()
         };
-        let pw = generate_with_config(&config);
+        let pw = generate_with_config(&config).unwrap();
         assert_eq!(pw.matches('-').count(), 4, "5 words should have 4 separators: {}", pw);
     }
 
@@ -338,7 +1303,7 @@ mod tests {
             max_length: 100,
             ..Default::default()
         };
-        let batch = generate_batch(&config);
+        let batch = generate_batch(&config).unwrap();
         assert_eq!(batch.len(), 10);
     }
 
@@ -355,10 +1320,333 @@ mod tests {
             max_length: 100,
             ..Default::default()
         };
-        let pw = generate_with_config(&config);
+        let pw = generate_with_config(&config).unwrap();
         assert!(pw.chars().all(|c| c.is_lowercase() || c == '-'), "Should be lowercase: {}", pw);
     }
 
+    #[test]
+    fn test_eff_pools() {
+        for (pool, size) in [(WordPool::EffLong, 7776), (WordPool::EffShort, 1296)] {
+            let config = MemorableConfig { word_pool: pool, ..Default::default() };
+            assert_eq!(external_pool(&config).unwrap().len(), size);
+        }
+    }
+
+    #[test]
+    fn test_custom_words() {
+        let config = MemorableConfig {
+            custom_words: vec!["acme".to_string(), "widget".to_string()],
+            word_pool: WordPool::EffLong,
+            case_style: CaseStyle::Lower,
+            separator: "-".to_string(),
+            include_number: false,
+            include_special: false,
+            min_length: 0,
+            max_length: 100,
+            ..Default::default()
+        };
+        let pw = generate_with_config(&config).unwrap();
+        assert!(pw.split('-').all(|w| w == "acme" || w == "widget"), "{}", pw);
+    }
+
+    #[test]
+    fn test_language_pools() {
+        let config = MemorableConfig {
+            language: Language::German,
+            style: MemorableStyle::Classic,
+            word_count: 2,
+            case_style: CaseStyle::Lower,
+            separator: "-".to_string(),
+            include_number: false,
+            include_special: false,
+            min_length: 0,
+            max_length: 100,
+            ..Default::default()
+        };
+        let pw = generate_with_config(&config).unwrap();
+        let words: Vec<&str> = pw.split('-').collect();
+        assert!(languages::GERMAN.adjectives.contains(&words[0]), "{}", pw);
+        assert!(languages::GERMAN.nouns.contains(&words[1]), "{}", pw);
+    }
+
+    #[test]
+    fn test_custom_pools_fall_back() {
+        let config = MemorableConfig {
+            custom_pools: Some(CustomPools {
+                nouns: vec!["gizmo".to_string()],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let pools = active_pools(&config);
+        assert_eq!(pools.nouns, vec!["gizmo"]);
+        assert_eq!(pools.verbs, VERBS);
+    }
+
+    #[test]
+    fn test_compile_template() {
+        let plan = compile_template("{adj}-{noun}{num2}{special}").unwrap();
+        assert_eq!(plan, vec![
+            TemplateToken::Word(WordKind::Adjective),
+            TemplateToken::Literal("-".to_string()),
+            TemplateToken::Word(WordKind::Noun),
+            TemplateToken::Number(Some(2)),
+            TemplateToken::Special,
+        ]);
+        assert_eq!(compile_template("{{x}}").unwrap(), vec![TemplateToken::Literal("{x}".to_string())]);
+        assert!(compile_template("{bogus}").is_err());
+        assert!(compile_template("{adj").is_err());
+        assert!(compile_template("adj}").is_err());
+    }
+
+    #[test]
+    fn test_template_generation() {
+        let config = MemorableConfig {
+            template: Some("{adj}-{noun}{num3}".to_string()),
+            case_style: CaseStyle::Lower,
+            min_length: 0,
+            max_length: 100,
+            ..Default::default()
+        };
+        let pw = generate_with_config(&config).unwrap();
+        let (adj, rest) = pw.split_once('-').unwrap();
+        assert!(ADJECTIVES.contains(&adj), "{}", pw);
+        let (noun, digits) = rest.split_at(rest.len() - 3);
+        assert!(NOUNS.contains(&noun), "{}", pw);
+        assert!(digits.chars().all(|c| c.is_ascii_digit()), "{}", pw);
+    }
+
+    #[test]
+    fn test_entropy_bits() {
+        let config = MemorableConfig {
+            word_pool: WordPool::EffLong,
+            word_count: 4,
+            include_number: false,
+            include_special: false,
+            ..Default::default()
+        };
+        assert!((entropy_bits(&config) - 4.0 * 7776f64.log2()).abs() < 1e-9);
+
+        let template = MemorableConfig {
+            template: Some("x{num4}{special}".to_string()),
+            ..Default::default()
+        };
+        assert!((entropy_bits(&template) - (4.0 * 10f64.log2() + 12f64.log2())).abs() < 1e-9);
+
+        // Duplicate wordlist lines don't add entropy
+        let words = |list: &[&str]| MemorableConfig {
+            custom_words: list.iter().map(|w| w.to_string()).collect(),
+            word_count: 2,
+            include_number: false,
+            include_special: false,
+            ..Default::default()
+        };
+        assert_eq!(entropy_bits(&words(&["apple", "pear", "Apple", "apple"])), entropy_bits(&words(&["apple", "pear"])));
+    }
+
+    #[test]
+    fn test_meet_min_entropy() {
+        let mut config = MemorableConfig::default();
+        let start = config.word_count;
+        let bits = meet_min_entropy(&mut config, 60.0).unwrap();
+        assert!(bits >= 60.0);
+        assert!(config.word_count > start);
+
+        let mut template = MemorableConfig {
+            template: Some("{noun}".to_string()),
+            ..Default::default()
+        };
+        assert!(meet_min_entropy(&mut template, 20.0).unwrap() >= 20.0);
+        assert!(template.template.unwrap().contains("{digit}"));
+
+        assert!(meet_min_entropy(&mut MemorableConfig::default(), 10_000.0).is_err());
+    }
+
+    #[test]
+    fn test_policy_parse() {
+        let policy = Policy::parse("max=16, upper,digit,symbol,no-spaces,disallow=&%").unwrap();
+        assert_eq!(policy.max_length, Some(16));
+        assert!(policy.require_upper && policy.require_digit && policy.require_symbol && policy.no_spaces);
+        assert_eq!(policy.disallowed, "&%");
+        assert!(policy.allows("Tiger42!"));
+        assert!(!policy.allows("Tiger42&"));
+        assert!(!policy.allows("Tiger 42!"));
+        assert!(!policy.allows("tiger42!"));
+
+        assert!(Policy::parse("max=abc").is_err());
+        assert!(Policy::parse("emoji").is_err());
+        assert!(Policy::parse("min=20,max=10").is_err());
+    }
+
+    #[test]
+    fn test_policy_compliance() {
+        let mut config = MemorableConfig {
+            word_count: 4,
+            separator: " ".to_string(),
+            case_style: CaseStyle::Lower,
+            include_number: false,
+            include_special: false,
+            count: 50,
+            ..Default::default()
+        };
+        let policy = Policy::parse("max=16,upper,digit,symbol,no-spaces,disallow=!@#$").unwrap();
+        apply_policy(&mut config, policy.clone()).unwrap();
+        assert!(config.word_count < 4);
+        assert!(config.separator.is_empty());
+
+        for pw in generate_batch(&config).unwrap() {
+            assert!(pw.len() <= 16, "{}", pw);
+            assert!(policy.allows(&pw), "{}", pw);
+        }
+
+        let impossible = Policy::parse("max=4,upper,digit,symbol").unwrap();
+        assert!(apply_policy(&mut MemorableConfig::default(), impossible).is_err());
+
+        // A policy the config can never meet is an error, not a fallback
+        let unmet = MemorableConfig { include_number: false, policy: Policy::parse("digit").ok(), ..Default::default() };
+        assert!(generate_with_config(&unmet).is_err());
+    }
+
+    #[test]
+    fn test_leet_substitution() {
+        let mut rng = rand::rng();
+        for _ in 0..50 {
+            let word = apply_leet("Tigers", 1.0, &mut rng);
+            let changed = word.chars().zip("Tigers".chars()).filter(|(a, b)| a != b).count();
+            assert!((1..=2).contains(&changed), "{}", word);
+        }
+        assert_eq!(apply_leet("Tigers", 0.0, &mut rng), "Tigers");
+        assert_eq!(apply_leet("Bull", 1.0, &mut rng), "Bull");
+
+        let config = MemorableConfig {
+            leet_probability: 1.0,
+            include_number: false,
+            include_special: false,
+            count: 20,
+            min_length: 0,
+            ..Default::default()
+        };
+        assert!(generate_batch(&config).unwrap().iter().any(|pw| pw.contains(|c: char| !c.is_alphabetic())));
+    }
+
+    #[test]
+    fn test_seeded_batch() {
+        let config = MemorableConfig { count: 5, seed: Some(42), leet_probability: 0.5, ..Default::default() };
+        let first = generate_batch(&config).unwrap();
+        assert_eq!(first, generate_batch(&config).unwrap());
+        assert_eq!(generate_with_config(&config).unwrap(), first[0]);
+        assert_ne!(first, generate_batch(&MemorableConfig { seed: Some(43), ..config.clone() }).unwrap());
+
+        let template = MemorableConfig { template: Some("{adj}-{noun}{num2}".to_string()), ..config };
+        assert_eq!(generate_batch(&template).unwrap(), generate_batch(&template).unwrap());
+
+        // The seeded stream is pinned: the ChaCha20 keystream for key 42
+        assert_eq!(seeded_rng(42).next_u64(), 0x6ae30a5126e5761f);
+    }
+
+    #[test]
+    fn test_custom_specials() {
+        let config = MemorableConfig {
+            specials: Some("_#_ ".to_string()),
+            count: 30,
+            ..Default::default()
+        };
+        assert_eq!(special_set(&config), vec!['_', '#']);
+        for pw in generate_batch(&config).unwrap() {
+            let symbols: Vec<char> = pw.chars().filter(|c| !c.is_alphanumeric()).collect();
+            assert!(symbols.iter().all(|c| *c == '_' || *c == '#'), "{}", pw);
+        }
+
+        let policy = Policy::parse("symbol,disallow=_#").unwrap();
+        assert!(apply_policy(&mut config.clone(), policy).is_err());
+    }
+
+    #[test]
+    fn test_word_length_limits() {
+        let config = MemorableConfig {
+            word_pool: WordPool::EffLong,
+            min_word_len: Some(4),
+            max_word_len: Some(6),
+            separator: ".".to_string(),
+            include_number: false,
+            include_special: false,
+            count: 20,
+            min_length: 0,
+            ..Default::default()
+        };
+        check_word_lengths(&config).unwrap();
+        for pw in generate_batch(&config).unwrap() {
+            assert!(pw.split('.').all(|w| (4..=6).contains(&w.len())), "{}", pw);
+        }
+
+        let builtin = MemorableConfig { word_pool: WordPool::Builtin, ..config.clone() };
+        check_word_lengths(&builtin).unwrap();
+        for pw in generate_batch(&builtin).unwrap() {
+            assert!(pw.split('.').all(|w| (4..=6).contains(&w.len())), "{}", pw);
+        }
+
+        assert!(check_word_lengths(&MemorableConfig { max_word_len: Some(1), ..config.clone() }).is_err());
+        assert!(check_word_lengths(&MemorableConfig { min_word_len: Some(7), max_word_len: Some(5), ..config }).is_err());
+    }
+
+    #[test]
+    fn test_story_grammar() {
+        assert_eq!(pluralize("tiger"), "tigers");
+        assert_eq!(pluralize("fox"), "foxes");
+        assert_eq!(pluralize("galaxy"), "galaxies");
+        assert_eq!(pluralize("wolf"), "wolves");
+        assert_eq!(third_person("fly"), "flies");
+        assert_eq!(third_person("crush"), "crushes");
+        assert_eq!(third_person("play"), "plays");
+
+        assert_eq!(story_chunks(4), vec![4]);
+        assert_eq!(story_chunks(7), vec![4, 3]);
+        assert_eq!(story_chunks(13), vec![5, 4, 4]);
+        for len in 1..=MAX_SENTENCE {
+            assert!(!sentence_shapes(len).is_empty());
+        }
+
+        let config = MemorableConfig::default();
+        let pools = active_pools(&config);
+        let mut rng = rand::rng();
+        for count in 1..=9 {
+            for _ in 0..20 {
+                let words = pick_story(&mut rng, &pools, count);
+                assert_eq!(words.len(), count);
+                for pair in words.windows(2) {
+                    if pair[0] == "a" {
+                        assert!(!pair[1].starts_with(['a', 'e', 'i', 'o', 'u']), "{:?}", words);
+                    }
+                    if pair[0] == "an" {
+                        assert!(pair[1].starts_with(['a', 'e', 'i', 'o', 'u']), "{:?}", words);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_numbers_as_words() {
+        assert_eq!(spell_number(7), vec!["seven"]);
+        assert_eq!(spell_number(42), vec!["forty", "two"]);
+        assert_eq!(spell_number(90), vec!["ninety"]);
+        assert_eq!(spell_number(305), vec!["three", "hundred", "five"]);
+        assert_eq!(spell_number(12_000), vec!["twelve", "thousand"]);
+        assert_eq!(spell_number(999_999).join(" "),
+            "nine hundred ninety nine thousand nine hundred ninety nine");
+
+        let mut rng = rand::rng();
+        let words = MemorableConfig { number_form: NumberForm::Words, ..Default::default() };
+        assert_eq!(write_number("07", &words, &mut rng), "Seven");
+        let both = MemorableConfig { number_form: NumberForm::WordsAndDigits, ..Default::default() };
+        assert_eq!(write_number("42", &both, &mut rng), "FortyTwo42");
+
+        let config = MemorableConfig { number_form: NumberForm::Words, count: 10, max_length: 64, ..Default::default() };
+        for pw in generate_batch(&config).unwrap() {
+            assert!(!pw.chars().any(|c| c.is_ascii_digit()), "{}", pw);
+        }
+    }
+
     #[test]
     fn test_upper_case() {
         let config = MemorableConfig {
@@ -369,7 +1657,7 @@ mod tests {
             max_length: 100,
             ..Default::default()
         };
-        let pw = generate_with_config(&config);
+        let pw = generate_with_config(&config).unwrap();
         assert!(pw.chars().all(|c| c.is_uppercase()), "Should be uppercase: {}", pw);
     }
 }
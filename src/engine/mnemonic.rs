@@ -0,0 +1,149 @@
+use serde::{Serialize, Deserialize};
+
+// ═══════════════════════════════════════════════════════════════
+// CONFIGURATION
+// ═══════════════════════════════════════════════════════════════
+
+/// Case applied to the initial letters
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MnemonicCase {
+    /// As written in the sentence (My dog Rex → MdR)
+    #[default]
+    Preserve,
+    Lower,
+    Upper,
+    /// Upper, lower, upper… over the letters only
+    Alternating,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MnemonicConfig {
+    #[serde(default)]
+    pub case: MnemonicCase,
+    /// Swap letters for look-alikes (a→@, e→3, i→1, o→0, s→$)
+    #[serde(default)]
+    pub leet: bool,
+    /// Sound-alike words become digits (to→2, for→4, one→1, ate→8)
+    #[serde(default)]
+    pub number_words: bool,
+    /// Keep punctuation attached to words ("2015!" stays "2015!")
+    #[serde(default = "default_true")]
+    pub keep_punctuation: bool,
+}
+
+fn default_true() -> bool { true }
+
+impl Default for MnemonicConfig {
+    fn default() -> Self {
+        Self {
+            case: MnemonicCase::Preserve,
+            leet: false,
+            number_words: false,
+            keep_punctuation: true,
+        }
+    }
+}
+
+const LEET: &[(char, char)] = &[('a', '@'), ('e', '3'), ('i', '1'), ('o', '0'), ('s', '$')];
+
+const NUMBER_WORDS: &[(&str, &str)] = &[
+    ("one", "1"), ("won", "1"),
+    ("two", "2"), ("to", "2"), ("too", "2"),
+    ("three", "3"),
+    ("four", "4"), ("for", "4"), ("fore", "4"),
+    ("five", "5"), ("six", "6"), ("seven", "7"),
+    ("eight", "8"), ("ate", "8"),
+    ("nine", "9"), ("ten", "10"),
+];
+
+// ═══════════════════════════════════════════════════════════════
+// GENERATION
+// ═══════════════════════════════════════════════════════════════
+
+/// Initial-letter acronym of `sentence`. Numbers are kept whole, so
+/// "My dog Rex was born in 2015!" becomes "MdRwbi2015!".
+pub fn acronym(sentence: &str, config: &MnemonicConfig) -> String {
+    let mut out = String::new();
+    let mut letters = 0usize;
+
+    for token in sentence.split_whitespace() {
+        let word: String = token.chars().filter(|c| c.is_alphanumeric()).collect();
+        let punctuation = token.chars().filter(|c| !c.is_alphanumeric());
+
+        if word.chars().all(|c| c.is_ascii_digit()) && !word.is_empty() {
+            out.push_str(&word);
+        } else if let Some(digit) = number_word(&word, config) {
+            out.push_str(digit);
+        } else if let Some(first) = word.chars().next() {
+            let cased = match config.case {
+                MnemonicCase::Preserve => first,
+                MnemonicCase::Lower => first.to_lowercase().next().unwrap_or(first),
+                MnemonicCase::Upper => first.to_uppercase().next().unwrap_or(first),
+                MnemonicCase::Alternating if letters.is_multiple_of(2) => first.to_uppercase().next().unwrap_or(first),
+                MnemonicCase::Alternating => first.to_lowercase().next().unwrap_or(first),
+            };
+            letters += 1;
+            out.push(if config.leet { leet(cased) } else { cased });
+        }
+
+        if config.keep_punctuation {
+            out.extend(punctuation);
+        }
+    }
+    out
+}
+
+/// The acronym under each combination of leet and number words, starting
+/// with the plain form; duplicates are dropped
+pub fn variants(sentence: &str, config: &MnemonicConfig) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for (leet, number_words) in [(false, false), (false, true), (true, false), (true, true)] {
+        let variant = acronym(sentence, &MnemonicConfig { leet, number_words, ..config.clone() });
+        if !out.contains(&variant) {
+            out.push(variant);
+        }
+    }
+    out
+}
+
+fn number_word(word: &str, config: &MnemonicConfig) -> Option<&'static str> {
+    if !config.number_words {
+        return None;
+    }
+    let lower = word.to_lowercase();
+    NUMBER_WORDS.iter().find(|(w, _)| *w == lower).map(|(_, d)| *d)
+}
+
+fn leet(c: char) -> char {
+    LEET.iter()
+        .find(|(from, _)| c.eq_ignore_ascii_case(from))
+        .map(|(_, to)| *to)
+        .unwrap_or(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_acronym() {
+        let config = MnemonicConfig::default();
+        assert_eq!(acronym("My dog Rex was born in 2015!", &config), "MdRwbi2015!");
+        assert_eq!(acronym("  I love  you, Mom.  ", &config), "Ily,M.");
+
+        let bare = MnemonicConfig { keep_punctuation: false, case: MnemonicCase::Upper, ..Default::default() };
+        assert_eq!(acronym("My dog Rex was born in 2015!", &bare), "MDRWBI2015");
+    }
+
+    #[test]
+    fn test_decoration() {
+        let config = MnemonicConfig { leet: true, number_words: true, ..Default::default() };
+        assert_eq!(acronym("I want to eat four apples", &config), "1w234@");
+
+        let alternating = MnemonicConfig { case: MnemonicCase::Alternating, ..Default::default() };
+        assert_eq!(acronym("my dog rex was born", &alternating), "MdRwB");
+
+        let all = variants("I went to Oslo", &MnemonicConfig::default());
+        assert_eq!(all, vec!["IwtO", "Iw2O", "1wt0", "1w20"]);
+    }
+}
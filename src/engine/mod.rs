@@ -1,5 +1,47 @@
 pub mod mask;
 pub mod rules;
 pub mod markov;
+#[cfg(feature = "neural")]
+pub mod neural;
 pub mod personal;
+pub mod nicknames;
+pub mod leet;
+pub mod case;
 pub mod memorable;
+pub mod mnemonic;
+pub mod languages;
+pub mod bloom;
+pub mod validate;
+pub mod templates;
+pub mod sample;
+pub mod prince;
+pub mod combinator;
+pub mod analyze;
+pub mod strength;
+pub mod walks;
+pub mod dates;
+pub mod rank;
+pub mod audit;
+pub mod filter;
+
+/// A mistake in what the caller asked for (a flag, mask, rule, profile,
+/// config or input file), as opposed to a failure while running. The
+/// command line exits 2 for these and 3 for anything it can't place.
+#[derive(Debug)]
+pub struct InvalidInput(pub String);
+
+impl std::fmt::Display for InvalidInput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for InvalidInput {}
+
+/// `anyhow!` for an [`InvalidInput`](crate::engine::InvalidInput)
+#[macro_export]
+macro_rules! invalid {
+    ($($arg:tt)*) => {
+        anyhow::Error::new($crate::engine::InvalidInput(format!($($arg)*)))
+    };
+}
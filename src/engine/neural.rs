@@ -0,0 +1,423 @@
+use anyhow::Result;
+use candle_core::{DType, Device, Tensor, Var};
+use candle_nn::{AdamW, Optimizer, ParamsAdamW};
+use rand::{Rng, RngExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufRead;
+use std::path::Path;
+use crate::invalid;
+
+// ═══════════════════════════════════════════════════════════════
+// NEURAL CHARACTER MODEL
+// ═══════════════════════════════════════════════════════════════
+//
+// A small recurrent language model over characters (a char-RNN with a
+// GRU cell, Cho et al., 2014) on candle, the `neural` feature's
+// alternative to the Markov chain. Each character is looked up in an
+// embedding table and folded into a hidden state that carries the whole
+// word so far, and the state is read out to a softmax over every
+// character seen in training plus a word boundary. Where a Markov chain
+// only knows contexts it has seen verbatim, and only its last `order`
+// characters of them, the state here has no fixed window and similar
+// prefixes share weights, so candidates generalise instead of replaying
+// the corpus. Words start from the boundary and end when the model picks
+// it, so starts and ends are learned too. Training is backpropagation
+// through time with AdamW, over mini-batches of words of like length from
+// a sample of at most MAX_WORDS corpus words; weights start from the
+// seeded generator, so a seed repeats a run.
+
+/// Corpus words kept for training; bigger corpora are sampled down
+const MAX_WORDS: usize = 200_000;
+/// Width of each character's embedding
+const EMBED: usize = 16;
+/// Words per optimiser step
+const BATCH: usize = 64;
+/// Learning rate at the start of training, decaying linearly to a tenth
+const LEARNING_RATE: f64 = 0.01;
+
+/// How a model is trained
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Training {
+    /// Units in the recurrent state
+    pub hidden: usize,
+    /// Passes over the training words
+    pub epochs: usize,
+    pub seed: u64,
+}
+
+impl Default for Training {
+    fn default() -> Self {
+        Self { hidden: 128, epochs: 10, seed: 0 }
+    }
+}
+
+/// A trained model: weights are inputs by outputs, and the three GRU
+/// gates (update, reset, candidate) sit side by side in `wx`, `uh` and `b`
+#[derive(Debug)]
+pub struct NeuralModel {
+    pub hidden: usize,
+    /// Characters the model writes; index 0, `\0`, is the word boundary
+    vocab: Vec<char>,
+    embeddings: Tensor,
+    wx: Tensor,
+    uh: Tensor,
+    b: Tensor,
+    wo: Tensor,
+    bo: Tensor,
+}
+
+/// A model as saved: each tensor flattened row-major
+#[derive(Serialize, Deserialize)]
+struct Saved {
+    hidden: usize,
+    vocab: Vec<char>,
+    embeddings: Vec<f32>,
+    wx: Vec<f32>,
+    uh: Vec<f32>,
+    b: Vec<f32>,
+    wo: Vec<f32>,
+    bo: Vec<f32>,
+}
+
+impl NeuralModel {
+    /// Train on a corpus file, one word per line (compressed files work too).
+    /// Returns the model and the mean loss of each epoch.
+    #[cfg(feature = "cli")]
+    pub fn train(corpus_path: &Path, training: &Training) -> Result<(Self, Vec<f32>)> {
+        Self::train_reader(crate::io::reader::open(corpus_path)?, training)
+    }
+
+    /// Train on a corpus already open, one word per line
+    pub fn train_reader(reader: impl BufRead, training: &Training) -> Result<(Self, Vec<f32>)> {
+        if training.hidden == 0 {
+            return Err(invalid!("a neural model needs a hidden state of at least 1"));
+        }
+        let mut rng = crate::engine::memorable::seeded_rng(training.seed);
+
+        // Reservoir-sample the words, so any corpus fits in memory
+        let mut words: Vec<Vec<char>> = Vec::new();
+        let mut seen = 0usize;
+        for line in reader.split(b'\n') {
+            // Leaked corpora are full of non-UTF-8 lines; skip them
+            let Ok(line) = String::from_utf8(line?) else { continue };
+            let word: Vec<char> = line.trim_end_matches('\r').chars().filter(|&c| c != '\0').collect();
+            if word.is_empty() {
+                continue;
+            }
+            seen += 1;
+            if words.len() < MAX_WORDS {
+                words.push(word);
+            } else {
+                let slot = rng.random_range(0..seen);
+                if slot < MAX_WORDS {
+                    words[slot] = word;
+                }
+            }
+        }
+        if words.is_empty() {
+            return Err(invalid!("the corpus has no words to train on"));
+        }
+
+        let mut vocab = vec!['\0'];
+        vocab.extend(words.iter().flatten().copied().collect::<std::collections::BTreeSet<char>>());
+        let index: HashMap<char, u32> = vocab.iter().enumerate().map(|(i, &c)| (c, i as u32)).collect();
+        let words: Vec<Vec<u32>> = words.iter().map(|word| word.iter().map(|c| index[c]).collect()).collect();
+        // Every character of every word is predicted, and so is its end
+        let predictions: usize = words.iter().map(|word| word.len() + 1).sum();
+
+        let vars = Self::initialised(vocab.len(), training.hidden, &mut rng)?;
+        let [embeddings, wx, uh, b, wo, bo] = vars.each_ref().map(|var| var.as_tensor().clone());
+        let mut model = Self { hidden: training.hidden, vocab, embeddings, wx, uh, b, wo, bo };
+        let mut optimiser = AdamW::new(vars.to_vec(), ParamsAdamW { lr: LEARNING_RATE, ..Default::default() })?;
+
+        let steps = (training.epochs * words.len().div_ceil(BATCH)).max(1);
+        let mut step = 0;
+        let mut order: Vec<usize> = (0..words.len()).collect();
+        let mut losses = Vec::with_capacity(training.epochs);
+        for _ in 0..training.epochs {
+            // Fisher-Yates, from the seeded generator, then batch words of like
+            // length together so little of each batch is padding
+            for i in (1..order.len()).rev() {
+                order.swap(i, rng.random_range(0..=i));
+            }
+            order.sort_by_key(|&i| words[i].len());
+            let mut batches: Vec<&[usize]> = order.chunks(BATCH).collect();
+            for i in (1..batches.len()).rev() {
+                batches.swap(i, rng.random_range(0..=i));
+            }
+
+            let mut loss = 0.0;
+            for batch in batches {
+                optimiser.set_learning_rate(LEARNING_RATE * (1.0 - 0.9 * step as f64 / steps as f64));
+                let (inputs, targets, mask, count) = Self::padded(batch.iter().map(|&i| &words[i][..]))?;
+                let batch_loss = model.loss(&inputs, &targets, &mask, count)?;
+                loss += batch_loss.to_scalar::<f32>()? * count as f32;
+                optimiser.backward_step(&batch_loss)?;
+                step += 1;
+            }
+            losses.push(loss / predictions as f32);
+        }
+
+        // Done training: keep the weights, not their link to the optimiser
+        for weights in [&mut model.embeddings, &mut model.wx, &mut model.uh, &mut model.b, &mut model.wo, &mut model.bo] {
+            *weights = weights.detach();
+        }
+        Ok((model, losses))
+    }
+
+    /// Small random weights, scaled to each layer's fan-in
+    fn initialised(v: usize, h: usize, rng: &mut impl Rng) -> Result<[Var; 6]> {
+        let mut weights = |shape: &[usize], fan_in: usize| -> Result<Var> {
+            let scale = 1.0 / (fan_in as f32).sqrt();
+            let values = (0..shape.iter().product()).map(|_| rng.random_range(-scale..scale)).collect::<Vec<f32>>();
+            Ok(Var::from_vec(values, shape, &Device::Cpu)?)
+        };
+        Ok([
+            weights(&[v, EMBED], 1)?,
+            weights(&[EMBED, 3 * h], EMBED)?,
+            weights(&[h, 3 * h], h)?,
+            Var::zeros(3 * h, DType::F32, &Device::Cpu)?,
+            weights(&[h, v], h)?,
+            Var::zeros(v, DType::F32, &Device::Cpu)?,
+        ])
+    }
+
+    /// A batch of words as rows padded to the longest: each row's inputs are
+    /// the boundary then the word, its targets the word then the boundary,
+    /// and the mask zeroes the padding. Also returns the unpadded count.
+    fn padded<'a>(words: impl Iterator<Item = &'a [u32]> + Clone) -> candle_core::Result<(Tensor, Tensor, Tensor, usize)> {
+        let steps = words.clone().map(|word| word.len() + 1).max().unwrap_or(1);
+        let (mut inputs, mut targets, mut mask) = (Vec::new(), Vec::new(), Vec::new());
+        let mut rows = 0;
+        for word in words {
+            let pad = steps - word.len() - 1;
+            inputs.push(0);
+            inputs.extend_from_slice(word);
+            inputs.extend(std::iter::repeat_n(0, pad));
+            targets.extend_from_slice(word);
+            targets.extend(std::iter::repeat_n(0, pad + 1));
+            mask.extend(std::iter::repeat_n(1f32, word.len() + 1).chain(std::iter::repeat_n(0.0, pad)));
+            rows += 1;
+        }
+        let count = mask.iter().filter(|&&m| m > 0.0).count();
+        let cells = targets.len();
+        Ok((
+            Tensor::from_vec(inputs, (rows, steps), &Device::Cpu)?,
+            Tensor::from_vec(targets, (cells, 1), &Device::Cpu)?,
+            Tensor::from_vec(mask, cells, &Device::Cpu)?,
+            count,
+        ))
+    }
+
+    /// Mean cross-entropy of the unmasked predictions of a padded batch
+    fn loss(&self, inputs: &Tensor, targets: &Tensor, mask: &Tensor, count: usize) -> candle_core::Result<Tensor> {
+        let scores = candle_nn::ops::log_softmax(&self.logits(inputs)?, 1)?;
+        let picked = scores.gather(targets, 1)?.squeeze(1)?;
+        (picked * mask)?.sum_all()?.affine(-1.0 / count as f64, 0.0)
+    }
+
+    /// One GRU step: the state after reading a character whose input
+    /// projection (its embedding through `wx`, plus `b`) is `projected`
+    fn step(&self, projected: &Tensor, state: &Tensor) -> candle_core::Result<Tensor> {
+        let h = self.hidden;
+        let recurrent = state.matmul(&self.uh)?;
+        let gate = |i: usize| -> candle_core::Result<Tensor> {
+            candle_nn::ops::sigmoid(&(projected.narrow(1, i * h, h)? + recurrent.narrow(1, i * h, h)?)?)
+        };
+        let (update, reset) = (gate(0)?, gate(1)?);
+        let candidate = (projected.narrow(1, 2 * h, h)? + (reset * recurrent.narrow(1, 2 * h, h)?)?)?.tanh()?;
+        // (1 - update) * candidate + update * state
+        &candidate + (update * (state - &candidate)?)?
+    }
+
+    /// Input projection of each of `ids`, one row per id
+    fn project(&self, ids: &Tensor) -> candle_core::Result<Tensor> {
+        self.embeddings.embedding(ids)?.matmul(&self.wx)?.broadcast_add(&self.b)
+    }
+
+    /// Unnormalised scores of the next character after every prefix of each
+    /// row of `ids` (vocab indices), one output row per input character
+    fn logits(&self, ids: &Tensor) -> candle_core::Result<Tensor> {
+        let (rows, steps) = ids.dims2()?;
+        let projected = self.project(&ids.flatten_all()?)?.reshape((rows, steps, 3 * self.hidden))?;
+        let mut state = Tensor::zeros((rows, self.hidden), DType::F32, &Device::Cpu)?;
+        let mut states = Vec::with_capacity(steps);
+        for t in 0..steps {
+            state = self.step(&projected.narrow(1, t, 1)?.squeeze(1)?, &state)?;
+            states.push(state.clone());
+        }
+        let states = Tensor::stack(&states, 1)?.reshape((rows * steps, self.hidden))?;
+        states.matmul(&self.wo)?.broadcast_add(&self.bo)
+    }
+
+    /// Read one character into `state`: the new state and the chances of
+    /// each character after it
+    fn read(&self, id: u32, state: &Tensor) -> candle_core::Result<(Tensor, Vec<f32>)> {
+        let state = self.step(&self.project(&Tensor::new(&[id], &Device::Cpu)?)?, state)?;
+        let logits = state.matmul(&self.wo)?.broadcast_add(&self.bo)?;
+        let probs = candle_nn::ops::softmax_last_dim(&logits)?.squeeze(0)?.to_vec1()?;
+        Ok((state, probs))
+    }
+
+    /// One candidate of `min_len` to `max_len` characters, drawn from `rng`.
+    /// The boundary can't be drawn before `min_len`, so no draw is wasted.
+    pub fn generate(&self, rng: &mut impl Rng, min_len: usize, max_len: usize) -> String {
+        let checked = "weights are checked when made or loaded";
+        let mut state = Tensor::zeros((1, self.hidden), DType::F32, &Device::Cpu).expect(checked);
+        let mut last = 0;
+        let mut word = String::new();
+        for len in 0..max_len {
+            let (next_state, mut probs) = self.read(last, &state).expect(checked);
+            state = next_state;
+            if len < min_len || len == 0 {
+                probs[0] = 0.0;
+            }
+            let mut r = rng.random::<f32>() * probs.iter().sum::<f32>();
+            let next = probs.iter().position(|&p| { r -= p; r <= 0.0 }).unwrap_or(probs.len() - 1);
+            if next == 0 {
+                break;
+            }
+            word.push(self.vocab[next]);
+            last = next as u32;
+        }
+        word
+    }
+
+    /// Natural log of the chance `generate` writes `word` and stops, ignoring
+    /// length limits; `None` if it has a character the model never saw
+    pub fn log_probability(&self, word: &str) -> Option<f64> {
+        let mut targets = Vec::new();
+        for c in word.chars() {
+            targets.push(self.vocab.iter().skip(1).position(|&v| v == c)? as u32 + 1);
+        }
+        targets.push(0);
+
+        // Every prediction of the word in one pass: read the boundary, then
+        // each character but the last target
+        let ids: Vec<u32> = std::iter::once(0).chain(targets[..targets.len() - 1].iter().copied()).collect();
+        let scores = || -> candle_core::Result<Vec<Vec<f32>>> {
+            let ids = Tensor::from_vec(ids, (1, targets.len()), &Device::Cpu)?;
+            candle_nn::ops::log_softmax(&self.logits(&ids)?, 1)?.to_vec2()
+        };
+        let scores = scores().expect("weights are checked when made or loaded");
+        Some(targets.iter().zip(&scores).map(|(&target, row)| row[target as usize] as f64).sum())
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let flat = |weights: &Tensor| weights.flatten_all()?.to_vec1::<f32>();
+        let saved = Saved {
+            hidden: self.hidden,
+            vocab: self.vocab.clone(),
+            embeddings: flat(&self.embeddings)?,
+            wx: flat(&self.wx)?,
+            uh: flat(&self.uh)?,
+            b: flat(&self.b)?,
+            wo: flat(&self.wo)?,
+            bo: flat(&self.bo)?,
+        };
+        serde_json::to_writer(File::create(path)?, &saved)?;
+        Ok(())
+    }
+
+    /// Load a saved model, refusing one whose weights don't fit its sizes
+    pub fn load(path: &Path) -> Result<Self> {
+        let saved: Saved = serde_json::from_reader(File::open(path)?)?;
+        Self::from_saved(saved)
+    }
+
+    fn from_saved(saved: Saved) -> Result<Self> {
+        let (v, h) = (saved.vocab.len(), saved.hidden);
+        if h == 0 {
+            return Err(invalid!("model has a hidden state of 0 units; it must be at least 1"));
+        }
+        if saved.vocab.first() != Some(&'\0') {
+            return Err(invalid!("model vocabulary must start with the word boundary"));
+        }
+        let tensor = |name: &str, values: Vec<f32>, shape: &[usize]| -> Result<Tensor> {
+            let expected: usize = shape.iter().product();
+            if values.len() != expected {
+                return Err(invalid!("model {} has {} weights, expected {} for its sizes", name, values.len(), expected));
+            }
+            Ok(Tensor::from_vec(values, shape, &Device::Cpu)?)
+        };
+        Ok(Self {
+            embeddings: tensor("embeddings", saved.embeddings, &[v, EMBED])?,
+            wx: tensor("wx", saved.wx, &[EMBED, 3 * h])?,
+            uh: tensor("uh", saved.uh, &[h, 3 * h])?,
+            b: tensor("b", saved.b, &[3 * h])?,
+            wo: tensor("wo", saved.wo, &[h, v])?,
+            bo: tensor("bo", saved.bo, &[v])?,
+            hidden: h,
+            vocab: saved.vocab,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn corpus() -> String {
+        ["summer", "summit", "hammer", "simmer", "mummy", "summer1", "hammer2", "summer!"].join("\n")
+    }
+
+    #[test]
+    fn test_training_learns_the_corpus() {
+        let training = Training { hidden: 32, epochs: 60, seed: 1 };
+        let (model, losses) = NeuralModel::train_reader(corpus().as_bytes(), &training).unwrap();
+        assert!(losses.last().unwrap() < &(losses[0] * 0.6), "{:?}", losses);
+        assert!(model.log_probability("summer").unwrap() > model.log_probability("remmus").unwrap());
+        assert!(model.log_probability("summer?").is_none());
+
+        // Seeded training repeats exactly
+        let (again, _) = NeuralModel::train_reader(corpus().as_bytes(), &training).unwrap();
+        let uh = |model: &NeuralModel| model.uh.flatten_all().unwrap().to_vec1::<f32>().unwrap();
+        assert_eq!(uh(&again), uh(&model));
+    }
+
+    #[test]
+    fn test_state_remembers_past_any_window() {
+        // The last letter repeats the first, six characters back: no
+        // window shorter than the word can tell the two endings apart
+        let corpus = ["axxxxxa", "bxxxxxb"].repeat(8).join("\n");
+        let training = Training { hidden: 16, epochs: 150, seed: 3 };
+        let (model, _) = NeuralModel::train_reader(corpus.as_bytes(), &training).unwrap();
+        for (right, wrong) in [("axxxxxa", "axxxxxb"), ("bxxxxxb", "bxxxxxa")] {
+            assert!(model.log_probability(right).unwrap() > model.log_probability(wrong).unwrap() + 1.0);
+        }
+    }
+
+    #[test]
+    fn test_generate_lengths() {
+        let training = Training { hidden: 8, epochs: 2, seed: 0 };
+        let (model, _) = NeuralModel::train_reader(corpus().as_bytes(), &training).unwrap();
+        let mut rng = crate::engine::memorable::seeded_rng(5);
+        for _ in 0..50 {
+            let word = model.generate(&mut rng, 4, 7);
+            assert!((4..=7).contains(&word.chars().count()), "{}", word);
+            assert!(word.chars().all(|c| "summerithay!12".contains(c)), "{}", word);
+        }
+    }
+
+    #[test]
+    fn test_load_checks_sizes() {
+        let training = Training { hidden: 8, epochs: 1, seed: 0 };
+        let (model, _) = NeuralModel::train_reader(corpus().as_bytes(), &training).unwrap();
+        let path = std::env::temp_dir().join(format!("jigsaw-neural-{}.json", std::process::id()));
+        model.save(&path).unwrap();
+        let loaded = NeuralModel::load(&path).unwrap();
+        assert_eq!(loaded.log_probability("summer"), model.log_probability("summer"));
+
+        // A file whose weights don't match its sizes is refused, not run
+        let mut saved: serde_json::Value = serde_json::from_reader(File::open(&path).unwrap()).unwrap();
+        saved["hidden"] = 9.into();
+        std::fs::write(&path, saved.to_string()).unwrap();
+        assert!(NeuralModel::load(&path).unwrap_err().to_string().contains("wx"));
+        saved["hidden"] = 0.into();
+        std::fs::write(&path, saved.to_string()).unwrap();
+        assert!(NeuralModel::load(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+use std::path::Path;
+use anyhow::Result;
+use crate::invalid;
+
+// ═══════════════════════════════════════════════════════════════
+// BUILT-IN TABLE
+// ═══════════════════════════════════════════════════════════════
+
+/// Common English given names and the short forms people actually use
+const NICKNAMES: &[(&str, &[&str])] = &[
+    ("abigail", &["abby", "abbie", "gail"]),
+    ("alexander", &["alex", "al", "xander", "sasha", "lex"]),
+    ("alexandra", &["alex", "alexa", "lexi", "sandra", "sasha"]),
+    ("alfred", &["al", "alfie", "fred"]),
+    ("andrew", &["andy", "drew"]),
+    ("angela", &["angie"]),
+    ("anthony", &["tony", "ant"]),
+    ("barbara", &["barb", "barbie", "babs"]),
+    ("benjamin", &["ben", "benny", "benji"]),
+    ("bradley", &["brad"]),
+    ("catherine", &["cathy", "cat", "kate", "katie"]),
+    ("charles", &["charlie", "chuck", "chas", "chaz"]),
+    ("charlotte", &["charlie", "lottie", "lotte"]),
+    ("christina", &["chris", "tina", "chrissy"]),
+    ("christine", &["chris", "chrissy", "tina"]),
+    ("christopher", &["chris", "topher", "kit"]),
+    ("daniel", &["dan", "danny"]),
+    ("david", &["dave", "davy"]),
+    ("deborah", &["deb", "debbie"]),
+    ("donald", &["don", "donnie"]),
+    ("dorothy", &["dot", "dottie", "dolly"]),
+    ("edward", &["ed", "eddie", "ted", "ned"]),
+    ("elizabeth", &["liz", "lizzie", "beth", "betty", "eliza", "libby", "lisa"]),
+    ("emily", &["em", "emmy", "millie"]),
+    ("frances", &["fran", "frannie"]),
+    ("francis", &["frank", "fran"]),
+    ("frederick", &["fred", "freddie", "rick"]),
+    ("gabriel", &["gabe"]),
+    ("gregory", &["greg"]),
+    ("harold", &["harry", "hal"]),
+    ("henry", &["harry", "hank", "hal"]),
+    ("isabella", &["bella", "izzy", "isa"]),
+    ("jacob", &["jake", "jay"]),
+    ("james", &["jim", "jimmy", "jamie", "jay"]),
+    ("jennifer", &["jen", "jenny", "jenn"]),
+    ("jessica", &["jess", "jessie"]),
+    ("john", &["johnny", "jack", "jon"]),
+    ("jonathan", &["jon", "jonny", "nate"]),
+    ("joseph", &["joe", "joey", "jo"]),
+    ("joshua", &["josh"]),
+    ("katherine", &["kate", "katie", "kathy", "kat", "kay"]),
+    ("kenneth", &["ken", "kenny"]),
+    ("lawrence", &["larry", "lars"]),
+    ("leonard", &["leo", "len", "lenny"]),
+    ("margaret", &["maggie", "meg", "peggy", "marge", "greta"]),
+    ("matthew", &["matt", "matty"]),
+    ("michael", &["mike", "mikey", "mick", "mickey"]),
+    ("nathaniel", &["nate", "nathan", "nat"]),
+    ("nicholas", &["nick", "nicky", "nico"]),
+    ("olivia", &["liv", "livvy", "ollie"]),
+    ("pamela", &["pam"]),
+    ("patricia", &["pat", "patty", "trish", "tricia"]),
+    ("patrick", &["pat", "paddy", "rick"]),
+    ("peter", &["pete"]),
+    ("philip", &["phil", "pip"]),
+    ("rebecca", &["becky", "becca", "bex"]),
+    ("richard", &["rick", "ricky", "rich", "dick"]),
+    ("robert", &["bob", "bobby", "rob", "robbie", "bert"]),
+    ("ronald", &["ron", "ronnie"]),
+    ("samantha", &["sam", "sammy"]),
+    ("samuel", &["sam", "sammy"]),
+    ("stephanie", &["steph", "stevie"]),
+    ("stephen", &["steve", "stevie"]),
+    ("steven", &["steve", "stevie"]),
+    ("susan", &["sue", "susie", "suzy"]),
+    ("theodore", &["ted", "teddy", "theo"]),
+    ("thomas", &["tom", "tommy"]),
+    ("timothy", &["tim", "timmy"]),
+    ("victoria", &["vicky", "tori", "vic"]),
+    ("william", &["will", "bill", "billy", "liam", "willy"]),
+    ("zachary", &["zach", "zack"]),
+];
+
+// ═══════════════════════════════════════════════════════════════
+// LOOKUP
+// ═══════════════════════════════════════════════════════════════
+
+/// Nicknames for `name` from the built-in table plus `custom`, in both
+/// directions: "Robert" → bob, rob…; "Bob" → robert plus its siblings.
+pub fn expand(name: &str, custom: &HashMap<String, Vec<String>>) -> Vec<String> {
+    let lower = name.to_lowercase();
+    let mut out: Vec<String> = Vec::new();
+
+    let builtin = NICKNAMES.iter().map(|(formal, nicks)| {
+        (*formal, nicks.iter().map(|n| n.to_string()).collect::<Vec<_>>())
+    });
+    let custom = custom.iter().map(|(formal, nicks)| {
+        (formal.as_str(), nicks.iter().map(|n| n.to_lowercase()).collect::<Vec<_>>())
+    });
+
+    for (formal, nicks) in builtin.chain(custom) {
+        let formal = formal.to_lowercase();
+        if formal == lower || nicks.contains(&lower) {
+            out.push(formal);
+            out.extend(nicks);
+        }
+    }
+
+    out.sort();
+    out.dedup();
+    out.retain(|n| *n != lower && !n.is_empty());
+    out
+}
+
+/// Load a custom mapping file with one `name: nick1, nick2` entry per line
+/// (`=` also works as the separator; `#` starts a comment).
+pub fn load_map(path: &Path) -> Result<HashMap<String, Vec<String>>> {
+    let text = std::fs::read_to_string(path)?;
+    let mut map: HashMap<String, Vec<String>> = HashMap::new();
+
+    for (lineno, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+        let (name, nicks) = line.split_once([':', '='])
+            .ok_or_else(|| invalid!("{}:{}: expected 'name: nick1, nick2'", path.display(), lineno + 1))?;
+        map.entry(name.trim().to_lowercase())
+            .or_default()
+            .extend(nicks.split(',').map(|n| n.trim().to_lowercase()).filter(|n| !n.is_empty()));
+    }
+
+    Ok(map)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_formal_to_nicknames() {
+        let nicks = expand("Robert", &HashMap::new());
+        assert!(nicks.contains(&"bob".to_string()));
+        assert!(nicks.contains(&"rob".to_string()));
+        assert!(!nicks.contains(&"robert".to_string()));
+    }
+
+    #[test]
+    fn test_nickname_to_formal() {
+        let nicks = expand("Liz", &HashMap::new());
+        assert!(nicks.contains(&"elizabeth".to_string()));
+        assert!(nicks.contains(&"beth".to_string()));
+    }
+
+    #[test]
+    fn test_custom_map() {
+        let mut custom = HashMap::new();
+        custom.insert("maximilian".to_string(), vec!["Maxi".to_string()]);
+        assert_eq!(expand("maximilian", &custom), vec!["maxi"]);
+        assert!(expand("unknownname", &custom).is_empty());
+    }
+}
@@ -1,12 +1,33 @@
 use serde::{Serialize, Deserialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::fs::File;
 use std::io::BufReader;
 use anyhow::Result;
-
-const CURRENT_YEAR: u32 = 2026;
-
+use crate::engine::bloom::BloomFilter;
+use crate::engine::nicknames;
+use crate::engine::walks::{self, Layout};
+
+pub const CURRENT_YEAR: u32 = 2026;
+
+const SEPARATORS: [&str; 11] = ["", "_", ".", "-", "@", "#", "!", "$", "&", "+", "="];
+const SPECIALS: [&str; 18] = [
+    "!", "@", "#", "$", "*", "?", "1!", "123!",
+    "!!", "!!!", "...", "___", "###", "***", "!@#", "!@#$",
+    "123", "007",
+];
+/// Appended after word + suffix (john1990!)
+const DOUBLE_SUFFIXES: [&str; 6] = ["123", "!", "@", "#", "00", "007"];
+const IDIOM_PREFIXES: [&str; 8] = ["ilove", "iluv", "i_love_", "my", "miss", "go", "team", "the"];
+const IDIOM_POSTFIXES: [&str; 10] = ["4ever", "4life", "fan", "#1", "rules", "sucks",
+    "lover", "rocks", "ftw", "islife"];
+const INITIAL_SEPARATORS: [&str; 4] = ["", "_", ".", "#"];
+const FAMILY_SEPARATORS: [&str; 9] = ["&", "+", "and", "And", "_", "x", "X", "<3", "loves"];
+const TRIPLE_SEPARATORS: [&str; 3] = ["", "_", "."];
+const REPEAT_EXTRAS: [&str; 5] = ["1", "!", "12", "123", "1!"];
+const BLEND_SEPARATORS: [&str; 3] = ["", "_", "."];
+
+/// What is known about a target; every field is optional in JSON
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
 pub struct Profile {
     #[serde(default)]
@@ -55,6 +76,77 @@ pub struct Profile {
     pub min_length: Option<usize>,
     #[serde(default)]
     pub max_length: Option<usize>,
+
+    // Optional extra variants
+    #[serde(default)]
+    pub typos: bool,
+
+    // Layout the keyboard-walk suffixes are walked on
+    #[serde(default)]
+    pub keyboard: Layout,
+
+    // Extra name → nicknames entries on top of the built-in table
+    #[serde(default)]
+    pub nickname_map: HashMap<String, Vec<String>>,
+
+    // Common-password base words to cross with profile suffixes
+    #[serde(default)]
+    pub blend: Vec<String>,
+
+    // Ranking: per-field ("pets": 3.0) and per-word ("rex": 5.0) weights.
+    // Higher-weighted words are emitted first; 0 drops them entirely.
+    #[serde(default)]
+    pub field_weights: HashMap<String, f32>,
+    #[serde(default)]
+    pub word_weights: HashMap<String, f32>,
+
+    // Keep only the N highest-weighted profile values (derived forms such
+    // as nicknames don't count); --level sets it when weights are given
+    #[serde(default)]
+    pub word_limit: Option<usize>,
+
+    // Candidate families to leave out
+    #[serde(default)]
+    pub skip: Vec<Category>,
+}
+
+/// Candidate families that can be switched off individually
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Category {
+    /// qwerty/asdf/1qaz… suffixes
+    KeyboardWalks,
+    /// p@ssw0rd substitutions
+    Leet,
+    /// xXwordXx, _word_ and similar wraps
+    Decorative,
+    /// ilove…, …4ever, kid's mom/dad, my+pet
+    Idioms,
+    /// Initials of names (jd, jdm…)
+    Initials,
+    /// Two-word combinations
+    Combos,
+    /// Three-word combinations
+    Triples,
+    /// johnjohn, johnnhoj
+    Repetition,
+}
+
+/// How duplicate candidates are removed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Dedup {
+    /// HashSet of every candidate — exact, memory grows with output
+    Exact,
+    /// Bloom filter sized from `estimate()` — bounded memory, drops about
+    /// `fp_rate` of unique candidates as false duplicates
+    Bloom { fp_rate: f64 },
+}
+
+/// Dry-run size of a personal run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Estimate {
+    pub candidates: u64,
+    pub bytes: u64,
 }
 
 impl Profile {
@@ -75,6 +167,62 @@ impl Profile {
         Ok(())
     }
 
+    /// Mutable access to a list field by its JSON key (e.g. "first_names")
+    pub fn field_mut(&mut self, name: &str) -> Option<&mut Vec<String>> {
+        let field = match name {
+            "first_names" => &mut self.first_names,
+            "last_names" => &mut self.last_names,
+            "partners" => &mut self.partners,
+            "kids" => &mut self.kids,
+            "pets" => &mut self.pets,
+            "company" => &mut self.company,
+            "school" => &mut self.school,
+            "city" => &mut self.city,
+            "sports" => &mut self.sports,
+            "music" => &mut self.music,
+            "usernames" => &mut self.usernames,
+            "dates" => &mut self.dates,
+            "keywords" => &mut self.keywords,
+            "numbers" => &mut self.numbers,
+            "email" => &mut self.email,
+            "parents" => &mut self.parents,
+            "maiden_name" => &mut self.maiden_name,
+            "hobbies" => &mut self.hobbies,
+            _ => return None,
+        };
+        Some(field)
+    }
+
+    /// Every list field paired with its JSON key, in declaration order
+    pub fn fields(&self) -> [(&'static str, &Vec<String>); 18] {
+        [
+            ("first_names", &self.first_names),
+            ("last_names", &self.last_names),
+            ("partners", &self.partners),
+            ("kids", &self.kids),
+            ("pets", &self.pets),
+            ("company", &self.company),
+            ("school", &self.school),
+            ("city", &self.city),
+            ("sports", &self.sports),
+            ("music", &self.music),
+            ("usernames", &self.usernames),
+            ("dates", &self.dates),
+            ("keywords", &self.keywords),
+            ("numbers", &self.numbers),
+            ("email", &self.email),
+            ("parents", &self.parents),
+            ("maiden_name", &self.maiden_name),
+            ("hobbies", &self.hobbies),
+        ]
+    }
+
+    /// True when no list field holds any value
+    pub fn is_empty(&self) -> bool {
+        self.fields().iter().all(|(_, values)| values.is_empty())
+    }
+
+    /// Every unique candidate, in no particular order
     pub fn generate(&self) -> Vec<Vec<u8>> {
         let mut candidates = HashSet::new();
         self.iter_candidates(|s| {
@@ -84,6 +232,208 @@ impl Profile {
         candidates.into_iter().map(|s| s.into_bytes()).collect()
     }
 
+    /// Stream unique candidates to `callback` in generation order, so
+    /// higher-weighted words come out first. Return true to stop early.
+    pub fn for_each_unique<F>(&self, dedup: Dedup, mut callback: F)
+    where F: FnMut(String) -> bool
+    {
+        match dedup {
+            Dedup::Exact => {
+                let mut seen = HashSet::new();
+                self.iter_candidates(|s| {
+                    if seen.contains(&s) {
+                        return false;
+                    }
+                    seen.insert(s.clone());
+                    callback(s)
+                });
+            }
+            Dedup::Bloom { fp_rate } => {
+                let mut filter = BloomFilter::with_rate(self.estimate().candidates, fp_rate);
+                self.iter_candidates(|s| filter.insert(s.as_bytes()) && callback(s));
+            }
+        }
+    }
+
+    /// Count candidates and their output size (newline included) without
+    /// generating them. Duplicates are counted, so `candidates` is an upper
+    /// bound on `generate().len()`. Each section of `iter_candidates` is a
+    /// product of string lists, so it's sized from their length histograms
+    /// (see `Lengths`), which keeps the length filters exact.
+    pub fn estimate(&self) -> Estimate {
+        let all_words = self.gather_words();
+        let (suffixes, dates_expanded) = self.gather_suffixes();
+        let one = Lengths::empty_string();
+        let suffixed = one.plus(&Lengths::of(&suffixes));
+        let suffix = Lengths::of(&suffixes);
+        let separators = Lengths::of(SEPARATORS);
+        let specials = Lengths::of(SPECIALS);
+        let mut total = Lengths::default();
+
+        // 4. Word variants
+        let forms = Lengths::of(all_words.iter().filter(|w| !w.is_empty()).flat_map(|w| self.word_forms(w)));
+        let nonempty_separators: Vec<&str> = SEPARATORS.into_iter().filter(|sep| !sep.is_empty()).collect();
+        let mut tails = one
+            .plus(&separators.then(&suffix).times(2))
+            .plus(&suffix.then(&specials))
+            .plus(&suffix.then(&Lengths::doubled(SPECIALS)))
+            .plus(&suffix.then(&Lengths::doubled(nonempty_separators)))
+            .plus(&suffix.then(&Lengths::of(DOUBLE_SUFFIXES)))
+            .plus(&specials.times(2));
+        if !self.skips(Category::Decorative) {
+            tails = tails.plus(&Lengths::of(["xXXx", "__", "xx", "xxxx"]));
+        }
+        total = total.plus(&forms.then(&tails));
+
+        // 5. Idioms
+        if !self.skips(Category::Idioms) {
+            let words = Lengths::of(self.idiom_words().into_iter().flat_map(|w| {
+                let lower = w.to_lowercase();
+                let title = to_title_case(&lower);
+                [lower, title]
+            }));
+            let affixes = Lengths::of(IDIOM_PREFIXES).plus(&Lengths::of(IDIOM_POSTFIXES));
+            total = total.plus(&words.then(&affixes).then(&suffixed));
+            let templates = Lengths::of(self.kids.iter().flat_map(|kid| kid_templates(kid)))
+                .plus(&Lengths::of(self.pets.iter().flat_map(|pet| pet_templates(pet))));
+            total = total.plus(&templates.then(&suffixed));
+        }
+
+        // 6. Initials
+        if !self.skips(Category::Initials) {
+            let initials = Lengths::of(generate_initials(&self.first_names, &self.last_names, &self.partners, &self.kids));
+            let tails = suffixed.plus(&suffix.then(&Lengths::of(INITIAL_SEPARATORS))).plus(&specials);
+            total = total.plus(&initials.then(&tails));
+        }
+
+        // 7. Two-word combinations
+        if !self.skips(Category::Combos) {
+            let family = Lengths::of(&self.first_names).then(&Lengths::of(&self.partners));
+            total = total.plus(&family.then(&Lengths::of(FAMILY_SEPARATORS).plus(&suffix).times(2)));
+
+            let (left_sides, right_sides) = self.combo_sides();
+            let (mut pairs, mut camels) = (Lengths::default(), Lengths::default());
+            for left in &left_sides {
+                let l_lower = left.to_lowercase();
+                let l_variants = [l_lower.len(), to_title_case(&l_lower).len()];
+                for right in &right_sides {
+                    if *left == *right { continue; }
+                    let r_lower = right.to_lowercase();
+                    let r_title = to_title_case(&r_lower);
+                    for l in l_variants {
+                        for r in [r_lower.len(), r_title.len()] {
+                            pairs.add(l + r, 1);
+                        }
+                    }
+                    camels.add(l_lower.len() + r_title.len(), 1);
+                }
+            }
+            let joins = Lengths::of(["", "_", "..", "#", "##", "@#"]);
+            let tails = separators.plus(&separators.then(&suffix).times(2)).plus(&suffix.then(&joins));
+            total = total.plus(&pairs.then(&tails)).plus(&camels.then(&suffixed));
+        }
+
+        // 8. Triples
+        if !self.skips(Category::Triples) {
+            let tokens: Vec<usize> = self.triple_tokens().iter().map(|t| t.to_lowercase().len()).collect();
+            let mut triples = Lengths::default();
+            if tokens.len() >= 3 {
+                for i in 0..tokens.len() {
+                    for j in (0..tokens.len()).filter(|&j| j != i) {
+                        for k in (0..tokens.len()).filter(|&k| k != i && k != j) {
+                            triples.add(tokens[i] + tokens[j] + tokens[k], 1);
+                        }
+                    }
+                }
+            }
+            total = total.plus(&triples.then(&Lengths::doubled(TRIPLE_SEPARATORS).plus(&suffix)));
+        }
+
+        // 9. Repetition
+        if !self.skips(Category::Repetition) {
+            let repeats = Lengths::of(all_words.iter().filter(|w| w.len() <= 8).flat_map(|w| repetitions(w)));
+            total = total.plus(&repeats.then(&suffixed.plus(&Lengths::of(REPEAT_EXTRAS))));
+        }
+
+        // 10. Standalone dates and suffixes
+        total = total.plus(&Lengths::of(&dates_expanded)).plus(&suffix);
+
+        // 11. Common-word blend
+        if !self.blend.is_empty() {
+            let forms = Lengths::of(self.blend.iter().flat_map(|common| blend_forms(common)));
+            let tails = suffix.then(&Lengths::of(BLEND_SEPARATORS)).plus(&suffix).plus(&suffix.then(&Lengths::of(["!"])));
+            let mixed = Lengths::of(profile_forms(&all_words)).then(&Lengths::of(&self.blend)).times(2);
+            total = total.plus(&forms.then(&tails)).plus(&mixed);
+        }
+
+        total.tally(self.min_length.unwrap_or(0), self.max_length.unwrap_or(usize::MAX))
+    }
+
+    /// The first `limit` candidates in generation order, duplicates
+    /// included; enough to time a run without making all of it
+    pub fn sample(&self, limit: usize) -> Vec<String> {
+        let mut sample = Vec::with_capacity(limit.min(1 << 16));
+        if limit > 0 {
+            self.iter_candidates(|s| {
+                sample.push(s);
+                sample.len() >= limit
+            });
+        }
+        sample
+    }
+
+    /// Encode the profile as a hashcat attack instead of a full expansion:
+    /// returns `(base_words, rules)` where the rules carry the case, leet,
+    /// separator and suffix mangling. Length filters aren't encoded since
+    /// hashcat's reject functions don't run on GPU.
+    pub fn hashcat_rules(&self) -> (Vec<String>, Vec<String>) {
+        let mut words = self.gather_words();
+        words.extend(self.blend.iter().cloned());
+        words.sort();
+        words.dedup();
+
+        let (suffixes, _) = self.gather_suffixes();
+        let cases = [":", "l", "u", "c", "C"];
+        let leet = "sa@ se3 si1 so0 ss$";
+
+        let mut rules: Vec<String> = Vec::new();
+        let mut seen: HashSet<String> = HashSet::new();
+        let mut push = |rule: String| {
+            // hashcat caps a rule at 31 functions
+            if rule.split(' ').count() <= 31 && seen.insert(rule.clone()) {
+                rules.push(rule);
+            }
+        };
+
+        for case in cases {
+            push(case.to_string());
+            if !self.skips(Category::Leet) {
+                push(format!("{} {}", case, leet));
+            }
+
+            for suffix in &suffixes {
+                for sep in SEPARATORS {
+                    push(format!("{} {}", case, hashcat_append(&format!("{}{}", sep, suffix))));
+                    push(format!("{} {}", case, hashcat_prepend(&format!("{}{}", suffix, sep))));
+                }
+                for special in SPECIALS {
+                    push(format!("{} {}", case, hashcat_append(&format!("{}{}", suffix, special))));
+                }
+                if !self.skips(Category::Leet) {
+                    push(format!("{} {} {}", case, leet, hashcat_append(suffix)));
+                }
+            }
+
+            for special in SPECIALS {
+                push(format!("{} {}", case, hashcat_append(special)));
+                push(format!("{} {}", case, hashcat_prepend(special)));
+            }
+        }
+
+        (words, rules)
+    }
+
+    /// Whether `target` is among the candidates, stopping once found
     pub fn check_password(&self, target: &str) -> bool {
         let mut found = false;
         self.iter_candidates(|s| {
@@ -96,57 +446,113 @@ impl Profile {
         found
     }
 
-    fn iter_candidates<F>(&self, mut callback: F)
-    where F: FnMut(String) -> bool
-    {
-        let min_len = self.min_length.unwrap_or(0);
-        let max_len = self.max_length.unwrap_or(usize::MAX);
-
-        macro_rules! emit {
-            ($s:expr) => {{
-                let s: String = $s;
-                if s.len() >= min_len && s.len() <= max_len {
-                    if callback(s) { return; }
-                }
-            }};
-        }
-
+    /// Profile words plus everything derived from them: username and email
+    /// parts, company tokens, nicknames and (optionally) typos
+    fn gather_words(&self) -> Vec<String> {
         // ═══════════════════════════════════════════════════════
         // 1. GATHER ALL TEXT INPUTS
         // ═══════════════════════════════════════════════════════
-        let mut all_words: Vec<String> = Vec::new();
-
-        for field in [
-            &self.first_names, &self.last_names, &self.partners,
-            &self.kids, &self.pets, &self.company, &self.school,
-            &self.city, &self.sports, &self.music, &self.keywords,
-            &self.parents, &self.maiden_name, &self.hobbies,
+        // Every word carries the weight of the field (or word entry) it came
+        // from; derived forms inherit it so ordering follows the source.
+        let mut sources: Vec<(&str, &String, f32)> = Vec::new();
+        for (field, values) in [
+            ("first_names", &self.first_names), ("last_names", &self.last_names),
+            ("partners", &self.partners), ("kids", &self.kids), ("pets", &self.pets),
+            ("company", &self.company), ("school", &self.school), ("city", &self.city),
+            ("sports", &self.sports), ("music", &self.music), ("keywords", &self.keywords),
+            ("parents", &self.parents), ("maiden_name", &self.maiden_name),
+            ("hobbies", &self.hobbies), ("usernames", &self.usernames), ("email", &self.email),
         ] {
-            all_words.extend(field.iter().cloned());
+            for value in values {
+                let weight = self.weight_of(field, value);
+                if weight > 0.0 {
+                    sources.push((field, value, weight));
+                }
+            }
         }
 
-        // Usernames: whole + decomposed parts
-        for username in &self.usernames {
-            all_words.push(username.clone());
-            all_words.extend(decompose_username(username));
+        // word_limit keeps the N highest-weighted profile values; the sort is
+        // stable, so equal weights keep field order
+        if let Some(limit) = self.word_limit {
+            sources.sort_by(|a, b| b.2.total_cmp(&a.2));
+            sources.truncate(limit);
         }
 
-        // Emails: decompose and add parts
-        for email in &self.email {
-            all_words.extend(decompose_email(email));
+        let mut base_words: Vec<(String, f32)> = Vec::new();
+        for &(field, value, weight) in &sources {
+            match field {
+                // Usernames: whole + decomposed parts
+                "usernames" => {
+                    base_words.push((value.clone(), weight));
+                    base_words.extend(decompose_username(value).into_iter().map(|w| (w, weight)));
+                }
+                // Emails: decompose and add parts
+                "email" => base_words.extend(decompose_email(value).into_iter().map(|w| (w, weight))),
+                // Companies: stripped names, abbreviations, acronyms, domains
+                "company" => {
+                    base_words.push((value.clone(), weight));
+                    base_words.extend(derive_company_tokens(value).into_iter().map(|w| (w, weight)));
+                }
+                _ => base_words.push((value.clone(), weight)),
+            }
         }
 
+        let mut all_words = base_words.clone();
+
         // Generate nicknames/truncations
-        let base_words = all_words.clone();
-        for word in &base_words {
-            all_words.extend(generate_nicknames(word));
+        for (word, weight) in &base_words {
+            all_words.extend(generate_nicknames(word).into_iter().map(|w| (w, *weight)));
+        }
+
+        // Dictionary nicknames (Robert → Bob, Liz → Elizabeth)
+        for &(field, name, weight) in &sources {
+            if matches!(field, "first_names" | "partners" | "kids" | "parents") {
+                all_words.extend(nicknames::expand(name, &self.nickname_map).into_iter().map(|w| (w, weight)));
+            }
+        }
+
+        // Keyboard-proximity typos (jphn, joohn), ranked below the real word
+        if self.typos {
+            for (word, weight) in &base_words {
+                all_words.extend(generate_typos(word).into_iter().map(|w| (w, weight * 0.5)));
+            }
+        }
+
+        // Deduplicate, keeping each word's first position and highest weight;
+        // the stable sort then leaves equal weights in field order
+        let mut position: HashMap<String, usize> = HashMap::new();
+        let mut ranked: Vec<(String, f32)> = Vec::new();
+        for (word, weight) in all_words {
+            if word.is_empty() { continue; }
+            match position.get(&word) {
+                Some(&i) => ranked[i].1 = ranked[i].1.max(weight),
+                None => {
+                    position.insert(word.clone(), ranked.len());
+                    ranked.push((word, weight));
+                }
+            }
         }
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        ranked.into_iter().map(|(word, _)| word).collect()
+    }
 
-        // Deduplicate
-        all_words.sort();
-        all_words.dedup();
-        all_words.retain(|w| !w.is_empty());
+    fn skips(&self, category: Category) -> bool {
+        self.skip.contains(&category)
+    }
+
+    /// Weight for `value` from `field`: a `word_weights` entry wins over the
+    /// field's entry in `field_weights`; unweighted words count as 1.0
+    pub fn weight_of(&self, field: &str, value: &str) -> f32 {
+        self.word_weights.get(&value.to_lowercase())
+            .or_else(|| self.field_weights.get(field))
+            .copied()
+            .unwrap_or(1.0)
+    }
 
+    /// Numeric and keyboard suffixes, plus the expanded date forms that are
+    /// also emitted standalone
+    fn gather_suffixes(&self) -> (Vec<String>, Vec<String>) {
         // ═══════════════════════════════════════════════════════
         // 2. SUFFIX EXPANSION (Dates, Numbers, Pins, Keyboard)
         // ═══════════════════════════════════════════════════════
@@ -225,7 +631,7 @@ impl Profile {
             }
             // 4-digit non-year (MMDD) — also generate DDMM flip
             else if date.len() == 4 && date.chars().all(char::is_numeric)
-                && !years.iter().any(|y| *y == date)
+                && !years.contains(&date)
             {
                 let p1 = &date[0..2];
                 let p2 = &date[2..4];
@@ -301,9 +707,12 @@ impl Profile {
             }
         }
 
+        // --- Derived Dates (gaps between dates, decades, zodiac) ---
+        suffixes.extend(derive_date_tokens(&self.dates));
+
         // --- Keyboard Walk Suffixes ---
-        for kw in ["qwerty", "asdf", "zxcvbn", "qazwsx", "1qaz", "2wsx", "qwer", "asdfgh"] {
-            suffixes.push(kw.to_string());
+        if !self.skips(Category::KeyboardWalks) {
+            suffixes.extend(walks::common(self.keyboard));
         }
 
         // --- Pin / Common Number Suffixes ---
@@ -319,90 +728,94 @@ impl Profile {
         suffixes.sort();
         suffixes.dedup();
 
-        // ═══════════════════════════════════════════════════════
-        // 3. SEPARATORS & SPECIALS
-        // ═══════════════════════════════════════════════════════
-        let separators = ["", "_", ".", "-", "@", "#", "!", "$", "&", "+", "="];
-        let specials = [
-            "!", "@", "#", "$", "*", "?", "1!", "123!",
-            "!!", "!!!", "...", "___", "###", "***", "!@#", "!@#$",
-            "123", "007",
-        ];
-
-        // ═══════════════════════════════════════════════════════
-        // 4. WORD VARIANT GENERATION
-        // ═══════════════════════════════════════════════════════
-        for word in &all_words {
-            if word.is_empty() { continue; }
+        (suffixes, dates_expanded)
+    }
 
-            let base_variants = case_variants(word);
+    /// Case, reversed and leet forms of one word (section 4)
+    fn word_forms(&self, word: &str) -> Vec<String> {
+        let mut all_bases = case_variants(word);
+        // Only reverse short words (≤ 6 chars)
+        if word.len() <= 6 {
+            let reversed: String = word.chars().rev().collect();
+            all_bases.extend(case_variants(&reversed));
+        }
 
-            // Only reverse short words (≤ 6 chars)
-            let mut all_bases = base_variants.clone();
-            if word.len() <= 6 {
-                let reversed: String = word.chars().rev().collect();
-                all_bases.extend(case_variants(&reversed));
+        // Generate leet for all case variants
+        let mut forms: Vec<String> = Vec::new();
+        for v in &all_bases {
+            forms.push(v.clone());
+            if !self.skips(Category::Leet) {
+                forms.extend(generate_leet(v));
             }
+        }
+        forms.sort();
+        forms.dedup();
+        forms
+    }
 
-            // Generate leet for all case variants
-            let mut word_forms: Vec<String> = Vec::new();
-            for v in &all_bases {
-                word_forms.push(v.clone());
-                word_forms.extend(generate_leet(v));
-            }
-            word_forms.sort();
-            word_forms.dedup();
+    /// Case, reversed and leet forms of `word`, bare and with suffixes,
+    /// separators, specials and wraps (section 4). Returns true once
+    /// `callback` does, to stop early.
+    fn word_variants(&self, word: &str, suffixes: &[String], callback: &mut dyn FnMut(String) -> bool) -> bool {
+        macro_rules! emit {
+            ($s:expr) => {
+                if callback($s) { return true; }
+            };
+        }
+        let separators = SEPARATORS;
+        let specials = SPECIALS;
 
-            for form in &word_forms {
-                emit!(form.clone());
+        for form in &self.word_forms(word) {
+            emit!(form.clone());
 
-                // Word + Sep + Suffix
-                for suffix in &suffixes {
-                    for sep in &separators {
-                        emit!(format!("{}{}{}", form, sep, suffix));
-                    }
-                    // Suffix + Sep + Word
-                    for sep in &separators {
-                        emit!(format!("{}{}{}", suffix, sep, form));
-                    }
-                    // Word + Suffix + Special
-                    for special in &specials {
-                        emit!(format!("{}{}{}", form, suffix, special));
-                    }
-                    // Sandwich: Special + Word + Suffix + Special
-                    for special in &specials {
-                        emit!(format!("{}{}{}{}", special, form, suffix, special));
-                    }
-                    // Complex Sandwich with separators
-                    for sep in &separators {
-                        if !sep.is_empty() {
-                            emit!(format!("{}{}{}{}", sep, form, sep, suffix));
-                        }
-                    }
-                    // Double suffix
-                    for extra in ["123", "!", "@", "#", "00", "007"] {
-                        emit!(format!("{}{}{}", form, suffix, extra));
-                    }
+            // Word + Sep + Suffix
+            for suffix in suffixes {
+                for sep in &separators {
+                    emit!(format!("{}{}{}", form, sep, suffix));
                 }
-
-                // Specials only (no suffix)
+                // Suffix + Sep + Word
+                for sep in &separators {
+                    emit!(format!("{}{}{}", suffix, sep, form));
+                }
+                // Word + Suffix + Special
                 for special in &specials {
-                    emit!(format!("{}{}", form, special));
-                    emit!(format!("{}{}", special, form));
+                    emit!(format!("{}{}{}", form, suffix, special));
+                }
+                // Sandwich: Special + Word + Suffix + Special
+                for special in &specials {
+                    emit!(format!("{}{}{}{}", special, form, suffix, special));
+                }
+                // Complex Sandwich with separators
+                for sep in &separators {
+                    if !sep.is_empty() {
+                        emit!(format!("{}{}{}{}", sep, form, sep, suffix));
+                    }
                 }
+                // Double suffix
+                for extra in DOUBLE_SUFFIXES {
+                    emit!(format!("{}{}{}", form, suffix, extra));
+                }
+            }
+
+            // Specials only (no suffix)
+            for special in &specials {
+                emit!(format!("{}{}", form, special));
+                emit!(format!("{}{}", special, form));
+            }
 
-                // Decorative wraps
+            // Decorative wraps
+            if !self.skips(Category::Decorative) {
                 emit!(format!("xX{}Xx", form));
                 emit!(format!("_{}_", form));
                 emit!(format!("x{}x", form));
                 emit!(format!("xx{}xx", form));
             }
         }
+        false
+    }
 
-        // ═══════════════════════════════════════════════════════
-        // 5. IDIOMATIC PHRASES
-        // ═══════════════════════════════════════════════════════
-        let idiom_words: Vec<&String> = self.first_names.iter()
+    fn idiom_words(&self) -> Vec<&String> {
+        self.first_names.iter()
             .chain(self.partners.iter())
             .chain(self.kids.iter())
             .chain(self.pets.iter())
@@ -410,81 +823,11 @@ impl Profile {
             .chain(self.music.iter())
             .chain(self.keywords.iter())
             .chain(self.hobbies.iter())
-            .collect();
-
-        let idiom_prefixes = ["ilove", "iluv", "i_love_", "my", "miss", "go", "team", "the"];
-        let idiom_postfixes = ["4ever", "4life", "fan", "#1", "rules", "sucks",
-            "lover", "rocks", "ftw", "islife"];
-
-        for word in &idiom_words {
-            let lower = word.to_lowercase();
-            let title = to_title_case(&lower);
-            for w in [&lower, &title] {
-                for prefix in &idiom_prefixes {
-                    emit!(format!("{}{}", prefix, w));
-                    for suffix in &suffixes {
-                        emit!(format!("{}{}{}", prefix, w, suffix));
-                    }
-                }
-                for postfix in &idiom_postfixes {
-                    emit!(format!("{}{}", w, postfix));
-                    for suffix in &suffixes {
-                        emit!(format!("{}{}{}", w, postfix, suffix));
-                    }
-                }
-            }
-        }
-
-        // Family-specific idioms
-        for kid in &self.kids {
-            let lower = kid.to_lowercase();
-            for tmpl in [
-                format!("{}smom", lower), format!("{}sdad", lower),
-                format!("{}s_mom", lower), format!("{}s_dad", lower),
-                format!("mama{}", lower), format!("papa{}", lower),
-            ] {
-                emit!(tmpl.clone());
-                for suffix in &suffixes {
-                    emit!(format!("{}{}", tmpl, suffix));
-                }
-            }
-        }
-
-        for pet in &self.pets {
-            let lower = pet.to_lowercase();
-            for tmpl in [
-                format!("my{}", lower), format!("my_{}", lower),
-            ] {
-                emit!(tmpl.clone());
-                for suffix in &suffixes {
-                    emit!(format!("{}{}", tmpl, suffix));
-                }
-            }
-        }
-
-        // ═══════════════════════════════════════════════════════
-        // 6. INITIALS-BASED PASSWORDS
-        // ═══════════════════════════════════════════════════════
-        let initials = generate_initials(
-            &self.first_names, &self.last_names, &self.partners, &self.kids,
-        );
-
-        for init in &initials {
-            emit!(init.clone());
-            for suffix in &suffixes {
-                emit!(format!("{}{}", init, suffix));
-                for sep in ["", "_", ".", "#"] {
-                    emit!(format!("{}{}{}", init, sep, suffix));
-                }
-            }
-            for special in &specials {
-                emit!(format!("{}{}", init, special));
-            }
-        }
+            .collect()
+    }
 
-        // ═══════════════════════════════════════════════════════
-        // 7. TWO-WORD COMBINATIONS (Fixed: all categories)
-        // ═══════════════════════════════════════════════════════
+    /// Left and right words of two-word combinations
+    fn combo_sides(&self) -> (Vec<&String>, Vec<&String>) {
         let mut left_sides: Vec<&String> = Vec::new();
         left_sides.extend(self.first_names.iter());
         left_sides.extend(self.usernames.iter());
@@ -511,85 +854,203 @@ impl Profile {
         right_sides.extend(self.parents.iter());
         right_sides.extend(self.maiden_name.iter());
         right_sides.extend(self.hobbies.iter());
+        (left_sides, right_sides)
+    }
 
-        // Explicit Family Combinations
-        for p in &self.partners {
-            for n in &self.first_names {
-                for sep in ["&", "+", "and", "And", "_", "x", "X", "<3", "loves"] {
-                    emit!(format!("{}{}{}", n, sep, p));
-                    emit!(format!("{}{}{}", p, sep, n));
-                }
-                for suffix in &suffixes {
-                    emit!(format!("{}{}{}", n, p, suffix));
-                    emit!(format!("{}{}{}", p, n, suffix));
+    /// Up to 8 tokens for three-word combinations
+    fn triple_tokens(&self) -> Vec<&String> {
+        self.first_names.iter()
+            .chain(self.last_names.iter())
+            .chain(self.partners.iter())
+            .chain(self.kids.iter())
+            .chain(self.pets.iter())
+            .chain(self.city.iter())
+            .take(8)
+            .collect()
+    }
+
+    fn iter_candidates<F>(&self, mut callback: F)
+    where F: FnMut(String) -> bool
+    {
+        let min_len = self.min_length.unwrap_or(0);
+        let max_len = self.max_length.unwrap_or(usize::MAX);
+
+        macro_rules! emit {
+            ($s:expr) => {{
+                let s: String = $s;
+                if s.len() >= min_len && s.len() <= max_len {
+                    if callback(s) { return; }
                 }
-            }
+            }};
         }
 
-        for left in &left_sides {
-            for right in &right_sides {
-                if *left == *right { continue; }
+        // ═══════════════════════════════════════════════════════
+        // 1. GATHER ALL TEXT INPUTS
+        // 2. SUFFIX EXPANSION (Dates, Numbers, Pins, Keyboard)
+        // ═══════════════════════════════════════════════════════
+        let all_words = self.gather_words();
+        let (suffixes, dates_expanded) = self.gather_suffixes();
 
-                let l_variants = vec![left.to_lowercase(), to_title_case(&left.to_lowercase())];
-                let r_variants = vec![right.to_lowercase(), to_title_case(&right.to_lowercase())];
+        // ═══════════════════════════════════════════════════════
+        // 3. SEPARATORS & SPECIALS
+        // ═══════════════════════════════════════════════════════
+        let separators = SEPARATORS;
+        let specials = SPECIALS;
 
-                for l in &l_variants {
-                    for r in &r_variants {
-                        for sep in &separators {
-                            emit!(format!("{}{}{}", l, sep, r));
+        // ═══════════════════════════════════════════════════════
+        // 4. WORD VARIANT GENERATION
+        // ═══════════════════════════════════════════════════════
+        for word in &all_words {
+            if word.is_empty() { continue; }
+            let mut keep = |s: String| s.len() >= min_len && s.len() <= max_len && callback(s);
+            if self.word_variants(word, &suffixes, &mut keep) { return; }
+        }
 
-                            for suffix in &suffixes {
-                                emit!(format!("{}{}{}{}", l, sep, r, suffix));
-                                emit!(format!("{}{}{}{}", r, sep, l, suffix));
-                            }
+        // ═══════════════════════════════════════════════════════
+        // 5. IDIOMATIC PHRASES
+        // ═══════════════════════════════════════════════════════
+        if !self.skips(Category::Idioms) {
+            for word in self.idiom_words() {
+                let lower = word.to_lowercase();
+                let title = to_title_case(&lower);
+                for w in [&lower, &title] {
+                    for prefix in IDIOM_PREFIXES {
+                        emit!(format!("{}{}", prefix, w));
+                        for suffix in &suffixes {
+                            emit!(format!("{}{}{}", prefix, w, suffix));
                         }
+                    }
+                    for postfix in IDIOM_POSTFIXES {
+                        emit!(format!("{}{}", w, postfix));
                         for suffix in &suffixes {
-                            emit!(format!("{}{}{}", l, r, suffix));
-                            emit!(format!("{}{}_{}", l, r, suffix));
-                            emit!(format!("{}.{}.{}", l, r, suffix));
-                            emit!(format!("{}#{}{}", l, r, suffix));
-                            emit!(format!("{}#{}#{}", l, r, suffix));
-                            emit!(format!("{}@{}#{}", l, r, suffix));
+                            emit!(format!("{}{}{}", w, postfix, suffix));
                         }
                     }
                 }
+            }
+
+            // Family-specific idioms
+            for kid in &self.kids {
+                for tmpl in kid_templates(kid) {
+                    emit!(tmpl.clone());
+                    for suffix in &suffixes {
+                        emit!(format!("{}{}", tmpl, suffix));
+                    }
+                }
+            }
 
-                // camelCase combo
-                let camel = format!("{}{}", left.to_lowercase(), to_title_case(&right.to_lowercase()));
-                emit!(camel.clone());
+            for pet in &self.pets {
+                for tmpl in pet_templates(pet) {
+                    emit!(tmpl.clone());
+                    for suffix in &suffixes {
+                        emit!(format!("{}{}", tmpl, suffix));
+                    }
+                }
+            }
+        }
+
+        // ═══════════════════════════════════════════════════════
+        // 6. INITIALS-BASED PASSWORDS
+        // ═══════════════════════════════════════════════════════
+        if !self.skips(Category::Initials) {
+            let initials = generate_initials(
+                &self.first_names, &self.last_names, &self.partners, &self.kids,
+            );
+
+            for init in &initials {
+                emit!(init.clone());
                 for suffix in &suffixes {
-                    emit!(format!("{}{}", camel, suffix));
+                    emit!(format!("{}{}", init, suffix));
+                    for sep in INITIAL_SEPARATORS {
+                        emit!(format!("{}{}{}", init, sep, suffix));
+                    }
+                }
+                for special in &specials {
+                    emit!(format!("{}{}", init, special));
                 }
             }
         }
 
         // ═══════════════════════════════════════════════════════
-        // 8. TRIPLE-TOKEN COMBINATIONS
+        // 7. TWO-WORD COMBINATIONS (Fixed: all categories)
         // ═══════════════════════════════════════════════════════
-        let triple_tokens: Vec<&String> = self.first_names.iter()
-            .chain(self.last_names.iter())
-            .chain(self.partners.iter())
-            .chain(self.kids.iter())
-            .chain(self.pets.iter())
-            .chain(self.city.iter())
-            .collect();
+        if !self.skips(Category::Combos) {
+            let (left_sides, right_sides) = self.combo_sides();
+
+            // Explicit Family Combinations
+            for p in &self.partners {
+                for n in &self.first_names {
+                    for sep in FAMILY_SEPARATORS {
+                        emit!(format!("{}{}{}", n, sep, p));
+                        emit!(format!("{}{}{}", p, sep, n));
+                    }
+                    for suffix in &suffixes {
+                        emit!(format!("{}{}{}", n, p, suffix));
+                        emit!(format!("{}{}{}", p, n, suffix));
+                    }
+                }
+            }
+
+            for left in &left_sides {
+                for right in &right_sides {
+                    if *left == *right { continue; }
+
+                    let l_variants = vec![left.to_lowercase(), to_title_case(&left.to_lowercase())];
+                    let r_variants = vec![right.to_lowercase(), to_title_case(&right.to_lowercase())];
+
+                    for l in &l_variants {
+                        for r in &r_variants {
+                            for sep in &separators {
+                                emit!(format!("{}{}{}", l, sep, r));
 
-        let max_t = triple_tokens.len().min(8);
-        if max_t >= 3 {
-            for i in 0..max_t {
-                for j in 0..max_t {
-                    if j == i { continue; }
-                    for k in 0..max_t {
-                        if k == i || k == j { continue; }
-                        let a = triple_tokens[i].to_lowercase();
-                        let b = triple_tokens[j].to_lowercase();
-                        let c = triple_tokens[k].to_lowercase();
-
-                        for sep in ["", "_", "."] {
-                            emit!(format!("{}{}{}{}{}", a, sep, b, sep, c));
+                                for suffix in &suffixes {
+                                    emit!(format!("{}{}{}{}", l, sep, r, suffix));
+                                    emit!(format!("{}{}{}{}", r, sep, l, suffix));
+                                }
+                            }
+                            for suffix in &suffixes {
+                                emit!(format!("{}{}{}", l, r, suffix));
+                                emit!(format!("{}{}_{}", l, r, suffix));
+                                emit!(format!("{}.{}.{}", l, r, suffix));
+                                emit!(format!("{}#{}{}", l, r, suffix));
+                                emit!(format!("{}#{}#{}", l, r, suffix));
+                                emit!(format!("{}@{}#{}", l, r, suffix));
+                            }
                         }
-                        for suffix in &suffixes {
-                            emit!(format!("{}{}{}{}", a, b, c, suffix));
+                    }
+
+                    // camelCase combo
+                    let camel = format!("{}{}", left.to_lowercase(), to_title_case(&right.to_lowercase()));
+                    emit!(camel.clone());
+                    for suffix in &suffixes {
+                        emit!(format!("{}{}", camel, suffix));
+                    }
+                }
+            }
+        }
+
+        // ═══════════════════════════════════════════════════════
+        // 8. TRIPLE-TOKEN COMBINATIONS
+        // ═══════════════════════════════════════════════════════
+        if !self.skips(Category::Triples) {
+            let triple_tokens = self.triple_tokens();
+            let max_t = triple_tokens.len();
+            if max_t >= 3 {
+                for i in 0..max_t {
+                    for j in 0..max_t {
+                        if j == i { continue; }
+                        for k in 0..max_t {
+                            if k == i || k == j { continue; }
+                            let a = triple_tokens[i].to_lowercase();
+                            let b = triple_tokens[j].to_lowercase();
+                            let c = triple_tokens[k].to_lowercase();
+
+                            for sep in TRIPLE_SEPARATORS {
+                                emit!(format!("{}{}{}{}{}", a, sep, b, sep, c));
+                            }
+                            for suffix in &suffixes {
+                                emit!(format!("{}{}{}{}", a, b, c, suffix));
+                            }
                         }
                     }
                 }
@@ -597,7 +1058,24 @@ impl Profile {
         }
 
         // ═══════════════════════════════════════════════════════
-        // 9. SUFFIXES & DATES AS STANDALONE
+        // 9. WORD REPETITION (johnjohn, JohnJohn1, johnnhoj)
+        // ═══════════════════════════════════════════════════════
+        if !self.skips(Category::Repetition) {
+            for word in all_words.iter().filter(|w| w.len() <= 8) {
+                for form in &repetitions(word) {
+                    emit!(form.clone());
+                    for extra in REPEAT_EXTRAS {
+                        emit!(format!("{}{}", form, extra));
+                    }
+                    for suffix in &suffixes {
+                        emit!(format!("{}{}", form, suffix));
+                    }
+                }
+            }
+        }
+
+        // ═══════════════════════════════════════════════════════
+        // 10. SUFFIXES & DATES AS STANDALONE
         // ═══════════════════════════════════════════════════════
         for date in &dates_expanded {
             emit!(date.clone());
@@ -605,6 +1083,74 @@ impl Profile {
         for suffix in &suffixes {
             emit!(suffix.clone());
         }
+
+        // ═══════════════════════════════════════════════════════
+        // 11. COMMON-WORD BLEND (Password1990, johnmonkey)
+        // ═══════════════════════════════════════════════════════
+        if self.blend.is_empty() {
+            return;
+        }
+
+        let profile_forms = profile_forms(&all_words);
+
+        for common in &self.blend {
+            // Common word + personal suffix
+            for form in &blend_forms(common) {
+                for suffix in &suffixes {
+                    for sep in BLEND_SEPARATORS {
+                        emit!(format!("{}{}{}", form, sep, suffix));
+                    }
+                    emit!(format!("{}{}", suffix, form));
+                    emit!(format!("{}{}!", form, suffix));
+                }
+            }
+
+            // Personal word + common word
+            for word in &profile_forms {
+                emit!(format!("{}{}", word, common));
+                emit!(format!("{}{}", common, word));
+            }
+        }
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════
+// MANGLER
+// ═══════════════════════════════════════════════════════════════
+//
+// The word variants of section 4 for words that come from a list rather
+// than a profile. The profile still decides the suffixes (its dates and
+// numbers on top of the common pins and walks) and what is skipped, but an
+// empty one is enough: every word then gets the same forms a profile word
+// would.
+
+/// Runs any word through a profile's case, leet and suffix forms
+pub struct Mangler {
+    profile: Profile,
+    suffixes: Vec<String>,
+}
+
+impl Mangler {
+    pub fn new(profile: Profile) -> Self {
+        let (suffixes, _) = profile.gather_suffixes();
+        Self { profile, suffixes }
+    }
+
+    /// Suffixes each form is combined with
+    pub fn suffixes(&self) -> usize {
+        self.suffixes.len()
+    }
+
+    /// Every variant of `word`, each once. Returns true once `callback`
+    /// does, to stop early.
+    pub fn mangle<F>(&self, word: &str, mut callback: F) -> bool
+    where F: FnMut(String) -> bool
+    {
+        if word.is_empty() {
+            return false;
+        }
+        let mut seen = HashSet::new();
+        self.profile.word_variants(word, &self.suffixes, &mut |s| seen.insert(s.clone()) && callback(s))
     }
 }
 
@@ -612,6 +1158,17 @@ impl Profile {
 // HELPER FUNCTIONS
 // ═══════════════════════════════════════════════════════════════
 
+/// `$x $y` functions appending `s`
+fn hashcat_append(s: &str) -> String {
+    s.chars().map(|c| format!("${}", c)).collect::<Vec<_>>().join(" ")
+}
+
+/// `^x` functions prepending `s` (hashcat prepends one char at a time, so
+/// the characters go in reverse)
+fn hashcat_prepend(s: &str) -> String {
+    s.chars().rev().map(|c| format!("^{}", c)).collect::<Vec<_>>().join(" ")
+}
+
 fn to_title_case(s: &str) -> String {
     let mut c = s.chars();
     match c.next() {
@@ -634,16 +1191,136 @@ fn to_inverted_title(s: &str) -> String {
 
 /// johN from john
 fn to_last_upper(s: &str) -> String {
-    if s.is_empty() { return String::new(); }
-    let len = s.len();
-    let (head, tail) = s.split_at(len - 1);
+    let Some((last, _)) = s.char_indices().last() else { return String::new() };
+    let (head, tail) = s.split_at(last);
     format!("{}{}", head, tail.to_uppercase())
 }
 
-/// Generate all case variants for a word
+/// Family idioms for a kid's name (emmasmom, mamaemma)
+fn kid_templates(kid: &str) -> [String; 6] {
+    let lower = kid.to_lowercase();
+    [
+        format!("{}smom", lower), format!("{}sdad", lower),
+        format!("{}s_mom", lower), format!("{}s_dad", lower),
+        format!("mama{}", lower), format!("papa{}", lower),
+    ]
+}
+
+fn pet_templates(pet: &str) -> [String; 2] {
+    let lower = pet.to_lowercase();
+    [format!("my{}", lower), format!("my_{}", lower)]
+}
+
+/// johnjohn, JohnJohn, Johnjohn, johnJohn, johnnhoj, Johnnhoj
+fn repetitions(word: &str) -> Vec<String> {
+    let lower = word.to_lowercase();
+    let title = to_title_case(&lower);
+    let reversed: String = lower.chars().rev().collect();
+
+    let mut repeats = vec![
+        format!("{}{}", lower, lower),
+        format!("{}{}", title, title),
+        format!("{}{}", title, lower),
+        format!("{}{}", lower, title),
+        format!("{}{}", lower, reversed),
+        format!("{}{}", title, reversed),
+    ];
+    repeats.dedup();
+    repeats
+}
+
+/// Lower and title forms of every profile word, for the common-word blend
+fn profile_forms(words: &[String]) -> Vec<String> {
+    words.iter()
+        .flat_map(|w| [w.to_lowercase(), to_title_case(&w.to_lowercase())])
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+fn blend_forms(common: &str) -> Vec<String> {
+    let mut forms = vec![common.to_string(), common.to_lowercase(), to_title_case(&common.to_lowercase())];
+    forms.sort();
+    forms.dedup();
+    forms
+}
+
+/// Byte-length histogram of a list of strings: `self.0[n]` of them are n
+/// bytes long. Joining every string of one list to every string of another
+/// convolves their histograms, so a section's size follows from its parts.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Lengths(Vec<u64>);
+
+impl Lengths {
+    fn of<S: AsRef<str>>(items: impl IntoIterator<Item = S>) -> Self {
+        let mut lengths = Self::default();
+        for item in items {
+            lengths.add(item.as_ref().len(), 1);
+        }
+        lengths
+    }
+
+    /// Each item twice over (`sep` + word + `sep`)
+    fn doubled<S: AsRef<str>>(items: impl IntoIterator<Item = S>) -> Self {
+        Self::of(items.into_iter().map(|item| item.as_ref().repeat(2)))
+    }
+
+    /// Just the empty string: joining it changes nothing
+    fn empty_string() -> Self {
+        Lengths(vec![1])
+    }
+
+    fn add(&mut self, len: usize, count: u64) {
+        if self.0.len() <= len {
+            self.0.resize(len + 1, 0);
+        }
+        self.0[len] += count;
+    }
+
+    /// Both lists, one after the other
+    fn plus(&self, other: &Lengths) -> Lengths {
+        let mut sum = self.clone();
+        for (len, &count) in other.0.iter().enumerate() {
+            if count > 0 {
+                sum.add(len, count);
+            }
+        }
+        sum
+    }
+
+    /// Every string of `self` joined to every string of `other`
+    fn then(&self, other: &Lengths) -> Lengths {
+        let mut joined = Lengths::default();
+        for (a, &x) in self.0.iter().enumerate().filter(|(_, &x)| x > 0) {
+            for (b, &y) in other.0.iter().enumerate().filter(|(_, &y)| y > 0) {
+                joined.add(a + b, x * y);
+            }
+        }
+        joined
+    }
+
+    fn times(&self, n: u64) -> Lengths {
+        Lengths(self.0.iter().map(|count| count * n).collect())
+    }
+
+    /// Strings within the length limits and their bytes, newline included
+    fn tally(&self, min_len: usize, max_len: usize) -> Estimate {
+        let mut estimate = Estimate::default();
+        for (len, &count) in self.0.iter().enumerate() {
+            if len >= min_len && len <= max_len {
+                estimate.candidates += count;
+                estimate.bytes += count * (len as u64 + 1);
+            }
+        }
+        estimate
+    }
+}
+
+/// Generate all case variants for a word (keeping its original casing)
 fn case_variants(word: &str) -> Vec<String> {
     let lower = word.to_lowercase();
     let mut variants = vec![
+        word.to_string(),
         lower.clone(),
         word.to_uppercase(),
         to_title_case(&lower),
@@ -711,7 +1388,7 @@ fn generate_leet(s: &str) -> Vec<String> {
     }
 
     // 3. Common s → $ only pattern
-    let s_leet: String = s.replace('s', "$").replace('S', "$");
+    let s_leet: String = s.replace(['s', 'S'], "$");
     if s_leet != *s {
         results.push(s_leet);
     }
@@ -742,6 +1419,47 @@ fn generate_nicknames(word: &str) -> Vec<String> {
     nicknames
 }
 
+/// QWERTY neighbours of a lowercase letter
+fn keyboard_neighbors(c: char) -> &'static str {
+    match c {
+        'q' => "wa", 'w' => "qes", 'e' => "wrd", 'r' => "etf", 't' => "ryg",
+        'y' => "tuh", 'u' => "yij", 'i' => "uok", 'o' => "ipl", 'p' => "ol",
+        'a' => "qsz", 's' => "awdx", 'd' => "sfec", 'f' => "dgrv", 'g' => "fhtb",
+        'h' => "gjyn", 'j' => "hkum", 'k' => "jli", 'l' => "kop",
+        'z' => "ax", 'x' => "zcs", 'c' => "xvd", 'v' => "cbf", 'b' => "vng",
+        'n' => "bmh", 'm' => "nj",
+        _ => "",
+    }
+}
+
+/// Adjacent-key substitutions and doubled letters (john → jphn, joohn)
+fn generate_typos(word: &str) -> Vec<String> {
+    let chars: Vec<char> = word.chars().collect();
+    let mut typos = Vec::new();
+
+    for (i, &ch) in chars.iter().enumerate() {
+        let lower = ch.to_ascii_lowercase();
+        for near in keyboard_neighbors(lower).chars() {
+            let near = if ch.is_ascii_uppercase() { near.to_ascii_uppercase() } else { near };
+            let mut variant = chars.clone();
+            variant[i] = near;
+            typos.push(variant.into_iter().collect());
+        }
+
+        // Doubled letter, skipping runs that are already doubled
+        if ch.is_alphabetic() && chars.get(i + 1) != Some(&ch) {
+            let mut variant = chars.clone();
+            variant.insert(i, ch);
+            typos.push(variant.into_iter().collect());
+        }
+    }
+
+    typos.sort();
+    typos.dedup();
+    typos.retain(|t| t != word);
+    typos
+}
+
 /// Generate initials from name lists (e.g., JD, jd, J.D.)
 fn generate_initials(
     first_names: &[String],
@@ -828,29 +1546,174 @@ fn month_name(month: u32) -> Option<(&'static str, &'static str)> {
     }
 }
 
-/// Decompose a phone number into suffix fragments
+/// Tokens derived from combinations of profile dates: the gap in years
+/// between any two dates (age at graduation, years married), years since
+/// 8-digit dates, decade strings ("80s", "1980s") and zodiac signs.
+fn derive_date_tokens(dates: &[String]) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut years: Vec<u32> = Vec::new();
+    let mut month_days: Vec<(&str, &str)> = Vec::new();
+
+    for date in dates {
+        if !date.chars().all(|c| c.is_ascii_digit()) {
+            continue;
+        }
+        match date.len() {
+            4 if date.starts_with("19") || date.starts_with("20") => {
+                years.extend(date.parse::<u32>().ok());
+            }
+            4 => month_days.push((&date[0..2], &date[2..4])),
+            8 => {
+                years.extend(date[4..8].parse::<u32>().ok());
+                month_days.push((&date[0..2], &date[2..4]));
+            }
+            _ => {}
+        }
+    }
+
+    years.sort();
+    years.dedup();
+
+    for (i, &year) in years.iter().enumerate() {
+        // Years since the event
+        if year > 1920 && year < CURRENT_YEAR {
+            tokens.push((CURRENT_YEAR - year).to_string());
+        }
+        // Gap between two events (age at graduation, years married)
+        for &later in &years[i + 1..] {
+            let gap = later - year;
+            if (1..=100).contains(&gap) {
+                tokens.push(gap.to_string());
+            }
+        }
+        // Decades
+        let decade = year / 10 * 10;
+        tokens.push(format!("{:02}s", decade % 100));
+        tokens.push(format!("{}s", decade));
+    }
+
+    for (p1, p2) in month_days {
+        let (a, b) = (p1.parse::<u32>().unwrap_or(0), p2.parse::<u32>().unwrap_or(0));
+        // MMDD when it parses as one, otherwise DDMM
+        let sign = zodiac_sign(a, b).or_else(|| zodiac_sign(b, a));
+        if let Some(sign) = sign {
+            tokens.push(sign.to_string());
+        }
+    }
+
+    tokens.sort();
+    tokens.dedup();
+    tokens
+}
+
+/// Western zodiac sign for a month/day, if the date is valid
+fn zodiac_sign(month: u32, day: u32) -> Option<&'static str> {
+    if day == 0 || day > 31 {
+        return None;
+    }
+    let sign = match (month, day) {
+        (1, d) if d <= 19 => "capricorn",
+        (1, _) | (2, 1..=18) => "aquarius",
+        (2, _) | (3, 1..=20) => "pisces",
+        (3, _) | (4, 1..=19) => "aries",
+        (4, _) | (5, 1..=20) => "taurus",
+        (5, _) | (6, 1..=20) => "gemini",
+        (6, _) | (7, 1..=22) => "cancer",
+        (7, _) | (8, 1..=22) => "leo",
+        (8, _) | (9, 1..=22) => "virgo",
+        (9, _) | (10, 1..=22) => "libra",
+        (10, _) | (11, 1..=21) => "scorpio",
+        (11, _) | (12, 1..=21) => "sagittarius",
+        (12, _) => "capricorn",
+        _ => return None,
+    };
+    Some(sign)
+}
+
+/// Decompose a phone number into suffix fragments. Handles international
+/// prefixes (+44…, 0044…), NANP numbers with or without the leading 1, and
+/// national trunk zeros, emitting forms with and without each.
 fn decompose_phone(number: &str) -> Vec<String> {
     let digits: String = number.chars().filter(|c| c.is_ascii_digit()).collect();
     let mut parts = Vec::new();
+    if digits.is_empty() {
+        return parts;
+    }
+
+    // Split off the country code when the number is written internationally
+    let international = if number.trim_start().starts_with('+') {
+        Some(digits.as_str())
+    } else if digits.starts_with("00") && digits.len() > 9 {
+        Some(&digits[2..])
+    } else if digits.len() == 11 && digits.starts_with('1') {
+        Some(digits.as_str())
+    } else {
+        None
+    };
+
+    let (country_code, national) = match international {
+        Some(intl) => {
+            let cc_len = country_code_len(intl);
+            if intl.len() > cc_len + 4 {
+                parts.push(intl.to_string());
+                (Some(&intl[..cc_len]), &intl[cc_len..])
+            } else {
+                (None, intl)
+            }
+        }
+        None => (None, digits.as_str()),
+    };
+
+    // National number with and without the trunk prefix (07700… / 7700…)
+    let core = national.trim_start_matches('0');
+    let uses_trunk_zero = national.starts_with('0') || country_code.is_some_and(|cc| cc != "1");
+    if !core.is_empty() {
+        parts.push(core.to_string());
+        if uses_trunk_zero {
+            parts.push(format!("0{}", core));
+        }
+    }
 
-    if digits.len() >= 7 {
-        parts.push(digits[digits.len()-4..].to_string());
-        if digits.len() >= 6 {
-            parts.push(digits[digits.len()-6..].to_string());
+    if core.len() >= 7 {
+        // Subscriber number tails
+        for n in [4, 6, 7] {
+            parts.push(core[core.len() - n..].to_string());
+        }
+        // Area / operator code
+        parts.push(core[..3].to_string());
+        if uses_trunk_zero {
+            parts.push(format!("0{}", &core[..3]));
         }
-        parts.push(digits[..3].to_string());
     }
 
     let reversed: String = digits.chars().rev().collect();
-    if reversed != digits && !reversed.is_empty() {
+    if reversed != digits {
         parts.push(reversed);
     }
 
     parts.sort();
     parts.dedup();
+    parts.retain(|p| *p != digits);
     parts
 }
 
+/// Length of the ITU calling code at the start of an international number
+fn country_code_len(digits: &str) -> usize {
+    const TWO_DIGIT: &[&str] = &[
+        "20", "27", "30", "31", "32", "33", "34", "36", "39", "40", "41", "43", "44",
+        "45", "46", "47", "48", "49", "51", "52", "53", "54", "55", "56", "57", "58",
+        "60", "61", "62", "63", "64", "65", "66", "81", "82", "84", "86", "90", "91",
+        "92", "93", "94", "95", "98",
+    ];
+    if digits.starts_with('1') || digits.starts_with('7') {
+        1
+    } else if digits.len() >= 2 && TWO_DIGIT.contains(&&digits[..2]) {
+        2
+    } else {
+        3
+    }
+}
+
 /// Decompose an email into reusable word parts
 fn decompose_email(email: &str) -> Vec<String> {
     let mut parts = Vec::new();
@@ -871,6 +1734,68 @@ fn decompose_email(email: &str) -> Vec<String> {
     parts
 }
 
+/// Legal-form suffixes and their short forms (Corporation → Corp)
+const COMPANY_SUFFIXES: &[(&str, &str)] = &[
+    ("corporation", "Corp"), ("corp", "Corp"), ("incorporated", "Inc"), ("inc", "Inc"),
+    ("company", "Co"), ("co", "Co"), ("limited", "Ltd"), ("ltd", "Ltd"),
+    ("llc", "LLC"), ("plc", "PLC"), ("gmbh", "GmbH"), ("ag", "AG"), ("sa", "SA"),
+    ("group", "Group"), ("holdings", "Holdings"), ("technologies", "Tech"),
+    ("international", "Intl"),
+];
+
+/// Derive corporate forms from a company name:
+/// "Acme Corporation" → Acme, AcmeCorp, AcmeCorporation, AC, acme.com
+fn derive_company_tokens(company: &str) -> Vec<String> {
+    let words: Vec<&str> = company
+        .split(|c: char| !c.is_alphanumeric() && c != '&')
+        .filter(|w| !w.is_empty() && *w != "&")
+        .collect();
+    if words.is_empty() {
+        return Vec::new();
+    }
+
+    let mut tokens = Vec::new();
+    let suffix_of = |w: &str| COMPANY_SUFFIXES.iter()
+        .find(|(long, _)| w.eq_ignore_ascii_case(long))
+        .map(|(_, short)| *short);
+
+    let core: Vec<&str> = words.iter().copied().filter(|w| suffix_of(w).is_none()).collect();
+    let suffixes: Vec<&str> = words.iter().filter_map(|w| suffix_of(w)).collect();
+    let core = if core.is_empty() { words.clone() } else { core };
+
+    let core_joined: String = core.iter().map(|w| to_title_case(w)).collect();
+    let core_lower = core_joined.to_lowercase();
+    tokens.push(core_joined.clone());
+    tokens.push(words.iter().map(|w| to_title_case(w)).collect());
+
+    // AcmeCorp, Acme_Inc
+    for short in &suffixes {
+        tokens.push(format!("{}{}", core_joined, short));
+        tokens.push(format!("{}_{}", core_joined, short));
+    }
+
+    // Acronyms / ticker-like forms: IBM, AC
+    for source in [&words, &core] {
+        if source.len() >= 2 {
+            let acronym: String = source.iter()
+                .filter_map(|w| w.chars().next())
+                .map(|c| c.to_ascii_uppercase())
+                .collect();
+            tokens.push(acronym);
+        }
+    }
+
+    // Domains
+    for tld in ["com", "net", "org"] {
+        tokens.push(format!("{}.{}", core_lower, tld));
+    }
+
+    tokens.sort();
+    tokens.dedup();
+    tokens.retain(|t| t.len() > 1 && t != company);
+    tokens
+}
+
 /// Decompose a username into component parts
 fn decompose_username(username: &str) -> Vec<String> {
     let mut parts = Vec::new();
@@ -940,6 +1865,63 @@ mod tests {
         assert!(profile_generates(&p, "chris"));
     }
 
+    #[test]
+    fn test_dictionary_nicknames() {
+        let mut p = Profile {
+            first_names: vec!["Robert".to_string()],
+            ..Default::default()
+        };
+        assert!(profile_generates(&p, "bob"));
+        assert!(profile_generates(&p, "Bobby123"));
+
+        p.nickname_map.insert("robert".to_string(), vec!["robster".to_string()]);
+        assert!(profile_generates(&p, "Robster"));
+    }
+
+    #[test]
+    fn test_mangler() {
+        let collect = |mangler: &Mangler, word: &str| {
+            let mut out = Vec::new();
+            mangler.mangle(word, |s| { out.push(s); false });
+            out
+        };
+        let mangler = Mangler::new(Profile::new());
+        let forms = collect(&mangler, "summer");
+        assert!(["summer", "Summer", "$ummer", "summer123!", "Summer1234", "1234_summer"].iter().all(|w| forms.contains(&w.to_string())));
+        assert_eq!(forms.len(), forms.iter().collect::<HashSet<_>>().len());
+        assert!(collect(&mangler, "").is_empty());
+
+        let mut stopped = 0;
+        assert!(mangler.mangle("summer", |_| { stopped += 1; stopped == 3 }));
+        assert_eq!(stopped, 3);
+
+        let mut profile = Profile { dates: vec!["1990".to_string()], ..Default::default() };
+        profile.skip.push(Category::Leet);
+        let forms = collect(&Mangler::new(profile), "summer");
+        assert!(forms.contains(&"Summer1990".to_string()));
+        assert!(!forms.contains(&"$ummer".to_string()));
+    }
+
+    #[test]
+    fn test_company_tokens() {
+        let tokens = derive_company_tokens("Acme Corporation");
+        assert!(tokens.contains(&"Acme".to_string()));
+        assert!(tokens.contains(&"AcmeCorp".to_string()));
+        assert!(tokens.contains(&"AcmeCorporation".to_string()));
+        assert!(tokens.contains(&"AC".to_string()));
+        assert!(tokens.contains(&"acme.com".to_string()));
+
+        let tokens = derive_company_tokens("International Business Machines");
+        assert!(tokens.contains(&"IBM".to_string()));
+
+        let p = Profile {
+            company: vec!["Acme Corporation".to_string()],
+            ..Default::default()
+        };
+        assert!(profile_generates(&p, "ACME123"));
+        assert!(profile_generates(&p, "AcmeCorp!"));
+    }
+
     #[test]
     fn test_initials() {
         let p = make_basic_profile();
@@ -1016,6 +1998,175 @@ mod tests {
         assert!(profile_generates(&p, "john555"));
     }
 
+    #[test]
+    fn test_international_phone() {
+        let uk = decompose_phone("+44 7700 900123");
+        assert!(uk.contains(&"7700900123".to_string()));
+        assert!(uk.contains(&"07700900123".to_string()));
+        assert!(uk.contains(&"900123".to_string()));
+        assert!(uk.contains(&"0123".to_string()));
+
+        let national = decompose_phone("07700 900123");
+        assert!(national.contains(&"7700900123".to_string()));
+        assert!(national.contains(&"0123".to_string()));
+
+        let dialled = decompose_phone("0091 98765 43210");
+        assert!(dialled.contains(&"9876543210".to_string()));
+        assert!(dialled.contains(&"3210".to_string()));
+
+        let nanp = decompose_phone("1-555-123-4567");
+        assert!(nanp.contains(&"5551234567".to_string()));
+        assert!(nanp.contains(&"555".to_string()));
+        assert!(!nanp.contains(&"05551234567".to_string()));
+    }
+
+    /// Size a run by walking it, as `estimate` did before it went analytic
+    fn walked_estimate(profile: &Profile) -> Estimate {
+        let mut estimate = Estimate::default();
+        profile.iter_candidates(|s| {
+            estimate.candidates += 1;
+            estimate.bytes += s.len() as u64 + 1;
+            false
+        });
+        estimate
+    }
+
+    #[test]
+    fn test_estimate_matches_walk() {
+        let mut profile = Profile {
+            first_names: vec!["John".to_string(), "Élodie".to_string()],
+            last_names: vec!["Doe".to_string()],
+            partners: vec!["Jane".to_string()],
+            kids: vec!["Emma".to_string()],
+            pets: vec!["Rex".to_string()],
+            city: vec!["Paris".to_string()],
+            usernames: vec!["jdoe_99".to_string()],
+            company: vec!["Acme Corp".to_string()],
+            dates: vec!["1990-05-17".to_string()],
+            numbers: vec!["5551234567".to_string()],
+            blend: vec!["password".to_string(), "Monkey".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(profile.estimate(), walked_estimate(&profile));
+
+        profile.min_length = Some(8);
+        profile.max_length = Some(14);
+        profile.skip = vec![Category::Leet, Category::Decorative];
+        assert_eq!(profile.estimate(), walked_estimate(&profile));
+
+        assert_eq!(make_basic_profile().estimate(), walked_estimate(&make_basic_profile()));
+    }
+
+    #[test]
+    fn test_estimate_bounds_generation() {
+        let profile = make_basic_profile();
+        let generated = profile.generate();
+        let estimate = profile.estimate();
+        assert!(estimate.candidates >= generated.len() as u64);
+        let unique_bytes: u64 = generated.iter().map(|c| c.len() as u64 + 1).sum();
+        assert!(estimate.bytes >= unique_bytes);
+    }
+
+    #[test]
+    fn test_bloom_dedup() {
+        let profile = make_basic_profile();
+        let exact = profile.generate().len();
+        let mut bloom = Vec::new();
+        profile.for_each_unique(Dedup::Bloom { fp_rate: 0.001 }, |s| { bloom.push(s); false });
+
+        let unique: HashSet<&String> = bloom.iter().collect();
+        assert_eq!(unique.len(), bloom.len());
+        assert!(bloom.len() <= exact);
+        assert!(bloom.len() as f64 >= exact as f64 * 0.99);
+    }
+
+    #[test]
+    fn test_blend_common_words() {
+        let mut profile = make_basic_profile();
+        assert!(!profile_generates(&profile, "Password1990"));
+        profile.blend = vec!["password".to_string(), "monkey".to_string()];
+        assert!(profile_generates(&profile, "Password1990"));
+        assert!(profile_generates(&profile, "monkey_1990"));
+        assert!(profile_generates(&profile, "johnmonkey"));
+    }
+
+    #[test]
+    fn test_hashcat_rules() {
+        let profile = make_basic_profile();
+        let (words, rules) = profile.hashcat_rules();
+        assert!(words.contains(&"John".to_string()));
+        assert!(rules.contains(&":".to_string()));
+        assert!(rules.contains(&"c $1 $9 $9 $0".to_string()));
+        assert!(rules.contains(&"l ^_ ^0 ^9 ^9 ^1".to_string()));
+        assert!(rules.iter().all(|r| r.split(' ').count() <= 31));
+    }
+
+    #[test]
+    fn test_field_weights() {
+        let mut profile = Profile {
+            pets: vec!["Rex".to_string()],
+            city: vec!["Paris".to_string()],
+            ..Default::default()
+        };
+        profile.field_weights.insert("pets".to_string(), 3.0);
+        profile.field_weights.insert("city".to_string(), 0.5);
+
+        let words = profile.gather_words();
+        let rex = words.iter().position(|w| w == "Rex").unwrap();
+        let paris = words.iter().position(|w| w == "Paris").unwrap();
+        assert!(rex < paris);
+
+        let mut first = None;
+        profile.for_each_unique(Dedup::Exact, |s| { first.get_or_insert(s); false });
+        assert!(!first.unwrap().to_lowercase().contains("par"));
+
+        profile.word_weights.insert("paris".to_string(), 0.0);
+        assert!(!profile.gather_words().contains(&"Paris".to_string()));
+
+        profile.word_limit = Some(1);
+        assert_eq!(profile.gather_words(), vec!["Rex".to_string()]);
+    }
+
+    #[test]
+    fn test_word_limit_counts_profile_values() {
+        // Equal weights: the limit keeps the first values in field order, and
+        // their derived forms come along without using up the limit
+        let profile = Profile {
+            first_names: vec!["robert".to_string()],
+            keywords: vec!["Zeta".to_string(), "alpha".to_string()],
+            word_limit: Some(2),
+            ..Default::default()
+        };
+        let words = profile.gather_words();
+        assert!(words.contains(&"robert".to_string()) && words.contains(&"Zeta".to_string()));
+        assert!(words.contains(&"bob".to_string()));
+        assert!(!words.contains(&"alpha".to_string()));
+    }
+
+    #[test]
+    fn test_word_repetition() {
+        let profile = make_basic_profile();
+        assert!(profile_generates(&profile, "johnjohn"));
+        assert!(profile_generates(&profile, "JohnJohn1"));
+        assert!(profile_generates(&profile, "johnnhoj"));
+        assert!(profile_generates(&profile, "doedoe1990"));
+    }
+
+    #[test]
+    fn test_skip_categories() {
+        let mut profile = make_basic_profile();
+        profile.pets = vec!["Rex".to_string()];
+        assert!(profile_generates(&profile, "J0hn"));
+        assert!(profile_generates(&profile, "xXJohnXx"));
+        assert!(profile_generates(&profile, "myrex"));
+
+        profile.skip = vec![Category::Leet, Category::Decorative, Category::Idioms];
+        assert!(!profile_generates(&profile, "J0hn"));
+        assert!(!profile_generates(&profile, "xXJohnXx"));
+        assert!(!profile_generates(&profile, "myrex"));
+        assert!(profile_generates(&profile, "John1990"));
+    }
+
     #[test]
     fn test_keyboard_walks() {
         let p = make_basic_profile();
@@ -1092,6 +2243,43 @@ mod tests {
         assert!(profile_generates(&p, "john_doe_max"));
     }
 
+    #[test]
+    fn test_typos() {
+        let mut p = Profile {
+            first_names: vec!["John".to_string()],
+            ..Default::default()
+        };
+        assert!(!profile_generates(&p, "jphn"));
+
+        p.typos = true;
+        assert!(profile_generates(&p, "jphn"));
+        assert!(profile_generates(&p, "joohn"));
+        assert!(profile_generates(&p, "Jphn123"));
+    }
+
+    #[test]
+    fn test_derived_dates() {
+        let tokens = derive_date_tokens(&[
+            "1990".to_string(), "2012".to_string(), "0517".to_string(), "25121985".to_string(),
+        ]);
+        // 2012 - 1990 (age at an event)
+        assert!(tokens.contains(&"22".to_string()));
+        // Years since the 8-digit date
+        assert!(tokens.contains(&"41".to_string()));
+        assert!(tokens.contains(&"90s".to_string()));
+        assert!(tokens.contains(&"1980s".to_string()));
+        assert!(tokens.contains(&"taurus".to_string()));
+        assert!(tokens.contains(&"capricorn".to_string()));
+
+        let p = Profile {
+            first_names: vec!["John".to_string()],
+            dates: vec!["1990".to_string(), "2012".to_string()],
+            ..Default::default()
+        };
+        assert!(profile_generates(&p, "john22"));
+        assert!(profile_generates(&p, "John90s"));
+    }
+
     #[test]
     fn test_age_derivation() {
         let p = Profile {
@@ -0,0 +1,176 @@
+use std::collections::HashSet;
+
+// ═══════════════════════════════════════════════════════════════
+// PRINCE CHAINS
+// ═══════════════════════════════════════════════════════════════
+//
+// PRINCE builds candidates by chaining elements of one wordlist:
+// "pass" + "word" + "1". The wordlist is taken to be in likelihood order,
+// so an element's rank stands in for its probability, and a chain costs
+// the sum of its elements' ranks counted from one. Chains come out
+// cheapest first: every single element and pair of top elements before
+// anything built from the tail of the list, with each extra element
+// costing at least one more. Only chains whose byte length falls in the
+// target range are emitted; the same string built two ways is emitted
+// twice, as in princeprocessor (use --unique to drop repeats).
+
+/// Chains of `min_elems..=max_elems` elements, `min_len..=max_len` bytes long
+#[derive(Debug, Clone)]
+pub struct Prince {
+    elements: Vec<Vec<u8>>,
+    pub min_len: usize,
+    pub max_len: usize,
+    pub min_elems: usize,
+    pub max_elems: usize,
+}
+
+impl Prince {
+    /// Elements in likelihood order, best first. Repeats keep their first
+    /// rank, and elements that could never fit in `max_len` are dropped.
+    pub fn new(words: impl IntoIterator<Item = String>, min_len: usize, max_len: usize, min_elems: usize, max_elems: usize) -> Self {
+        let mut seen = HashSet::new();
+        let elements = words.into_iter()
+            .map(String::into_bytes)
+            .filter(|word| !word.is_empty() && word.len() <= max_len && seen.insert(word.clone()))
+            .collect();
+        Self { elements, min_len, max_len, min_elems: min_elems.max(1), max_elems }
+    }
+
+    /// Elements kept, in rank order
+    pub fn elements(&self) -> &[Vec<u8>] {
+        &self.elements
+    }
+
+    /// How many chains `for_each` emits, saturating at `u128::MAX`
+    pub fn keyspace(&self) -> u128 {
+        // ways[len]: chains of the current element count `len` bytes long
+        let mut by_len = vec![0u128; self.max_len + 1];
+        for element in &self.elements {
+            by_len[element.len()] += 1;
+        }
+        let mut ways = vec![0u128; self.max_len + 1];
+        ways[0] = 1;
+        let mut total = 0u128;
+        for elems in 1..=self.max_elems {
+            let mut next = vec![0u128; self.max_len + 1];
+            for (len, &count) in ways.iter().enumerate().filter(|(_, count)| **count > 0) {
+                for (element_len, &elements) in by_len.iter().enumerate().skip(1).take(self.max_len - len) {
+                    next[len + element_len] = next[len + element_len].saturating_add(count.saturating_mul(elements));
+                }
+            }
+            ways = next;
+            if elems >= self.min_elems {
+                let in_range = ways.iter().skip(self.min_len).fold(0u128, |sum, &n| sum.saturating_add(n));
+                total = total.saturating_add(in_range);
+            }
+        }
+        total
+    }
+
+    /// Every chain in cost order; `emit` returns true to stop early
+    pub fn for_each(&self, mut emit: impl FnMut(&[u8]) -> bool) {
+        let n = self.elements.len();
+        if n == 0 || self.min_elems > self.max_elems || self.min_len > self.max_len {
+            return;
+        }
+        let shortest = self.elements.iter().map(Vec::len).min().unwrap_or(1);
+        let longest = self.elements.iter().map(Vec::len).max().unwrap_or(1);
+        let walk = Walk { prince: self, shortest, longest };
+        let mut chain = Vec::with_capacity(self.max_len);
+        for cost in self.min_elems..=self.max_elems * n {
+            for elems in self.min_elems..=self.max_elems.min(cost) {
+                // Too many elements to ever reach min_len, or too few to spend the cost
+                if elems * longest < self.min_len || elems * shortest > self.max_len || cost > elems * n {
+                    continue;
+                }
+                if walk.chains(elems, cost - elems, &mut chain, &mut emit) {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// One pass over the chains of a given element count and cost
+struct Walk<'a> {
+    prince: &'a Prince,
+    shortest: usize,
+    longest: usize,
+}
+
+impl Walk<'_> {
+    /// Extend `chain` by `slots` elements whose zero-based ranks sum to
+    /// `ranks`; true once `emit` asks to stop
+    fn chains(&self, slots: usize, ranks: usize, chain: &mut Vec<u8>, emit: &mut impl FnMut(&[u8]) -> bool) -> bool {
+        let elements = &self.prince.elements;
+        if slots == 0 {
+            return chain.len() >= self.prince.min_len && emit(chain);
+        }
+        let last = elements.len() - 1;
+        // The slots after this one can absorb at most (slots - 1) * last
+        let lowest = ranks.saturating_sub((slots - 1) * last);
+        for (rank, element) in elements.iter().enumerate().take(ranks.min(last) + 1).skip(lowest) {
+            let len = chain.len() + element.len();
+            let rest = slots - 1;
+            if len + rest * self.shortest > self.prince.max_len || len + rest * self.longest < self.prince.min_len {
+                continue;
+            }
+            let start = chain.len();
+            chain.extend_from_slice(element);
+            let stop = self.chains(rest, ranks - rank, chain, emit);
+            chain.truncate(start);
+            if stop {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prince(words: &[&str], min_len: usize, max_len: usize, min_elems: usize, max_elems: usize) -> Prince {
+        Prince::new(words.iter().map(|w| w.to_string()), min_len, max_len, min_elems, max_elems)
+    }
+
+    fn chains(prince: &Prince) -> Vec<String> {
+        let mut out = Vec::new();
+        prince.for_each(|chain| {
+            out.push(String::from_utf8(chain.to_vec()).unwrap());
+            false
+        });
+        out
+    }
+
+    #[test]
+    fn test_cheapest_chains_first() {
+        let p = prince(&["pass", "word", "1"], 1, 12, 1, 2);
+        let out = chains(&p);
+        assert_eq!(&out[..4], ["pass", "word", "passpass", "1"]);
+        assert_eq!(&out[4..6], ["password", "wordpass"]);
+        assert_eq!(out.len(), 3 + 9);
+        assert_eq!(p.keyspace(), 12);
+    }
+
+    #[test]
+    fn test_length_and_element_bounds() {
+        let p = prince(&["ab", "c", "def", "ab"], 4, 5, 2, 3);
+        assert_eq!(p.elements().len(), 3);
+        let out = chains(&p);
+        assert!(out.iter().all(|c| (4..=5).contains(&c.len())));
+        assert!(out.contains(&"abdef".to_string()) && out.contains(&"cabc".to_string()));
+        assert!(!out.contains(&"defdef".to_string()) && !out.contains(&"abc".to_string()));
+        assert_eq!(out.len() as u128, p.keyspace());
+    }
+
+    #[test]
+    fn test_stops_early() {
+        let p = prince(&["a", "b", "c"], 1, 8, 1, 8);
+        let mut seen = 0;
+        p.for_each(|_| { seen += 1; seen == 5 });
+        assert_eq!(seen, 5);
+        assert!(chains(&prince(&[], 1, 8, 1, 2)).is_empty());
+    }
+}
@@ -0,0 +1,119 @@
+use crate::engine::personal::Profile;
+use crate::engine::strength::UNLEET;
+
+// ═══════════════════════════════════════════════════════════════
+// LIKELIHOOD RANKING
+// ═══════════════════════════════════════════════════════════════
+//
+// Scores for `jigsaw rank`, which reorders a wordlist so a limited
+// cracking budget goes on the likeliest candidates first. A score is a
+// natural log probability: the trained model's chance of writing the
+// word, raised when it contains a term from the target's profile. Words the model
+// can't produce at all fall back to a uniform guess over printable
+// ASCII, which ranks them below nearly anything it can.
+
+/// Printable ASCII characters, the fallback's alphabet
+const PRINTABLE: f64 = 95.0;
+/// Shortest profile term matched; shorter ones ("7", "jo") are everywhere
+const MIN_TERM: usize = 3;
+
+/// Scores words from a model's log probability and a profile's terms
+#[derive(Debug, Clone, Default)]
+pub struct Ranker {
+    /// Lowercase terms and their profile weights
+    terms: Vec<(Vec<char>, f32)>,
+    /// Log of how much likelier a weight-1 profile match makes a word
+    boost: f64,
+}
+
+impl Ranker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Boost words containing a value from `profile` (or a 3+ character
+    /// piece of one, like a birth year) by `factor` raised to the value's
+    /// weight; the best-weighted match counts
+    pub fn with_profile(mut self, profile: &Profile, factor: f64) -> Self {
+        self.boost = factor.max(1.0).ln();
+        for (field, values) in profile.fields() {
+            for value in values {
+                let weight = profile.weight_of(field, value);
+                if weight <= 0.0 {
+                    continue;
+                }
+                let value = value.to_lowercase();
+                let value = match field {
+                    "email" => value.split('@').next().unwrap_or_default().to_string(),
+                    _ => value,
+                };
+                let whole: String = value.chars().filter(|c| c.is_alphanumeric()).collect();
+                for term in std::iter::once(whole.as_str()).chain(value.split(|c: char| !c.is_alphanumeric())) {
+                    let term: Vec<char> = term.chars().collect();
+                    if term.len() >= MIN_TERM && !self.terms.iter().any(|(t, w)| *t == term && *w >= weight) {
+                        self.terms.retain(|(t, _)| *t != term);
+                        self.terms.push((term, weight));
+                    }
+                }
+            }
+        }
+        self
+    }
+
+    /// Number of distinct profile terms matched against
+    pub fn terms(&self) -> usize {
+        self.terms.len()
+    }
+
+    /// Score for `word`, given the model's log probability of it
+    /// (`None` when the model can't produce it)
+    pub fn score(&self, word: &str, log_probability: Option<f64>) -> f64 {
+        let base = log_probability.unwrap_or_else(|| unseen(word));
+        base + self.boost * self.best_match(word) as f64
+    }
+
+    /// Weight of the best profile term in `word`, read through leetspeak
+    fn best_match(&self, word: &str) -> f32 {
+        if self.terms.is_empty() {
+            return 0.0;
+        }
+        let word: Vec<char> = word.to_lowercase().chars().collect();
+        let reads_as = |c: char, t: char| c == t || UNLEET.iter().any(|(leet, letters)| *leet == c && letters.contains(&t));
+        self.terms.iter()
+            .filter(|(term, _)| word.windows(term.len()).any(|window| window.iter().zip(term).all(|(&c, &t)| reads_as(c, t))))
+            .map(|(_, weight)| *weight)
+            .fold(0.0, f32::max)
+    }
+}
+
+/// Log probability of typing `word` and stopping at random over printable ASCII
+pub fn unseen(word: &str) -> f64 {
+    -((word.chars().count() + 1) as f64) * PRINTABLE.ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_boost() {
+        let mut profile = Profile::new();
+        profile.pets = vec!["Rex".to_string()];
+        profile.first_names = vec!["Anna Maria".to_string()];
+        profile.dates = vec!["1990-05-12".to_string()];
+        profile.email = vec!["asmith@corp.com".to_string()];
+        profile.field_weights.insert("pets".to_string(), 2.0);
+        let ranker = Ranker::new().with_profile(&profile, 1000.0);
+
+        // annamaria, anna, maria, 19900512, 1990, asmith, rex
+        assert_eq!(ranker.terms(), 7);
+        let plain = ranker.score("sunshine1", Some(-20.0));
+        assert_eq!(plain, -20.0);
+        assert!((ranker.score("Maria1990", Some(-20.0)) - (-20.0 + 1000f64.ln())).abs() < 1e-9);
+        assert!((ranker.score("r3x!", Some(-20.0)) - (-20.0 + 2.0 * 1000f64.ln())).abs() < 1e-9);
+        assert!(ranker.score("corp2024", Some(-20.0)) == plain);
+
+        // Words the model can't write rank below ones it can
+        assert!(ranker.score("zqxj", None) < ranker.score("zqxj", Some(-10.0)));
+    }
+}
@@ -1,6 +1,8 @@
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 use std::str::FromStr;
+use crate::invalid;
 
+/// One hashcat rule function
 #[derive(Debug, Clone, PartialEq)]
 pub enum Rule {
     NoOp,               // :
@@ -17,6 +19,7 @@ pub enum Rule {
 }
 
 impl Rule {
+    /// Transform `candidate` in place
     pub fn apply(&self, candidate: &mut Vec<u8>) {
         match self {
             Rule::NoOp => {},
@@ -74,6 +77,8 @@ impl Rule {
     }
 }
 
+/// A rule chain, applied left to right; parse one from hashcat syntax
+/// (`c$1$!`) with `FromStr`
 pub struct RuleSet {
     rules: Vec<Rule>,
 }
@@ -83,6 +88,7 @@ impl RuleSet {
         Self { rules }
     }
 
+    /// Run every rule of the chain over `candidate` in place
     pub fn apply(&self, candidate: &mut Vec<u8>) {
         for rule in &self.rules {
             rule.apply(candidate);
@@ -121,10 +127,10 @@ impl FromStr for RuleSet {
                         if arg.is_ascii() {
                             rules.push(Rule::Append(arg as u8));
                         } else {
-                            return Err(anyhow!("Rule $ argument must be ASCII"));
+                            return Err(invalid!("Rule $ argument must be ASCII"));
                         }
                     } else {
-                        return Err(anyhow!("Rule $ requires an argument"));
+                        return Err(invalid!("Rule $ requires an argument"));
                     }
                 },
                 '^' => {
@@ -132,19 +138,46 @@ impl FromStr for RuleSet {
                         if arg.is_ascii() {
                             rules.push(Rule::Prepend(arg as u8));
                         } else {
-                            return Err(anyhow!("Rule ^ argument must be ASCII"));
+                            return Err(invalid!("Rule ^ argument must be ASCII"));
                         }
                     } else {
-                        return Err(anyhow!("Rule ^ requires an argument"));
+                        return Err(invalid!("Rule ^ requires an argument"));
                     }
                 },
-                _ => return Err(anyhow!("Unknown rule: {}", c)),
+                _ => return Err(invalid!("Unknown rule: {}", c)),
             }
         }
         Ok(RuleSet { rules })
     }
 }
 
+/// Parse a line of chains separated by spaces (`:$1 u$!`); a space that
+/// is the argument of `$` or `^` stays in its chain
+pub fn chains(s: &str) -> Result<Vec<RuleSet>> {
+    let mut chains = Vec::new();
+    let mut start = 0;
+    let mut argument = false;
+    for (i, c) in s.char_indices() {
+        if std::mem::take(&mut argument) {
+            continue;
+        }
+        match c {
+            '$' | '^' => argument = true,
+            ' ' => {
+                if start < i {
+                    chains.push(RuleSet::from_str(&s[start..i])?);
+                }
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if start < s.len() {
+        chains.push(RuleSet::from_str(&s[start..])?);
+    }
+    Ok(chains)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -221,4 +254,19 @@ mod tests {
         // Append ! -> "CBA!"
         assert_eq!(apply_ruleset("ru$!", "abc"), "CBA!");
     }
+
+    #[test]
+    fn test_chains() {
+        let apply = |chain: &RuleSet| {
+            let mut buf = b"pass".to_vec();
+            chain.apply(&mut buf);
+            String::from_utf8(buf).unwrap()
+        };
+        let outputs: Vec<String> = chains(":$1 u$!").unwrap().iter().map(apply).collect();
+        assert_eq!(outputs, ["pass1", "PASS!"]);
+        let outputs: Vec<String> = chains("  $ ^  r ").unwrap().iter().map(apply).collect();
+        assert_eq!(outputs, [" pass ", "ssap"]);
+        assert!(chains("").unwrap().is_empty());
+        assert!(chains(":$1 x").is_err());
+    }
 }
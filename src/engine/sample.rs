@@ -0,0 +1,103 @@
+use rand::{Rng, RngExt};
+use std::collections::HashSet;
+
+// ═══════════════════════════════════════════════════════════════
+// SAMPLING
+// ═══════════════════════════════════════════════════════════════
+//
+// --sample N keeps a uniform random subset of a run. Keyspaces that can be
+// indexed (masks) draw N distinct indices straight away; streams (profiles,
+// mnemonic variants) go through a reservoir, which holds N candidates
+// however long the stream is. Either way the sample comes back in
+// generation order.
+
+/// `n` distinct indices below `space`, chosen uniformly and sorted
+/// (Floyd's algorithm: `n` draws, whatever the size of `space`)
+pub fn distinct_indices(space: u128, n: u64, rng: &mut impl Rng) -> Vec<u128> {
+    if u128::from(n) >= space {
+        return (0..space).collect();
+    }
+    // Grown as needed past a modest start, since `n` can be far beyond memory
+    let mut chosen = HashSet::with_capacity((n as usize).min(1 << 16));
+    for j in space - u128::from(n)..space {
+        let t = rng.random_range(0..=j);
+        if !chosen.insert(t) {
+            chosen.insert(j);
+        }
+    }
+    let mut indices: Vec<u128> = chosen.into_iter().collect();
+    indices.sort_unstable();
+    indices
+}
+
+/// A uniform sample of a stream of unknown length (Algorithm R)
+pub struct Reservoir<T, R: Rng> {
+    size: usize,
+    seen: u64,
+    /// Kept items with their stream positions
+    items: Vec<(u64, T)>,
+    rng: R,
+}
+
+impl<T, R: Rng> Reservoir<T, R> {
+    pub fn new(size: usize, rng: R) -> Self {
+        Self { size, seen: 0, items: Vec::with_capacity(size.min(1 << 16)), rng }
+    }
+
+    pub fn push(&mut self, item: T) {
+        let position = self.seen;
+        self.seen += 1;
+        if self.items.len() < self.size {
+            self.items.push((position, item));
+        } else {
+            let slot = self.rng.random_range(0..self.seen);
+            if slot < self.size as u64 {
+                self.items[slot as usize] = (position, item);
+            }
+        }
+    }
+
+    /// The sample, in the order it was generated
+    pub fn into_vec(mut self) -> Vec<T> {
+        self.items.sort_unstable_by_key(|(position, _)| *position);
+        self.items.into_iter().map(|(_, item)| item).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::memorable::seeded_rng;
+
+    #[test]
+    fn test_sampling() {
+        let mut rng = seeded_rng(7);
+        let indices = distinct_indices(1 << 100, 1000, &mut rng);
+        assert_eq!(indices.len(), 1000);
+        assert!(indices.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(distinct_indices(5, 10, &mut rng), vec![0, 1, 2, 3, 4]);
+        // Past the preallocation, in a keyspace far bigger than the sample
+        let many = distinct_indices(1 << 100, 1 << 18, &mut rng);
+        assert_eq!(many.len(), 1 << 18);
+        assert!(many.windows(2).all(|w| w[0] < w[1]));
+
+        let mut reservoir = Reservoir::new(10, seeded_rng(7));
+        for i in 0..10_000 {
+            reservoir.push(i);
+        }
+        let sample = reservoir.into_vec();
+        assert_eq!(sample.len(), 10);
+        assert!(sample.windows(2).all(|w| w[0] < w[1]));
+        // Not just the head of the stream
+        assert!(sample[9] >= 10);
+
+        // Every position is about equally likely to be kept
+        let mut hits = [0u32; 10];
+        for seed in 0..2000 {
+            let mut reservoir = Reservoir::new(3, seeded_rng(seed));
+            (0..10).for_each(|i| reservoir.push(i));
+            reservoir.into_vec().into_iter().for_each(|i| hits[i] += 1);
+        }
+        assert!(hits.iter().all(|&h| (450..750).contains(&h)), "{:?}", hits);
+    }
+}
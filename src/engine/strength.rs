@@ -0,0 +1,628 @@
+use crate::engine::personal::Profile;
+use crate::engine::walks::Layout;
+use serde::Serialize;
+use std::collections::HashMap;
+
+// ═══════════════════════════════════════════════════════════════
+// STRENGTH ESTIMATION
+// ═══════════════════════════════════════════════════════════════
+//
+// The generators run in reverse, zxcvbn-style: find every part of a
+// password that one of them would produce cheaply (a ranked dictionary
+// word, a keyboard walk, a date, a repeat, a run like "abc"), price each
+// in guesses, and take the cheapest way to cover the whole password with
+// them, brute-forcing whatever is left. Covering with more pieces costs
+// extra, since an attacker must also guess how the pieces fit together.
+// The number of guesses is what the report turns into bits, a 0–4 score
+// and crack times.
+
+const COMMON: &str = include_str!("wordlists/common_passwords.txt");
+
+/// Guesses per second for each attack the report times
+pub const SCENARIOS: [(&str, f64); 4] = [
+    ("online, throttled (100/hour)", 100.0 / 3600.0),
+    ("online, unthrottled (10/s)", 10.0),
+    ("offline, slow hash (10k/s)", 1e4),
+    ("offline, fast hash (10B/s)", 1e10),
+];
+
+/// Characters matched against patterns; any past this are brute force
+pub const MAX_CHARS: usize = 128;
+
+/// Longest dictionary word looked for
+const MAX_WORD: usize = 32;
+
+/// Guess floor for a part of a longer password, so no piece is free
+const MIN_GUESSES_CHAR: f64 = 10.0;
+const MIN_GUESSES_PART: f64 = 50.0;
+
+/// Cost of each piece beyond the first
+const PIECE_PENALTY: f64 = 10_000.0;
+
+/// Characters read back as letters, as `generate_leet` writes them
+pub(crate) const UNLEET: &[(char, &[char])] = &[
+    ('4', &['a']), ('@', &['a']), ('8', &['b']), ('(', &['c']), ('{', &['c']),
+    ('3', &['e']), ('6', &['g']), ('1', &['i', 'l']), ('!', &['i']), ('|', &['i', 'l']),
+    ('0', &['o']), ('$', &['s']), ('5', &['s']), ('+', &['t']), ('7', &['t']),
+    ('%', &['x']), ('2', &['z']),
+];
+
+/// Un-leeted readings tried per token
+const MAX_UNLEET: usize = 16;
+
+/// Walks are priced on QWERTY: keys a walk can start on, and the mean number of neighbours per key
+const KEYBOARD_KEYS: f64 = 47.0;
+const KEYBOARD_DEGREE: f64 = 4.6;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "pattern", rename_all = "snake_case")]
+pub enum Pattern {
+    /// `rank` is 1-based within `dictionary`
+    Dictionary { dictionary: String, word: String, rank: usize, reversed: bool, leet: bool },
+    /// A walk across adjacent keys, changing direction `turns - 1` times
+    Spatial { turns: usize, shifted: bool },
+    Repeat { base: String, count: usize },
+    /// Consecutive characters, like abc or 9876
+    Sequence { ascending: bool },
+    Date { year: i32, separator: bool },
+    Year { year: i32 },
+    BruteForce,
+}
+
+/// Characters `start..end` of the password, and what they cost
+#[derive(Debug, Clone, Serialize)]
+pub struct Match {
+    pub token: String,
+    pub start: usize,
+    pub end: usize,
+    #[serde(flatten)]
+    pub pattern: Pattern,
+    pub guesses: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Strength {
+    pub guesses: f64,
+    pub guesses_log10: f64,
+    /// 0 (guessed almost at once) to 4 (out of reach of offline attacks)
+    pub score: u8,
+    /// The cheapest cover of the password, in order
+    pub matches: Vec<Match>,
+}
+
+impl Strength {
+    pub fn bits(&self) -> f64 {
+        self.guesses_log10 * std::f64::consts::LOG2_10
+    }
+
+    /// Seconds to reach this password at `rate` guesses a second
+    pub fn crack_seconds(&self, rate: f64) -> f64 {
+        self.guesses / rate
+    }
+
+    /// Why the password is weak, and what would help
+    pub fn feedback(&self) -> Vec<String> {
+        let mut notes = Vec::new();
+        let mut note = |text: String| if !notes.contains(&text) { notes.push(text) };
+        let whole = self.matches.len() == 1;
+        for m in &self.matches {
+            match &m.pattern {
+                Pattern::Dictionary { dictionary, rank, reversed, leet, .. } => {
+                    match dictionary.as_str() {
+                        "common" if whole && *rank <= 10 => note("This is a top-10 common password".to_string()),
+                        "common" if whole => note("This is a commonly used password".to_string()),
+                        "common" => note(format!("Contains \"{}\", a commonly used password", m.token)),
+                        "english" if whole => note("A single word is easy to guess".to_string()),
+                        "english" => {}
+                        other => note(format!("Contains \"{}\" from the {} list", m.token, other)),
+                    }
+                    if *reversed || *leet {
+                        note("Reversed words and substitutions like '@' for 'a' don't help much".to_string());
+                    }
+                }
+                Pattern::Spatial { .. } => note("Keyboard walks like qwerty or 1qaz are easy to guess".to_string()),
+                Pattern::Repeat { .. } => note("Repeats like \"aaa\" or \"abcabc\" are easy to guess".to_string()),
+                Pattern::Sequence { .. } => note("Sequences like abc or 6543 are easy to guess".to_string()),
+                Pattern::Date { .. } | Pattern::Year { .. } => note("Dates and years are easy to guess".to_string()),
+                Pattern::BruteForce => {}
+            }
+        }
+        if self.score < 3 {
+            note("Add another word or two; uncommon words are better".to_string());
+        }
+        notes
+    }
+}
+
+/// Matches passwords against ranked dictionaries and the other patterns
+pub struct Estimator {
+    dictionaries: Vec<(String, HashMap<String, usize>)>,
+}
+
+impl Default for Estimator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Estimator {
+    /// Common passwords, then English words (the EFF long list)
+    pub fn new() -> Self {
+        Self { dictionaries: Vec::new() }
+            .with_dictionary("common", COMMON.lines().map(str::to_string))
+            .with_dictionary("english", crate::engine::memorable::eff_long_words().iter().map(|w| w.to_string()))
+    }
+
+    /// Another dictionary, ranked by `words`' order, most likely first
+    pub fn with_dictionary(mut self, name: &str, words: impl IntoIterator<Item = String>) -> Self {
+        let mut ranks = HashMap::new();
+        for word in words {
+            let word = word.trim().to_lowercase();
+            if !word.is_empty() {
+                let rank = ranks.len() + 1;
+                ranks.entry(word).or_insert(rank);
+            }
+        }
+        self.dictionaries.push((name.to_string(), ranks));
+        self
+    }
+
+    /// Every value in `profile` as a likely word, as a targeted attack would try them
+    pub fn with_profile(self, profile: &Profile) -> Self {
+        let values = profile.fields().into_iter()
+            .flat_map(|(_, values)| values.iter())
+            .map(|value| value.split_whitespace().collect::<String>())
+            .collect::<Vec<_>>();
+        self.with_dictionary("profile", values)
+    }
+
+    pub fn estimate(&self, password: &str) -> Strength {
+        let chars: Vec<char> = password.chars().collect();
+        let n = chars.len();
+        let head = n.min(MAX_CHARS);
+
+        let mut matches = self.dictionary_matches(&chars[..head]);
+        matches.extend(spatial_matches(&chars[..head]));
+        matches.extend(sequence_matches(&chars[..head]));
+        matches.extend(self.repeat_matches(&chars[..head]));
+        matches.extend(date_matches(&chars[..head]));
+        for start in 0..head {
+            for end in start + 1..=head {
+                matches.push(brute_force(&chars, start, end));
+            }
+        }
+        if head < n {
+            matches.push(brute_force(&chars, head, n));
+        }
+        for m in &mut matches {
+            if m.end - m.start < n {
+                m.guesses = m.guesses.max(if m.end - m.start == 1 { MIN_GUESSES_CHAR } else { MIN_GUESSES_PART });
+            }
+        }
+
+        let (log_guesses, cover) = cheapest_cover(n, &matches);
+        let guesses_log10 = log_guesses / std::f64::consts::LN_10;
+        let guesses = 10f64.powf(guesses_log10.min(300.0));
+        let score = match guesses {
+            g if g < 1e3 + 5.0 => 0,
+            g if g < 1e6 + 5.0 => 1,
+            g if g < 1e8 + 5.0 => 2,
+            g if g < 1e10 + 5.0 => 3,
+            _ => 4,
+        };
+        Strength { guesses, guesses_log10, score, matches: cover.into_iter().map(|i| matches[i].clone()).collect() }
+    }
+
+    fn dictionary_matches(&self, chars: &[char]) -> Vec<Match> {
+        let lower: Vec<char> = chars.iter().map(|c| c.to_lowercase().next().unwrap_or(*c)).collect();
+        let mut matches = Vec::new();
+        for start in 0..chars.len() {
+            for end in start + 3..=chars.len().min(start + MAX_WORD) {
+                let token = &lower[start..end];
+                let word: String = token.iter().collect();
+                let reversed: String = token.iter().rev().collect();
+                let case = upper_variations(&chars[start..end]);
+                for (name, ranks) in &self.dictionaries {
+                    let mut found = |word: &str, rank: usize, reversed: bool, leet: f64| {
+                        matches.push(Match {
+                            token: chars[start..end].iter().collect(),
+                            start,
+                            end,
+                            pattern: Pattern::Dictionary { dictionary: name.clone(), word: word.to_string(), rank, reversed, leet: leet > 1.0 },
+                            guesses: rank as f64 * case * leet * if reversed { 2.0 } else { 1.0 },
+                        });
+                    };
+                    if let Some(&rank) = ranks.get(&word) {
+                        found(&word, rank, false, 1.0);
+                    }
+                    if reversed != word {
+                        if let Some(&rank) = ranks.get(&reversed) {
+                            found(&reversed, rank, true, 1.0);
+                        }
+                    }
+                    for plain in unleet(token) {
+                        if let Some(&rank) = ranks.get(&plain) {
+                            found(&plain, rank, false, leet_variations(token, &plain));
+                        }
+                    }
+                }
+            }
+        }
+        matches
+    }
+
+    /// A block repeated back to back, priced as the block times the repeats
+    fn repeat_matches(&self, chars: &[char]) -> Vec<Match> {
+        let mut matches = Vec::new();
+        let mut start = 0;
+        while start < chars.len() {
+            let found = (1..=(chars.len() - start) / 2).find_map(|block| {
+                let base = &chars[start..start + block];
+                let count = chars[start..].chunks(block).take_while(|chunk| *chunk == base).count();
+                (count >= if block == 1 { 3 } else { 2 }).then_some((block, count))
+            });
+            let Some((block, count)) = found else {
+                start += 1;
+                continue;
+            };
+            let end = start + block * count;
+            let base: String = chars[start..start + block].iter().collect();
+            let guesses = self.estimate(&base).guesses * count as f64;
+            matches.push(Match {
+                token: chars[start..end].iter().collect(),
+                start,
+                end,
+                pattern: Pattern::Repeat { base, count },
+                guesses,
+            });
+            start = end;
+        }
+        matches
+    }
+}
+
+/// Least ln(guesses) over covers of `n` characters by `matches`, and the
+/// cover: l pieces cost l! times their product, plus PIECE_PENALTY^(l-1)
+fn cheapest_cover(n: usize, matches: &[Match]) -> (f64, Vec<usize>) {
+    if n == 0 {
+        return (0.0, Vec::new());
+    }
+    let mut by_end: Vec<Vec<usize>> = vec![Vec::new(); n + 1];
+    for (i, m) in matches.iter().enumerate() {
+        by_end[m.end].push(i);
+    }
+    // product[j][l]: least ln(product) covering chars[..j] in l pieces
+    let mut product = vec![vec![f64::INFINITY; n + 1]; n + 1];
+    let mut last = vec![vec![usize::MAX; n + 1]; n + 1];
+    product[0][0] = 0.0;
+    for end in 1..=n {
+        for &i in &by_end[end] {
+            let m = &matches[i];
+            let cost = m.guesses.ln();
+            for pieces in 0..=m.start {
+                let total = product[m.start][pieces] + cost;
+                if total < product[end][pieces + 1] {
+                    product[end][pieces + 1] = total;
+                    last[end][pieces + 1] = i;
+                }
+            }
+        }
+    }
+
+    let mut best = (f64::INFINITY, 0);
+    let mut factorial = 0.0;
+    for (pieces, &covered) in product[n].iter().enumerate().skip(1) {
+        factorial += (pieces as f64).ln();
+        if covered.is_infinite() {
+            continue;
+        }
+        let (a, b) = (factorial + covered, (pieces - 1) as f64 * PIECE_PENALTY.ln());
+        let total = a.max(b) + (-(a - b).abs()).exp().ln_1p();
+        if total < best.0 {
+            best = (total, pieces);
+        }
+    }
+
+    let mut cover = Vec::with_capacity(best.1);
+    let (mut end, mut pieces) = (n, best.1);
+    while pieces > 0 {
+        let i = last[end][pieces];
+        cover.push(i);
+        end = matches[i].start;
+        pieces -= 1;
+    }
+    cover.reverse();
+    (best.0, cover)
+}
+
+fn brute_force(chars: &[char], start: usize, end: usize) -> Match {
+    Match {
+        token: chars[start..end].iter().collect(),
+        start,
+        end,
+        pattern: Pattern::BruteForce,
+        guesses: 10f64.powi((end - start).min(300) as i32),
+    }
+}
+
+/// n choose k
+fn choose(n: usize, k: usize) -> f64 {
+    (0..k).fold(1.0, |acc, i| acc * (n - i) as f64 / (i + 1) as f64)
+}
+
+/// Ways to place `changed` of `changed + unchanged` positions, at least one
+fn variations(changed: usize, unchanged: usize) -> f64 {
+    if changed == 0 || unchanged == 0 {
+        return 2.0;
+    }
+    (1..=changed.min(unchanged)).map(|i| choose(changed + unchanged, i)).sum()
+}
+
+/// Capitalisations an attacker tries before this one: 1 for lowercase,
+/// 2 for Capitalised, trailinG or ALL CAPS, more for anything else
+fn upper_variations(token: &[char]) -> f64 {
+    let upper = token.iter().filter(|c| c.is_uppercase()).count();
+    let lower = token.iter().filter(|c| c.is_lowercase()).count();
+    if upper == 0 {
+        return 1.0;
+    }
+    let edge = upper == 1 && (token[0].is_uppercase() || token[token.len() - 1].is_uppercase());
+    if lower == 0 || edge {
+        return 2.0;
+    }
+    variations(upper, lower)
+}
+
+/// Readings of `token` with its leet characters turned back into letters
+fn unleet(token: &[char]) -> Vec<String> {
+    if !token.iter().any(|c| UNLEET.iter().any(|(leet, _)| leet == c)) {
+        return Vec::new();
+    }
+    let mut readings = vec![String::new()];
+    for c in token {
+        let options = UNLEET.iter().find(|(leet, _)| leet == c).map_or(std::slice::from_ref(c), |(_, letters)| letters);
+        readings = readings.iter()
+            .flat_map(|reading| options.iter().map(move |letter| format!("{}{}", reading, letter)))
+            .take(MAX_UNLEET)
+            .collect();
+    }
+    readings
+}
+
+/// How many ways the substitutions in `token` could have been made in `word`
+fn leet_variations(token: &[char], word: &str) -> f64 {
+    let word: Vec<char> = word.chars().collect();
+    let mut pairs: Vec<(char, char)> = token.iter().zip(&word).filter(|(a, b)| a != b).map(|(a, b)| (*a, *b)).collect();
+    pairs.sort_unstable();
+    pairs.dedup();
+    pairs.iter().map(|&(leet, letter)| {
+        let changed = token.iter().filter(|&&c| c == leet).count();
+        let unchanged = token.iter().filter(|&&c| c == letter).count();
+        variations(changed, unchanged)
+    }).product()
+}
+
+/// Runs of three or more adjacent keys
+fn spatial_matches(chars: &[char]) -> Vec<Match> {
+    let mut matches = Vec::new();
+    let mut start = 0;
+    while start + 2 < chars.len() {
+        let (mut end, mut turns, mut direction) = (start + 1, 0, None);
+        while end < chars.len() {
+            let Some(next) = Layout::Qwerty.step(chars[end - 1], chars[end]) else { break };
+            if direction != Some(next) {
+                turns += 1;
+                direction = Some(next);
+            }
+            end += 1;
+        }
+        if end - start < 3 {
+            start += 1;
+            continue;
+        }
+        let len = end - start;
+        let mut guesses: f64 = (2..=len)
+            .map(|i| (1..=turns.min(i - 1)).map(|j| choose(i - 1, j - 1) * KEYBOARD_KEYS * KEYBOARD_DEGREE.powi(j as i32)).sum::<f64>())
+            .sum();
+        let shifted = chars[start..end].iter().filter(|&&c| Layout::Qwerty.key(c).is_some_and(|(_, _, shift)| shift)).count();
+        if shifted > 0 {
+            guesses *= variations(shifted, len - shifted);
+        }
+        matches.push(Match {
+            token: chars[start..end].iter().collect(),
+            start,
+            end,
+            pattern: Pattern::Spatial { turns, shifted: shifted > 0 },
+            guesses,
+        });
+        start = end - 1;
+    }
+    matches
+}
+
+/// Runs of three or more letters or digits stepping by one, either way
+fn sequence_matches(chars: &[char]) -> Vec<Match> {
+    let class = |c: char| match c {
+        'a'..='z' => Some(0),
+        'A'..='Z' => Some(1),
+        '0'..='9' => Some(2),
+        _ => None,
+    };
+    let mut matches = Vec::new();
+    let mut start = 0;
+    while start + 2 < chars.len() {
+        let delta = chars[start + 1] as i32 - chars[start] as i32;
+        let same = |a: char, b: char| class(a).is_some() && class(a) == class(b) && b as i32 - a as i32 == delta;
+        let mut end = start + 1;
+        while delta.abs() == 1 && end < chars.len() && same(chars[end - 1], chars[end]) {
+            end += 1;
+        }
+        if end - start < 3 {
+            start += 1;
+            continue;
+        }
+        let first = chars[start];
+        let base = if "aAzZ019".contains(first) { 4.0 } else if first.is_ascii_digit() { 10.0 } else { 26.0 };
+        let ascending = delta > 0;
+        matches.push(Match {
+            token: chars[start..end].iter().collect(),
+            start,
+            end,
+            pattern: Pattern::Sequence { ascending },
+            guesses: base * (end - start) as f64 * if ascending { 1.0 } else { 2.0 },
+        });
+        start = end - 1;
+    }
+    matches
+}
+
+/// The year now, which dates are priced against
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+fn reference_year() -> i32 {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    1970 + (secs / 31_556_952) as i32
+}
+
+/// The browser has no system clock to ask (SystemTime panics there)
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+fn reference_year() -> i32 {
+    crate::engine::personal::CURRENT_YEAR as i32
+}
+
+/// Years from now, at least 20, so recent years aren't priced near zero
+fn year_distance(year: i32) -> f64 {
+    f64::from((year - reference_year()).abs().max(20))
+}
+
+/// A full year from two or four digits: 51–99 are 19xx, 00–50 are 20xx
+fn year(digits: &str) -> Option<i32> {
+    let value: i32 = digits.parse().ok()?;
+    match digits.len() {
+        2 => Some(if value > 50 { 1900 + value } else { 2000 + value }),
+        4 => (1900..=2099).contains(&value).then_some(value),
+        _ => None,
+    }
+}
+
+/// The year of `parts` read as day, month and year in any usual order
+fn date_year(parts: [&str; 3]) -> Option<i32> {
+    let number = |part: &str| part.parse::<u32>().ok().filter(|_| part.len() <= 2);
+    let valid = |a: &str, b: &str| {
+        let (Some(a), Some(b)) = (number(a), number(b)) else { return false };
+        let day_month = |day: u32, month: u32| (1..=31).contains(&day) && (1..=12).contains(&month);
+        day_month(a, b) || day_month(b, a)
+    };
+    let [a, b, c] = parts;
+    let last = year(c).filter(|_| valid(a, b));
+    let first = year(a).filter(|_| valid(b, c));
+    match (first, last) {
+        (Some(x), Some(y)) => Some(if year_distance(x) <= year_distance(y) { x } else { y }),
+        (x, y) => x.or(y),
+    }
+}
+
+/// Digits in each part of an undelimited date: 1190, 010190, 19900101…
+const SPLITS: &[[usize; 3]] = &[
+    [1, 2, 2], [2, 1, 2], [2, 2, 2], [1, 1, 4], [4, 1, 1], [1, 2, 4],
+    [2, 1, 4], [4, 1, 2], [4, 2, 1], [2, 2, 4], [4, 2, 2],
+];
+
+/// Dates like 310190, 1.1.1990 or 1990-01-31, and years like 1990
+fn date_matches(chars: &[char]) -> Vec<Match> {
+    let mut matches = Vec::new();
+    for start in 0..chars.len() {
+        for end in start + 4..=chars.len().min(start + 10) {
+            let token: String = chars[start..end].iter().collect();
+            let found = if token.chars().all(|c| c.is_ascii_digit()) {
+                match token.len() {
+                    4 => year(&token).map(|year| Pattern::Year { year }),
+                    len => SPLITS.iter()
+                        .filter(|split| split.iter().sum::<usize>() == len)
+                        .filter_map(|&[a, b, _]| date_year([&token[..a], &token[a..a + b], &token[a + b..]]))
+                        .min_by(|a, b| year_distance(*a).total_cmp(&year_distance(*b)))
+                        .map(|year| Pattern::Date { year, separator: false }),
+                }
+            } else {
+                let separator = token.chars().find(|c| !c.is_ascii_digit()).filter(|c| " -/._\\".contains(*c));
+                separator.and_then(|sep| {
+                    let parts: Vec<&str> = token.split(sep).collect();
+                    let digits = parts.iter().all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()));
+                    match parts.as_slice() {
+                        [a, b, c] if digits => date_year([a, b, c]),
+                        _ => None,
+                    }
+                }).map(|year| Pattern::Date { year, separator: true })
+            };
+            if let Some(pattern) = found {
+                let guesses = match &pattern {
+                    Pattern::Year { year } => year_distance(*year),
+                    Pattern::Date { year, separator } => 365.0 * year_distance(*year) * if *separator { 4.0 } else { 1.0 },
+                    _ => unreachable!(),
+                };
+                matches.push(Match { token, start, end, pattern, guesses });
+            }
+        }
+    }
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patterns(strength: &Strength) -> Vec<&'static str> {
+        strength.matches.iter().map(|m| match m.pattern {
+            Pattern::Dictionary { .. } => "dictionary",
+            Pattern::Spatial { .. } => "spatial",
+            Pattern::Repeat { .. } => "repeat",
+            Pattern::Sequence { .. } => "sequence",
+            Pattern::Date { .. } => "date",
+            Pattern::Year { .. } => "year",
+            Pattern::BruteForce => "brute_force",
+        }).collect()
+    }
+
+    #[test]
+    fn test_common_passwords_score_zero() {
+        let estimator = Estimator::new();
+        for password in ["password", "123456", "qwerty", "P@ssw0rd"] {
+            let strength = estimator.estimate(password);
+            assert_eq!(strength.score, 0, "{}", password);
+            assert_eq!(patterns(&strength), ["dictionary"], "{}", password);
+        }
+        assert!(estimator.estimate("").matches.is_empty());
+    }
+
+    #[test]
+    fn test_patterns() {
+        let estimator = Estimator::new();
+        assert_eq!(patterns(&estimator.estimate("zxcvfdsa")), ["spatial"]);
+        assert_eq!(patterns(&estimator.estimate("abcdefgh")), ["sequence"]);
+        assert_eq!(patterns(&estimator.estimate("zzzzzzzz")), ["repeat"]);
+        assert_eq!(patterns(&estimator.estimate("14.07.1989")), ["date"]);
+        assert_eq!(patterns(&estimator.estimate("walnut1987")), ["dictionary", "year"]);
+        let reversed = estimator.estimate("tunlaw");
+        assert!(matches!(reversed.matches[0].pattern, Pattern::Dictionary { reversed: true, .. }));
+    }
+
+    #[test]
+    fn test_longer_and_stranger_is_stronger() {
+        let estimator = Estimator::new();
+        let weak = estimator.estimate("walnut");
+        let strong = estimator.estimate("walnut-Osprey-quilt-88-tundra");
+        assert!(strong.guesses_log10 > weak.guesses_log10 + 8.0);
+        assert_eq!(strong.score, 4);
+        assert!(estimator.estimate("k#9Vq!2xLp$7").score >= 3);
+        assert!(estimator.estimate(&"x7".repeat(200)).guesses.is_finite());
+    }
+
+    #[test]
+    fn test_custom_dictionary() {
+        let estimator = Estimator::new().with_dictionary("profile", ["Rex".to_string(), "Smith".to_string()]);
+        let strength = estimator.estimate("smithrex");
+        assert_eq!(patterns(&strength), ["dictionary", "dictionary"]);
+        assert!(strength.feedback().iter().any(|note| note.contains("profile")));
+    }
+}
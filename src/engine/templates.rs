@@ -0,0 +1,77 @@
+use crate::engine::personal::Profile;
+
+/// Starting points for common targets. Values are placeholders showing the
+/// expected shape of each field; replace them before generating.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Template {
+    /// Employee of an organisation: company, work email, seasons, policy length
+    Corporate,
+    /// Gaming handle, clan tags, leetspeak-friendly keywords
+    Gamer,
+    /// Household: partner, kids, pets, parents and anniversaries
+    Family,
+}
+
+fn strings(values: &[&str]) -> Vec<String> {
+    values.iter().map(|s| s.to_string()).collect()
+}
+
+pub fn build(template: Template) -> Profile {
+    match template {
+        Template::Corporate => Profile {
+            first_names: strings(&["Jane"]),
+            last_names: strings(&["Doe"]),
+            company: strings(&["Acme Corporation"]),
+            city: strings(&["Springfield"]),
+            usernames: strings(&["jdoe"]),
+            email: strings(&["jane.doe@acme.com"]),
+            dates: strings(&["1985", "0314"]),
+            keywords: strings(&["welcome", "summer", "winter", "spring", "autumn", "password"]),
+            numbers: strings(&["2026", "01"]),
+            // Typical corporate policy: 8+ characters
+            min_length: Some(8),
+            ..Default::default()
+        },
+        Template::Gamer => Profile {
+            first_names: strings(&["Alex"]),
+            usernames: strings(&["xXShadowXx", "shadow_sniper"]),
+            keywords: strings(&["shadow", "sniper", "dragon", "clan"]),
+            hobbies: strings(&["gaming", "minecraft"]),
+            music: strings(&["eminem"]),
+            sports: strings(&["esports"]),
+            dates: strings(&["2005"]),
+            numbers: strings(&["1337", "69", "420"]),
+            min_length: Some(6),
+            ..Default::default()
+        },
+        Template::Family => Profile {
+            first_names: strings(&["John"]),
+            last_names: strings(&["Smith"]),
+            partners: strings(&["Mary"]),
+            kids: strings(&["Emma", "Liam"]),
+            pets: strings(&["Buddy"]),
+            parents: strings(&["Robert", "Linda"]),
+            maiden_name: strings(&["Johnson"]),
+            city: strings(&["Portland"]),
+            dates: strings(&["1978", "0612", "2010", "2014"]),
+            min_length: Some(6),
+            ..Default::default()
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::validate::validate_profile;
+
+    #[test]
+    fn test_templates_validate_cleanly() {
+        for template in [Template::Corporate, Template::Gamer, Template::Family] {
+            let profile = build(template);
+            assert!(!profile.is_empty());
+            let json = serde_json::to_string_pretty(&profile).unwrap();
+            assert!(validate_profile(&json).unwrap().is_empty(), "{:?}", template);
+        }
+    }
+}
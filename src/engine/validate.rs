@@ -0,0 +1,192 @@
+use crate::engine::personal::Profile;
+use crate::invalid;
+use anyhow::Result;
+use serde_json::Value;
+use std::fmt;
+
+// ═══════════════════════════════════════════════════════════════
+// DIAGNOSTICS
+// ═══════════════════════════════════════════════════════════════
+
+/// A suspicious spot in a profile file. Serde accepts these silently, so
+/// they're reported separately rather than failing the load.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    /// 1-based line in the source JSON, when it could be located
+    pub line: Option<usize>,
+    pub field: String,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {}: {}: {}", line, self.field, self.message),
+            None => write!(f, "{}: {}", self.field, self.message),
+        }
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════
+// CHECKS
+// ═══════════════════════════════════════════════════════════════
+
+/// Lint profile JSON: unknown keys (with a "did you mean"), an empty
+/// profile, non-numeric dates/numbers, malformed emails and inverted
+/// length bounds. Syntax and type errors are returned as `Err`.
+pub fn validate_profile(text: &str) -> Result<Vec<Diagnostic>> {
+    let value: Value = serde_json::from_str(text)?;
+    let object = value.as_object()
+        .ok_or_else(|| invalid!("profile must be a JSON object"))?;
+    let profile: Profile = serde_json::from_value(value.clone())?;
+
+    let known = known_keys();
+    let mut out = Vec::new();
+    let mut push = |field: &str, line: Option<usize>, message: String| {
+        out.push(Diagnostic { line, field: field.to_string(), message });
+    };
+
+    for key in object.keys() {
+        if known.iter().any(|k| k == key) {
+            continue;
+        }
+        let message = match closest(key, &known) {
+            Some(suggestion) => format!("unknown field, ignored (did you mean \"{}\"?)", suggestion),
+            None => "unknown field, ignored".to_string(),
+        };
+        push(key, line_of(text, key, None), message);
+    }
+
+    if profile.is_empty() {
+        push("profile", None, "no fields filled in; nothing will be generated".to_string());
+    }
+
+    for date in &profile.dates {
+        if !date.chars().all(|c| c.is_ascii_digit()) {
+            push("dates", line_of(text, "dates", Some(date)),
+                format!("\"{}\" isn't numeric; use YYYY, MMDD or MMDDYYYY", date));
+        } else if ![2, 4, 6, 8].contains(&date.len()) {
+            push("dates", line_of(text, "dates", Some(date)),
+                format!("\"{}\" has {} digits; expected YYYY, MMDD or MMDDYYYY", date, date.len()));
+        }
+    }
+
+    for number in &profile.numbers {
+        if !number.chars().all(|c| c.is_ascii_digit() || " +-().".contains(c)) {
+            push("numbers", line_of(text, "numbers", Some(number)),
+                format!("\"{}\" contains letters; words belong in keywords", number));
+        }
+    }
+
+    for email in &profile.email {
+        if !email.contains('@') {
+            push("email", line_of(text, "email", Some(email)),
+                format!("\"{}\" is not an email address", email));
+        }
+    }
+
+    for (field, weight) in &profile.field_weights {
+        if !profile.fields().iter().any(|(name, _)| name == field) {
+            push("field_weights", line_of(text, "field_weights", Some(field)),
+                format!("\"{}\" is not a profile field", field));
+        } else if *weight < 0.0 {
+            push("field_weights", line_of(text, "field_weights", Some(field)),
+                format!("negative weight {} for \"{}\"; use 0 to drop a field", weight, field));
+        }
+    }
+
+    if let (Some(min), Some(max)) = (profile.min_length, profile.max_length) {
+        if min > max {
+            push("min_length", line_of(text, "min_length", None),
+                format!("min_length {} is greater than max_length {}; nothing will be generated", min, max));
+        }
+    }
+
+    Ok(out)
+}
+
+/// Every top-level key `Profile` understands
+fn known_keys() -> Vec<String> {
+    match serde_json::to_value(Profile::default()) {
+        Ok(Value::Object(map)) => map.keys().cloned().collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Known key within edit distance 3 of `key`, if any
+fn closest(key: &str, known: &[String]) -> Option<String> {
+    let key = key.to_lowercase().replace(['-', ' '], "_");
+    known.iter()
+        .map(|k| (edit_distance(&key, k), k))
+        .filter(|(d, _)| *d <= 3)
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, k)| k.clone())
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut row = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            row[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(row[j] + 1);
+        }
+        prev = row;
+    }
+    prev[b.len()]
+}
+
+/// Line of `"key"`, or of the string `"value"` inside that key's entry
+fn line_of(text: &str, key: &str, value: Option<&str>) -> Option<usize> {
+    let key_pos = text.find(&format!("\"{}\"", key))?;
+    let pos = match value {
+        Some(v) => {
+            let needle = serde_json::to_string(v).ok()?;
+            key_pos + text[key_pos..].find(&needle)?
+        }
+        None => key_pos,
+    };
+    Some(text[..pos].matches('\n').count() + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_key_suggestion() {
+        let text = "{\n  \"firstnames\": [\"John\"],\n  \"pets\": [\"Rex\"]\n}";
+        let diags = validate_profile(text).unwrap();
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].line, Some(2));
+        assert_eq!(diags[0].field, "firstnames");
+        assert!(diags[0].message.contains("first_names"));
+    }
+
+    #[test]
+    fn test_suspicious_values() {
+        let text = "{\n  \"dates\": [\n    \"1990\",\n    \"May 5\"\n  ],\n  \"email\": [\"john.doe\"]\n}";
+        let diags = validate_profile(text).unwrap();
+        assert_eq!(diags.len(), 2);
+        assert_eq!(diags[0].field, "dates");
+        assert_eq!(diags[0].line, Some(4));
+        assert_eq!(diags[1].field, "email");
+    }
+
+    #[test]
+    fn test_unknown_weighted_field() {
+        let text = "{\"pets\": [\"Rex\"], \"field_weights\": {\"pet\": 2.0, \"pets\": 3.0}}";
+        let diags = validate_profile(text).unwrap();
+        assert_eq!(diags.len(), 1);
+        assert!(diags[0].message.contains("\"pet\""));
+    }
+
+    #[test]
+    fn test_empty_and_clean() {
+        let diags = validate_profile("{}").unwrap();
+        assert_eq!(diags[0].field, "profile");
+        assert!(validate_profile("{\"first_names\": [\"John\"], \"dates\": [\"1990\"]}").unwrap().is_empty());
+        assert!(validate_profile("{\"first_names\": \"John\"}").is_err());
+    }
+}
@@ -0,0 +1,285 @@
+use serde::{Deserialize, Serialize};
+
+// ═══════════════════════════════════════════════════════════════
+// KEYBOARD WALKS
+// ═══════════════════════════════════════════════════════════════
+//
+// People type shapes: runs of neighbouring keys like qwerty, 1qaz or
+// zse4, sometimes held on shift (!QAZ). A walk is a path over adjacent
+// keys that never comes back to a key; each change of direction is a
+// turn. Layouts are their rows as printed, each offset by its stagger in
+// key widths, so adjacency is geometric: the next key along a row, or a
+// key on the row above or below within three quarters of a width.
+// Walks come out straightest first, then shortest first.
+
+/// Physical keyboard layouts
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Layout {
+    /// US/UK
+    #[default]
+    Qwerty,
+    /// French
+    Azerty,
+    /// German
+    Qwertz,
+}
+
+/// Rows top to bottom: unshifted keys, shifted keys, stagger in key widths
+type Rows = [(&'static str, &'static str, f64); 4];
+
+const QWERTY: Rows = [
+    ("`1234567890-=", "~!@#$%^&*()_+", 0.0),
+    ("qwertyuiop[]\\", "QWERTYUIOP{}|", 1.5),
+    ("asdfghjkl;'", "ASDFGHJKL:\"", 1.75),
+    ("zxcvbnm,./", "ZXCVBNM<>?", 2.25),
+];
+// The key left of 1 (²) has no shifted form, so the row starts one in
+const AZERTY: Rows = [
+    ("&é\"'(-è_çà)=", "1234567890°+", 1.0),
+    ("azertyuiop^$", "AZERTYUIOP¨£", 1.5),
+    ("qsdfghjklmù*", "QSDFGHJKLM%µ", 1.75),
+    ("<wxcvbn,;:!", ">WXCVBN?./§", 1.25),
+];
+const QWERTZ: Rows = [
+    ("^1234567890ß´", "°!\"§$%&/()=?`", 0.0),
+    ("qwertzuiopü+", "QWERTZUIOPÜ*", 1.5),
+    ("asdfghjklöä#", "ASDFGHJKLÖÄ'", 1.75),
+    ("<yxcvbnm,.-", ">YXCVBNM;:_", 1.25),
+];
+
+/// Way from one key to an adjacent one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Left,
+    Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+}
+
+pub const DIRECTIONS: [Direction; 6] = [
+    Direction::Left, Direction::Right, Direction::UpLeft, Direction::UpRight, Direction::DownLeft, Direction::DownRight,
+];
+
+impl Layout {
+    fn rows(self) -> &'static Rows {
+        match self {
+            Layout::Qwerty => &QWERTY,
+            Layout::Azerty => &AZERTY,
+            Layout::Qwertz => &QWERTZ,
+        }
+    }
+
+    /// Row and horizontal position of a key, and whether it needs shift
+    pub fn key(self, c: char) -> Option<(usize, f64, bool)> {
+        self.rows().iter().enumerate().find_map(|(row, (plain, shifted, offset))| {
+            let at = |keys: &str| keys.chars().position(|k| k == c);
+            at(plain).map(|x| (row, x as f64 + offset, false))
+                .or_else(|| at(shifted).map(|x| (row, x as f64 + offset, true)))
+        })
+    }
+
+    /// The direction from one key to an adjacent one, or None if they aren't
+    pub fn step(self, from: char, to: char) -> Option<Direction> {
+        let ((row_a, x_a, _), (row_b, x_b, _)) = (self.key(from)?, self.key(to)?);
+        direction(row_a, x_a, row_b, x_b)
+    }
+}
+
+fn direction(row_a: usize, x_a: f64, row_b: usize, x_b: f64) -> Option<Direction> {
+    let dx = x_b - x_a;
+    let rightward = dx > 0.0;
+    match row_b as i32 - row_a as i32 {
+        0 if (dx.abs() - 1.0).abs() < 1e-9 => Some(if rightward { Direction::Right } else { Direction::Left }),
+        -1 if dx.abs() <= 0.75 => Some(if rightward { Direction::UpRight } else { Direction::UpLeft }),
+        1 if dx.abs() <= 0.75 => Some(if rightward { Direction::DownRight } else { Direction::DownLeft }),
+        _ => None,
+    }
+}
+
+/// Walks of `min_len..=max_len` keys with at most `max_turns` turns
+#[derive(Debug, Clone)]
+pub struct Walks {
+    /// Unshifted and shifted character of every key, row by row
+    keys: Vec<(char, char)>,
+    /// Adjacent keys reachable in an allowed direction
+    neighbours: Vec<Vec<(usize, Direction)>>,
+    pub min_len: usize,
+    pub max_len: usize,
+    pub max_turns: usize,
+    /// Also emit each walk fully shifted and with its first key shifted
+    pub shifted: bool,
+}
+
+impl Walks {
+    pub fn new(layout: Layout, min_len: usize, max_len: usize, max_turns: usize, directions: &[Direction], shifted: bool) -> Self {
+        let mut keys = Vec::new();
+        let mut positions = Vec::new();
+        for (row, (plain, shift, offset)) in layout.rows().iter().enumerate() {
+            for (x, pair) in plain.chars().zip(shift.chars()).enumerate() {
+                keys.push(pair);
+                positions.push((row, x as f64 + offset));
+            }
+        }
+        let neighbours = positions.iter()
+            .map(|&(row_a, x_a)| positions.iter().enumerate()
+                .filter_map(|(to, &(row_b, x_b))| direction(row_a, x_a, row_b, x_b).map(|dir| (to, dir)))
+                .filter(|(_, dir)| directions.contains(dir))
+                .collect())
+            .collect();
+        Self { keys, neighbours, min_len: min_len.max(1), max_len, max_turns, shifted }
+    }
+
+    /// Every walk, shifted forms included, best first. Return true to stop.
+    pub fn for_each(&self, mut emit: impl FnMut(&str) -> bool) {
+        let mut text = String::new();
+        self.paths(|path| {
+            let forms: &[(bool, bool)] = if self.shifted { &[(false, false), (true, true), (true, false)] } else { &[(false, false)] };
+            forms.iter().any(|&(first, rest)| {
+                // A one-key walk shifted is the same as its first key shifted
+                if first && !rest && path.len() == 1 {
+                    return false;
+                }
+                text.clear();
+                for (i, &key) in path.iter().enumerate() {
+                    let (plain, shift) = self.keys[key];
+                    text.push(if (i == 0 && first) || (i > 0 && rest) { shift } else { plain });
+                }
+                emit(&text)
+            })
+        });
+    }
+
+    /// Number of candidates `for_each` emits
+    pub fn keyspace(&self) -> u64 {
+        let mut count = 0;
+        self.for_each(|_| { count += 1; false });
+        count
+    }
+
+    /// Key indices of every walk, by turns then length. Return true to stop.
+    fn paths(&self, mut visit: impl FnMut(&[usize]) -> bool) {
+        let mut path = Vec::with_capacity(self.max_len);
+        for turns in 0..=self.max_turns {
+            for len in self.min_len..=self.max_len {
+                // Every turn needs a step before and after it
+                if turns > 0 && turns + 2 > len {
+                    continue;
+                }
+                for start in 0..self.keys.len() {
+                    path.clear();
+                    path.push(start);
+                    if self.extend(&mut path, None, turns, len, &mut visit) {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    fn extend(&self, path: &mut Vec<usize>, heading: Option<Direction>, turns: usize, len: usize, visit: &mut impl FnMut(&[usize]) -> bool) -> bool {
+        if path.len() == len {
+            return turns == 0 && visit(path);
+        }
+        let last = path[path.len() - 1];
+        for &(next, dir) in &self.neighbours[last] {
+            let turn = heading.is_some_and(|heading| heading != dir);
+            if (turn && turns == 0) || path.contains(&next) {
+                continue;
+            }
+            path.push(next);
+            let stop = self.extend(path, Some(dir), turns - usize::from(turn), len, visit);
+            path.pop();
+            if stop {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+/// The walks people actually use, as personal-engine suffixes: the first
+/// four to six letters of each letter row (qwerty, asdf, zxcvbn), the
+/// columns under the digits (1qaz, 2wsx) and the first two columns run
+/// together (1qaz2wsx, qazwsx). The digit row reads as digits, shifted
+/// or not, so AZERTY gives 1aqw rather than &aqw.
+pub fn common(layout: Layout) -> Vec<String> {
+    let mut walks = Vec::new();
+    for (plain, _, _) in &layout.rows()[1..] {
+        let letters: Vec<char> = plain.chars().skip_while(|c| !c.is_alphabetic()).collect();
+        for len in 4..=6.min(letters.len()) {
+            walks.push(letters[..len].iter().collect());
+        }
+    }
+
+    let down = Walks::new(layout, 4, 4, 0, &[Direction::DownRight], false);
+    let mut columns: Vec<String> = Vec::new();
+    down.paths(|path| {
+        let column: String = path.iter().map(|&key| match down.keys[key] {
+            (_, shift) if shift.is_ascii_digit() => shift,
+            (plain, _) => plain,
+        }).collect();
+        if column.starts_with(|c: char| c.is_ascii_digit()) && column.chars().all(|c| c.is_ascii_alphanumeric()) {
+            columns.push(column);
+        }
+        false
+    });
+    if let [first, second, ..] = columns.as_slice() {
+        walks.push(format!("{}{}", first, second));
+        walks.push(format!("{}{}", &first[1..], &second[1..]));
+    }
+    walks.extend(columns);
+    walks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn all(walks: &Walks) -> Vec<String> {
+        let mut out = Vec::new();
+        walks.for_each(|walk| { out.push(walk.to_string()); false });
+        out
+    }
+
+    #[test]
+    fn test_straight_walks() {
+        let walks = Walks::new(Layout::Qwerty, 4, 4, 0, &DIRECTIONS, false);
+        let out = all(&walks);
+        for walk in ["qwer", "rewq", "1qaz", "zaq1", "zse4", "4esz", "asdf", "7890"] {
+            assert!(out.contains(&walk.to_string()), "{}", walk);
+        }
+        assert!(!out.contains(&"qwsx".to_string()));
+        assert_eq!(walks.keyspace(), out.len() as u64);
+    }
+
+    #[test]
+    fn test_turns_and_shift() {
+        let walks = Walks::new(Layout::Qwerty, 3, 5, 1, &[Direction::Right, Direction::DownRight], true);
+        let out = all(&walks);
+        // Straight walks come first
+        let first_turn = out.iter().position(|w| w == "qwsx").unwrap();
+        assert!(out.iter().position(|w| w == "qwert").unwrap() < first_turn);
+        assert!(out.contains(&"!QAZ".to_string()) && out.contains(&"!qaz".to_string()));
+        assert!(!out.contains(&"trewq".to_string()) && !out.contains(&"qwsxc".to_string()));
+    }
+
+    #[test]
+    fn test_layouts_and_common() {
+        assert_eq!(Layout::Qwertz.step('t', 'z'), Some(Direction::Right));
+        assert_eq!(Layout::Azerty.step('a', 'q'), Some(Direction::DownRight));
+        assert_eq!(Layout::Qwerty.step('a', 'q'), Some(Direction::UpLeft));
+        assert_eq!(Layout::Qwerty.step('q', 'p'), None);
+
+        let qwerty = common(Layout::Qwerty);
+        for walk in ["qwerty", "asdf", "zxcvbn", "1qaz", "2wsx", "1qaz2wsx", "qazwsx"] {
+            assert!(qwerty.contains(&walk.to_string()), "{}", walk);
+        }
+        let azerty = common(Layout::Azerty);
+        for walk in ["azerty", "qsdf", "wxcvbn", "1aqw", "aqwzsx"] {
+            assert!(azerty.contains(&walk.to_string()), "{}", walk);
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use crate::cli::args::{GenerationLevel, JigsawArgs, MemCase, MemStyle, NumPosition, OutputFormat};
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use std::path::{Path, PathBuf};
+
+// ═══════════════════════════════════════════════════════════════
+// REPEATABLE RUNS
+// ═══════════════════════════════════════════════════════════════
+//
+// A finished wizard run as the flags that repeat it: a command line to
+// paste, and a --config file holding the same choices. Only options a
+// wizard sets are considered, and only those that differ from the
+// defaults are written, so the result reads like something typed by hand.
+
+/// One option's value as a config value; `None` leaves it out
+trait Choice {
+    fn value(&self) -> Option<toml::Value>;
+}
+
+impl Choice for bool {
+    // Switches can only be turned on from the command line
+    fn value(&self) -> Option<toml::Value> {
+        self.then_some(toml::Value::Boolean(true))
+    }
+}
+
+impl Choice for usize {
+    fn value(&self) -> Option<toml::Value> {
+        Some(toml::Value::Integer(*self as i64))
+    }
+}
+
+impl Choice for u32 {
+    fn value(&self) -> Option<toml::Value> {
+        Some(toml::Value::Integer(*self as i64))
+    }
+}
+
+impl Choice for String {
+    fn value(&self) -> Option<toml::Value> {
+        Some(toml::Value::String(self.clone()))
+    }
+}
+
+impl Choice for PathBuf {
+    fn value(&self) -> Option<toml::Value> {
+        Some(toml::Value::String(self.to_string_lossy().into_owned()))
+    }
+}
+
+impl<T: Choice> Choice for Option<T> {
+    fn value(&self) -> Option<toml::Value> {
+        self.as_ref().and_then(Choice::value)
+    }
+}
+
+macro_rules! value_enum_choice {
+    ($($ty:ty),*) => {$(
+        impl Choice for $ty {
+            fn value(&self) -> Option<toml::Value> {
+                self.to_possible_value().map(|v| toml::Value::String(v.get_name().to_string()))
+            }
+        }
+    )*};
+}
+
+value_enum_choice!(GenerationLevel, OutputFormat, MemStyle, MemCase, NumPosition);
+
+/// The options `args` sets away from their defaults, keyed by flag name
+pub fn choices(args: &JigsawArgs) -> toml::Table {
+    let defaults = JigsawArgs::default();
+    let mut table = toml::Table::new();
+    // Flattened groups are named by their path (filter.min_length), keyed by the last part
+    macro_rules! keep {
+        ($($($field:ident).+),* $(,)?) => {$(
+            if let Some(value) = args.$($field).+.value().filter(|value| Some(value) != defaults.$($field).+.value().as_ref()) {
+                let path = stringify!($($field).+);
+                table.insert(path.rsplit('.').next().unwrap().trim().replace('_', "-"), value);
+            }
+        )*};
+    }
+    keep!(
+        personal, profile, level, filter.min_length, filter.max_length, check,
+        memorable, words, mem_sep, mem_style, mem_case, no_number, num_pos, num_max,
+        no_special, special_pos, mem_count, mem_min_len, mem_max_len,
+        mask, train, order, markov, model, count, markov_min_len, markov_max_len,
+        threads, format, output,
+    );
+    table
+}
+
+/// The command line that repeats `args`
+pub fn command_line(args: &JigsawArgs) -> String {
+    let mut words = vec!["jigsaw".to_string()];
+    for (key, value) in choices(args) {
+        words.push(format!("--{}", key));
+        match value {
+            toml::Value::Boolean(_) => {}
+            toml::Value::String(text) => words.push(shell_quote(&text)),
+            other => words.push(other.to_string()),
+        }
+    }
+    words.join(" ")
+}
+
+/// Write `args` as a config file for `jigsaw --config`, with the
+/// equivalent command line as a comment on top
+pub fn save_config(args: &JigsawArgs, path: &Path) -> Result<()> {
+    let text = format!("# {}\n{}", command_line(args), toml::to_string(&choices(args))?);
+    std::fs::write(path, text).with_context(|| format!("can't write config {}", path.display()))
+}
+
+/// `text` as one POSIX shell word
+fn shell_quote(text: &str) -> String {
+    let plain = |c: char| c.is_ascii_alphanumeric() || "_-./:=@%+,".contains(c);
+    if !text.is_empty() && text.chars().all(plain) {
+        text.to_string()
+    } else {
+        format!("'{}'", text.replace('\'', "'\\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::config;
+    use std::ffi::OsString;
+
+    #[test]
+    fn test_choices_round_trip_through_config() {
+        let args = JigsawArgs {
+            memorable: true,
+            words: 4,
+            mem_sep: " ".to_string(),
+            mem_case: MemCase::Upper,
+            mem_number: false,
+            no_number: true,
+            output: Some(PathBuf::from("my list.txt")),
+            ..Default::default()
+        };
+        assert_eq!(
+            command_line(&args),
+            "jigsaw --mem-case upper --mem-sep ' ' --memorable --no-number --output 'my list.txt' --words 4"
+        );
+
+        let path = std::env::temp_dir().join(format!("jigsaw-export-{}.toml", std::process::id()));
+        save_config(&args, &path).unwrap();
+        let argv = vec![OsString::from("jigsaw"), OsString::from("--config"), path.clone().into()];
+        let parsed = config::parse_from(argv).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(parsed.memorable && parsed.no_number);
+        assert_eq!((parsed.words, parsed.mem_sep.as_str()), (4, " "));
+        assert!(matches!(parsed.mem_case, MemCase::Upper));
+        assert_eq!(parsed.output, Some(PathBuf::from("my list.txt")));
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("target.json"), "target.json");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+        assert_eq!(shell_quote(""), "''");
+    }
+}
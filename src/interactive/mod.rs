@@ -1,11 +1,53 @@
-use crate::cli::args::{JigsawArgs, GenerationLevel, OutputFormat, MemStyle, MemCase, NumPosition};
+use crate::cli::args::{JigsawArgs, FilterArgs, GenerationLevel, OutputFormat, MemStyle, MemCase, NumPosition};
+use crate::cli::session;
 use crate::engine::mask::Mask;
-use crate::engine::personal::Profile;
+use crate::engine::personal::{Dedup, Profile};
+use crate::engine::rules::RuleSet;
 use std::str::FromStr;
-use dialoguer::{theme::ColorfulTheme, Input, Select, Confirm};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-pub fn run_wizard() -> anyhow::Result<JigsawArgs> {
+mod export;
+pub mod prompt;
+pub mod tui;
+
+/// The profile's word lists, as the wizard and the TUI edit them
+macro_rules! fields {
+    ($($label:literal => $field:ident),* $(,)?) => {
+        const FIELDS: &[&str] = &[$($label),*];
+
+        fn field(profile: &Profile, index: usize) -> &[String] {
+            [$(&profile.$field),*][index]
+        }
+
+        fn field_mut(profile: &mut Profile, index: usize) -> &mut Vec<String> {
+            [$(&mut profile.$field),*].into_iter().nth(index).expect("field index")
+        }
+    };
+}
+
+fields! {
+    "First names" => first_names,
+    "Last names" => last_names,
+    "Usernames" => usernames,
+    "Partners" => partners,
+    "Kids" => kids,
+    "Pets" => pets,
+    "Parents" => parents,
+    "Maiden names" => maiden_name,
+    "Company" => company,
+    "School" => school,
+    "City" => city,
+    "Sports" => sports,
+    "Music" => music,
+    "Hobbies" => hobbies,
+    "Keywords" => keywords,
+    "Email" => email,
+    "Dates" => dates,
+    "Numbers" => numbers,
+}
+
+/// Run the wizard; `profiles_dir` is where the profile browser looks
+pub fn run_wizard(profiles_dir: &Path) -> anyhow::Result<JigsawArgs> {
     println!();
     println!("  ╔═══════════════════════════════════════════╗");
     println!("  ║     JIGSAW — Interactive Wizard            ║");
@@ -17,24 +59,68 @@ pub fn run_wizard() -> anyhow::Result<JigsawArgs> {
         "🎲 Memorable Password — Generate strong memorable passwords",
         "🔍 Check Password — Test if a password is in the wordlist",
         "🎭 Mask Attack — Brute-force with mask patterns",
+        "🧪 Rules Tester — Try rules on a sample word",
+        "🧠 Train Markov Model — Learn a model from a wordlist",
+        "🤖 Markov Generate — Generate candidates from a trained model",
         "📖 Load Existing Profile — Load and re-run a saved profile",
+        "✏️  Edit Profile — Add or remove entries in a saved profile",
         "❌ Quit",
     ];
+    println!("  Pick \"{}\", type \"<\" or press Esc to go back a step.\n", prompt::BACK);
+
+    loop {
+        let mode_selection = prompt::menu("Select Action", &modes, 0)?;
+
+        let result = match mode_selection {
+            0 => run_personal_wizard(),
+            1 => run_memorable_wizard(),
+            2 => run_check_wizard(),
+            3 => run_mask_wizard(),
+            4 => run_rules_wizard(),
+            5 => run_train_wizard(),
+            6 => run_markov_wizard(),
+            7 => run_load_profile_wizard(profiles_dir),
+            8 => run_edit_profile_wizard(profiles_dir),
+            _ => std::process::exit(0),
+        };
+        match result {
+            Err(e) if prompt::is_back(&e) => println!(),
+            Ok(args) => {
+                offer_export(&args)?;
+                return Ok(args);
+            }
+            result => return result,
+        }
+    }
+}
 
-    let mode_selection = Select::with_theme(&ColorfulTheme::default())
-        .with_prompt("Select Action")
-        .default(0)
-        .items(&modes)
-        .interact()?;
-
-    match mode_selection {
-        0 => run_personal_wizard(),
-        1 => run_memorable_wizard(),
-        2 => run_check_wizard(),
-        3 => run_mask_wizard(),
-        4 => run_load_profile_wizard(),
-        _ => std::process::exit(0),
+/// Show the command line that repeats this run, and offer to save it
+/// as a config file
+fn offer_export(args: &JigsawArgs) -> anyhow::Result<()> {
+    println!("\n  Same run from the command line:\n    {}\n", export::command_line(args));
+    let save = match prompt::confirm("Save these choices as a config file?", false) {
+        Err(e) if prompt::is_back(&e) => false,
+        save => save?,
+    };
+    if save {
+        let path: String = prompt::input("Config file")
+            .default("jigsaw-run.toml".into())
+            .interact_text()?;
+        export::save_config(args, Path::new(&path))?;
+        println!("  ✓ Saved; repeat with: jigsaw --config {}\n", path);
     }
+    Ok(())
+}
+
+fn ask_list(prompt: &str) -> anyhow::Result<Vec<String>> {
+    let input: String = prompt::input(format!("{} (comma separated)", prompt))
+        .allow_empty(true)
+        .interact_text()?;
+
+    Ok(input.split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect())
 }
 
 // ═══════════════════════════════════════════════════════════════
@@ -44,98 +130,171 @@ pub fn run_wizard() -> anyhow::Result<JigsawArgs> {
 fn run_personal_wizard() -> anyhow::Result<JigsawArgs> {
     println!("\n  ── Personal Attack Profile Builder ──\n");
 
+    // Scripted runs neither resume nor leave a draft behind
+    let draft = if prompt::is_scripted() { None } else { draft_path().ok() };
     let mut profile = Profile::new();
+    if let Some(saved) = draft.as_deref().and_then(load_draft) {
+        if prompt::confirm("Resume the unfinished profile from last time?", true)? {
+            profile = saved;
+        }
+    }
 
-    fn ask_list(prompt: &str) -> anyhow::Result<Vec<String>> {
-        let input: String = Input::with_theme(&ColorfulTheme::default())
-            .with_prompt(format!("{} (comma separated)", prompt))
-            .allow_empty(true)
-            .interact_text()?;
+    let mut level = GenerationLevel::Standard;
+    let mut format = OutputFormat::Plain;
+    let mut path = PathBuf::new();
+    let mut output_path = None;
+
+    // Each section is a step; going back from one re-asks the one before
+    let mut step = 0;
+    while step < PERSONAL_STEPS {
+        let result = match step {
+            7 => ask_generation_settings(&mut profile).map(|(l, f)| {
+                level = l;
+                format = f;
+            }),
+            8 => preview_profile(&mut profile, level),
+            9 => ask_save_and_output(&profile).map(|(p, o)| {
+                path = p;
+                output_path = o;
+            }),
+            _ => ask_profile_section(&mut profile, step),
+        };
+        match result {
+            Ok(()) => {
+                step += 1;
+                // Kept on disk as it grows, so Ctrl-C or a crash loses nothing
+                if let Some(draft) = &draft {
+                    save_draft(&profile, draft)?;
+                }
+            }
+            Err(e) if prompt::is_back(&e) && step > 0 => step -= 1,
+            Err(e) => return Err(e),
+        }
+    }
+    if let Some(draft) = &draft {
+        let _ = std::fs::remove_file(draft);
+    }
 
-        Ok(input.split(',')
-            .map(|s| s.trim().to_string())
-            .filter(|s| !s.is_empty())
-            .collect())
+    Ok(JigsawArgs {
+        output: output_path,
+        format,
+        personal: true,
+        profile: Some(path),
+        level,
+        filter: FilterArgs { min_length: profile.min_length, max_length: profile.max_length, ..Default::default() },
+        ..Default::default()
+    })
+}
+
+const PERSONAL_STEPS: usize = 10;
+
+/// Candidates shown before the full list is written
+const PREVIEW: usize = 50;
+
+/// Where the personal wizard keeps the profile it is building. It holds
+/// the target's details, so it lives in the user's own state directory.
+fn draft_path() -> anyhow::Result<PathBuf> {
+    Ok(session::state_dir()?.join("wizard-draft.json"))
+}
+
+/// The saved draft, unless something other than a plain file sits there
+fn load_draft(path: &Path) -> Option<Profile> {
+    let metadata = std::fs::symlink_metadata(path).ok()?;
+    if !metadata.file_type().is_file() {
+        return None;
     }
+    Profile::load(path).ok()
+}
 
+/// Replace the draft with a freshly created owner-only file, renamed into
+/// place so a symlink planted at either path is never written through
+fn save_draft(profile: &Profile, path: &Path) -> anyhow::Result<()> {
+    if let Some(dir) = path.parent() {
+        let mut builder = std::fs::DirBuilder::new();
+        builder.recursive(true);
+        #[cfg(unix)]
+        std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+        builder.create(dir)?;
+    }
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    let _ = std::fs::remove_file(&temp);
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let file = options.open(&temp)?;
+    serde_json::to_writer_pretty(file, profile)?;
+    std::fs::rename(&temp, path)?;
+    Ok(())
+}
+
+/// One section of the profile questions
+fn ask_profile_section(profile: &mut Profile, step: usize) -> anyhow::Result<()> {
     fn ask_category(category_name: &str, items_prompt: &str) -> anyhow::Result<Vec<String>> {
-         if Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt(format!("Add {}?", category_name))
-            .default(false)
-            .interact()?
-        {
+        if prompt::confirm(&format!("Add {}?", category_name), false)? {
             ask_list(items_prompt)
         } else {
             Ok(Vec::new())
         }
     }
 
-    // ── Identity ──
-    println!("  [ Identity ]");
-    profile.first_names = ask_list("Target's First Name(s)")?;
-    profile.last_names = ask_list("Target's Last Name(s)")?;
-    profile.usernames = ask_category("Usernames / Handles", "Usernames (e.g. jdoe99, xX_Slayer_Xx)")?;
-
-    // ── Family ──
-    println!("\n  [ Family & Relationships ]");
-    if Confirm::with_theme(&ColorfulTheme::default())
-        .with_prompt("Add Family info?")
-        .default(false)
-        .interact()?
-    {
-        profile.partners = ask_list("Partner/Spouse Name(s)")?;
-        profile.kids = ask_list("Children's Name(s)")?;
-        profile.pets = ask_list("Pet's Name(s)")?;
-        profile.parents = ask_list("Parent Name(s)")?;
-        profile.maiden_name = ask_list("Maiden Name(s)")?;
-    }
-
-    // ── Work & Education ──
-    println!("\n  [ Work & Education ]");
-    if Confirm::with_theme(&ColorfulTheme::default())
-        .with_prompt("Add Work/School info?")
-        .default(false)
-        .interact()?
-    {
-        profile.company = ask_list("Company / Organization")?;
-        profile.school = ask_list("School / University")?;
-    }
-
-    // ── Location ──
-    println!("\n  [ Location ]");
-    profile.city = ask_category("Location", "City / Town / Region")?;
-
-    // ── Interests ──
-    println!("\n  [ Interests & Favorites ]");
-    if Confirm::with_theme(&ColorfulTheme::default())
-        .with_prompt("Add Interests?")
-        .default(false)
-        .interact()?
-    {
-        profile.sports = ask_list("Sports Teams / Athletes")?;
-        profile.music = ask_list("Music Bands / Artists")?;
-        profile.hobbies = ask_list("Hobbies (Gaming, Cooking, etc.)")?;
-        profile.keywords = ask_list("Other Keywords (Car, Color, Movie, Brand)")?;
+    match step {
+        0 => {
+            println!("  [ Identity ]");
+            profile.first_names = ask_list("Target's First Name(s)")?;
+            profile.last_names = ask_list("Target's Last Name(s)")?;
+            profile.usernames = ask_category("Usernames / Handles", "Usernames (e.g. jdoe99, xX_Slayer_Xx)")?;
+        }
+        1 => {
+            println!("\n  [ Family & Relationships ]");
+            if prompt::confirm("Add Family info?", false)? {
+                profile.partners = ask_list("Partner/Spouse Name(s)")?;
+                profile.kids = ask_list("Children's Name(s)")?;
+                profile.pets = ask_list("Pet's Name(s)")?;
+                profile.parents = ask_list("Parent Name(s)")?;
+                profile.maiden_name = ask_list("Maiden Name(s)")?;
+            }
+        }
+        2 => {
+            println!("\n  [ Work & Education ]");
+            if prompt::confirm("Add Work/School info?", false)? {
+                profile.company = ask_list("Company / Organization")?;
+                profile.school = ask_list("School / University")?;
+            }
+        }
+        3 => {
+            println!("\n  [ Location ]");
+            profile.city = ask_category("Location", "City / Town / Region")?;
+        }
+        4 => {
+            println!("\n  [ Interests & Favorites ]");
+            if prompt::confirm("Add Interests?", false)? {
+                profile.sports = ask_list("Sports Teams / Athletes")?;
+                profile.music = ask_list("Music Bands / Artists")?;
+                profile.hobbies = ask_list("Hobbies (Gaming, Cooking, etc.)")?;
+                profile.keywords = ask_list("Other Keywords (Car, Color, Movie, Brand)")?;
+            }
+        }
+        5 => {
+            println!("\n  [ Online Presence ]");
+            profile.email = ask_category("Email Addresses", "Email(s)")?;
+        }
+        _ => {
+            println!("\n  [ Numbers & Dates ]");
+            profile.dates = ask_list("Important Dates (Years like 1990, MMDD like 0101)")?;
+            profile.numbers = ask_list("Important Numbers (Phone, Zip, Room #)")?;
+        }
     }
+    Ok(())
+}
 
-    // ── Online ──
-    println!("\n  [ Online Presence ]");
-    profile.email = ask_category("Email Addresses", "Email(s)")?;
-
-    // ── Numbers & Dates ──
-    println!("\n  [ Numbers & Dates ]");
-    profile.dates = ask_list("Important Dates (Years like 1990, MMDD like 0101)")?;
-    profile.numbers = ask_list("Important Numbers (Phone, Zip, Room #)")?;
-
-    // ── Generation Settings ──
+/// Level, length filter, typos and output format
+fn ask_generation_settings(profile: &mut Profile) -> anyhow::Result<(GenerationLevel, OutputFormat)> {
     println!("\n  [ Generation Settings ]");
 
     let levels = vec!["Quick (~10K)", "Standard (~100K)", "Deep (~500K+)", "Insane (~1M+)"];
-    let level_idx = Select::with_theme(&ColorfulTheme::default())
-        .with_prompt("Generation Level")
-        .default(1)
-        .items(&levels)
-        .interact()?;
+    let level_idx = prompt::select("Generation Level", &levels, 1)?;
 
     let level = match level_idx {
         0 => GenerationLevel::Quick,
@@ -145,40 +304,80 @@ fn run_personal_wizard() -> anyhow::Result<JigsawArgs> {
     };
 
     // Length Filter
-    let use_length_filter = Confirm::with_theme(&ColorfulTheme::default())
-        .with_prompt("Set password length filter?")
-        .default(false)
-        .interact()?;
+    let use_length_filter = prompt::confirm("Set password length filter?", false)?;
 
+    profile.min_length = None;
+    profile.max_length = None;
     if use_length_filter {
-        let min_val: usize = Input::with_theme(&ColorfulTheme::default())
-            .with_prompt("Minimum length (0 = none)")
+        let min_val: usize = prompt::input("Minimum length (0 = none)")
             .default(0)
             .interact_text()?;
-        let max_val: usize = Input::with_theme(&ColorfulTheme::default())
-            .with_prompt("Maximum length (0 = none)")
+        let max_val: usize = prompt::input("Maximum length (0 = none)")
             .default(0)
             .interact_text()?;
         if min_val > 0 { profile.min_length = Some(min_val); }
         if max_val > 0 { profile.max_length = Some(max_val); }
     }
 
+    profile.typos = prompt::confirm("Include keyboard typo variants (jphn, joohn)?", false)?;
+
     // Output Format
-    let format_options = vec!["Plain text (one per line)", "JSON"];
-    let format_idx = Select::with_theme(&ColorfulTheme::default())
-        .with_prompt("Output Format")
-        .default(0)
-        .items(&format_options)
-        .interact()?;
+    let format_options = vec!["Plain text (one per line)", "JSON", "JSON Lines (streamed)", "Parquet (for DuckDB/Spark)"];
+    let format_idx = prompt::select("Output Format", &format_options, 0)?;
 
     let format = match format_idx {
         1 => OutputFormat::Json,
+        2 => OutputFormat::Ndjson,
+        3 => OutputFormat::Parquet,
         _ => OutputFormat::Plain,
     };
 
+    Ok((level, format))
+}
+
+/// Show the first candidates and the estimated total, and let the fields
+/// be edited until it looks right; going back re-asks the settings
+fn preview_profile(profile: &mut Profile, level: GenerationLevel) -> anyhow::Result<()> {
+    loop {
+        let mut effective = profile.clone();
+        let weighted = !effective.field_weights.is_empty() || !effective.word_weights.is_empty();
+        if effective.word_limit.is_none() && weighted {
+            effective.word_limit = level.word_limit();
+        }
+        let mut preview = Vec::with_capacity(PREVIEW);
+        effective.for_each_unique(Dedup::Exact, |candidate| {
+            preview.push(candidate);
+            preview.len() >= PREVIEW
+        });
+
+        println!("\n  [ Preview ]");
+        for row in preview.chunks(5) {
+            let row: Vec<String> = row.iter().map(|candidate| format!("{:<16}", candidate)).collect();
+            println!("  {}", row.join(" ").trim_end());
+        }
+        println!("\n  ~{} candidates before dedup\n", effective.estimate().candidates);
+
+        let actions = vec!["Looks good — continue", "Edit a field", "Change level or settings"];
+        match prompt::select("Preview", &actions, 0)? {
+            0 => return Ok(()),
+            1 => {
+                let items: Vec<String> = FIELDS.iter().enumerate()
+                    .map(|(i, label)| format!("{:<13} ({})", label, field(profile, i).len()))
+                    .collect();
+                match prompt::select("Field to edit", &items, 0).and_then(|i| edit_field(profile, i)) {
+                    Err(e) if !prompt::is_back(&e) => return Err(e),
+                    _ => {}
+                }
+            }
+            _ => return Err(prompt::Back.into()),
+        }
+    }
+}
+
+/// Save the profile, then ask where the wordlist goes
+fn ask_save_and_output(profile: &Profile) -> anyhow::Result<(PathBuf, Option<PathBuf>)> {
     // Save Profile
-    let save_path: String = Input::with_theme(&ColorfulTheme::default())
-        .with_prompt("Save profile to")
+    let save_path: String = prompt::input("Save profile to")
         .default("target.json".into())
         .interact_text()?;
 
@@ -187,8 +386,7 @@ fn run_personal_wizard() -> anyhow::Result<JigsawArgs> {
     println!("  ✓ Profile saved to {:?}", path);
 
     // Output file
-    let output_file: String = Input::with_theme(&ColorfulTheme::default())
-        .with_prompt("Output wordlist file (empty = stdout)")
+    let output_file: String = prompt::input("Output wordlist file (empty = stdout)")
         .allow_empty(true)
         .interact_text()?;
 
@@ -198,25 +396,7 @@ fn run_personal_wizard() -> anyhow::Result<JigsawArgs> {
         Some(PathBuf::from(output_file))
     };
 
-    Ok(JigsawArgs {
-        mask: None, rules: None, threads: None,
-        output: output_path,
-        format,
-        interactive: false,
-        train: None, model: None, markov: false, count: 0,
-        personal: true,
-        profile: Some(path),
-        level,
-        min_length: profile.min_length,
-        max_length: profile.max_length,
-        memorable: false,
-        words: 3, mem_sep: String::new(), mem_style: MemStyle::Classic,
-        mem_case: MemCase::Title, mem_number: true, no_number: false,
-        num_pos: NumPosition::End, num_max: 99,
-        mem_special: true, no_special: false, special_pos: NumPosition::End,
-        mem_count: 1, mem_min_len: 12, mem_max_len: 32,
-        check: None, command: None,
-    })
+    Ok((path, output_path))
 }
 
 // ═══════════════════════════════════════════════════════════════
@@ -230,14 +410,10 @@ fn run_memorable_wizard() -> anyhow::Result<JigsawArgs> {
     let style_options = vec![
         "Classic (Adjective-Noun-Verb)",
         "Passphrase (random words)",
-        "Story (Subject-Verb-Object)",
+        "Story (TheBraveTigerRunsQuickly)",
         "Alliterative (same letter)",
     ];
-    let style_idx = Select::with_theme(&ColorfulTheme::default())
-        .with_prompt("Password Style")
-        .default(0)
-        .items(&style_options)
-        .interact()?;
+    let style_idx = prompt::select("Password Style", &style_options, 0)?;
 
     let mem_style = match style_idx {
         0 => MemStyle::Classic,
@@ -247,8 +423,7 @@ fn run_memorable_wizard() -> anyhow::Result<JigsawArgs> {
     };
 
     // Word Count
-    let words: usize = Input::with_theme(&ColorfulTheme::default())
-        .with_prompt("Number of words")
+    let words: usize = prompt::input("Number of words")
         .default(3)
         .validate_with(|v: &usize| {
             if *v >= 2 && *v <= 8 { Ok(()) } else { Err("Must be 2-8") }
@@ -257,11 +432,7 @@ fn run_memorable_wizard() -> anyhow::Result<JigsawArgs> {
 
     // Separator
     let sep_options = vec!["None (CamelCase)", "Dash (-)", "Underscore (_)", "Dot (.)", "Space ( )", "Custom"];
-    let sep_idx = Select::with_theme(&ColorfulTheme::default())
-        .with_prompt("Word Separator")
-        .default(0)
-        .items(&sep_options)
-        .interact()?;
+    let sep_idx = prompt::select("Word Separator", &sep_options, 0)?;
 
     let mem_sep = match sep_idx {
         0 => String::new(),
@@ -270,8 +441,7 @@ fn run_memorable_wizard() -> anyhow::Result<JigsawArgs> {
         3 => ".".to_string(),
         4 => " ".to_string(),
         _ => {
-            let custom: String = Input::with_theme(&ColorfulTheme::default())
-                .with_prompt("Custom separator")
+            let custom: String = prompt::input("Custom separator")
                 .interact_text()?;
             custom
         }
@@ -279,11 +449,7 @@ fn run_memorable_wizard() -> anyhow::Result<JigsawArgs> {
 
     // Case Style
     let case_options = vec!["Title Case", "lowercase", "UPPERCASE", "rAnDoM", "AlTeRnAtInG"];
-    let case_idx = Select::with_theme(&ColorfulTheme::default())
-        .with_prompt("Case Style")
-        .default(0)
-        .items(&case_options)
-        .interact()?;
+    let case_idx = prompt::select("Case Style", &case_options, 0)?;
 
     let mem_case = match case_idx {
         0 => MemCase::Title,
@@ -294,20 +460,13 @@ fn run_memorable_wizard() -> anyhow::Result<JigsawArgs> {
     };
 
     // Number
-    let mem_number = Confirm::with_theme(&ColorfulTheme::default())
-        .with_prompt("Include a number?")
-        .default(true)
-        .interact()?;
+    let mem_number = prompt::confirm("Include a number?", true)?;
 
     let mut num_pos = NumPosition::End;
     let mut num_max: u32 = 99;
     if mem_number {
         let pos_options = vec!["End", "Start", "Between words"];
-        let pos_idx = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("Number position")
-            .default(0)
-            .items(&pos_options)
-            .interact()?;
+        let pos_idx = prompt::select("Number position", &pos_options, 0)?;
         num_pos = match pos_idx {
             1 => NumPosition::Start,
             2 => NumPosition::Between,
@@ -315,11 +474,7 @@ fn run_memorable_wizard() -> anyhow::Result<JigsawArgs> {
         };
 
         let max_options = vec!["0-9", "00-99", "000-999", "0000-9999"];
-        let max_idx = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("Number range")
-            .default(1)
-            .items(&max_options)
-            .interact()?;
+        let max_idx = prompt::select("Number range", &max_options, 1)?;
         num_max = match max_idx {
             0 => 9,
             1 => 99,
@@ -329,19 +484,12 @@ fn run_memorable_wizard() -> anyhow::Result<JigsawArgs> {
     }
 
     // Special
-    let mem_special = Confirm::with_theme(&ColorfulTheme::default())
-        .with_prompt("Include a special character?")
-        .default(true)
-        .interact()?;
+    let mem_special = prompt::confirm("Include a special character?", true)?;
 
     let mut special_pos = NumPosition::End;
     if mem_special {
         let pos_options = vec!["End", "Start", "Between words"];
-        let pos_idx = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("Special char position")
-            .default(0)
-            .items(&pos_options)
-            .interact()?;
+        let pos_idx = prompt::select("Special char position", &pos_options, 0)?;
         special_pos = match pos_idx {
             1 => NumPosition::Start,
             2 => NumPosition::Between,
@@ -350,38 +498,27 @@ fn run_memorable_wizard() -> anyhow::Result<JigsawArgs> {
     }
 
     // Count
-    let mem_count: usize = Input::with_theme(&ColorfulTheme::default())
-        .with_prompt("How many passwords to generate?")
+    let mem_count: usize = prompt::input("How many passwords to generate?")
         .default(5)
         .interact_text()?;
 
     // Length
-    let mem_min_len: usize = Input::with_theme(&ColorfulTheme::default())
-        .with_prompt("Minimum password length")
+    let mem_min_len: usize = prompt::input("Minimum password length")
         .default(12)
         .interact_text()?;
 
-    let mem_max_len: usize = Input::with_theme(&ColorfulTheme::default())
-        .with_prompt("Maximum password length")
+    let mem_max_len: usize = prompt::input("Maximum password length")
         .default(32)
         .interact_text()?;
 
     Ok(JigsawArgs {
-        mask: None, rules: None, threads: None,
-        output: None,
-        format: OutputFormat::Plain,
-        interactive: false,
-        train: None, model: None, markov: false, count: 0,
-        personal: false, profile: None,
-        level: GenerationLevel::Standard,
-        min_length: None, max_length: None,
         memorable: true,
         words, mem_sep, mem_style, mem_case,
         mem_number, no_number: !mem_number,
         num_pos, num_max,
         mem_special, no_special: !mem_special,
         special_pos, mem_count, mem_min_len, mem_max_len,
-        check: None, command: None,
+        ..Default::default()
     })
 }
 
@@ -392,31 +529,18 @@ fn run_memorable_wizard() -> anyhow::Result<JigsawArgs> {
 fn run_check_wizard() -> anyhow::Result<JigsawArgs> {
     println!("\n  ── Password Checker ──\n");
 
-    let profile_path: String = Input::with_theme(&ColorfulTheme::default())
-        .with_prompt("Path to Profile JSON")
+    let profile_path: String = prompt::input("Path to Profile JSON")
         .default("target.json".into())
         .interact_text()?;
 
-    let password: String = Input::with_theme(&ColorfulTheme::default())
-        .with_prompt("Password to Check")
+    let password: String = prompt::input("Password to Check")
         .interact_text()?;
 
     Ok(JigsawArgs {
-        mask: None, rules: None, threads: None,
-        output: None, format: OutputFormat::Plain,
-        interactive: false,
-        train: None, model: None, markov: false, count: 0,
         personal: true,
         profile: Some(PathBuf::from(profile_path)),
-        level: GenerationLevel::Standard,
-        min_length: None, max_length: None,
-        memorable: false,
-        words: 3, mem_sep: String::new(), mem_style: MemStyle::Classic,
-        mem_case: MemCase::Title, mem_number: true, no_number: false,
-        num_pos: NumPosition::End, num_max: 99,
-        mem_special: true, no_special: false, special_pos: NumPosition::End,
-        mem_count: 1, mem_min_len: 12, mem_max_len: 32,
-        check: Some(password), command: None,
+        check: Some(password),
+        ..Default::default()
     })
 }
 
@@ -426,17 +550,10 @@ fn run_check_wizard() -> anyhow::Result<JigsawArgs> {
 
 fn run_mask_wizard() -> anyhow::Result<JigsawArgs> {
     println!("\n  ── Mask Attack ──\n");
-    println!("  Patterns: ?l=lower ?u=upper ?d=digit ?s=special\n");
 
-    let mask_input: String = Input::with_theme(&ColorfulTheme::default())
-        .with_prompt("Enter Mask Pattern (e.g. ?u?l?l?d)")
-        .validate_with(|input: &String| -> Result<(), &str> {
-            if Mask::from_str(input).is_ok() { Ok(()) } else { Err("Invalid pattern") }
-        })
-        .interact_text()?;
+    let mask_input = build_mask()?;
 
-    let output_file: String = Input::with_theme(&ColorfulTheme::default())
-        .with_prompt("Output file (empty = stdout)")
+    let output_file: String = prompt::input("Output file (empty = stdout)")
         .allow_empty(true)
         .interact_text()?;
 
@@ -446,13 +563,8 @@ fn run_mask_wizard() -> anyhow::Result<JigsawArgs> {
         Some(PathBuf::from(output_file))
     };
 
-    let threads = if Confirm::with_theme(&ColorfulTheme::default())
-        .with_prompt("Configure threads?")
-        .default(false)
-        .interact()?
-    {
-        Some(Input::with_theme(&ColorfulTheme::default())
-            .with_prompt("Number of threads")
+    let threads = if prompt::confirm("Configure threads?", false)? {
+        Some(prompt::input("Number of threads")
             .default(4)
             .interact_text()?)
     } else {
@@ -460,36 +572,228 @@ fn run_mask_wizard() -> anyhow::Result<JigsawArgs> {
     };
 
     Ok(JigsawArgs {
-        mask: Some(mask_input), rules: None, threads,
-        output: output_path, format: OutputFormat::Plain,
-        interactive: false,
-        train: None, model: None, markov: false, count: 10000,
-        personal: false, profile: None,
-        level: GenerationLevel::Standard,
-        min_length: None, max_length: None,
-        memorable: false,
-        words: 3, mem_sep: String::new(), mem_style: MemStyle::Classic,
-        mem_case: MemCase::Title, mem_number: true, no_number: false,
-        num_pos: NumPosition::End, num_max: 99,
-        mem_special: true, no_special: false, special_pos: NumPosition::End,
-        mem_count: 1, mem_min_len: 12, mem_max_len: 32,
-        check: None, command: None,
+        mask: Some(mask_input), threads,
+        output: output_path,
+        ..Default::default()
     })
 }
 
+/// Compose a mask token by token, showing the keyspace and a few random
+/// candidates after each step
+fn build_mask() -> anyhow::Result<String> {
+    use rand::RngExt;
+
+    let tokens = [
+        ("?l", "lowercase letter"),
+        ("?u", "uppercase letter"),
+        ("?d", "digit"),
+        ("?s", "special character"),
+    ];
+    // Each entry is one step, so a literal word comes off in one go
+    let mut parts: Vec<String> = Vec::new();
+    loop {
+        let mask_str = parts.concat();
+        let mask = Mask::from_str(&mask_str)?;
+        if parts.is_empty() {
+            println!("  Mask: (empty)");
+        } else {
+            let space = mask.search_space_size();
+            let mut rng = rand::rng();
+            let samples: Vec<String> = (0..5)
+                .filter_map(|_| mask.nth_candidate(rng.random_range(0..space)))
+                .map(|c| String::from_utf8_lossy(&c).into_owned())
+                .collect();
+            println!("  Mask: {}   Keyspace: {}", mask_str, space);
+            println!("  Samples: {}", samples.join("  "));
+        }
+
+        let mut items: Vec<String> = tokens.iter()
+            .map(|(token, name)| {
+                let size = Mask::from_str(token).map_or(0, |m| m.search_space_size());
+                format!("+ {}  {} ({})", token, name, size)
+            })
+            .collect();
+        items.push("+ Literal text".to_string());
+        items.push("⌫ Remove last".to_string());
+        items.push("✓ Done".to_string());
+        items.push("✎ Type a raw mask instead".to_string());
+
+        let choice = prompt::select("Add to mask", &items, 0)?;
+        match choice {
+            i if i < tokens.len() => parts.push(tokens[i].0.to_string()),
+            4 => match prompt::input::<String>("Literal text").interact_text() {
+                Ok(text) => parts.push(text.replace('?', "??")),
+                Err(e) if prompt::is_back(&e) => {}
+                Err(e) => return Err(e),
+            },
+            5 => {
+                parts.pop();
+            }
+            6 if parts.is_empty() => println!("  The mask is empty; add a token first."),
+            6 => return Ok(mask_str),
+            _ => {
+                return prompt::input("Enter Mask Pattern (e.g. ?u?l?l?d)")
+                    .validate_with(|input: &String| -> Result<(), &str> {
+                        if Mask::from_str(input).is_ok() { Ok(()) } else { Err("Invalid pattern") }
+                    })
+                    .interact_text();
+            }
+        }
+        println!();
+    }
+}
+
 // ═══════════════════════════════════════════════════════════════
-// LOAD EXISTING PROFILE
+// RULES TESTER
 // ═══════════════════════════════════════════════════════════════
 
-fn run_load_profile_wizard() -> anyhow::Result<JigsawArgs> {
-    println!("\n  ── Load Existing Profile ──\n");
+fn run_rules_wizard() -> anyhow::Result<JigsawArgs> {
+    println!("\n  ── Rules Tester ──\n");
+    println!("  Rules: : r u l t d f {{ }} $x ^x\n");
 
-    let profile_path: String = Input::with_theme(&ColorfulTheme::default())
-        .with_prompt("Path to Profile JSON")
-        .default("target.json".into())
+    let mut rules = ask_rules()?;
+    loop {
+        let word: String = prompt::input("Sample word")
+            .interact_text()?;
+
+        println!();
+        for (line, rule) in &rules {
+            let mut candidate = word.as_bytes().to_vec();
+            rule.apply(&mut candidate);
+            println!("  {:<16} → {}", line, String::from_utf8_lossy(&candidate));
+        }
+        println!();
+
+        let actions = vec!["Try another word", "Change rules"];
+        let action_idx = prompt::select("What next?", &actions, 0)?;
+        if action_idx == 1 {
+            rules = ask_rules()?;
+        }
+    }
+}
+
+/// A typed rule string or every rule in a rules file, paired with its source
+/// line. Blank lines and `#` comments in the file are skipped.
+fn ask_rules() -> anyhow::Result<Vec<(String, RuleSet)>> {
+    let sources = vec!["Type a rule", "Load a rules file"];
+    let source_idx = prompt::select("Rules from", &sources, 0)?;
+
+    if source_idx == 0 {
+        let line: String = prompt::input("Rule (e.g. u$1$!)")
+            .validate_with(|input: &String| -> Result<(), String> {
+                RuleSet::from_str(input).map(|_| ()).map_err(|e| e.to_string())
+            })
+            .interact_text()?;
+        let rule = RuleSet::from_str(&line)?;
+        return Ok(vec![(line, rule)]);
+    }
+
+    loop {
+        let path: String = prompt::input("Path to rules file")
+            .interact_text()?;
+
+        let lines = match crate::io::reader::read_words(Path::new(&path)) {
+            Ok(lines) => lines,
+            Err(e) => {
+                println!("  Cannot read {}: {}", path, e);
+                continue;
+            }
+        };
+        let mut rules = Vec::new();
+        for line in lines {
+            if line.starts_with('#') {
+                continue;
+            }
+            match RuleSet::from_str(&line) {
+                Ok(rule) => rules.push((line, rule)),
+                Err(e) => println!("  Skipping {:?}: {}", line, e),
+            }
+        }
+        if !rules.is_empty() {
+            println!("  Loaded {} rules", rules.len());
+            return Ok(rules);
+        }
+        println!("  No usable rules in {}", path);
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════
+// MARKOV WIZARDS
+// ═══════════════════════════════════════════════════════════════
+
+fn run_train_wizard() -> anyhow::Result<JigsawArgs> {
+    println!("\n  ── Train Markov Model ──\n");
+
+    let corpus: String = prompt::input("Training wordlist (.gz/.zst/.xz/.zip work too)")
+        .validate_with(|input: &String| -> Result<(), &str> {
+            if PathBuf::from(input).is_file() { Ok(()) } else { Err("No such file") }
+        })
+        .interact_text()?;
+
+    let order: usize = prompt::input("Order (characters of context; higher fits the corpus closer)")
+        .default(3)
+        .validate_with(|v: &usize| {
+            if *v >= 1 && *v <= 8 { Ok(()) } else { Err("Must be 1-8") }
+        })
+        .interact_text()?;
+
+    let model_path: String = prompt::input("Save model to")
+        .default("jigsaw.model".into())
+        .interact_text()?;
+
+    Ok(JigsawArgs {
+        train: Some(PathBuf::from(corpus)),
+        order,
+        model: Some(PathBuf::from(model_path)),
+        ..Default::default()
+    })
+}
+
+fn run_markov_wizard() -> anyhow::Result<JigsawArgs> {
+    println!("\n  ── Markov Generate ──\n");
+
+    let model_path: String = prompt::input("Model file")
+        .default("jigsaw.model".into())
+        .validate_with(|input: &String| -> Result<(), &str> {
+            if PathBuf::from(input).is_file() { Ok(()) } else { Err("No such file (train one first)") }
+        })
+        .interact_text()?;
+
+    let count: usize = prompt::input("How many candidates?")
+        .default(10000)
         .interact_text()?;
 
-    let path = PathBuf::from(&profile_path);
+    let markov_min_len: usize = prompt::input("Minimum length")
+        .default(6)
+        .validate_with(|v: &usize| if *v >= 1 { Ok(()) } else { Err("Must be at least 1") })
+        .interact_text()?;
+
+    let markov_max_len: usize = prompt::input("Maximum length")
+        .default(12.max(markov_min_len))
+        .validate_with(move |v: &usize| if *v >= markov_min_len { Ok(()) } else { Err("Must be at least the minimum") })
+        .interact_text()?;
+
+    let output_file: String = prompt::input("Output file (empty = stdout)")
+        .allow_empty(true)
+        .interact_text()?;
+
+    Ok(JigsawArgs {
+        markov: true,
+        model: Some(PathBuf::from(model_path)),
+        count, markov_min_len, markov_max_len,
+        output: if output_file.trim().is_empty() { None } else { Some(PathBuf::from(output_file)) },
+        ..Default::default()
+    })
+}
+
+// ═══════════════════════════════════════════════════════════════
+// LOAD EXISTING PROFILE
+// ═══════════════════════════════════════════════════════════════
+
+fn run_load_profile_wizard(profiles_dir: &Path) -> anyhow::Result<JigsawArgs> {
+    println!("\n  ── Load Existing Profile ──\n");
+
+    let path = choose_profile(profiles_dir)?;
     let profile = Profile::load(&path)?;
 
     println!("\n  Profile loaded successfully:");
@@ -502,82 +806,248 @@ fn run_load_profile_wizard() -> anyhow::Result<JigsawArgs> {
     println!("    Numbers:  {:?}", profile.numbers);
     println!();
 
-    let actions = vec!["Generate wordlist", "Check a password", "Back to menu"];
-    let action_idx = Select::with_theme(&ColorfulTheme::default())
-        .with_prompt("What to do?")
-        .default(0)
-        .items(&actions)
-        .interact()?;
+    let actions = vec!["Generate wordlist", "Check a password"];
+    let action_idx = prompt::select("What to do?", &actions, 0)?;
 
     match action_idx {
-        0 => {
-            // Generation level
-            let levels = vec!["Quick", "Standard", "Deep", "Insane"];
-            let level_idx = Select::with_theme(&ColorfulTheme::default())
-                .with_prompt("Generation Level")
-                .default(1)
-                .items(&levels)
-                .interact()?;
-
-            let level = match level_idx {
-                0 => GenerationLevel::Quick,
-                1 => GenerationLevel::Standard,
-                2 => GenerationLevel::Deep,
-                _ => GenerationLevel::Insane,
-            };
-
-            let format_options = vec!["Plain text", "JSON"];
-            let format_idx = Select::with_theme(&ColorfulTheme::default())
-                .with_prompt("Output Format")
-                .default(0)
-                .items(&format_options)
-                .interact()?;
-
-            let output_file: String = Input::with_theme(&ColorfulTheme::default())
-                .with_prompt("Output file (empty = stdout)")
-                .allow_empty(true)
+        0 => ask_generation(&path, &profile),
+        _ => {
+            let password: String = prompt::input("Password to check")
                 .interact_text()?;
 
             Ok(JigsawArgs {
-                mask: None, rules: None, threads: None,
-                output: if output_file.trim().is_empty() { None } else { Some(PathBuf::from(output_file)) },
-                format: if format_idx == 1 { OutputFormat::Json } else { OutputFormat::Plain },
-                interactive: false,
-                train: None, model: None, markov: false, count: 0,
                 personal: true, profile: Some(path),
-                level,
-                min_length: profile.min_length, max_length: profile.max_length,
-                memorable: false,
-                words: 3, mem_sep: String::new(), mem_style: MemStyle::Classic,
-                mem_case: MemCase::Title, mem_number: true, no_number: false,
-                num_pos: NumPosition::End, num_max: 99,
-                mem_special: true, no_special: false, special_pos: NumPosition::End,
-                mem_count: 1, mem_min_len: 12, mem_max_len: 32,
-                check: None, command: None,
+                check: Some(password),
+                ..Default::default()
             })
         }
-        1 => {
-            let password: String = Input::with_theme(&ColorfulTheme::default())
-                .with_prompt("Password to check")
-                .interact_text()?;
+    }
+}
 
-            Ok(JigsawArgs {
-                mask: None, rules: None, threads: None,
-                output: None, format: OutputFormat::Plain,
-                interactive: false,
-                train: None, model: None, markov: false, count: 0,
-                personal: true, profile: Some(path),
-                level: GenerationLevel::Standard,
-                min_length: None, max_length: None,
-                memorable: false,
-                words: 3, mem_sep: String::new(), mem_style: MemStyle::Classic,
-                mem_case: MemCase::Title, mem_number: true, no_number: false,
-                num_pos: NumPosition::End, num_max: 99,
-                mem_special: true, no_special: false, special_pos: NumPosition::End,
-                mem_count: 1, mem_min_len: 12, mem_max_len: 32,
-                check: Some(password), command: None,
-            })
+/// Pick a profile from `dir`, narrowing the list with a fuzzy search, or
+/// type a path when the one wanted lives elsewhere
+fn choose_profile(dir: &Path) -> anyhow::Result<PathBuf> {
+    let found = discover_profiles(dir);
+    if found.is_empty() {
+        println!("  No profiles in {:?}", dir);
+    } else {
+        println!("  {} profile(s) in {:?}", found.len(), dir);
+    }
+
+    while !found.is_empty() {
+        let query: String = prompt::input("Search profiles (empty = all)")
+            .allow_empty(true)
+            .interact_text()?;
+
+        let mut matches: Vec<(usize, &(PathBuf, String))> = found.iter()
+            .filter_map(|entry| fuzzy_score(&query, &entry.1).map(|score| (score, entry)))
+            .collect();
+        matches.sort_by_key(|(score, _)| *score);
+        if matches.is_empty() {
+            println!("  Nothing matches {:?}", query);
+            continue;
         }
+
+        let mut items: Vec<&str> = matches.iter().map(|(_, (_, label))| label.as_str()).collect();
+        items.push("✎ Type a path instead");
+        match prompt::select("Profile", &items, 0) {
+            Ok(i) if i < matches.len() => return Ok(matches[i].1.0.clone()),
+            Ok(_) => break,
+            Err(e) if prompt::is_back(&e) => continue,
+            Err(e) => return Err(e),
+        }
+    }
+
+    let profile_path: String = prompt::input("Path to Profile JSON")
+        .default("target.json".into())
+        .validate_with(|input: &String| -> Result<(), &str> {
+            if PathBuf::from(input).is_file() { Ok(()) } else { Err("No such file") }
+        })
+        .interact_text()?;
+    Ok(PathBuf::from(profile_path))
+}
+
+/// The profiles in `dir`, each with a one-line summary, by file name.
+/// JSON files that aren't profiles (or hold nothing) are left out.
+fn discover_profiles(dir: &Path) -> Vec<(PathBuf, String)> {
+    let Ok(entries) = std::fs::read_dir(dir) else { return Vec::new() };
+    let mut found: Vec<(PathBuf, String)> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| {
+            let profile = Profile::load(&path).ok().filter(|profile| !profile.is_empty())?;
+            let name: Vec<&str> = profile.first_names.iter().chain(&profile.last_names).take(2).map(String::as_str).collect();
+            let filled = profile.fields().iter().filter(|(_, values)| !values.is_empty()).count();
+            let entries: usize = profile.fields().iter().map(|(_, values)| values.len()).sum();
+            let file = path.file_name()?.to_string_lossy().into_owned();
+            let label = format!("{:<24} {:<20} {} fields, {} entries", file, name.join(" "), filled, entries);
+            Some((path, label))
+        })
+        .collect();
+    found.sort();
+    found
+}
+
+/// How well `query` matches `text`: its letters must appear in order
+/// (ignoring case), and a tighter span scores lower. An empty query
+/// matches everything equally.
+fn fuzzy_score(query: &str, text: &str) -> Option<usize> {
+    let text = text.to_lowercase();
+    let mut chars = text.char_indices();
+    let mut first = None;
+    let mut last = 0;
+    for wanted in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let (i, _) = chars.find(|(_, c)| *c == wanted)?;
+        first.get_or_insert(i);
+        last = i;
+    }
+    Some(first.map_or(0, |first| last - first))
+}
+
+/// Level, format and output for a wordlist from a saved profile
+fn ask_generation(path: &Path, profile: &Profile) -> anyhow::Result<JigsawArgs> {
+    // Generation level
+    let levels = vec!["Quick", "Standard", "Deep", "Insane"];
+    let level_idx = prompt::select("Generation Level", &levels, 1)?;
+
+    let level = match level_idx {
+        0 => GenerationLevel::Quick,
+        1 => GenerationLevel::Standard,
+        2 => GenerationLevel::Deep,
+        _ => GenerationLevel::Insane,
+    };
+
+    let format_options = vec!["Plain text", "JSON", "JSON Lines", "Parquet"];
+    let format_idx = prompt::select("Output Format", &format_options, 0)?;
+
+    let output_file: String = prompt::input("Output file (empty = stdout)")
+        .allow_empty(true)
+        .interact_text()?;
+
+    Ok(JigsawArgs {
+        output: if output_file.trim().is_empty() { None } else { Some(PathBuf::from(output_file)) },
+        format: match format_idx {
+            1 => OutputFormat::Json,
+            2 => OutputFormat::Ndjson,
+            3 => OutputFormat::Parquet,
+            _ => OutputFormat::Plain,
+        },
+        personal: true, profile: Some(path.to_path_buf()),
+        level,
+        filter: FilterArgs { min_length: profile.min_length, max_length: profile.max_length, ..Default::default() },
+        ..Default::default()
+    })
+}
+
+// ═══════════════════════════════════════════════════════════════
+// EDIT PROFILE WIZARD
+// ═══════════════════════════════════════════════════════════════
+
+fn run_edit_profile_wizard(profiles_dir: &Path) -> anyhow::Result<JigsawArgs> {
+    println!("\n  ── Edit Profile ──\n");
+
+    let path = choose_profile(profiles_dir)?;
+    let mut profile = Profile::load(&path)?;
+
+    let mut selected = 0;
+    loop {
+        let mut items: Vec<String> = FIELDS.iter().enumerate()
+            .map(|(i, label)| format!("{:<13} ({})", label, field(&profile, i).len()))
+            .collect();
+        items.push("💾 Save and finish".to_string());
+        items.push("❌ Discard changes".to_string());
+
+        selected = prompt::select("Field to edit", &items, selected)?;
+        match selected {
+            i if i < FIELDS.len() => match edit_field(&mut profile, i) {
+                Err(e) if !prompt::is_back(&e) => return Err(e),
+                _ => {}
+            },
+            i if i == FIELDS.len() => break,
+            _ => std::process::exit(0),
+        }
+    }
+
+    profile.save(&path)?;
+    println!("  ✓ Profile saved to {:?}", path);
+
+    let actions = vec!["Generate wordlist", "Quit"];
+    let action_idx = prompt::select("What next?", &actions, 0)?;
+
+    match action_idx {
+        0 => ask_generation(&path, &profile),
         _ => std::process::exit(0),
     }
 }
+
+/// Add, remove or clear the entries of one profile field
+fn edit_field(profile: &mut Profile, index: usize) -> anyhow::Result<()> {
+    loop {
+        let values = field(profile, index);
+        println!("\n  {}: {}", FIELDS[index], if values.is_empty() { "(empty)".to_string() } else { values.join(", ") });
+
+        let actions = vec!["Add entries", "Remove entries", "Clear all", "Done"];
+        let action_idx = prompt::select(FIELDS[index], &actions, 0)?;
+
+        match action_idx {
+            0 => {
+                let values = field_mut(profile, index);
+                for value in ask_list("New entries")? {
+                    if !values.contains(&value) {
+                        values.push(value);
+                    }
+                }
+            }
+            1 => {
+                if values.is_empty() {
+                    continue;
+                }
+                let chosen = prompt::multi_select("Entries to remove (space selects, enter confirms)", values)?;
+                let values = field_mut(profile, index);
+                for i in chosen.into_iter().rev() {
+                    values.remove(i);
+                }
+            }
+            2 => field_mut(profile, index).clear(),
+            _ => return Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(fuzzy_score("", "target.json"), Some(0));
+        assert_eq!(fuzzy_score("tgt", "target.json"), Some(5));
+        assert_eq!(fuzzy_score("TAR", "target.json"), Some(2));
+        assert!(fuzzy_score("tar", "target.json") < fuzzy_score("tgt", "target.json"));
+        assert_eq!(fuzzy_score("xyz", "target.json"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_draft_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = std::env::temp_dir().join(format!("jigsaw_draft_{}", std::process::id()));
+        let path = dir.join("wizard-draft.json");
+        let mut profile = Profile::new();
+        profile.first_names = vec!["alice".to_string()];
+        save_draft(&profile, &path).unwrap();
+        assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        assert_eq!(load_draft(&path).unwrap().first_names, profile.first_names);
+
+        // A planted symlink is neither offered nor written through
+        let target = dir.join("elsewhere.json");
+        std::fs::write(&target, "{}").unwrap();
+        std::fs::remove_file(&path).unwrap();
+        std::os::unix::fs::symlink(&target, &path).unwrap();
+        assert!(load_draft(&path).is_none());
+        save_draft(&profile, &path).unwrap();
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "{}");
+        assert!(std::fs::symlink_metadata(&path).unwrap().file_type().is_file());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,328 @@
+use anyhow::{Context, Result};
+use dialoguer::theme::ColorfulTheme;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Mutex;
+use crate::invalid;
+
+// ═══════════════════════════════════════════════════════════════
+// WIZARD PROMPTS
+// ═══════════════════════════════════════════════════════════════
+//
+// Every wizard question goes through here, so a run can be scripted
+// (--answers) or captured for later (--record-answers). An answers file
+// is TOML, one entry per question in the order the wizard asks them:
+//
+//     [[answer]]
+//     prompt = "Select Action"
+//     value = "Memorable Password"
+//
+//     [[answer]]
+//     prompt = "Include a number?"
+//     value = true
+//
+// A menu answer is the item's text (or any unique part of it, ignoring
+// case) or its index; a yes/no question takes a boolean; a multi-choice
+// question an array. An empty string takes the question's default. The
+// prompt is checked against the question asked, so a file that drifts
+// out of step with the wizard fails at the first mismatch instead of
+// feeding answers to the wrong questions.
+//
+// Any question can also go back a step: menus end with "← Back", text
+// questions take a lone "<", and yes/no questions take Esc. The prompt
+// fails with `Back`, and the wizard unwinds to its previous step.
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Answers {
+    #[serde(default)]
+    answer: Vec<Answer>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Answer {
+    prompt: String,
+    value: toml::Value,
+}
+
+enum Script {
+    Replay(VecDeque<Answer>),
+    Record(PathBuf, Vec<Answer>),
+}
+
+static SCRIPT: Mutex<Option<Script>> = Mutex::new(None);
+
+/// The menu item that goes back a step
+pub const BACK: &str = "← Back";
+/// What to type at a text question to go back a step
+const BACK_TEXT: &str = "<";
+
+/// The user asked to go back a step
+#[derive(Debug)]
+pub struct Back;
+
+impl std::fmt::Display for Back {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("went back a step")
+    }
+}
+
+impl std::error::Error for Back {}
+
+/// Whether `e` is a request to go back rather than a failure
+pub fn is_back(e: &anyhow::Error) -> bool {
+    e.is::<Back>()
+}
+
+/// Take the wizard's answers from `path` instead of the terminal
+pub fn replay(path: &Path) -> Result<()> {
+    let text = std::fs::read_to_string(path).with_context(|| format!("can't read answers {}", path.display()))?;
+    let answers: Answers = toml::from_str(&text).map_err(|e| invalid!("{}: {}", path.display(), e))?;
+    *SCRIPT.lock().unwrap() = Some(Script::Replay(answers.answer.into()));
+    Ok(())
+}
+
+/// Save each answer given at the terminal to `path`, as an answers file
+pub fn record(path: &Path) {
+    *SCRIPT.lock().unwrap() = Some(Script::Record(path.to_path_buf(), Vec::new()));
+}
+
+/// Whether answers come from a file rather than the terminal
+pub fn is_scripted() -> bool {
+    matches!(*SCRIPT.lock().unwrap(), Some(Script::Replay(_)))
+}
+
+/// The next scripted answer, if answers are being replayed
+fn scripted(prompt: &str) -> Result<Option<toml::Value>> {
+    let mut script = SCRIPT.lock().unwrap();
+    let Some(Script::Replay(answers)) = script.as_mut() else { return Ok(None) };
+    let Some(answer) = answers.pop_front() else {
+        return Err(invalid!("answers file has no answer for {:?}", prompt));
+    };
+    if answer.prompt != prompt {
+        return Err(invalid!("answers file is out of step: expected an answer for {:?}, found one for {:?}", prompt, answer.prompt));
+    }
+    Ok(Some(answer.value))
+}
+
+/// Keep an answer given at the terminal, rewriting the file each time so
+/// a wizard that quits early still leaves what it asked
+fn remember(prompt: &str, value: toml::Value) -> Result<()> {
+    let mut script = SCRIPT.lock().unwrap();
+    let Some(Script::Record(path, answers)) = script.as_mut() else { return Ok(()) };
+    answers.push(Answer { prompt: prompt.to_string(), value });
+    let text = toml::to_string(&Answers { answer: answers.clone() })?;
+    std::fs::write(&*path, text).with_context(|| format!("can't write answers {}", path.display()))
+}
+
+fn text(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// The index of the item an answer names: its index, its exact text, or
+/// the one item containing it
+fn pick<S: AsRef<str>>(prompt: &str, items: &[S], value: &toml::Value) -> Result<usize> {
+    if let toml::Value::Integer(i) = value {
+        return usize::try_from(*i).ok().filter(|&i| i < items.len())
+            .ok_or_else(|| invalid!("{:?}: no item {}", prompt, i));
+    }
+    let wanted = text(value);
+    if let Some(i) = items.iter().position(|item| item.as_ref() == wanted) {
+        return Ok(i);
+    }
+    let lower = wanted.to_lowercase();
+    let matches: Vec<usize> = items.iter().enumerate()
+        .filter(|(_, item)| item.as_ref().to_lowercase().contains(&lower))
+        .map(|(i, _)| i)
+        .collect();
+    match matches[..] {
+        [i] => Ok(i),
+        [] => match wanted.parse::<usize>() {
+            Ok(i) if i < items.len() => Ok(i),
+            _ => Err(invalid!("{:?}: no item matches {:?}", prompt, wanted)),
+        },
+        _ => Err(invalid!("{:?}: {:?} matches more than one item", prompt, wanted)),
+    }
+}
+
+/// A menu ending in "← Back"; returns the chosen item's index
+pub fn select<S: AsRef<str> + Display>(prompt: &str, items: &[S], default: usize) -> Result<usize> {
+    let mut items: Vec<String> = items.iter().map(|item| item.to_string()).collect();
+    items.push(BACK.to_string());
+    match menu(prompt, &items, default)? {
+        i if i == items.len() - 1 => Err(Back.into()),
+        i => Ok(i),
+    }
+}
+
+/// A menu with no way back, for the wizard's first question
+pub fn menu<S: AsRef<str> + Display>(prompt: &str, items: &[S], default: usize) -> Result<usize> {
+    if let Some(value) = scripted(prompt)? {
+        if text(&value).is_empty() {
+            return Ok(default);
+        }
+        return pick(prompt, items, &value);
+    }
+    let index = dialoguer::Select::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .default(default)
+        .items(items)
+        .interact()?;
+    remember(prompt, toml::Value::String(items[index].as_ref().to_string()))?;
+    Ok(index)
+}
+
+/// A checklist; returns the ticked items' indices
+pub fn multi_select<S: AsRef<str> + Display>(prompt: &str, items: &[S]) -> Result<Vec<usize>> {
+    if let Some(value) = scripted(prompt)? {
+        return match value {
+            toml::Value::Array(values) => values.iter().map(|value| pick(prompt, items, value)).collect(),
+            toml::Value::String(s) if s == BACK_TEXT => Err(Back.into()),
+            _ => return Err(invalid!("{:?}: expected an array of items", prompt)),
+        };
+    }
+    let Some(chosen) = dialoguer::MultiSelect::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .items(items)
+        .interact_opt()?
+    else {
+        remember(prompt, toml::Value::String(BACK_TEXT.to_string()))?;
+        return Err(Back.into());
+    };
+    let names = chosen.iter().map(|&i| toml::Value::String(items[i].as_ref().to_string())).collect();
+    remember(prompt, toml::Value::Array(names))?;
+    Ok(chosen)
+}
+
+/// A yes/no question
+pub fn confirm(prompt: &str, default: bool) -> Result<bool> {
+    if let Some(value) = scripted(prompt)? {
+        return match value {
+            toml::Value::Boolean(yes) => Ok(yes),
+            toml::Value::String(s) if s.is_empty() => Ok(default),
+            toml::Value::String(s) if s == BACK_TEXT => Err(Back.into()),
+            _ => return Err(invalid!("{:?}: expected true or false", prompt)),
+        };
+    }
+    let Some(yes) = dialoguer::Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .default(default)
+        .interact_opt()?
+    else {
+        remember(prompt, toml::Value::String(BACK_TEXT.to_string()))?;
+        return Err(Back.into());
+    };
+    remember(prompt, toml::Value::Boolean(yes))?;
+    Ok(yes)
+}
+
+type Validator<T> = Box<dyn FnMut(&T) -> Result<(), String>>;
+
+/// A typed free-text question, built like dialoguer's `Input`
+pub struct Input<T> {
+    prompt: String,
+    default: Option<T>,
+    allow_empty: bool,
+    validator: Option<Validator<T>>,
+}
+
+pub fn input<T>(prompt: impl Into<String>) -> Input<T> {
+    Input { prompt: prompt.into(), default: None, allow_empty: false, validator: None }
+}
+
+impl<T> Input<T>
+where
+    T: Clone + Display + FromStr + 'static,
+    T::Err: Display,
+{
+    pub fn default(mut self, value: T) -> Self {
+        self.default = Some(value);
+        self
+    }
+
+    pub fn allow_empty(mut self, allow: bool) -> Self {
+        self.allow_empty = allow;
+        self
+    }
+
+    pub fn validate_with<E: ToString>(mut self, mut validator: impl FnMut(&T) -> Result<(), E> + 'static) -> Self {
+        self.validator = Some(Box::new(move |value| validator(value).map_err(|e| e.to_string())));
+        self
+    }
+
+    pub fn interact_text(mut self) -> Result<T> {
+        if let Some(value) = scripted(&self.prompt)? {
+            let answer = text(&value);
+            if answer == BACK_TEXT {
+                return Err(Back.into());
+            }
+            let value = match (answer.is_empty(), self.default.take()) {
+                (true, Some(default)) => default,
+                (true, None) if !self.allow_empty => return Err(invalid!("{:?}: an answer is required", self.prompt)),
+                _ => answer.parse().map_err(|e| invalid!("{:?}: {}", self.prompt, e))?,
+            };
+            if let Some(validator) = self.validator.as_mut() {
+                validator(&value).map_err(|e| invalid!("{:?}: {}", self.prompt, e))?;
+            }
+            return Ok(value);
+        }
+
+        // Asked as text, so "<" gets through whatever type the answer is
+        let theme = ColorfulTheme::default();
+        let mut prompt = dialoguer::Input::<String>::with_theme(&theme)
+            .with_prompt(&self.prompt)
+            .allow_empty(self.allow_empty);
+        if let Some(default) = &self.default {
+            prompt = prompt.default(default.to_string());
+        }
+        let mut validator = self.validator.take();
+        prompt = prompt.validate_with(move |answer: &String| -> Result<(), String> {
+            if answer == BACK_TEXT {
+                return Ok(());
+            }
+            let value = answer.parse::<T>().map_err(|e| e.to_string())?;
+            validator.as_mut().map_or(Ok(()), |validator| validator(&value))
+        });
+        let answer = prompt.interact_text()?;
+        remember(&self.prompt, toml::Value::String(answer.clone()))?;
+        if answer == BACK_TEXT {
+            return Err(Back.into());
+        }
+        answer.parse().map_err(|e| invalid!("{:?}: {}", self.prompt, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pick_by_index_text_and_fragment() {
+        let items = ["🔑 Personal Attack", "🎲 Memorable Password", "❌ Quit"];
+        assert_eq!(pick("menu", &items, &toml::Value::Integer(2)).unwrap(), 2);
+        assert_eq!(pick("menu", &items, &toml::Value::String("❌ Quit".into())).unwrap(), 2);
+        assert_eq!(pick("menu", &items, &toml::Value::String("memorable".into())).unwrap(), 1);
+        assert_eq!(pick("menu", &items, &toml::Value::String("0".into())).unwrap(), 0);
+        assert!(pick("menu", &items, &toml::Value::String("a".into())).is_err());
+        assert!(pick("menu", &items, &toml::Value::Integer(3)).is_err());
+    }
+
+    #[test]
+    fn test_answers_file_round_trip() {
+        let answers = Answers {
+            answer: vec![
+                Answer { prompt: "Select Action".into(), value: toml::Value::String("Mask Attack".into()) },
+                Answer { prompt: "Include a number?".into(), value: toml::Value::Boolean(true) },
+            ],
+        };
+        let text = toml::to_string(&answers).unwrap();
+        let parsed: Answers = toml::from_str(&text).unwrap();
+        assert_eq!(parsed.answer.len(), 2);
+        assert_eq!(parsed.answer[1].value, toml::Value::Boolean(true));
+    }
+}
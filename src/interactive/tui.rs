@@ -0,0 +1,518 @@
+use crate::cli::args::GenerationLevel;
+use crate::engine::personal::{Dedup, Estimate, Profile};
+use crate::io::writer::{self, BatchSender, Output, Stats, Writer};
+use super::{field, field_mut, FIELDS};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Gauge, List, ListItem, ListState, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+// ═══════════════════════════════════════════════════════════════
+// FULL-SCREEN TUI
+// ═══════════════════════════════════════════════════════════════
+//
+// `jigsaw tui` edits a profile on the left while the right-hand panes
+// follow every keystroke: the analytic keyspace estimate, the first unique
+// candidates, and, once generation starts, its progress. Generation runs
+// on a background thread through the same writer as the CLI.
+
+/// How often the screen redraws while idle or generating
+const TICK: Duration = Duration::from_millis(100);
+
+/// Unique candidates shown in the preview pane
+const PREVIEW: usize = 200;
+
+const LEVELS: [(&str, GenerationLevel); 4] = [
+    ("Quick", GenerationLevel::Quick),
+    ("Standard", GenerationLevel::Standard),
+    ("Deep", GenerationLevel::Deep),
+    ("Insane", GenerationLevel::Insane),
+];
+
+/// A line of the editor pane
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Row {
+    Field(usize),
+    Level,
+    MinLength,
+    MaxLength,
+    Typos,
+    Output,
+}
+
+const SETTINGS: [Row; 5] = [Row::Level, Row::MinLength, Row::MaxLength, Row::Typos, Row::Output];
+
+fn row(index: usize) -> Row {
+    if index < FIELDS.len() { Row::Field(index) } else { SETTINGS[index - FIELDS.len()] }
+}
+
+fn row_count() -> usize {
+    FIELDS.len() + SETTINGS.len()
+}
+
+/// Set a profile row from its text form: a comma separated list, or a
+/// length where empty means no limit
+fn set(profile: &mut Profile, row: Row, text: &str) -> Result<(), String> {
+    let length = |text: &str| match text.trim() {
+        "" | "0" => Ok(None),
+        n => n.parse().map(Some).map_err(|_| format!("'{}' isn't a length", n)),
+    };
+    match row {
+        Row::Field(i) => {
+            *field_mut(profile, i) = text.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect();
+        }
+        Row::MinLength => profile.min_length = length(text)?,
+        Row::MaxLength => profile.max_length = length(text)?,
+        Row::Level | Row::Typos | Row::Output => {}
+    }
+    Ok(())
+}
+
+enum Progress {
+    Idle,
+    Running(Job),
+    Done { stats: Stats, output: PathBuf },
+    Failed(String),
+}
+
+/// A generation run on its own thread
+struct Job {
+    generated: Arc<AtomicU64>,
+    stop: Arc<AtomicBool>,
+    /// The estimate when the run started, before dedup
+    expected: u64,
+    started: Instant,
+    handle: JoinHandle<anyhow::Result<Stats>>,
+}
+
+struct App {
+    path: PathBuf,
+    output: PathBuf,
+    profile: Profile,
+    level: usize,
+    selected: ListState,
+    /// Text being typed into the selected row
+    draft: Option<String>,
+    estimate: Estimate,
+    preview: Vec<String>,
+    progress: Progress,
+    message: String,
+    unsaved: bool,
+    /// `q` pressed once with unsaved changes
+    quitting: bool,
+}
+
+pub fn run(path: &Path, output: &Path) -> anyhow::Result<()> {
+    let (profile, message) = if path.exists() {
+        (Profile::load(path)?, format!("Loaded {}", path.display()))
+    } else {
+        (Profile::new(), format!("New profile; s saves it to {}", path.display()))
+    };
+    // Status lines would land on top of the screen
+    crate::cli::status::set_quiet(true);
+
+    let mut app = App {
+        path: path.to_path_buf(),
+        output: output.to_path_buf(),
+        profile,
+        level: 1,
+        selected: ListState::default().with_selected(Some(0)),
+        draft: None,
+        estimate: Estimate::default(),
+        preview: Vec::new(),
+        progress: Progress::Idle,
+        message,
+        unsaved: false,
+        quitting: false,
+    };
+    app.refresh();
+
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+impl App {
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> anyhow::Result<()> {
+        loop {
+            self.poll_job();
+            terminal.draw(|frame| self.draw(frame))?;
+            if event::poll(TICK)? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press && self.key(key) {
+                        break;
+                    }
+                }
+            }
+        }
+        self.stop_job();
+        Ok(())
+    }
+
+    fn row(&self) -> Row {
+        row(self.selected.selected().unwrap_or(0))
+    }
+
+    /// The profile as generation would see it: the draft typed so far
+    /// applied, and the level's word limit for weighted profiles
+    fn effective(&self) -> Profile {
+        let mut profile = self.profile.clone();
+        if let Some(draft) = &self.draft {
+            let _ = set(&mut profile, self.row(), draft);
+        }
+        let weighted = !profile.field_weights.is_empty() || !profile.word_weights.is_empty();
+        if profile.word_limit.is_none() && weighted {
+            profile.word_limit = LEVELS[self.level].1.word_limit();
+        }
+        profile
+    }
+
+    /// Recompute the estimate and preview after an edit
+    fn refresh(&mut self) {
+        let profile = self.effective();
+        self.estimate = profile.estimate();
+        self.preview.clear();
+        profile.for_each_unique(Dedup::Exact, |candidate| {
+            self.preview.push(candidate);
+            self.preview.len() >= PREVIEW
+        });
+    }
+
+    /// Handle a key press; true to quit
+    fn key(&mut self, key: KeyEvent) -> bool {
+        let ctrl_c = key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c');
+        if let Some(draft) = &mut self.draft {
+            match key.code {
+                _ if ctrl_c => self.draft = None,
+                KeyCode::Esc => self.draft = None,
+                KeyCode::Enter => self.commit(),
+                KeyCode::Backspace => {
+                    draft.pop();
+                }
+                KeyCode::Char(c) => draft.push(c),
+                _ => return false,
+            }
+            self.refresh();
+            return false;
+        }
+
+        if !matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) {
+            self.quitting = false;
+        }
+        let last = row_count() - 1;
+        match key.code {
+            _ if ctrl_c => return true,
+            KeyCode::Char('q') | KeyCode::Esc => {
+                if self.unsaved && !self.quitting {
+                    self.quitting = true;
+                    self.message = "Unsaved changes: s saves, q again quits without saving".to_string();
+                    return false;
+                }
+                return true;
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                let i = self.selected.selected().unwrap_or(0);
+                self.selected.select(Some(if i == 0 { last } else { i - 1 }));
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                let i = self.selected.selected().unwrap_or(0);
+                self.selected.select(Some(if i == last { 0 } else { i + 1 }));
+            }
+            KeyCode::Left | KeyCode::Right if self.row() == Row::Level => {
+                let step = if key.code == KeyCode::Left { LEVELS.len() - 1 } else { 1 };
+                self.level = (self.level + step) % LEVELS.len();
+                self.refresh();
+            }
+            KeyCode::Enter => match self.row() {
+                Row::Level => {
+                    self.level = (self.level + 1) % LEVELS.len();
+                    self.refresh();
+                }
+                Row::Typos => {
+                    self.profile.typos = !self.profile.typos;
+                    self.unsaved = true;
+                    self.refresh();
+                }
+                row => self.draft = Some(self.value(row)),
+            },
+            KeyCode::Delete | KeyCode::Char('d') => {
+                let row = self.row();
+                if matches!(row, Row::Field(_) | Row::MinLength | Row::MaxLength) {
+                    let _ = set(&mut self.profile, row, "");
+                    self.unsaved = true;
+                    self.refresh();
+                }
+            }
+            KeyCode::Char('s') => self.save(),
+            KeyCode::Char('g') => self.generate(),
+            KeyCode::Char('x') => {
+                if matches!(self.progress, Progress::Running(_)) {
+                    self.stop_job();
+                    self.message = "Generation stopped".to_string();
+                }
+            }
+            _ => {}
+        }
+        false
+    }
+
+    /// Apply the draft to the selected row
+    fn commit(&mut self) {
+        let Some(draft) = self.draft.take() else { return };
+        let row = self.row();
+        if row == Row::Output {
+            if draft.trim().is_empty() {
+                self.message = "The output needs a file name".to_string();
+            } else {
+                self.output = PathBuf::from(draft.trim());
+            }
+            return;
+        }
+        match set(&mut self.profile, row, &draft) {
+            Ok(()) => self.unsaved = true,
+            Err(e) => self.message = e,
+        }
+    }
+
+    fn save(&mut self) {
+        match self.profile.save(&self.path) {
+            Ok(()) => {
+                self.unsaved = false;
+                self.message = format!("Saved {}", self.path.display());
+            }
+            Err(e) => self.message = format!("Can't save {}: {}", self.path.display(), e),
+        }
+    }
+
+    fn generate(&mut self) {
+        if matches!(self.progress, Progress::Running(_)) {
+            return;
+        }
+        let profile = self.effective();
+        let (sender, receiver) = writer::channel(writer::DEFAULT_CHANNEL_CAPACITY);
+        let writer = Writer::new(receiver, Output::File(self.output.clone())).start();
+        let generated = Arc::new(AtomicU64::new(0));
+        let stop = Arc::new(AtomicBool::new(false));
+        let handle = {
+            let (generated, stop) = (generated.clone(), stop.clone());
+            std::thread::spawn(move || {
+                // A closed channel means the writer failed; joining it reports why
+                let mut batcher = BatchSender::new(sender, 1000);
+                profile.for_each_unique(Dedup::Exact, |candidate| {
+                    if stop.load(Ordering::Relaxed) {
+                        return true;
+                    }
+                    generated.fetch_add(1, Ordering::Relaxed);
+                    batcher.push(candidate.into_bytes()).is_err()
+                });
+                drop(batcher);
+                writer.join().expect("Writer panic")
+            })
+        };
+        self.message = format!("Writing {}", self.output.display());
+        self.progress = Progress::Running(Job { generated, stop, expected: self.estimate.candidates, started: Instant::now(), handle });
+    }
+
+    /// Collect a finished run
+    fn poll_job(&mut self) {
+        if matches!(&self.progress, Progress::Running(job) if job.handle.is_finished()) {
+            self.finish_job();
+        }
+    }
+
+    fn stop_job(&mut self) {
+        if let Progress::Running(job) = &self.progress {
+            job.stop.store(true, Ordering::Relaxed);
+            self.finish_job();
+        }
+    }
+
+    fn finish_job(&mut self) {
+        let Progress::Running(job) = std::mem::replace(&mut self.progress, Progress::Idle) else { return };
+        self.progress = match job.handle.join() {
+            Ok(Ok(stats)) => Progress::Done { stats, output: self.output.clone() },
+            Ok(Err(e)) => Progress::Failed(format!("{:#}", e)),
+            Err(_) => Progress::Failed("the generator panicked".to_string()),
+        };
+    }
+
+    /// A row's value as the editor shows and edits it
+    fn value(&self, row: Row) -> String {
+        let length = |n: Option<usize>| n.map_or(String::new(), |n| n.to_string());
+        match row {
+            Row::Field(i) => field(&self.profile, i).join(", "),
+            Row::Level => LEVELS[self.level].0.to_string(),
+            Row::MinLength => length(self.profile.min_length),
+            Row::MaxLength => length(self.profile.max_length),
+            Row::Typos => if self.profile.typos { "on" } else { "off" }.to_string(),
+            Row::Output => self.output.display().to_string(),
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, help, status] = Layout::vertical([Constraint::Fill(1), Constraint::Length(1), Constraint::Length(1)])
+            .areas(frame.area());
+        let [editor, right] = Layout::horizontal([Constraint::Percentage(45), Constraint::Fill(1)]).areas(main);
+        let [keyspace, preview, progress] = Layout::vertical([Constraint::Length(4), Constraint::Fill(1), Constraint::Length(3)])
+            .areas(right);
+
+        // Profile editor
+        let selected = self.selected.selected();
+        let items: Vec<ListItem> = (0..row_count()).map(|i| {
+            let row = row(i);
+            let label = match row {
+                Row::Field(i) => FIELDS[i],
+                Row::Level => "Level",
+                Row::MinLength => "Min length",
+                Row::MaxLength => "Max length",
+                Row::Typos => "Typos",
+                Row::Output => "Output",
+            };
+            let value = match (&self.draft, selected == Some(i)) {
+                (Some(draft), true) => Span::styled(format!("{}▏", draft), Style::new().fg(Color::Yellow)),
+                _ => match self.value(row) {
+                    v if v.is_empty() => Span::styled("—", Style::new().fg(Color::DarkGray)),
+                    v if row == Row::Level => Span::raw(format!("◂ {} ▸", v)),
+                    v => Span::raw(v),
+                },
+            };
+            let label = Span::styled(format!("{:<13}", label), Style::new().fg(Color::Cyan));
+            ListItem::new(Line::from(vec![label, value]))
+        }).collect();
+        let title = format!(" Profile: {}{} ", self.path.display(), if self.unsaved { " *" } else { "" });
+        let list = List::new(items)
+            .block(Block::bordered().title(title))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, editor, &mut self.selected);
+
+        // Keyspace estimate
+        let weighted = !self.profile.field_weights.is_empty() || !self.profile.word_weights.is_empty();
+        let lines = vec![
+            Line::from(format!("~{} candidates before dedup", self.estimate.candidates)),
+            Line::from(format!("~{} as plain text{}", crate::human_bytes(self.estimate.bytes),
+                if weighted { "" } else { " · level applies to weighted profiles" })),
+        ];
+        frame.render_widget(Paragraph::new(lines).block(Block::bordered().title(" Keyspace ")), keyspace);
+
+        // Live preview
+        let shown = self.preview.len();
+        let items: Vec<ListItem> = self.preview.iter().map(|c| ListItem::new(c.as_str())).collect();
+        let title = if shown < PREVIEW { format!(" Preview ({} unique) ", shown) } else { format!(" Preview (first {} unique) ", shown) };
+        frame.render_widget(List::new(items).block(Block::bordered().title(title)), preview);
+
+        // Generation progress
+        let block = Block::bordered().title(" Generation ");
+        match &self.progress {
+            Progress::Idle => {
+                let idle = Paragraph::new(format!("g writes {}", self.output.display())).fg(Color::DarkGray);
+                frame.render_widget(idle.block(block), progress);
+            }
+            Progress::Running(job) => {
+                let generated = job.generated.load(Ordering::Relaxed);
+                let secs = job.started.elapsed().as_secs_f64();
+                let ratio = if job.expected == 0 { 0.0 } else { (generated as f64 / job.expected as f64).min(1.0) };
+                let label = format!("{} / ~{} · {:.0}/s · {:.1}s · x stops", generated, job.expected, generated as f64 / secs.max(1e-9), secs);
+                frame.render_widget(Gauge::default().block(block).gauge_style(Color::Green).ratio(ratio).label(label), progress);
+            }
+            Progress::Done { stats, output } => {
+                let label = format!("{} candidates written to {} in {:.1}s", stats.candidates, output.display(), stats.elapsed.as_secs_f64());
+                frame.render_widget(Gauge::default().block(block).gauge_style(Color::Green).ratio(1.0).label(label), progress);
+            }
+            Progress::Failed(error) => {
+                frame.render_widget(Paragraph::new(error.as_str()).fg(Color::Red).block(block), progress);
+            }
+        }
+
+        let keys = if self.draft.is_some() {
+            " Enter apply · Esc cancel · comma separates values"
+        } else {
+            " ↑↓ move · Enter edit · ←→ level · d clear · s save · g generate · x stop · q quit"
+        };
+        frame.render_widget(Paragraph::new(keys).fg(Color::DarkGray), help);
+        frame.render_widget(Paragraph::new(format!(" {}", self.message)), status);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app(profile: Profile) -> App {
+        App {
+            path: PathBuf::from("target.json"),
+            output: PathBuf::from("wordlist.txt"),
+            profile,
+            level: 1,
+            selected: ListState::default().with_selected(Some(0)),
+            draft: None,
+            estimate: Estimate::default(),
+            preview: Vec::new(),
+            progress: Progress::Idle,
+            message: String::new(),
+            unsaved: false,
+            quitting: false,
+        }
+    }
+
+    #[test]
+    fn test_row() {
+        assert_eq!(row(0), Row::Field(0));
+        assert_eq!(row(FIELDS.len() - 1), Row::Field(FIELDS.len() - 1));
+        assert_eq!(row(FIELDS.len()), Row::Level);
+        assert_eq!(row(row_count() - 1), Row::Output);
+    }
+
+    #[test]
+    fn test_set() {
+        let mut profile = Profile::new();
+        set(&mut profile, row(0), " alice, , bob ").unwrap();
+        assert_eq!(profile.first_names, ["alice", "bob"]);
+        set(&mut profile, Row::MinLength, "8").unwrap();
+        set(&mut profile, Row::MaxLength, " 12 ").unwrap();
+        assert_eq!((profile.min_length, profile.max_length), (Some(8), Some(12)));
+
+        // A bad length is refused and leaves the old one
+        assert_eq!(set(&mut profile, Row::MinLength, "eight"), Err("'eight' isn't a length".to_string()));
+        assert_eq!(profile.min_length, Some(8));
+
+        // Clearing a row puts it back to its default
+        set(&mut profile, row(0), "").unwrap();
+        set(&mut profile, Row::MinLength, "").unwrap();
+        set(&mut profile, Row::MaxLength, "0").unwrap();
+        assert!(profile.first_names.is_empty());
+        assert_eq!((profile.min_length, profile.max_length), (None, None));
+    }
+
+    #[test]
+    fn test_effective() {
+        let mut profile = Profile::new();
+        profile.first_names = vec!["alice".to_string()];
+        let mut app = app(profile);
+
+        // The draft counts before it's committed, without touching the profile
+        app.draft = Some("alice, bob".to_string());
+        assert_eq!(app.effective().first_names, ["alice", "bob"]);
+        assert_eq!(app.profile.first_names, ["alice"]);
+        app.selected.select(Some(FIELDS.len() + 1));
+        app.draft = Some("short".to_string());
+        assert_eq!(app.effective().min_length, None);
+        app.draft = None;
+
+        // Only weighted profiles take the level's word limit
+        assert_eq!(app.effective().word_limit, None);
+        app.profile.word_weights.insert("alice".to_string(), 2.0);
+        assert_eq!(app.effective().word_limit, GenerationLevel::Standard.word_limit());
+        app.level = 3;
+        assert_eq!(app.effective().word_limit, None);
+        app.profile.word_limit = Some(5);
+        assert_eq!(app.effective().word_limit, Some(5));
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+// ═══════════════════════════════════════════════════════════════
+// CHECKPOINTS
+// ═══════════════════════════════════════════════════════════════
+//
+// Everything a long run keeps so it can pick up after a crash — the
+// writer's `<output>.ckpt` sidecar, session files — is written here.
+// A write goes to a temp file beside the target, is synced, renamed over
+// the target and the directory synced, so after a power cut the file is
+// the old version or the new one, never a torn mix. Checkpoints carry a
+// format version: files from before versioning read as version 0, and a
+// file from a newer jigsaw is refused rather than misread.
+
+/// Format version written into new checkpoints
+pub const VERSION: u32 = 1;
+
+/// Replace `path` with `contents` so that a crash at any point leaves
+/// either the old file or the new one
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    let temp = PathBuf::from(temp);
+    let mut file = File::create(&temp)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    drop(file);
+    std::fs::rename(&temp, path)?;
+    sync_dir(path)
+}
+
+/// Make the rename that put `path` in place durable
+#[cfg(unix)]
+fn sync_dir(path: &Path) -> io::Result<()> {
+    let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    File::open(dir)?.sync_all()
+}
+
+/// Windows has no directory handles to sync; the rename is journaled
+#[cfg(not(unix))]
+fn sync_dir(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+/// Refuse a checkpoint written in a newer format than this build reads
+pub fn check_version(version: u32, path: &Path) -> io::Result<()> {
+    if version > VERSION {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, format!(
+            "{} is a version {} checkpoint; this jigsaw reads up to version {}", path.display(), version, VERSION)));
+    }
+    Ok(())
+}
+
+/// `key=value` lines of a checkpoint, `version` first
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Fields {
+    pub version: u32,
+    values: HashMap<String, String>,
+}
+
+impl Fields {
+    /// A value, if present and it parses
+    pub fn get<T: FromStr>(&self, key: &str) -> Option<T> {
+        self.values.get(key)?.trim().parse().ok()
+    }
+}
+
+/// Write `fields` to `path` as a current-version checkpoint
+pub fn save(path: &Path, fields: &[(&str, String)]) -> io::Result<()> {
+    let mut text = format!("version={}\n", VERSION);
+    for (key, value) in fields {
+        text.push_str(&format!("{}={}\n", key, value));
+    }
+    write_atomic(path, text.as_bytes())
+}
+
+/// The fields saved at `path`, or None if there is no checkpoint
+pub fn load(path: &Path) -> io::Result<Option<Fields>> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let mut fields = Fields::default();
+    for (key, value) in text.lines().filter_map(|line| line.split_once('=')) {
+        fields.values.insert(key.trim().to_string(), value.to_string());
+    }
+    if fields.values.contains_key("version") {
+        fields.version = fields.get("version").ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData,
+            format!("{} has an unreadable version", path.display())))?;
+    }
+    check_version(fields.version, path)?;
+    Ok(Some(fields))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_versions() {
+        let dir = std::env::temp_dir().join(format!("jigsaw_checkpoint_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("run.ckpt");
+        assert_eq!(load(&path).unwrap(), None);
+
+        save(&path, &[("lines", "12".to_string())]).unwrap();
+        let fields = load(&path).unwrap().unwrap();
+        assert_eq!((fields.version, fields.get::<u64>("lines")), (VERSION, Some(12)));
+        assert!(!dir.join("run.ckpt.tmp").exists());
+
+        // Unversioned files are the first format
+        std::fs::write(&path, "lines=3\n").unwrap();
+        assert_eq!(load(&path).unwrap().unwrap().version, 0);
+        std::fs::write(&path, format!("version={}\nlines=3\n", VERSION + 1)).unwrap();
+        assert!(load(&path).unwrap_err().to_string().contains(&format!("version {} checkpoint", VERSION + 1)));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,78 @@
+use anyhow::{anyhow, Result};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+// ═══════════════════════════════════════════════════════════════
+// SYSTEM CLIPBOARD
+// ═══════════════════════════════════════════════════════════════
+//
+// Shells out to the platform's clipboard tools rather than linking a
+// windowing toolkit. The first tool that runs successfully wins.
+
+#[cfg(target_os = "macos")]
+const COPY: &[&[&str]] = &[&["pbcopy"]];
+#[cfg(target_os = "macos")]
+const PASTE: &[&[&str]] = &[&["pbpaste"]];
+
+#[cfg(windows)]
+const COPY: &[&[&str]] = &[&["clip"]];
+#[cfg(windows)]
+const PASTE: &[&[&str]] = &[&["powershell", "-NoProfile", "-Command", "Get-Clipboard"]];
+
+#[cfg(not(any(target_os = "macos", windows)))]
+const COPY: &[&[&str]] = &[
+    &["wl-copy"],
+    &["xclip", "-selection", "clipboard"],
+    &["xsel", "--clipboard", "--input"],
+];
+#[cfg(not(any(target_os = "macos", windows)))]
+const PASTE: &[&[&str]] = &[
+    &["wl-paste", "--no-newline"],
+    &["xclip", "-selection", "clipboard", "-o"],
+    &["xsel", "--clipboard", "--output"],
+];
+
+/// Put `text` on the system clipboard
+pub fn copy(text: &str) -> Result<()> {
+    for tool in COPY {
+        let Ok(mut child) = Command::new(tool[0])
+            .args(&tool[1..])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            continue;
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(text.as_bytes())?;
+        }
+        if child.wait()?.success() {
+            return Ok(());
+        }
+    }
+    let names: Vec<&str> = COPY.iter().map(|tool| tool[0]).collect();
+    Err(anyhow!("no clipboard tool available (tried {})", names.join(", ")))
+}
+
+/// Current clipboard text, if a paste tool is available
+pub fn paste() -> Option<String> {
+    PASTE.iter().find_map(|tool| {
+        let output = Command::new(tool[0])
+            .args(&tool[1..])
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+        output.status.success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim_end_matches(['\r', '\n']).to_string())
+    })
+}
+
+/// Empty the clipboard, but only if it still holds `text` (the user may
+/// have copied something else since). Returns whether it was cleared.
+pub fn clear_if_unchanged(text: &str) -> Result<bool> {
+    match paste() {
+        Some(current) if current != text => Ok(false),
+        _ => copy("").map(|_| true),
+    }
+}
@@ -0,0 +1,465 @@
+use crate::engine::personal::Profile;
+use crate::invalid;
+use anyhow::Result;
+use std::path::Path;
+
+// ═══════════════════════════════════════════════════════════════
+// FORMAT DETECTION
+// ═══════════════════════════════════════════════════════════════
+
+/// Parse a contacts export into profiles, picking the format from the
+/// file extension (falling back to sniffing for `BEGIN:VCARD`).
+pub fn import_file(path: &Path, mapping: Option<&str>) -> Result<Vec<Profile>> {
+    let text = std::fs::read_to_string(path)?;
+    let ext = path.extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    let is_vcard = match ext.as_str() {
+        "vcf" | "vcard" => true,
+        "csv" => false,
+        _ => text.trim_start().to_uppercase().starts_with("BEGIN:VCARD"),
+    };
+
+    if is_vcard {
+        Ok(from_vcard(&text))
+    } else {
+        let mapping = match mapping {
+            Some(spec) => Some(parse_mapping(spec)?),
+            None => None,
+        };
+        from_csv(&text, mapping.as_deref())
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════
+// VCARD
+// ═══════════════════════════════════════════════════════════════
+
+/// Parse every `BEGIN:VCARD` … `END:VCARD` block into a profile.
+/// Contacts that yield no usable values are skipped.
+pub fn from_vcard(text: &str) -> Vec<Profile> {
+    // Unfold continuation lines (RFC 6350 §3.2)
+    let mut lines: Vec<String> = Vec::new();
+    for raw in text.lines() {
+        if (raw.starts_with(' ') || raw.starts_with('\t')) && !lines.is_empty() {
+            lines.last_mut().unwrap().push_str(&raw[1..]);
+        } else {
+            lines.push(raw.to_string());
+        }
+    }
+
+    let mut profiles = Vec::new();
+    let mut current: Option<Profile> = None;
+
+    for line in &lines {
+        let Some((key, value)) = line.split_once(':') else { continue };
+        // Drop parameters (TEL;TYPE=CELL) and group prefixes (item1.EMAIL)
+        let name = key.split(';').next().unwrap_or("").to_uppercase();
+        let name = name.rsplit('.').next().unwrap_or("");
+
+        match name {
+            "BEGIN" if value.eq_ignore_ascii_case("VCARD") => {
+                current = Some(Profile::new());
+            }
+            "END" if value.eq_ignore_ascii_case("VCARD") => {
+                if let Some(profile) = current.take() {
+                    let profile = tidy(profile);
+                    if !profile.is_empty() {
+                        profiles.push(profile);
+                    }
+                }
+            }
+            _ => {
+                if let Some(profile) = current.as_mut() {
+                    apply_vcard_property(profile, name, value);
+                }
+            }
+        }
+    }
+
+    profiles
+}
+
+fn apply_vcard_property(profile: &mut Profile, name: &str, value: &str) {
+    let parts: Vec<String> = split_escaped(value, ';');
+
+    match name {
+        // N:Family;Given;Additional;Prefix;Suffix
+        "N" => {
+            if let Some(family) = parts.first() {
+                push_values(&mut profile.last_names, family);
+            }
+            if let Some(given) = parts.get(1) {
+                push_values(&mut profile.first_names, given);
+            }
+        }
+        // Only fall back to the formatted name when N was absent
+        "FN" if profile.first_names.is_empty() => {
+            let full = unescape(value);
+            let mut words = full.split_whitespace();
+            if let Some(first) = words.next() {
+                profile.first_names.push(first.to_string());
+            }
+            if let Some(last) = words.last() {
+                profile.last_names.push(last.to_string());
+            }
+        }
+        "NICKNAME" => push_values(&mut profile.usernames, &unescape(value)),
+        "EMAIL" => profile.email.push(unescape(value)),
+        "TEL" => {
+            let digits = phone_digits(value);
+            if !digits.is_empty() {
+                profile.numbers.push(digits);
+            }
+        }
+        "ORG" => {
+            if let Some(org) = parts.first() {
+                profile.company.push(org.clone());
+            }
+        }
+        // ADR:PO;Extended;Street;Locality;Region;Postal;Country
+        "ADR" => {
+            if let Some(city) = parts.get(3) {
+                profile.city.push(city.clone());
+            }
+            if let Some(postal) = parts.get(5) {
+                profile.numbers.push(postal.clone());
+            }
+        }
+        "BDAY" | "ANNIVERSARY" => profile.dates.extend(normalize_date(value)),
+        "X-SPOUSE" | "X-ANNIVERSARY-PARTNER" => profile.partners.push(unescape(value)),
+        "X-CHILD" => profile.kids.push(unescape(value)),
+        _ => {}
+    }
+}
+
+/// Split on an unescaped delimiter, then unescape each piece
+fn split_escaped(value: &str, delim: char) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            current.push(c);
+            if let Some(next) = chars.next() {
+                current.push(next);
+            }
+        } else if c == delim {
+            parts.push(unescape(&current));
+            current.clear();
+        } else {
+            current.push(c);
+        }
+    }
+    parts.push(unescape(&current));
+    parts
+}
+
+fn unescape(value: &str) -> String {
+    value.replace("\\n", " ")
+        .replace("\\N", " ")
+        .replace("\\,", ",")
+        .replace("\\;", ";")
+        .replace("\\\\", "\\")
+        .trim()
+        .to_string()
+}
+
+// ═══════════════════════════════════════════════════════════════
+// CSV
+// ═══════════════════════════════════════════════════════════════
+
+/// Parse a mapping spec such as `"Given Name=first_names,Org=company"`
+pub fn parse_mapping(spec: &str) -> Result<Vec<(String, String)>> {
+    let mut mapping = Vec::new();
+    for pair in spec.split(',') {
+        let pair = pair.trim();
+        if pair.is_empty() { continue; }
+        let (column, field) = pair.split_once('=')
+            .ok_or_else(|| invalid!("Invalid mapping '{}': expected COLUMN=field", pair))?;
+        let field = field.trim();
+        if Profile::new().field_mut(field).is_none() {
+            return Err(invalid!("Unknown profile field '{}' in mapping", field));
+        }
+        mapping.push((column.trim().to_string(), field.to_string()));
+    }
+    Ok(mapping)
+}
+
+/// Parse a CSV export with a header row into one profile per data row.
+/// Without an explicit mapping, column headers are matched against the
+/// names used by common exporters (Google, Outlook, Apple).
+pub fn from_csv(text: &str, mapping: Option<&[(String, String)]>) -> Result<Vec<Profile>> {
+    let mut rows = parse_csv(text).into_iter();
+    let header = rows.next().ok_or_else(|| invalid!("CSV file is empty"))?;
+
+    let columns: Vec<Option<String>> = header.iter()
+        .map(|h| match mapping {
+            Some(pairs) => pairs.iter()
+                .find(|(column, _)| column.eq_ignore_ascii_case(h.trim()))
+                .map(|(_, field)| field.clone()),
+            None => default_field_for_header(h).map(str::to_string),
+        })
+        .collect();
+
+    if columns.iter().all(Option::is_none) {
+        return Err(invalid!("No CSV columns matched a profile field (use --map COLUMN=field)"));
+    }
+
+    let mut profiles = Vec::new();
+    for row in rows {
+        let mut profile = Profile::new();
+        for (cell, field) in row.iter().zip(&columns) {
+            let Some(field) = field else { continue };
+            // Google Contacts packs multiple values into one cell with " ::: "
+            for value in cell.split(":::").map(str::trim).filter(|v| !v.is_empty()) {
+                let values = match field.as_str() {
+                    "dates" => normalize_date(value),
+                    "numbers" => vec![phone_digits(value)],
+                    _ => vec![value.to_string()],
+                };
+                if let Some(list) = profile.field_mut(field) {
+                    list.extend(values.into_iter().filter(|v| !v.is_empty()));
+                }
+            }
+        }
+        let profile = tidy(profile);
+        if !profile.is_empty() {
+            profiles.push(profile);
+        }
+    }
+
+    Ok(profiles)
+}
+
+fn default_field_for_header(header: &str) -> Option<&'static str> {
+    let h: String = header.to_lowercase().chars().filter(|c| c.is_alphanumeric()).collect();
+
+    // Order matters: "Organization 1 - Title" must not map to company,
+    // and "Maiden Name" must win over the generic name checks.
+    let field = if h.contains("title") || h.contains("type") || h.contains("label") {
+        return None;
+    } else if h.contains("maiden") {
+        "maiden_name"
+    } else if h.contains("given") || h.contains("firstname") {
+        "first_names"
+    } else if h.contains("family") || h.contains("lastname") || h.contains("surname") {
+        "last_names"
+    } else if h.contains("nickname") || h.contains("username") || h.contains("handle") {
+        "usernames"
+    } else if h.contains("birthday") || h.contains("anniversary") || h.contains("date") {
+        "dates"
+    } else if h.contains("email") {
+        "email"
+    } else if h.contains("phone") || h.contains("mobile") || h.contains("zip") || h.contains("postal") {
+        "numbers"
+    } else if h.contains("organization") || h.contains("company") {
+        "company"
+    } else if h.contains("city") || h.contains("locality") {
+        "city"
+    } else if h.contains("school") || h.contains("university") {
+        "school"
+    } else if h.contains("spouse") || h.contains("partner") {
+        "partners"
+    } else if h.contains("child") || h.contains("kids") {
+        "kids"
+    } else if h.contains("pet") {
+        "pets"
+    } else if h.contains("hobby") || h.contains("hobbies") || h.contains("interest") {
+        "hobbies"
+    } else {
+        return None;
+    };
+    Some(field)
+}
+
+/// Minimal RFC 4180 reader: quoted cells, doubled quotes, embedded newlines
+fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut cell = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            if c == '"' {
+                if chars.peek() == Some(&'"') {
+                    cell.push('"');
+                    chars.next();
+                } else {
+                    in_quotes = false;
+                }
+            } else {
+                cell.push(c);
+            }
+            continue;
+        }
+        match c {
+            '"' => in_quotes = true,
+            ',' => row.push(std::mem::take(&mut cell)),
+            '\r' => {}
+            '\n' => {
+                row.push(std::mem::take(&mut cell));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => cell.push(c),
+        }
+    }
+    if !cell.is_empty() || !row.is_empty() {
+        row.push(cell);
+        rows.push(row);
+    }
+
+    rows.retain(|r| r.iter().any(|c| !c.trim().is_empty()));
+    rows
+}
+
+// ═══════════════════════════════════════════════════════════════
+// VALUE NORMALIZATION
+// ═══════════════════════════════════════════════════════════════
+
+/// Convert an exported date into the forms the personal engine expands:
+/// `YYYY`, `MMDD` and `MMDDYYYY`.
+fn normalize_date(value: &str) -> Vec<String> {
+    let groups: Vec<&str> = value.split(|c: char| !c.is_ascii_digit())
+        .filter(|g| !g.is_empty())
+        .collect();
+
+    let (year, month, day) = match groups.as_slice() {
+        // 1990-05-17
+        [y, m, d] if y.len() == 4 => (Some(*y), *m, *d),
+        // 05/17/1990
+        [m, d, y] if y.len() == 4 => (Some(*y), *m, *d),
+        // --05-17 (vCard birthday without a year)
+        [m, d] if m.len() <= 2 && d.len() <= 2 => (None, *m, *d),
+        // 19900517
+        [all] if all.len() == 8 && (all.starts_with("19") || all.starts_with("20")) => {
+            (Some(&all[0..4]), &all[4..6], &all[6..8])
+        }
+        _ => {
+            let digits: String = groups.concat();
+            return if digits.is_empty() { Vec::new() } else { vec![digits] };
+        }
+    };
+
+    let mmdd = format!("{:0>2}{:0>2}", month, day);
+    match year {
+        Some(y) => vec![y.to_string(), mmdd.clone(), format!("{}{}", mmdd, y)],
+        None => vec![mmdd],
+    }
+}
+
+fn phone_digits(value: &str) -> String {
+    value.chars().filter(|c| c.is_ascii_digit()).collect()
+}
+
+fn push_values(list: &mut Vec<String>, value: &str) {
+    list.extend(value.split(',').map(str::trim).filter(|v| !v.is_empty()).map(str::to_string));
+}
+
+/// Drop blanks and duplicates while keeping the original order
+fn tidy(mut profile: Profile) -> Profile {
+    let names: Vec<&'static str> = profile.fields().iter().map(|(name, _)| *name).collect();
+    for name in names {
+        if let Some(list) = profile.field_mut(name) {
+            let mut seen = std::collections::HashSet::new();
+            list.retain(|v| !v.trim().is_empty() && seen.insert(v.clone()));
+        }
+    }
+    profile
+}
+
+/// File stem for an imported profile, e.g. "john_doe"
+pub fn profile_file_stem(profile: &Profile) -> String {
+    let name = profile.first_names.iter()
+        .chain(profile.last_names.iter().take(1))
+        .take(2)
+        .cloned()
+        .collect::<Vec<_>>()
+        .join("_");
+    let stem: String = name.to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect();
+    if stem.trim_matches('_').is_empty() {
+        "contact".to_string()
+    } else {
+        stem
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════
+// TESTS
+// ═══════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vcard_basic() {
+        let vcf = "BEGIN:VCARD\r\nVERSION:3.0\r\nN:Doe;John;;;\r\nFN:John Doe\r\n\
+                   EMAIL;TYPE=INTERNET:john.doe@example.com\r\nTEL;TYPE=CELL:+1 (555) 123-4567\r\n\
+                   ORG:Acme Corporation;Sales\r\nBDAY:1990-05-17\r\n\
+                   item1.ADR;TYPE=HOME:;;1 Main St;Springfield;IL;62701;USA\r\nEND:VCARD\r\n";
+        let profiles = from_vcard(vcf);
+        assert_eq!(profiles.len(), 1);
+        let p = &profiles[0];
+        assert_eq!(p.first_names, vec!["John"]);
+        assert_eq!(p.last_names, vec!["Doe"]);
+        assert_eq!(p.email, vec!["john.doe@example.com"]);
+        assert_eq!(p.company, vec!["Acme Corporation"]);
+        assert_eq!(p.city, vec!["Springfield"]);
+        assert!(p.numbers.contains(&"15551234567".to_string()));
+        assert!(p.numbers.contains(&"62701".to_string()));
+        assert_eq!(p.dates, vec!["1990", "0517", "05171990"]);
+    }
+
+    #[test]
+    fn test_vcard_multiple_and_folded() {
+        let vcf = "BEGIN:VCARD\nFN:Jane\n  Smith\nEND:VCARD\nBEGIN:VCARD\nNICKNAME:Bobby,Rob\nN:Roe;Robert\nEND:VCARD\nBEGIN:VCARD\nEND:VCARD\n";
+        let profiles = from_vcard(vcf);
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0].first_names, vec!["Jane"]);
+        assert_eq!(profiles[0].last_names, vec!["Smith"]);
+        assert_eq!(profiles[1].usernames, vec!["Bobby", "Rob"]);
+    }
+
+    #[test]
+    fn test_csv_default_headers() {
+        let csv = "Given Name,Family Name,E-mail 1 - Value,Birthday,Organization 1 - Title\n\
+                   John,Doe,john@example.com ::: jd@work.com,1990-05-17,Manager\n";
+        let profiles = from_csv(csv, None).unwrap();
+        assert_eq!(profiles.len(), 1);
+        let p = &profiles[0];
+        assert_eq!(p.first_names, vec!["John"]);
+        assert_eq!(p.email, vec!["john@example.com", "jd@work.com"]);
+        assert!(p.company.is_empty());
+        assert!(p.dates.contains(&"1990".to_string()));
+    }
+
+    #[test]
+    fn test_csv_custom_mapping() {
+        let csv = "Who,\"Fav, Team\",Critter\nAlice,\"Red Sox\",Rex\n";
+        let mapping = parse_mapping("Who=first_names,Critter=pets").unwrap();
+        let profiles = from_csv(csv, Some(&mapping)).unwrap();
+        assert_eq!(profiles[0].first_names, vec!["Alice"]);
+        assert_eq!(profiles[0].pets, vec!["Rex"]);
+        assert!(profiles[0].sports.is_empty());
+    }
+
+    #[test]
+    fn test_mapping_rejects_unknown_field() {
+        assert!(parse_mapping("Name=firstnames").is_err());
+        assert!(parse_mapping("Name").is_err());
+    }
+
+    #[test]
+    fn test_normalize_date() {
+        assert_eq!(normalize_date("--05-17"), vec!["0517"]);
+        assert_eq!(normalize_date("19900517"), vec!["1990", "0517", "05171990"]);
+        assert_eq!(normalize_date("5/7/1990"), vec!["1990", "0507", "05071990"]);
+    }
+}
@@ -0,0 +1,88 @@
+use anyhow::Result;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// ═══════════════════════════════════════════════════════════════
+// MANIFEST
+// ═══════════════════════════════════════════════════════════════
+//
+// A sidecar `<output>.manifest.json` that records what a wordlist is and
+// how it was made, so a list passed around a team can be checked and
+// regenerated. The checksum covers the file as it sits on disk (after
+// compression, and including anything an earlier --append run wrote).
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Manifest {
+    pub file: String,
+    pub sha256: String,
+    pub bytes: u64,
+    pub candidates: u64,
+    pub duplicates: u64,
+    pub generator: String,
+    /// The command line that produced the file
+    pub command: Vec<String>,
+    /// Resolved generation parameters (mode, seed, lengths, ...)
+    pub parameters: serde_json::Value,
+    /// Unix timestamp, seconds
+    pub created: u64,
+}
+
+/// Where the manifest for `output` goes
+pub fn manifest_path(output: &Path) -> PathBuf {
+    let mut name = output.as_os_str().to_owned();
+    name.push(".manifest.json");
+    PathBuf::from(name)
+}
+
+/// Hex SHA-256 of a file, streamed
+pub fn sha256_file(path: &Path) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut File::open(path)?, &mut hasher)?;
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Checksum `output` and write its manifest next to it
+pub fn write(output: &Path, candidates: u64, duplicates: u64, parameters: serde_json::Value) -> Result<PathBuf> {
+    let manifest = Manifest {
+        file: output.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default(),
+        sha256: sha256_file(output)?,
+        bytes: std::fs::metadata(output)?.len(),
+        candidates,
+        duplicates,
+        generator: format!("jigsaw {}", env!("CARGO_PKG_VERSION")),
+        command: std::env::args().collect(),
+        parameters,
+        created: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+    };
+    let path = manifest_path(output);
+    std::fs::write(&path, serde_json::to_string_pretty(&manifest)? + "\n")?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest() {
+        let path = std::env::temp_dir().join(format!("jigsaw_manifest_{}.txt", std::process::id()));
+        std::fs::write(&path, "abc").unwrap();
+
+        let sidecar = write(&path, 1, 2, serde_json::json!({ "mode": "mask" })).unwrap();
+        assert_eq!(sidecar, manifest_path(&path));
+        let manifest: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&sidecar).unwrap()).unwrap();
+        // FIPS 180-2 test vector for "abc"
+        assert_eq!(manifest["sha256"], "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert_eq!((manifest["bytes"].as_u64(), manifest["candidates"].as_u64()), (Some(3), Some(1)));
+        assert_eq!(manifest["duplicates"], 2);
+        assert_eq!(manifest["parameters"]["mode"], "mask");
+        assert!(manifest["file"].as_str().unwrap().ends_with(".txt"));
+
+        let _ = std::fs::remove_file(path);
+        let _ = std::fs::remove_file(sidecar);
+    }
+}
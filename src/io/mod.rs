@@ -1 +1,13 @@
 pub mod writer;
+pub mod checkpoint;
+pub mod import;
+pub mod scrape;
+pub mod reader;
+pub mod clipboard;
+pub mod qr;
+pub mod sort;
+pub mod upload;
+pub mod manifest;
+pub mod parquet;
+pub mod pwned;
+pub mod wordlist;
@@ -0,0 +1,191 @@
+use crate::engine::bloom::BloomFilter;
+use crate::invalid;
+use anyhow::{Context, Result};
+use sha1::{Digest, Sha1};
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::path::Path;
+use std::time::Duration;
+
+// ═══════════════════════════════════════════════════════════════
+// HAVE I BEEN PWNED
+// ═══════════════════════════════════════════════════════════════
+//
+// Breach counts from the Pwned Passwords range API without revealing
+// the password: only the first five hex digits of its SHA-1 go out, the
+// service answers with every suffix under that prefix, and the match
+// happens here. Responses are padded so their size says nothing either.
+// JIGSAW_PWNED_URL points the lookups at a mirror instead.
+
+const RANGE_URL: &str = "https://api.pwnedpasswords.com/range/";
+
+/// Environment variable overriding RANGE_URL
+pub const URL_VAR: &str = "JIGSAW_PWNED_URL";
+
+/// Uppercase hex SHA-1 of `password`, the form the API uses
+pub fn sha1_hex(password: &str) -> String {
+    Sha1::digest(password.as_bytes()).iter().map(|b| format!("{:02X}", b)).collect()
+}
+
+/// The count for `suffix` in a range response (`SUFFIX:COUNT` lines)
+fn count_in_range(body: &str, suffix: &str) -> u64 {
+    body.lines()
+        .filter_map(|line| line.trim().split_once(':'))
+        .find(|(candidate, _)| candidate.eq_ignore_ascii_case(suffix))
+        .and_then(|(_, count)| count.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+/// Range lookups, remembering each prefix it has fetched
+pub struct Client {
+    agent: ureq::Agent,
+    url: String,
+    ranges: HashMap<String, String>,
+}
+
+impl Client {
+    pub fn new() -> Self {
+        Self {
+            agent: ureq::Agent::config_builder()
+                .timeout_global(Some(Duration::from_secs(15)))
+                .build()
+                .into(),
+            url: std::env::var(URL_VAR).unwrap_or_else(|_| RANGE_URL.to_string()),
+            ranges: HashMap::new(),
+        }
+    }
+
+    /// Times `password` appears in known breaches (0 = not found)
+    pub fn breach_count(&mut self, password: &str) -> Result<u64> {
+        self.hash_count(&sha1_hex(password))
+    }
+
+    /// Like `breach_count`, for a 40-digit hex SHA-1
+    pub fn hash_count(&mut self, sha1: &str) -> Result<u64> {
+        if sha1.len() != 40 || !sha1.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(invalid!("not a SHA-1 hash: {:?}", sha1));
+        }
+        let sha1 = sha1.to_ascii_uppercase();
+        let (prefix, suffix) = sha1.split_at(5);
+        if !self.ranges.contains_key(prefix) {
+            let url = format!("{}{}", self.url, prefix);
+            let body = self.agent.get(&url)
+                .header("Add-Padding", "true")
+                .header("User-Agent", concat!("jigsaw/", env!("CARGO_PKG_VERSION")))
+                .call()
+                .and_then(|mut response| response.body_mut().read_to_string())
+                .with_context(|| format!("Pwned Passwords lookup failed ({})", url))?;
+            self.ranges.insert(prefix.to_string(), body);
+        }
+        Ok(count_in_range(&self.ranges[prefix], suffix))
+    }
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════
+// OFFLINE FILTER
+// ═══════════════════════════════════════════════════════════════
+//
+// For machines that can't reach the API: a Bloom filter of SHA-1 hashes,
+// built once from the downloadable Pwned Passwords corpus (SHA1:COUNT
+// lines) or any list of plain passwords, and copied across. It answers
+// "breached or not" without counts, and a small share of clean passwords
+// (the --fp-rate it was built with) come back as breached.
+
+/// The 20-byte SHA-1 a corpus line stands for: the hash on a `SHA1[:COUNT]`
+/// line, otherwise the line hashed as a password (always, with `plain`)
+pub fn corpus_hash(line: &str, plain: bool) -> [u8; 20] {
+    let hex = line.split_once(':').map_or(line, |(hash, _)| hash).trim();
+    if !plain && hex.len() == 40 && hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        let mut hash = [0u8; 20];
+        for (i, byte) in hash.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).expect("hex digits");
+        }
+        return hash;
+    }
+    Sha1::digest(line.as_bytes()).into()
+}
+
+/// Breached-password hashes, checked locally
+pub struct Filter {
+    bloom: BloomFilter,
+}
+
+impl Filter {
+    /// Every line of `corpus`, in a filter sized for `expected` lines
+    pub fn build(corpus: impl BufRead, expected: u64, fp_rate: f64, plain: bool) -> Result<(Self, u64)> {
+        let mut bloom = BloomFilter::with_rate(expected, fp_rate);
+        let mut added = 0;
+        for line in corpus.split(b'\n') {
+            let line = line?;
+            let Ok(line) = std::str::from_utf8(&line) else { continue };
+            let line = line.trim_end_matches('\r');
+            if !line.is_empty() {
+                bloom.insert(&corpus_hash(line, plain));
+                added += 1;
+            }
+        }
+        Ok((Self { bloom }, added))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let file = std::fs::File::create(path).with_context(|| format!("can't create {}", path.display()))?;
+        self.bloom.write_to(std::io::BufWriter::new(file))?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let file = std::fs::File::open(path).with_context(|| format!("can't open pwned filter {}", path.display()))?;
+        let bloom = BloomFilter::read_from(std::io::BufReader::new(file))
+            .with_context(|| format!("can't read pwned filter {}", path.display()))?;
+        Ok(Self { bloom })
+    }
+
+    pub fn size_bytes(&self) -> usize {
+        self.bloom.size_bytes()
+    }
+
+    /// Whether `password` is (probably) in the corpus
+    pub fn contains(&self, password: &str) -> bool {
+        let hash: [u8; 20] = Sha1::digest(password.as_bytes()).into();
+        self.bloom.contains(&hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sha1_hex() {
+        assert_eq!(sha1_hex("password"), "5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8");
+    }
+
+    #[test]
+    fn test_count_in_range() {
+        let body = "1E4C9B93F3F0682250B6CF8331B7EE68FD8:9545824\r\n\
+                    0018A45C4D1DEF81644B54AB7F969B88D65:0\r\n";
+        assert_eq!(count_in_range(body, "1E4C9B93F3F0682250B6CF8331B7EE68FD8"), 9545824);
+        assert_eq!(count_in_range(body, "1e4c9b93f3f0682250b6cf8331b7ee68fd8"), 9545824);
+        assert_eq!(count_in_range(body, "0018A45C4D1DEF81644B54AB7F969B88D65"), 0);
+        assert_eq!(count_in_range(body, "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF"), 0);
+    }
+
+    #[test]
+    fn test_offline_filter() {
+        let corpus = "5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8:9545824\r\nletmein\n\n";
+        let (filter, added) = Filter::build(corpus.as_bytes(), 10, 0.001, false).unwrap();
+        assert_eq!(added, 2);
+        assert!(filter.contains("password") && filter.contains("letmein"));
+        assert!(!filter.contains("correct horse battery staple"));
+        // --plain hashes the hash line itself
+        let (plain, _) = Filter::build(corpus.as_bytes(), 10, 0.001, true).unwrap();
+        assert!(!plain.contains("password"));
+        assert_eq!(corpus_hash("zz", false), corpus_hash("zz", true));
+    }
+}
@@ -0,0 +1,101 @@
+use anyhow::{anyhow, Result};
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use qrcode::render::unicode::Dense1x2;
+use qrcode::{Color, QrCode};
+use std::io::Write;
+use std::path::Path;
+
+/// Pixels per QR module in PNG output
+const MODULE_PX: usize = 8;
+/// Light border around the code, in modules (the spec asks for 4)
+const QUIET_ZONE: usize = 4;
+
+fn encode(text: &str) -> Result<QrCode> {
+    QrCode::new(text.as_bytes()).map_err(|e| anyhow!("can't encode QR code: {}", e))
+}
+
+/// QR code drawn with half-block characters, two modules per line.
+/// Colours are inverted so it scans on a dark terminal background.
+pub fn render_terminal(text: &str) -> Result<String> {
+    Ok(encode(text)?
+        .render::<Dense1x2>()
+        .dark_color(Dense1x2::Light)
+        .light_color(Dense1x2::Dark)
+        .build())
+}
+
+/// Write the QR code as an 8-bit grayscale PNG
+pub fn write_png(text: &str, path: &Path) -> Result<()> {
+    let code = encode(text)?;
+    let modules = code.width();
+    let colors = code.to_colors();
+    let side = (modules + 2 * QUIET_ZONE) * MODULE_PX;
+
+    // Each scanline starts with filter type 0 (none)
+    let mut raw = Vec::with_capacity(side * (side + 1));
+    for y in 0..side {
+        raw.push(0);
+        let my = (y / MODULE_PX).checked_sub(QUIET_ZONE).filter(|m| *m < modules);
+        for x in 0..side {
+            let mx = (x / MODULE_PX).checked_sub(QUIET_ZONE).filter(|m| *m < modules);
+            let dark = matches!((mx, my), (Some(mx), Some(my)) if colors[my * modules + mx] == Color::Dark);
+            raw.push(if dark { 0x00 } else { 0xff });
+        }
+    }
+
+    let mut zlib = ZlibEncoder::new(Vec::new(), Compression::default());
+    zlib.write_all(&raw)?;
+    let data = zlib.finish()?;
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&(side as u32).to_be_bytes());
+    header.extend_from_slice(&(side as u32).to_be_bytes());
+    // bit depth 8, grayscale, deflate, adaptive filtering, no interlace
+    header.extend_from_slice(&[8, 0, 0, 0, 0]);
+
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+    push_chunk(&mut png, b"IHDR", &header);
+    push_chunk(&mut png, b"IDAT", &data);
+    push_chunk(&mut png, b"IEND", &[]);
+    std::fs::write(path, png)?;
+    Ok(())
+}
+
+fn push_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let mut crc = crc32fast::Hasher::new();
+    crc.update(kind);
+    crc.update(data);
+    png.extend_from_slice(&crc.finalize().to_be_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_terminal_render() {
+        let art = render_terminal("HappyTiger42!").unwrap();
+        assert!(art.lines().count() > 10);
+        assert!(art.contains('█') || art.contains('▀'));
+    }
+
+    #[test]
+    fn test_png_structure() {
+        let path = std::env::temp_dir().join(format!("jigsaw_qr_{}.png", std::process::id()));
+        write_png("HappyTiger42!", &path).unwrap();
+        let png = std::fs::read(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+        assert_eq!(&png[12..16], b"IHDR");
+        let side = u32::from_be_bytes(png[16..20].try_into().unwrap()) as usize;
+        // Version 1 is 21 modules wide
+        assert_eq!(side % MODULE_PX, 0);
+        assert!(side >= (21 + 2 * QUIET_ZONE) * MODULE_PX);
+        assert!(png.ends_with(&[0xae, 0x42, 0x60, 0x82]));
+    }
+}
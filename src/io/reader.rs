@@ -0,0 +1,170 @@
+use anyhow::Result;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Cursor, PipeReader, Read, Seek, Write};
+use std::path::Path;
+use std::thread::{self, JoinHandle};
+
+// ═══════════════════════════════════════════════════════════════
+// INPUT
+// ═══════════════════════════════════════════════════════════════
+//
+// Breach corpora almost always ship compressed, so every input (Markov
+// training, --wordlist, --blend) goes through `open`, which sniffs the
+// magic bytes rather than trusting the extension: gzip, zstd and xz are
+// decoded as a stream, and the files inside a .zip are read one after
+// another. A path of `-` reads stdin, so lists can be piped in
+// (`zcat leak.gz | jigsaw --train -`).
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+const XZ_MAGIC: &[u8] = &[0xfd, b'7', b'z', b'X', b'Z', 0x00];
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+
+/// Whether an input path means stdin
+pub fn is_stdin(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+/// Open an input file (or stdin for `-`), transparently decompressing
+/// .gz/.zst/.xz/.zip
+pub fn open(path: &Path) -> Result<Box<dyn BufRead + Send>> {
+    if is_stdin(path) {
+        return decode(Box::new(io::stdin()));
+    }
+    let mut file = BufReader::new(File::open(path)?);
+    if file.fill_buf()?.starts_with(ZIP_MAGIC) {
+        return Ok(Box::new(BufReader::new(unzip(File::open(path)?)?)));
+    }
+    decode(Box::new(file))
+}
+
+/// Decompress a stream by its magic bytes. A zip archive's directory is at
+/// its end, so one that can't be seeked (stdin) is read into memory first.
+fn decode(input: Box<dyn Read + Send>) -> Result<Box<dyn BufRead + Send>> {
+    let mut input = BufReader::new(input);
+    let magic = input.fill_buf()?;
+
+    Ok(if magic.starts_with(GZIP_MAGIC) {
+        Box::new(BufReader::new(flate2::bufread::MultiGzDecoder::new(input)))
+    } else if magic.starts_with(ZSTD_MAGIC) {
+        Box::new(BufReader::new(zstd::Decoder::with_buffer(input)?))
+    } else if magic.starts_with(XZ_MAGIC) {
+        Box::new(BufReader::new(liblzma::bufread::XzDecoder::new_multi_decoder(input)))
+    } else if magic.starts_with(ZIP_MAGIC) {
+        let mut archive = Vec::new();
+        input.read_to_end(&mut archive)?;
+        Box::new(BufReader::new(unzip(Cursor::new(archive))?))
+    } else {
+        Box::new(input)
+    })
+}
+
+/// Read a wordlist, one entry per line. Blank lines and lines that aren't
+/// valid UTF-8 (common in leaked lists) are skipped; CRLF is handled.
+pub fn read_words(path: &Path) -> Result<Vec<String>> {
+    words(open(path)?)
+}
+
+fn words(reader: impl BufRead) -> Result<Vec<String>> {
+    let mut words = Vec::new();
+    for line in reader.split(b'\n') {
+        let Ok(line) = String::from_utf8(line?) else { continue };
+        let word = line.trim_end_matches('\r').trim();
+        if !word.is_empty() {
+            words.push(word.to_string());
+        }
+    }
+    Ok(words)
+}
+
+/// The files of a zip archive, concatenated. Entries borrow the archive,
+/// so a thread walks them and streams the bytes through a pipe.
+struct Unzip {
+    pipe: PipeReader,
+    handle: Option<JoinHandle<io::Result<()>>>,
+}
+
+fn unzip<R: Read + Seek + Send + 'static>(archive: R) -> Result<Unzip> {
+    let mut archive = zip::ZipArchive::new(archive)?;
+    let (pipe, mut writer) = io::pipe()?;
+    let handle = thread::spawn(move || {
+        for index in 0..archive.len() {
+            let mut entry = archive.by_index(index).map_err(io::Error::other)?;
+            if entry.is_dir() {
+                continue;
+            }
+            io::copy(&mut entry, &mut writer)?;
+            // Keep the last line of one file off the first line of the next
+            writer.write_all(b"\n")?;
+        }
+        Ok(())
+    });
+    Ok(Unzip { pipe, handle: Some(handle) })
+}
+
+impl Read for Unzip {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.pipe.read(buf)?;
+        if n == 0 && !buf.is_empty() {
+            // The pipe closed: surface a corrupt archive instead of a short read
+            if let Some(handle) = self.handle.take() {
+                handle.join().map_err(|_| io::Error::other("zip reader panicked"))??;
+            }
+        }
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compressed_input() {
+        let dir = std::env::temp_dir();
+        let id = std::process::id();
+        let text = b"alpha\r\nbeta\n\xff\xfe\n\ngamma";
+        let expected = vec!["alpha", "beta", "gamma"];
+
+        // Extensions are deliberately wrong: the format comes from the magic bytes
+        let plain = dir.join(format!("jigsaw_reader_{}.gz", id));
+        std::fs::write(&plain, text).unwrap();
+
+        let gz = dir.join(format!("jigsaw_reader_{}.txt", id));
+        let mut encoder = flate2::write::GzEncoder::new(File::create(&gz).unwrap(), flate2::Compression::fast());
+        encoder.write_all(text).unwrap();
+        encoder.finish().unwrap();
+
+        let zst = dir.join(format!("jigsaw_reader_{}.zst", id));
+        std::fs::write(&zst, zstd::encode_all(&text[..], 3).unwrap()).unwrap();
+
+        let xz = dir.join(format!("jigsaw_reader_{}.xz", id));
+        let mut encoder = liblzma::write::XzEncoder::new(File::create(&xz).unwrap(), 6);
+        encoder.write_all(text).unwrap();
+        encoder.finish().unwrap();
+
+        for path in [&plain, &gz, &zst, &xz] {
+            assert_eq!(read_words(path).unwrap(), expected, "{:?}", path);
+            let _ = std::fs::remove_file(path);
+        }
+
+        let zip = dir.join(format!("jigsaw_reader_{}.zip", id));
+        let mut archive = zip::ZipWriter::new(File::create(&zip).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        archive.add_directory("lists/", options).unwrap();
+        archive.start_file("lists/a.txt", options).unwrap();
+        archive.write_all(b"alpha\nbeta").unwrap();
+        archive.start_file("lists/b.txt", options).unwrap();
+        archive.write_all(b"gamma\n").unwrap();
+        archive.finish().unwrap();
+        assert_eq!(read_words(&zip).unwrap(), expected);
+
+        // Piped input is decoded the same way, zip archives included
+        let piped = [std::fs::read(&zip).unwrap(), zstd::encode_all(&text[..], 3).unwrap()];
+        for bytes in piped {
+            assert_eq!(words(decode(Box::new(Cursor::new(bytes))).unwrap()).unwrap(), expected);
+        }
+        assert!(is_stdin(Path::new("-")) && !is_stdin(Path::new("./-")));
+        let _ = std::fs::remove_file(zip);
+    }
+}
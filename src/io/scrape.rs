@@ -0,0 +1,311 @@
+use anyhow::Result;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::Duration;
+
+// ═══════════════════════════════════════════════════════════════
+// CONFIGURATION
+// ═══════════════════════════════════════════════════════════════
+
+#[derive(Debug, Clone)]
+pub struct ScrapeConfig {
+    /// Link depth to follow from the start page (0 = start page only)
+    pub depth: usize,
+    /// Hard cap on fetched pages
+    pub max_pages: usize,
+    pub min_word_len: usize,
+    pub max_word_len: usize,
+    /// Drop words seen fewer times than this across the crawl
+    pub min_count: usize,
+    /// Follow links to other hosts
+    pub offsite: bool,
+    pub lowercase: bool,
+}
+
+impl Default for ScrapeConfig {
+    fn default() -> Self {
+        Self {
+            depth: 1,
+            max_pages: 50,
+            min_word_len: 3,
+            max_word_len: 24,
+            min_count: 1,
+            offsite: false,
+            lowercase: false,
+        }
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════
+// CRAWLER
+// ═══════════════════════════════════════════════════════════════
+
+/// Breadth-first crawl from `start`, returning words with their frequency,
+/// most frequent first. Pages that fail to load are skipped.
+pub fn crawl(start: &str, config: &ScrapeConfig) -> Result<Vec<(String, usize)>> {
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(Some(Duration::from_secs(15)))
+        .build()
+        .into();
+
+    let start_host = host_of(start).unwrap_or_default();
+    let mut queue: VecDeque<(String, usize)> = VecDeque::from([(start.to_string(), 0)]);
+    let mut visited: HashSet<String> = HashSet::new();
+    let mut counts: HashMap<String, usize> = HashMap::new();
+
+    while let Some((url, depth)) = queue.pop_front() {
+        if visited.len() >= config.max_pages {
+            break;
+        }
+        if !visited.insert(url.clone()) {
+            continue;
+        }
+
+        let html = match fetch(&agent, &url) {
+            Ok(Some(body)) => body,
+            Ok(None) => continue,
+            Err(e) => {
+                eprintln!("  [-] {}: {}", url, e);
+                continue;
+            }
+        };
+        eprintln!("  [+] {} (depth {})", url, depth);
+
+        count_words(&extract_text(&html), config, &mut counts);
+
+        if depth < config.depth {
+            for link in extract_links(&html, &url) {
+                if !config.offsite && host_of(&link).as_deref() != Some(start_host.as_str()) {
+                    continue;
+                }
+                if !visited.contains(&link) {
+                    queue.push_back((link, depth + 1));
+                }
+            }
+        }
+    }
+
+    let mut words: Vec<(String, usize)> = counts.into_iter()
+        .filter(|(_, n)| *n >= config.min_count)
+        .collect();
+    words.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    Ok(words)
+}
+
+/// Fetch a page, returning `None` for non-HTML responses
+fn fetch(agent: &ureq::Agent, url: &str) -> Result<Option<String>> {
+    let mut response = agent.get(url).call()?;
+    let is_html = response.headers()
+        .get("content-type")
+        .and_then(|v| v.to_str().ok())
+        .map(|ct| ct.contains("html") || ct.starts_with("text/"))
+        .unwrap_or(true);
+    if !is_html {
+        return Ok(None);
+    }
+    let body = response.body_mut()
+        .with_config()
+        .limit(5 * 1024 * 1024)
+        .read_to_string()?;
+    Ok(Some(body))
+}
+
+// ═══════════════════════════════════════════════════════════════
+// HTML HANDLING
+// ═══════════════════════════════════════════════════════════════
+
+/// Strip tags, scripts, styles and comments, keeping visible text plus
+/// the `alt`/`title`/meta content attributes CeWL also mines.
+pub fn extract_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len() / 2);
+    let lower = html.to_ascii_lowercase();
+    let mut i = 0;
+
+    while i < html.len() {
+        if lower[i..].starts_with("<!--") {
+            i = lower[i..].find("-->").map(|p| i + p + 3).unwrap_or(html.len());
+        } else if lower[i..].starts_with("<script") || lower[i..].starts_with("<style") {
+            let close = if lower[i..].starts_with("<script") { "</script" } else { "</style" };
+            i = lower[i..].find(close).map(|p| i + p + close.len()).unwrap_or(html.len());
+            i = lower[i..].find('>').map(|p| i + p + 1).unwrap_or(html.len());
+        } else if html.as_bytes()[i] == b'<' {
+            let end = lower[i..].find('>').map(|p| i + p + 1).unwrap_or(html.len());
+            let tag = &html[i..end];
+            for attr in ["alt", "title", "content"] {
+                if let Some(value) = attribute(tag, attr) {
+                    text.push(' ');
+                    text.push_str(&value);
+                }
+            }
+            text.push(' ');
+            i = end;
+        } else {
+            let end = html[i..].find('<').map(|p| i + p).unwrap_or(html.len());
+            text.push_str(&decode_entities(&html[i..end]));
+            i = end;
+        }
+    }
+
+    text
+}
+
+/// Absolute http(s) links found in `href` attributes
+pub fn extract_links(html: &str, base: &str) -> Vec<String> {
+    let mut links = Vec::new();
+    let lower = html.to_ascii_lowercase();
+    let mut pos = 0;
+
+    while let Some(offset) = lower[pos..].find("<a") {
+        let start = pos + offset;
+        let end = lower[start..].find('>').map(|p| start + p + 1).unwrap_or(html.len());
+        if let Some(href) = attribute(&html[start..end], "href") {
+            if let Some(url) = resolve_url(base, &href) {
+                links.push(url);
+            }
+        }
+        pos = end;
+    }
+
+    links.sort();
+    links.dedup();
+    links
+}
+
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let lower = tag.to_ascii_lowercase();
+    let needle = format!("{}=", name);
+    let mut search = 0;
+    while let Some(offset) = lower[search..].find(&needle) {
+        let at = search + offset;
+        search = at + needle.len();
+        // Must be a whole attribute name, not the tail of another one
+        if at > 0 && !lower.as_bytes()[at - 1].is_ascii_whitespace() {
+            continue;
+        }
+        let rest = &tag[at + needle.len()..];
+        let value = match rest.chars().next() {
+            Some(q @ ('"' | '\'')) => rest[1..].split(q).next().unwrap_or(""),
+            _ => rest.split(|c: char| c.is_whitespace() || c == '>').next().unwrap_or(""),
+        };
+        return Some(decode_entities(value));
+    }
+    None
+}
+
+fn decode_entities(s: &str) -> String {
+    s.replace("&nbsp;", " ")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+// ═══════════════════════════════════════════════════════════════
+// URL HELPERS
+// ═══════════════════════════════════════════════════════════════
+
+fn host_of(url: &str) -> Option<String> {
+    let rest = url.split_once("://")?.1;
+    let host = rest.split(['/', '?', '#']).next()?;
+    Some(host.to_ascii_lowercase())
+}
+
+/// Resolve an href against the page URL, dropping fragments and
+/// non-http schemes (mailto:, javascript:, …)
+fn resolve_url(base: &str, href: &str) -> Option<String> {
+    let href = href.trim().split('#').next()?;
+    if href.is_empty() {
+        return None;
+    }
+
+    let (scheme, rest) = base.split_once("://")?;
+    let host = rest.split(['/', '?', '#']).next()?;
+
+    let url = if href.starts_with("http://") || href.starts_with("https://") {
+        href.to_string()
+    } else if let Some(stripped) = href.strip_prefix("//") {
+        format!("{}://{}", scheme, stripped)
+    } else if href.starts_with('/') {
+        format!("{}://{}{}", scheme, host, href)
+    } else if href.contains(':') {
+        return None;
+    } else {
+        let path = &rest[host.len()..];
+        let path = path.split(['?', '#']).next().unwrap_or("");
+        let dir = match path.rfind('/') {
+            Some(p) => &path[..=p],
+            None => "/",
+        };
+        format!("{}://{}{}{}", scheme, host, dir, href)
+    };
+    Some(url)
+}
+
+// ═══════════════════════════════════════════════════════════════
+// WORD COUNTING
+// ═══════════════════════════════════════════════════════════════
+
+pub fn count_words(text: &str, config: &ScrapeConfig, counts: &mut HashMap<String, usize>) {
+    for word in text.split(|c: char| !(c.is_alphanumeric() || c == '-' || c == '\'')) {
+        let word = word.trim_matches(|c| c == '-' || c == '\'');
+        let len = word.chars().count();
+        if len < config.min_word_len || len > config.max_word_len {
+            continue;
+        }
+        // Pure numbers are noise here; dates/numbers belong in their own fields
+        if word.chars().all(|c| c.is_ascii_digit()) {
+            continue;
+        }
+        let word = if config.lowercase { word.to_lowercase() } else { word.to_string() };
+        *counts.entry(word).or_insert(0) += 1;
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════
+// TESTS
+// ═══════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_text_skips_scripts() {
+        let html = "<html><head><style>.a{}</style><script>var secret=1;</script></head>\
+                    <body><!-- hidden --><h1>Acme&amp;Sons</h1><img alt=\"Rocket\" src=x.png></body></html>";
+        let text = extract_text(html);
+        assert!(text.contains("Acme&Sons"));
+        assert!(text.contains("Rocket"));
+        assert!(!text.contains("secret"));
+        assert!(!text.contains("hidden"));
+    }
+
+    #[test]
+    fn test_extract_links() {
+        let html = "<a href=\"/about\">A</a><a class=x href='team.html#top'>B</a>\
+                    <a href=\"mailto:x@y.z\">C</a><a href=\"https://other.org/\">D</a>";
+        let links = extract_links(html, "https://acme.com/blog/post");
+        assert!(links.contains(&"https://acme.com/about".to_string()));
+        assert!(links.contains(&"https://acme.com/blog/team.html".to_string()));
+        assert!(links.contains(&"https://other.org/".to_string()));
+        assert_eq!(links.len(), 3);
+    }
+
+    #[test]
+    fn test_count_words_filters() {
+        let config = ScrapeConfig { min_word_len: 4, lowercase: true, ..Default::default() };
+        let mut counts = HashMap::new();
+        count_words("The Rocket rocket launch 2024 is go", &config, &mut counts);
+        assert_eq!(counts.get("rocket"), Some(&2));
+        assert_eq!(counts.get("launch"), Some(&1));
+        assert!(!counts.contains_key("the"));
+        assert!(!counts.contains_key("2024"));
+    }
+
+    #[test]
+    fn test_host_of() {
+        assert_eq!(host_of("https://Acme.com/x?y"), Some("acme.com".to_string()));
+        assert_eq!(host_of("not a url"), None);
+    }
+}
@@ -0,0 +1,178 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// ═══════════════════════════════════════════════════════════════
+// EXTERNAL MERGE SORT
+// ═══════════════════════════════════════════════════════════════
+//
+// Candidates are buffered until the memory budget is reached, then
+// sorted, deduplicated and spilled to a run file in the temp directory.
+// `finish` k-way merges the runs, dropping duplicates across runs
+// unless the sorter was told to keep them.
+// Run files hold length-prefixed records, so candidates may contain
+// newlines or NULs.
+
+/// Rough per-candidate overhead of a `Vec<u8>` in the buffer
+const ENTRY_OVERHEAD: usize = std::mem::size_of::<Vec<u8>>();
+
+static SORTERS: AtomicUsize = AtomicUsize::new(0);
+
+pub struct ExternalSorter {
+    buffer: Vec<Vec<u8>>,
+    buffered_bytes: usize,
+    memory_limit: usize,
+    dir: PathBuf,
+    runs: Vec<PathBuf>,
+    /// Duplicates dropped while sorting runs
+    duplicates: u64,
+    keep_duplicates: bool,
+}
+
+impl ExternalSorter {
+    /// Sorter that spills once roughly `memory_limit` bytes are buffered
+    pub fn new(memory_limit: usize) -> Self {
+        let id = SORTERS.fetch_add(1, Ordering::Relaxed);
+        let dir = std::env::temp_dir().join(format!("jigsaw-sort-{}-{}", std::process::id(), id));
+        Self { buffer: Vec::new(), buffered_bytes: 0, memory_limit: memory_limit.max(1), dir, runs: Vec::new(), duplicates: 0, keep_duplicates: false }
+    }
+
+    /// Emit repeated candidates as often as they were pushed
+    pub fn keep_duplicates(mut self) -> Self {
+        self.keep_duplicates = true;
+        self
+    }
+
+    pub fn push(&mut self, candidate: Vec<u8>) -> io::Result<()> {
+        self.buffered_bytes += candidate.len() + ENTRY_OVERHEAD;
+        self.buffer.push(candidate);
+        if self.buffered_bytes >= self.memory_limit {
+            self.spill()?;
+        }
+        Ok(())
+    }
+
+    /// Number of runs written to disk so far
+    pub fn runs(&self) -> usize {
+        self.runs.len()
+    }
+
+    fn sort_buffer(&mut self) {
+        let before = self.buffer.len();
+        self.buffer.sort_unstable();
+        if !self.keep_duplicates {
+            self.buffer.dedup();
+        }
+        self.duplicates += (before - self.buffer.len()) as u64;
+        self.buffered_bytes = 0;
+    }
+
+    fn spill(&mut self) -> io::Result<()> {
+        self.sort_buffer();
+        fs::create_dir_all(&self.dir)?;
+        let path = self.dir.join(format!("run-{:05}", self.runs.len()));
+        let mut out = BufWriter::new(File::create(&path)?);
+        for candidate in self.buffer.drain(..) {
+            out.write_all(&(candidate.len() as u32).to_le_bytes())?;
+            out.write_all(&candidate)?;
+        }
+        out.flush()?;
+        self.runs.push(path);
+        Ok(())
+    }
+
+    /// Feed every distinct candidate, in byte order, to `emit`. Returns
+    /// the number of duplicates dropped.
+    pub fn finish(mut self, mut emit: impl FnMut(&[u8]) -> io::Result<()>) -> io::Result<u64> {
+        self.sort_buffer();
+        if self.runs.is_empty() {
+            for candidate in &self.buffer {
+                emit(candidate)?;
+            }
+            return Ok(self.duplicates);
+        }
+        if !self.buffer.is_empty() {
+            self.spill()?;
+        }
+
+        let mut readers = self.runs.iter()
+            .map(|path| File::open(path).map(BufReader::new))
+            .collect::<io::Result<Vec<_>>>()?;
+        let mut heap = BinaryHeap::new();
+        for (run, reader) in readers.iter_mut().enumerate() {
+            if let Some(candidate) = read_record(reader)? {
+                heap.push(Reverse((candidate, run)));
+            }
+        }
+
+        let mut last: Option<Vec<u8>> = None;
+        while let Some(Reverse((candidate, run))) = heap.pop() {
+            if let Some(next) = read_record(&mut readers[run])? {
+                heap.push(Reverse((next, run)));
+            }
+            if !self.keep_duplicates && last.as_ref() == Some(&candidate) {
+                self.duplicates += 1;
+                continue;
+            }
+            emit(&candidate)?;
+            last = Some(candidate);
+        }
+        Ok(self.duplicates)
+    }
+}
+
+impl Drop for ExternalSorter {
+    fn drop(&mut self) {
+        if !self.runs.is_empty() {
+            let _ = fs::remove_dir_all(&self.dir);
+        }
+    }
+}
+
+fn read_record(reader: &mut impl Read) -> io::Result<Option<Vec<u8>>> {
+    let mut len = [0u8; 4];
+    match reader.read_exact(&mut len) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+    let mut candidate = vec![0; u32::from_le_bytes(len) as usize];
+    reader.read_exact(&mut candidate)?;
+    Ok(Some(candidate))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spilled_merge() {
+        // A tiny budget forces a run file every couple of candidates
+        let mut sorter = ExternalSorter::new(64);
+        for word in ["pear", "apple", "fig", "apple", "kiwi", "pear", "banana", "fig", "a\nb"] {
+            sorter.push(word.as_bytes().to_vec()).unwrap();
+        }
+        assert!(sorter.runs() > 1);
+        let dir = sorter.dir.clone();
+
+        let mut out = Vec::new();
+        let duplicates = sorter.finish(|c| { out.push(String::from_utf8_lossy(c).to_string()); Ok(()) }).unwrap();
+        assert_eq!(out, vec!["a\nb", "apple", "banana", "fig", "kiwi", "pear"]);
+        assert_eq!(duplicates, 3);
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn test_keep_duplicates() {
+        let mut sorter = ExternalSorter::new(16).keep_duplicates();
+        for word in ["pear", "fig", "pear", "fig", "pear"] {
+            sorter.push(word.as_bytes().to_vec()).unwrap();
+        }
+        let mut out = Vec::new();
+        assert_eq!(sorter.finish(|c| { out.push(c.to_vec()); Ok(()) }).unwrap(), 0);
+        assert_eq!(out, [&b"fig"[..], b"fig", b"pear", b"pear", b"pear"]);
+    }
+}
@@ -0,0 +1,143 @@
+use anyhow::{anyhow, Result};
+use std::io::Write;
+use std::process::{Child, Command, Stdio};
+use std::thread::{self, JoinHandle};
+use ureq::SendBody;
+
+// ═══════════════════════════════════════════════════════════════
+// REMOTE OUTPUT
+// ═══════════════════════════════════════════════════════════════
+//
+// Streams output off the machine while it is generated, so nothing
+// lands on local disk. http(s) targets get a chunked PUT (pre-signed S3,
+// GCS or Azure URLs, WebDAV, any upload endpoint); s3:// targets are
+// handed to `aws s3 cp -`, which does the multipart upload and picks up
+// the usual AWS credentials.
+
+/// Whether `target` names a remote destination rather than a local path
+pub fn is_remote(target: &str) -> bool {
+    ["http://", "https://", "s3://"].iter().any(|scheme| target.starts_with(scheme))
+}
+
+/// An upload in flight; `wait` once the writer half is closed
+pub enum Upload {
+    Http { url: String, handle: JoinHandle<Result<u16>> },
+    S3 { url: String, child: Child },
+}
+
+/// Start uploading to `url`, returning the stream to write the body into
+pub fn open(url: &str) -> Result<(Box<dyn Write + Send>, Upload)> {
+    if url.starts_with("s3://") {
+        let mut child = Command::new("aws")
+            .args(["s3", "cp", "-", url])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .spawn()
+            .map_err(|e| anyhow!("s3 output needs the AWS CLI ('aws'): {}", e))?;
+        let stdin = child.stdin.take().ok_or_else(|| anyhow!("no stdin for 'aws s3 cp'"))?;
+        return Ok((Box::new(stdin), Upload::S3 { url: url.to_string(), child }));
+    }
+
+    let (reader, writer) = std::io::pipe()?;
+    let target = url.to_string();
+    let handle = thread::spawn(move || {
+        let response = ureq::put(&target)
+            .header("Content-Type", "application/octet-stream")
+            .send(SendBody::from_owned_reader(reader))?;
+        Ok(response.status().as_u16())
+    });
+    Ok((Box::new(writer), Upload::Http { url: url.to_string(), handle }))
+}
+
+/// Upload a complete document (e.g. `--format json` output) to `url`
+pub fn put(url: &str, data: &[u8]) -> Result<()> {
+    let (mut body, upload) = open(url)?;
+    let written = body.write_all(data);
+    drop(body);
+    // A failed upload closes the pipe; report the upload's error first
+    upload.wait()?;
+    Ok(written?)
+}
+
+impl Upload {
+    /// Wait for the remote side to accept the body
+    pub fn wait(self) -> Result<()> {
+        match self {
+            Upload::Http { url, handle } => {
+                let status = handle.join().map_err(|_| anyhow!("upload thread panicked"))?
+                    .map_err(|e| anyhow!("upload to {} failed: {}", url, e))?;
+                crate::status!("  [+] uploaded to {} (HTTP {})", url, status);
+                Ok(())
+            }
+            Upload::S3 { url, mut child } => {
+                let status = child.wait()?;
+                if !status.success() {
+                    return Err(anyhow!("'aws s3 cp' to {} exited with {}", url, status));
+                }
+                crate::status!("  [+] uploaded to {}", url);
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read};
+    use std::net::TcpListener;
+
+    /// Accept one PUT, returning its de-chunked body
+    fn serve_once(listener: TcpListener) -> JoinHandle<Vec<u8>> {
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            let mut chunked = false;
+            loop {
+                line.clear();
+                reader.read_line(&mut line).unwrap();
+                chunked |= line.to_ascii_lowercase().starts_with("transfer-encoding: chunked");
+                if line == "\r\n" {
+                    break;
+                }
+            }
+            assert!(chunked);
+            let mut body = Vec::new();
+            loop {
+                line.clear();
+                reader.read_line(&mut line).unwrap();
+                let size = usize::from_str_radix(line.trim(), 16).unwrap();
+                let mut chunk = vec![0; size + 2];
+                reader.read_exact(&mut chunk).unwrap();
+                if size == 0 {
+                    break;
+                }
+                body.extend_from_slice(&chunk[..size]);
+            }
+            let mut stream = stream;
+            stream.write_all(b"HTTP/1.1 201 Created\r\nContent-Length: 0\r\n\r\n").unwrap();
+            body
+        })
+    }
+
+    #[test]
+    fn test_http_put() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/lists/out.txt", listener.local_addr().unwrap());
+        let server = serve_once(listener);
+
+        assert!(is_remote(&url) && is_remote("s3://bucket/key") && !is_remote("out.txt"));
+        let (mut body, upload) = open(&url).unwrap();
+        body.write_all(b"alpha\nbeta\n").unwrap();
+        drop(body);
+        upload.wait().unwrap();
+        assert_eq!(server.join().unwrap(), b"alpha\nbeta\n");
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/out.json", listener.local_addr().unwrap());
+        let server = serve_once(listener);
+        put(&url, b"{}").unwrap();
+        assert_eq!(server.join().unwrap(), b"{}");
+    }
+}
@@ -0,0 +1,268 @@
+use crate::io::sort::ExternalSorter;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use rayon::prelude::*;
+use std::io::{self, BufRead};
+
+// ═══════════════════════════════════════════════════════════════
+// WORDLIST HYGIENE
+// ═══════════════════════════════════════════════════════════════
+//
+// sort, unique, merge, diff and rank over lists too big for memory. Sorting
+// goes through ExternalSorter; the order-preserving operations tag each
+// line with its position, sort by line to find the first occurrences,
+// then sort those back by position. Lines are bytes compared in byte
+// order (what `LC_ALL=C sort` does); CRs are stripped and blank lines
+// skipped, as everywhere else a wordlist is read.
+
+/// Lines scored at a time, across threads, when ranking
+const SCORE_BATCH: usize = 16_384;
+
+/// Call `f` with every non-empty line of `reader`
+fn for_each_line(reader: impl BufRead, mut f: impl FnMut(Vec<u8>) -> io::Result<()>) -> io::Result<()> {
+    for line in reader.split(b'\n') {
+        let mut line = line?;
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+        if !line.is_empty() {
+            f(line)?;
+        }
+    }
+    Ok(())
+}
+
+/// Every line of `inputs` in byte order, repeats dropped if `unique`
+pub fn sort<R: BufRead>(inputs: Vec<R>, memory_limit: usize, unique: bool, emit: impl FnMut(&[u8]) -> io::Result<()>) -> io::Result<()> {
+    let mut sorter = ExternalSorter::new(memory_limit);
+    if !unique {
+        sorter = sorter.keep_duplicates();
+    }
+    for input in inputs {
+        for_each_line(input, |line| sorter.push(line))?;
+    }
+    sorter.finish(emit).map(|_| ())
+}
+
+/// Each distinct line of `inputs` once, where it first appeared
+pub fn unique<R: BufRead>(inputs: Vec<R>, memory_limit: usize, emit: impl FnMut(&[u8]) -> io::Result<()>) -> io::Result<()> {
+    first_occurrences(inputs, Vec::new(), memory_limit, |_| true, emit)
+}
+
+/// Distinct lines of `left` that are missing from `right` (or, with
+/// `common`, present in it), in `left`'s order
+pub fn diff<R: BufRead>(left: R, right: R, memory_limit: usize, common: bool, emit: impl FnMut(&[u8]) -> io::Result<()>) -> io::Result<()> {
+    first_occurrences(vec![left], vec![right], memory_limit, |in_right| in_right == common, emit)
+}
+
+// A record is the line behind a fixed-width length, so no line's key is
+// a prefix of another's and sorting by record sorts by line first. A
+// tag follows: excluded lines (tag 0) sort ahead of the numbered
+// occurrences (tag 1), ordered by position.
+const EXCLUDED: u8 = 0;
+const OCCURRENCE: u8 = 1;
+
+fn record(line: &[u8], tag: u8, position: u64) -> Vec<u8> {
+    let mut record = Vec::with_capacity(line.len() + 13);
+    record.extend_from_slice(&(line.len() as u32).to_be_bytes());
+    record.extend_from_slice(line);
+    record.push(tag);
+    if tag == OCCURRENCE {
+        record.extend_from_slice(&position.to_be_bytes());
+    }
+    record
+}
+
+fn first_occurrences<R: BufRead>(
+    inputs: Vec<R>,
+    excluded: Vec<R>,
+    memory_limit: usize,
+    keep: impl Fn(bool) -> bool,
+    mut emit: impl FnMut(&[u8]) -> io::Result<()>,
+) -> io::Result<()> {
+    // Both sorters hold a buffer while the first drains into the second
+    let memory_limit = memory_limit / 2;
+    let mut by_line = ExternalSorter::new(memory_limit);
+    for input in excluded {
+        for_each_line(input, |line| by_line.push(record(&line, EXCLUDED, 0)))?;
+    }
+    let mut position = 0u64;
+    for input in inputs {
+        for_each_line(input, |line| {
+            position += 1;
+            by_line.push(record(&line, OCCURRENCE, position))
+        })?;
+    }
+
+    let mut by_position = ExternalSorter::new(memory_limit);
+    let mut current: Vec<u8> = Vec::new();
+    let (mut in_excluded, mut seen) = (false, false);
+    by_line.finish(|record| {
+        let key_len = 4 + u32::from_be_bytes(record[..4].try_into().unwrap()) as usize;
+        let (key, tag) = (&record[..key_len], record[key_len]);
+        if key != current.as_slice() {
+            current = key.to_vec();
+            (in_excluded, seen) = (false, false);
+        }
+        if tag == EXCLUDED {
+            in_excluded = true;
+        } else if !seen {
+            seen = true;
+            if keep(in_excluded) {
+                let mut entry = record[key_len + 1..].to_vec();
+                entry.extend_from_slice(&key[4..]);
+                by_position.push(entry)?;
+            }
+        }
+        Ok(())
+    })?;
+    by_position.finish(|entry| emit(&entry[8..])).map(|_| ())
+}
+
+/// Union of `inputs` that are each already in byte order, streamed
+/// without sorting; repeats are dropped
+pub fn merge<R: BufRead>(inputs: Vec<R>, mut emit: impl FnMut(&[u8]) -> io::Result<()>) -> io::Result<()> {
+    let mut lines: Vec<_> = inputs.into_iter().map(|input| input.split(b'\n')).collect();
+    let count = lines.len();
+    let mut next = |input: usize| -> io::Result<Option<Vec<u8>>> {
+        for line in lines[input].by_ref() {
+            let mut line = line?;
+            if line.last() == Some(&b'\r') {
+                line.pop();
+            }
+            if !line.is_empty() {
+                return Ok(Some(line));
+            }
+        }
+        Ok(None)
+    };
+
+    let mut heap = BinaryHeap::new();
+    for input in 0..count {
+        if let Some(line) = next(input)? {
+            heap.push(Reverse((line, input)));
+        }
+    }
+    let mut last: Option<Vec<u8>> = None;
+    while let Some(Reverse((line, input))) = heap.pop() {
+        if let Some(following) = next(input)? {
+            if following < line {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, format!(
+                    "input {} is not sorted ({:?} comes after {:?}); sort it first",
+                    input + 1, String::from_utf8_lossy(&following), String::from_utf8_lossy(&line),
+                )));
+            }
+            heap.push(Reverse((following, input)));
+        }
+        if last.as_ref() != Some(&line) {
+            emit(&line)?;
+            last = Some(line);
+        }
+    }
+    Ok(())
+}
+
+/// Every line of `inputs`, highest `score` first, with its score; equal
+/// scores keep their input order
+pub fn rank<R: BufRead>(
+    inputs: Vec<R>,
+    memory_limit: usize,
+    score: impl Fn(&[u8]) -> f64 + Sync,
+    mut emit: impl FnMut(f64, &[u8]) -> io::Result<()>,
+) -> io::Result<()> {
+    // Records are the score's descending key, the position, then the line
+    let mut sorter = ExternalSorter::new(memory_limit);
+    let mut batch: Vec<Vec<u8>> = Vec::with_capacity(SCORE_BATCH);
+    let mut position = 0u64;
+    let mut flush = |batch: &mut Vec<Vec<u8>>, sorter: &mut ExternalSorter| -> io::Result<()> {
+        let scores: Vec<f64> = batch.par_iter().map(|line| score(line)).collect();
+        for (line, score) in batch.drain(..).zip(scores) {
+            let mut record = Vec::with_capacity(line.len() + 16);
+            record.extend_from_slice(&descending_key(score));
+            record.extend_from_slice(&position.to_be_bytes());
+            record.extend_from_slice(&line);
+            sorter.push(record)?;
+            position += 1;
+        }
+        Ok(())
+    };
+    for input in inputs {
+        for_each_line(input, |line| {
+            batch.push(line);
+            if batch.len() == SCORE_BATCH {
+                flush(&mut batch, &mut sorter)?;
+            }
+            Ok(())
+        })?;
+    }
+    flush(&mut batch, &mut sorter)?;
+    sorter.finish(|record| emit(score_of(record[..8].try_into().unwrap()), &record[16..])).map(|_| ())
+}
+
+/// Sort key for `score` whose byte order runs from highest score to lowest
+fn descending_key(score: f64) -> [u8; 8] {
+    let bits = score.to_bits();
+    // Flip so that unsigned order matches float order, then invert it
+    let ascending = if bits >> 63 == 1 { !bits } else { bits | 1 << 63 };
+    (!ascending).to_be_bytes()
+}
+
+/// The score a `descending_key` was made from
+fn score_of(key: [u8; 8]) -> f64 {
+    let ascending = !u64::from_be_bytes(key);
+    f64::from_bits(if ascending >> 63 == 1 { ascending & !(1 << 63) } else { !ascending })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect(run: impl FnOnce(&mut dyn FnMut(&[u8]) -> io::Result<()>) -> io::Result<()>) -> Vec<String> {
+        let mut out = Vec::new();
+        run(&mut |line| { out.push(String::from_utf8_lossy(line).into_owned()); Ok(()) }).unwrap();
+        out
+    }
+
+    #[test]
+    fn test_sort_and_unique() {
+        let list = || vec![&b"pear\nfig\r\n\npear\nfi\napple\nfig\n"[..]];
+        // A tiny budget forces spills in every case
+        assert_eq!(collect(|emit| sort(list(), 32, false, emit)), ["apple", "fi", "fig", "fig", "pear", "pear"]);
+        assert_eq!(collect(|emit| sort(list(), 32, true, emit)), ["apple", "fi", "fig", "pear"]);
+        assert_eq!(collect(|emit| unique(list(), 32, emit)), ["pear", "fig", "fi", "apple"]);
+    }
+
+    #[test]
+    fn test_diff() {
+        let (left, right) = (&b"summer\nwinter\nspring\nsummer\nautumn\n"[..], &b"autumn\nsummer\nsum\n"[..]);
+        assert_eq!(collect(|emit| diff(left, right, 64, false, emit)), ["winter", "spring"]);
+        assert_eq!(collect(|emit| diff(left, right, 64, true, emit)), ["summer", "autumn"]);
+    }
+
+    #[test]
+    fn test_merge() {
+        let inputs = vec![&b"apple\nfig\npear\n"[..], b"banana\nfig\n", b""];
+        assert_eq!(collect(|emit| merge(inputs, emit)), ["apple", "banana", "fig", "pear"]);
+        assert!(merge(vec![&b"pear\napple\n"[..]], |_| Ok(())).is_err());
+    }
+
+    #[test]
+    fn test_rank() {
+        let list = vec![&b"bb\na\ncccc\nddd\ne\n"[..]];
+        let mut out = Vec::new();
+        // Shorter is better; ties keep their order
+        rank(list, 32, |line| -(line.len() as f64), |score, line| {
+            out.push((score, String::from_utf8_lossy(line).into_owned()));
+            Ok(())
+        }).unwrap();
+        let expected = [(-1.0, "a"), (-1.0, "e"), (-2.0, "bb"), (-3.0, "ddd"), (-4.0, "cccc")];
+        assert_eq!(out, expected.map(|(score, line)| (score, line.to_string())));
+
+        let scores = [f64::NEG_INFINITY, -1e300, -40.5, -3.0, -0.0, 0.0, 2.5, 1e300];
+        let keys: Vec<[u8; 8]> = scores.iter().map(|&s| descending_key(s)).collect();
+        assert!(keys.windows(2).all(|pair| pair[0] > pair[1]));
+        for (&score, &key) in scores.iter().zip(&keys) {
+            assert_eq!(score_of(key).to_bits(), score.to_bits());
+        }
+    }
+}
@@ -1,44 +1,1279 @@
-use std::io::{self, Write, BufWriter};
-use std::fs::File;
-use std::path::PathBuf;
+use std::fmt;
+use std::io::{self, Write};
+use std::fs::{File, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::thread;
-use crossbeam_channel::Receiver;
-use anyhow::Result;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use crossbeam_channel::{bounded, Receiver, Sender};
+use anyhow::{anyhow, Result};
+use flate2::write::GzEncoder;
+use std::collections::HashSet;
+use crate::engine::bloom::BloomFilter;
+use crate::engine::filter::Filter;
+use crate::io::sort::ExternalSorter;
+use crate::io::upload::{self, Upload};
+use crate::io::checkpoint;
+use crate::io::manifest;
+use crate::io::parquet::ParquetWriter;
+use crate::invalid;
 
 pub enum Output {
     Stdout,
     File(PathBuf),
+    /// Shell command that reads candidates on stdin
+    Process(String),
+    /// Remote target streamed as it is written (http(s):// or s3://)
+    Upload(String),
+}
+
+/// Streaming compression applied to the output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    /// Guess from a file extension (.gz, .zst)
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("gz") => Compression::Gzip,
+            Some("zst") | Some("zstd") => Compression::Zstd,
+            _ => Compression::None,
+        }
+    }
+}
+
+/// Terminator written after each candidate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    CrLf,
+    /// NUL byte, for `xargs -0` and candidates that contain newlines
+    Nul,
+}
+
+impl LineEnding {
+    pub fn as_bytes(self) -> &'static [u8] {
+        match self {
+            LineEnding::Lf => b"\n",
+            LineEnding::CrLf => b"\r\n",
+            LineEnding::Nul => b"\0",
+        }
+    }
+}
+
+/// How each candidate is framed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RecordFormat {
+    /// Raw bytes plus the line ending
+    #[default]
+    Text,
+    /// JSON Lines: `{"candidate":"..."}` per line (always `\n`-terminated).
+    /// Candidates that aren't UTF-8 are hex-encoded and flagged with
+    /// `"encoding":"hex"`.
+    Ndjson,
+    /// Parquet rows (candidate, length, optional category); see `io::parquet`
+    Parquet,
+}
+
+/// How often an appending writer records its progress
+const CHECKPOINT_EVERY: Duration = Duration::from_secs(1);
+
+/// Bloom filter size when the caller can't predict the candidate count
+const DEFAULT_EXPECTED: u64 = 10_000_000;
+/// Largest count the Bloom filter is sized for (about 300 MB at a 1%
+/// rate); bigger runs share it and see a higher false-positive rate
+const MAX_EXPECTED: u64 = 250_000_000;
+
+/// Drop candidates already written, across batches
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Unique {
+    /// Remembers every candidate
+    Exact,
+    /// Bounded memory; a small fraction of new candidates may be dropped
+    Bloom { expected: Option<u64>, fp_rate: f64 },
+}
+
+enum Seen {
+    Exact(HashSet<Vec<u8>>),
+    Bloom(BloomFilter),
+}
+
+impl Seen {
+    fn new(unique: Unique) -> Self {
+        match unique {
+            Unique::Exact => Seen::Exact(HashSet::new()),
+            Unique::Bloom { expected, fp_rate } => {
+                let expected = expected.unwrap_or(DEFAULT_EXPECTED);
+                if expected > MAX_EXPECTED {
+                    eprintln!("  [!] --unique bloom: sized for {} of ~{} candidates; more will be dropped as false positives",
+                        MAX_EXPECTED, expected);
+                }
+                Seen::Bloom(BloomFilter::with_rate(expected.min(MAX_EXPECTED), fp_rate))
+            }
+        }
+    }
+
+    /// True the first time `candidate` is offered
+    fn first_sighting(&mut self, candidate: &[u8]) -> bool {
+        match self {
+            Seen::Exact(set) => !set.contains(candidate) && set.insert(candidate.to_vec()),
+            Seen::Bloom(filter) => filter.insert(candidate),
+        }
+    }
+}
+
+/// What the writer thread got through
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Stats {
+    /// Candidates written
+    pub candidates: u64,
+    /// Candidates dropped by `unique`
+    pub duplicates: u64,
+    /// Candidates dropped by `filter`
+    pub filtered: u64,
+    /// Bytes that reached the output (after compression)
+    pub bytes: u64,
+    pub elapsed: Duration,
+    /// Local files written (one per period with `roll`)
+    pub files: Vec<PathBuf>,
+}
+
+impl Stats {
+    pub fn per_second(&self) -> f64 {
+        self.candidates as f64 / self.elapsed.as_secs_f64().max(1e-9)
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} candidates, {:.2} MB, {:.0}/s, {:.1}s",
+            self.candidates,
+            self.bytes as f64 / (1024.0 * 1024.0),
+            self.per_second(),
+            self.elapsed.as_secs_f64())?;
+        if self.duplicates > 0 {
+            write!(f, ", {} duplicates dropped", self.duplicates)?;
+        }
+        if self.filtered > 0 {
+            write!(f, ", {} filtered out", self.filtered)?;
+        }
+        Ok(())
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════
+// APPEND / RESUME
+// ═══════════════════════════════════════════════════════════════
+//
+// An appending writer keeps `<output>.ckpt` next to the file, holding
+// how many candidates it had received, how many lines it wrote and the
+// byte offset known to be on disk. A restarted run cuts the file back to
+// that offset (dropping a torn last line) and skips the first `consumed`
+// candidates of the replay, so the engine must replay in the same order:
+// sequential generation, or a fixed seed for random engines.
+
+/// Sidecar checkpoint for an appended output file
+pub fn checkpoint_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".ckpt");
+    PathBuf::from(name)
+}
+
+/// Progress recorded in the sidecar
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Checkpoint {
+    /// Candidates received from the engine, duplicates included
+    pub consumed: u64,
+    /// Lines written to the file
+    pub lines: u64,
+    pub bytes: u64,
+}
+
+impl Checkpoint {
+    /// The checkpoint for the output at `path`, if it has a readable one
+    pub fn load(path: &Path) -> Option<Self> {
+        Self::try_load(path).ok().flatten()
+    }
+
+    /// Like `load`, but a sidecar from a newer jigsaw is an error; a
+    /// damaged one reads as absent, so the file's lines get counted instead
+    fn try_load(path: &Path) -> io::Result<Option<Self>> {
+        let Some(fields) = checkpoint::load(&checkpoint_path(path))? else { return Ok(None) };
+        let (Some(lines), Some(bytes)) = (fields.get("lines"), fields.get("bytes")) else { return Ok(None) };
+        Ok(Some(Checkpoint { consumed: fields.get("consumed").unwrap_or(lines), lines, bytes }))
+    }
+
+    fn save(&self, path: &Path) -> io::Result<()> {
+        checkpoint::save(&checkpoint_path(path), &[
+            ("consumed", self.consumed.to_string()),
+            ("lines", self.lines.to_string()),
+            ("bytes", self.bytes.to_string()),
+        ])
+    }
+}
+
+/// Open `path` for appending at its last checkpoint. Without a sidecar the
+/// existing records are counted (streaming, not held in memory) and a torn
+/// last record is cut off.
+fn reopen(path: &Path, format: RecordFormat, line_ending: LineEnding) -> Result<(File, Checkpoint)> {
+    let mut file = OpenOptions::new().create(true).truncate(false).read(true).write(true).open(path)?;
+    let length = file.metadata()?.len();
+    let checkpoint = match Checkpoint::try_load(path)? {
+        Some(checkpoint) => checkpoint,
+        None => {
+            // Every terminator ends in this byte (\n for lf, crlf and ndjson)
+            let last = match format {
+                RecordFormat::Text => *line_ending.as_bytes().last().unwrap_or(&b'\n'),
+                RecordFormat::Ndjson => b'\n',
+                RecordFormat::Parquet => unreachable!("Parquet output is never appended to"),
+            };
+            let (mut lines, mut bytes, mut offset) = (0, 0, 0);
+            let mut reader = io::BufReader::new(&file);
+            let mut chunk = [0u8; 64 * 1024];
+            loop {
+                let n = io::Read::read(&mut reader, &mut chunk)?;
+                if n == 0 {
+                    break;
+                }
+                for (i, byte) in chunk[..n].iter().enumerate() {
+                    if *byte == last {
+                        lines += 1;
+                        bytes = offset + i as u64 + 1;
+                    }
+                }
+                offset += n as u64;
+            }
+            Checkpoint { consumed: lines, lines, bytes }
+        }
+    };
+    if checkpoint.bytes > length {
+        return Err(invalid!("{} is shorter than its checkpoint ({} < {} bytes)", path.display(), length, checkpoint.bytes));
+    }
+    file.set_len(checkpoint.bytes)?;
+    io::Seek::seek(&mut file, io::SeekFrom::End(0))?;
+    Ok((file, checkpoint))
+}
+
+/// Where an appending run picks up
+struct Resume {
+    path: PathBuf,
+    /// The output, to sync before each checkpoint
+    file: File,
+    checkpoint: Checkpoint,
+    /// Candidates received in this run, skipped ones included
+    received: u64,
+}
+
+impl Resume {
+    /// Record progress once what it claims is on disk, so after a power
+    /// cut the checkpoint never points past the end of the file
+    fn save(&self, stats: &Stats, bytes: u64) -> io::Result<()> {
+        self.file.sync_data()?;
+        Checkpoint {
+            consumed: self.received.max(self.checkpoint.consumed),
+            lines: self.checkpoint.lines + stats.candidates,
+            bytes: self.checkpoint.bytes + bytes,
+        }.save(&self.path)
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════
+// ROLLING OUTPUT
+// ═══════════════════════════════════════════════════════════════
+//
+// Long-running jobs start a new file each hour or day. The period's UTC
+// timestamp goes in front of the extensions (wordlist.txt.gz becomes
+// wordlist-2026-10-16.txt.gz), so compression still follows the name.
+// Restarting inside a period appends to that period's file.
+
+/// How often a rolling output starts a new file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Roll {
+    Hourly,
+    Daily,
+}
+
+impl Roll {
+    fn seconds(self) -> u64 {
+        match self {
+            Roll::Hourly => 3600,
+            Roll::Daily => 86_400,
+        }
+    }
+
+    /// Index of the period that `time` falls in
+    pub fn period(self, time: SystemTime) -> u64 {
+        time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()) / self.seconds()
+    }
+
+    /// The file `base` rolls into for `period`
+    pub fn path(self, base: &Path, period: u64) -> PathBuf {
+        let start = period * self.seconds();
+        let (year, month, day) = civil_date(start / 86_400);
+        let stamp = match self {
+            Roll::Hourly => format!("{:04}-{:02}-{:02}T{:02}", year, month, day, start % 86_400 / 3600),
+            Roll::Daily => format!("{:04}-{:02}-{:02}", year, month, day),
+        };
+        let name = base.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        // Split at the first dot that isn't leading, keeping every extension
+        let name = match name.char_indices().skip(1).find(|&(_, c)| c == '.') {
+            Some((dot, _)) => format!("{}-{}{}", &name[..dot], stamp, &name[dot..]),
+            None => format!("{}-{}", name, stamp),
+        };
+        base.with_file_name(name)
+    }
+}
+
+/// (year, month, day) of a day count since 1970-01-01, proleptic Gregorian
+fn civil_date(days: u64) -> (u64, u64, u64) {
+    // Howard Hinnant's days_from_civil, inverted
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+/// The file a rolling writer has open
+struct Rolling {
+    roll: Roll,
+    base: PathBuf,
+    period: u64,
+    path: PathBuf,
+    /// `stats` counts when this file was opened, for its manifest
+    candidates_before: u64,
+    duplicates_before: u64,
+}
+
+/// Counts bytes on their way to the real output
+struct Counted<W> {
+    inner: W,
+    bytes: Arc<AtomicU64>,
+}
+
+impl<W: Write> Write for Counted<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.bytes.fetch_add(n as u64, Ordering::Relaxed);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// A sink that must be told when the stream ends so compressors can
+/// write their trailer
+trait Sink: Write + Send {
+    fn finish(self: Box<Self>) -> io::Result<()>;
+}
+
+impl<W: Write + Send> Sink for Counted<W> {
+    fn finish(mut self: Box<Self>) -> io::Result<()> {
+        self.flush()
+    }
+}
+
+impl<W: Write + Send> Sink for GzEncoder<W> {
+    fn finish(self: Box<Self>) -> io::Result<()> {
+        (*self).finish()?.flush()
+    }
+}
+
+impl<W: Write + Send> Sink for zstd::Encoder<'static, W> {
+    fn finish(self: Box<Self>) -> io::Result<()> {
+        (*self).finish()?.flush()
+    }
+}
+
+/// Framed records are gathered and handed to the sink in one write once
+/// this many bytes are waiting, or when the writer has caught up with
+/// the generators
+const DRAIN_AT: usize = 256 * 1024;
+
+/// What the pump writes records into: a byte stream, or Parquet rows
+/// encoded onto one
+enum Records {
+    Stream { sink: Box<dyn Sink>, buffer: Vec<u8> },
+    Parquet(Box<ParquetWriter<Box<dyn Sink>>>),
+}
+
+impl Records {
+    /// Hand everything buffered to the sink
+    fn drain(&mut self) -> io::Result<()> {
+        if let Records::Stream { sink, buffer } = self {
+            if !buffer.is_empty() {
+                sink.write_all(buffer)?;
+                buffer.clear();
+            }
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.drain()?;
+        match self {
+            Records::Stream { sink, .. } => sink.flush(),
+            // Row groups go out as they fill
+            Records::Parquet(_) => Ok(()),
+        }
+    }
+
+    fn finish(mut self) -> io::Result<()> {
+        self.drain()?;
+        match self {
+            Records::Stream { sink, .. } => sink.finish(),
+            Records::Parquet(rows) => rows.finish()?.finish(),
+        }
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════
+// BATCHING
+// ═══════════════════════════════════════════════════════════════
+
+/// Candidates as they travel to the writer thread
+pub type Batch = Vec<Vec<u8>>;
+
+/// Candidates per batch handed to the writer thread
+pub const DEFAULT_BATCH_SIZE: usize = 1000;
+/// Batches that may queue before generators block
+pub const DEFAULT_CHANNEL_CAPACITY: usize = 100;
+
+/// The writer thread has stopped; joining it gives the reason
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WriterClosed;
+
+impl fmt::Display for WriterClosed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "writer stopped")
+    }
+}
+
+impl std::error::Error for WriterClosed {}
+
+/// Why `Writer::start` stopped short without anything going wrong
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WriteError {
+    /// The output's reader went away mid-run (`| head`, hashcat done).
+    /// `code` is the `Process` reader's exit status, 0 for stdout; a CLI
+    /// exits with it, as a Unix tool would on SIGPIPE.
+    ReaderClosed { code: i32 },
+}
+
+impl fmt::Display for WriteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WriteError::ReaderClosed { code } => write!(f, "the output's reader closed its input (status {})", code),
+        }
+    }
+}
+
+impl std::error::Error for WriteError {}
+
+/// Channel between generators and a `Writer`
+pub fn channel(capacity: usize) -> (Sender<Batch>, Receiver<Batch>) {
+    bounded(capacity.max(1))
+}
+
+/// Collects candidates into batches for the writer. Cloning gives an
+/// empty buffer on the same channel (one per rayon worker); whatever is
+/// left is sent on drop.
+pub struct BatchSender {
+    buffer: Batch,
+    sender: Sender<Batch>,
+    batch_size: usize,
+    spent: Option<Receiver<Batch>>,
+}
+
+impl BatchSender {
+    pub fn new(sender: Sender<Batch>, batch_size: usize) -> Self {
+        let batch_size = batch_size.max(1);
+        Self { buffer: Vec::with_capacity(batch_size), sender, batch_size, spent: None }
+    }
+
+    /// Take written batches back from a `Writer::recycle` channel, for
+    /// `spare` to hand out
+    pub fn recycling(mut self, spent: Receiver<Batch>) -> Self {
+        self.spent = Some(spent);
+        self
+    }
+
+    /// A batch to fill and `send`: one the writer has finished with,
+    /// candidates and all, or a new one if none is back yet
+    pub fn spare(&self) -> Batch {
+        self.spent.as_ref()
+            .and_then(|spent| spent.try_recv().ok())
+            .unwrap_or_else(|| Vec::with_capacity(self.batch_size))
+    }
+
+    pub fn push(&mut self, candidate: Vec<u8>) -> Result<(), WriterClosed> {
+        self.buffer.push(candidate);
+        if self.buffer.len() >= self.batch_size {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Send a batch built elsewhere, such as by `Mask::fill_batch`, after
+    /// whatever was pushed before it
+    pub fn send(&mut self, batch: Batch) -> Result<(), WriterClosed> {
+        self.flush()?;
+        if batch.is_empty() {
+            return Ok(());
+        }
+        self.sender.send(batch).map_err(|_| WriterClosed)
+    }
+
+    /// Send the partial batch now
+    pub fn flush(&mut self) -> Result<(), WriterClosed> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let batch = std::mem::replace(&mut self.buffer, Vec::with_capacity(self.batch_size));
+        self.sender.send(batch).map_err(|_| WriterClosed)
+    }
+}
+
+impl Clone for BatchSender {
+    fn clone(&self) -> Self {
+        let mut clone = Self::new(self.sender.clone(), self.batch_size);
+        clone.spent = self.spent.clone();
+        clone
+    }
+}
+
+impl Drop for BatchSender {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
 }
 
 pub struct Writer {
     receiver: Receiver<Vec<Vec<u8>>>,
     output: Output,
+    compression: Compression,
+    unique: Option<Unique>,
+    line_ending: LineEnding,
+    format: RecordFormat,
+    report_every: Option<Duration>,
+    append: bool,
+    sort_memory: Option<usize>,
+    manifest: Option<serde_json::Value>,
+    category: Option<String>,
+    roll: Option<Roll>,
+    limit: Option<u64>,
+    resume_from: u64,
+    filter: Option<Filter>,
+    recycle: Option<Sender<Batch>>,
 }
 
 impl Writer {
+    /// Files ending in .gz or .zst are compressed to match
     pub fn new(receiver: Receiver<Vec<Vec<u8>>>, output: Output) -> Self {
-        Self { receiver, output }
+        let compression = match &output {
+            Output::File(path) => Compression::from_path(path),
+            Output::Upload(url) => Compression::from_path(Path::new(url)),
+            Output::Stdout | Output::Process(_) => Compression::None,
+        };
+        Self { receiver, output, compression, unique: None, line_ending: LineEnding::Lf, format: RecordFormat::Text, report_every: None, append: false, sort_memory: None, manifest: None, category: None, roll: None, limit: None, resume_from: 0, filter: None, recycle: None }
+    }
+
+    /// Override the compression guessed from the file name
+    pub fn compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Skip candidates that were already written
+    pub fn unique(mut self, unique: Unique) -> Self {
+        self.unique = Some(unique);
+        self
+    }
+
+    pub fn line_ending(mut self, line_ending: LineEnding) -> Self {
+        self.line_ending = line_ending;
+        self
+    }
+
+    pub fn format(mut self, format: RecordFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Print throughput to stderr at this interval and once at the end
+    pub fn report_every(mut self, interval: Duration) -> Self {
+        self.report_every = Some(interval);
+        self
+    }
+
+    /// Continue an existing output file from its checkpoint instead of
+    /// truncating it (uncompressed `File` output only)
+    pub fn append(mut self) -> Self {
+        self.append = true;
+        self
+    }
+
+    /// When appending, the engine itself starts `consumed` candidates in
+    /// (the checkpoint's count, reached by seeking rather than replaying),
+    /// so fewer of the candidates that arrive are replays to skip
+    pub fn resume_from(mut self, consumed: u64) -> Self {
+        self.resume_from = consumed;
+        self
     }
 
-    pub fn start(self) -> thread::JoinHandle<Result<()>> {
+    /// Hold output back and write it sorted and deduplicated at the end,
+    /// spilling sorted runs to the temp directory past `memory` bytes
+    pub fn sort_unique(mut self, memory: usize) -> Self {
+        self.sort_memory = Some(memory);
+        self
+    }
+
+    /// Write a `<output>.manifest.json` with checksum, counts and these
+    /// generation parameters once the output file is complete
+    pub fn manifest(mut self, parameters: serde_json::Value) -> Self {
+        self.manifest = Some(parameters);
+        self
+    }
+
+    /// Start a new, time-stamped output file every hour or day
+    pub fn roll(mut self, roll: Roll) -> Self {
+        self.roll = Some(roll);
+        self
+    }
+
+    /// Stop after `n` records; an appended file's earlier lines count
+    /// toward it. The engines see a closed channel and stop too.
+    pub fn limit(mut self, n: u64) -> Self {
+        self.limit = Some(n);
+        self
+    }
+
+    /// Drop candidates `filter` rejects before they are deduplicated or
+    /// written; they still count as consumed for a checkpoint
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.filter = Some(filter).filter(|filter| !filter.is_empty());
+        self
+    }
+
+    /// Hand each batch back on `spent` once written, so generators can
+    /// refill it instead of allocating another (see `BatchSender::spare`)
+    pub fn recycle(mut self, spent: Sender<Batch>) -> Self {
+        self.recycle = Some(spent);
+        self
+    }
+
+    /// Label every Parquet row with what generated it (the `category` column)
+    pub fn category(mut self, category: impl Into<String>) -> Self {
+        self.category = Some(category.into());
+        self
+    }
+
+    /// Spawn the writer thread. If a `Process` output or stdout is closed
+    /// by the reader (hashcat finished, `| head`), the thread ends with
+    /// `WriteError::ReaderClosed` carrying the reader's status.
+    pub fn start(self) -> thread::JoinHandle<Result<Stats>> {
         thread::spawn(move || {
-            let writer: Box<dyn Write> = match self.output {
-                Output::Stdout => Box::new(BufWriter::new(io::stdout().lock())),
-                Output::File(path) => Box::new(BufWriter::new(File::create(path)?)),
+            let started = Instant::now();
+            let bytes = Arc::new(AtomicU64::new(0));
+            let mut child: Option<Child> = None;
+            let mut upload: Option<Upload> = None;
+            if self.append && !matches!(self.output, Output::File(_)) {
+                return Err(invalid!("append needs a local output file"));
+            }
+            if self.manifest.is_some() && !matches!(self.output, Output::File(_)) {
+                return Err(invalid!("a manifest needs a local output file"));
+            }
+            if self.roll.is_some() {
+                if !matches!(self.output, Output::File(_)) {
+                    return Err(invalid!("rolling output needs a local output file"));
+                }
+                if self.append || self.sort_memory.is_some() {
+                    return Err(invalid!("rolling output can't be combined with append or sort-unique"));
+                }
+            }
+            if self.format == RecordFormat::Parquet {
+                if self.append {
+                    return Err(invalid!("can't append to Parquet output"));
+                }
+                if self.compression != Compression::None {
+                    return Err(invalid!("Parquet output is compressed internally; drop the .gz/.zst compression"));
+                }
+            }
+            let mut resume: Option<Resume> = None;
+            let mut rolling: Option<Rolling> = None;
+            let raw: Box<dyn Write + Send> = match &self.output {
+                Output::Stdout => Box::new(io::stdout()),
+                Output::File(path) if self.append => {
+                    if self.compression != Compression::None {
+                        return Err(invalid!("can't append to compressed output {}", path.display()));
+                    }
+                    let (file, checkpoint) = reopen(path, self.format, self.line_ending)?;
+                    if self.resume_from > 0 {
+                        crate::status!("  [resume] {} holds {} lines; carrying on from candidate {}",
+                            path.display(), checkpoint.lines, self.resume_from);
+                    } else if checkpoint.consumed > 0 {
+                        crate::status!("  [resume] {} holds {} lines; skipping the first {} candidates",
+                            path.display(), checkpoint.lines, checkpoint.consumed);
+                    }
+                    if self.resume_from > checkpoint.consumed {
+                        return Err(invalid!("the run starts {} candidates in, past {}'s checkpoint at {}",
+                            self.resume_from, path.display(), checkpoint.consumed));
+                    }
+                    resume = Some(Resume { path: path.clone(), file: file.try_clone()?, checkpoint, received: self.resume_from });
+                    Box::new(file)
+                }
+                Output::File(path) => match self.roll {
+                    Some(roll) => {
+                        let period = roll.period(SystemTime::now());
+                        let current = roll.path(path, period);
+                        let file = self.create_rolled(&current)?;
+                        rolling = Some(Rolling {
+                            roll, base: path.clone(), period, path: current, candidates_before: 0, duplicates_before: 0,
+                        });
+                        file
+                    }
+                    None => Box::new(File::create(path)?),
+                },
+                Output::Upload(url) => {
+                    let (body, started) = upload::open(url)?;
+                    upload = Some(started);
+                    body
+                }
+                Output::Process(command) => {
+                    let mut spawned = shell(command)
+                        .stdin(Stdio::piped())
+                        .spawn()
+                        .map_err(|e| anyhow!("can't start '{}': {}", command, e))?;
+                    let stdin = spawned.stdin.take().ok_or_else(|| anyhow!("no stdin for '{}'", command))?;
+                    child = Some(spawned);
+                    Box::new(stdin)
+                }
             };
+            let mut writer = self.records(raw, &bytes)?;
 
-            let mut writer = BufWriter::new(writer);
+            let mut stats = Stats::default();
+            match (&rolling, &self.output) {
+                (Some(rolling), _) => stats.files.push(rolling.path.clone()),
+                (None, Output::File(path)) => stats.files.push(path.clone()),
+                _ => {}
+            }
+            let pumped = self.pump(&mut writer, &mut stats, &bytes, started, resume.as_mut(), rolling.as_mut())
+                .and_then(|_| writer.finish());
+            if let (Ok(()), Some(resume)) = (&pumped, &resume) {
+                resume.save(&stats, bytes.load(Ordering::Relaxed))?;
+            }
+            stats.bytes = bytes.load(Ordering::Relaxed);
+            stats.elapsed = started.elapsed();
 
-            // Iterate over received batches
-            for batch in self.receiver {
-                for candidate in batch {
-                    writer.write_all(&candidate)?;
-                    writer.write_all(b"\n")?;
+            if let Err(e) = pumped {
+                if e.kind() != io::ErrorKind::BrokenPipe {
+                    return Err(e.into());
+                }
+                // The upload gave up mid-stream; its error says why
+                if let Some(upload) = upload {
+                    upload.wait()?;
+                    return Err(e.into());
                 }
+                let code = match (&self.output, child) {
+                    (Output::Process(command), Some(mut child)) => {
+                        eprintln!("  [!] '{}' closed its input after {} candidates", command, stats.candidates);
+                        child.wait().ok().and_then(|status| status.code()).unwrap_or(1)
+                    }
+                    _ => 0,
+                };
+                return Err(WriteError::ReaderClosed { code }.into());
             }
 
-            writer.flush()?;
-            Ok(())
+            if self.report_every.is_some() {
+                eprintln!("  [stats] done: {}", stats);
+            }
+            if let Some(upload) = upload {
+                upload.wait()?;
+            }
+            if let (Output::Process(command), Some(mut child)) = (&self.output, child) {
+                let status = child.wait()?;
+                if !status.success() {
+                    return Err(anyhow!("'{}' exited with {}", command, status));
+                }
+            }
+            if let Some(rolling) = &rolling {
+                let (lines, duplicates) = (stats.candidates - rolling.candidates_before, stats.duplicates - rolling.duplicates_before);
+                self.write_manifest(&rolling.path, lines, duplicates, &mut stats)?;
+            } else if let Output::File(path) = &self.output {
+                // An appended file holds earlier runs' lines as well
+                let lines = resume.as_ref().map_or(0, |resume| resume.checkpoint.lines) + stats.candidates;
+                let duplicates = stats.duplicates;
+                self.write_manifest(path, lines, duplicates, &mut stats)?;
+            }
+            Ok(stats)
         })
     }
+
+    /// Layer counting, buffering, compression and record framing over `raw`
+    fn records(&self, raw: Box<dyn Write + Send>, bytes: &Arc<AtomicU64>) -> io::Result<Records> {
+        // Records reach the sink in large buffers, so nothing here buffers again
+        let raw = Counted { inner: raw, bytes: bytes.clone() };
+        let sink: Box<dyn Sink> = match self.compression {
+            Compression::None => Box::new(raw),
+            Compression::Gzip => Box::new(GzEncoder::new(raw, flate2::Compression::default())),
+            Compression::Zstd => Box::new(zstd::Encoder::new(raw, 0)?),
+        };
+        Ok(match self.format {
+            RecordFormat::Parquet => Records::Parquet(Box::new(ParquetWriter::new(sink, self.category.as_deref())?)),
+            RecordFormat::Text | RecordFormat::Ndjson => Records::Stream { sink, buffer: Vec::with_capacity(DRAIN_AT) },
+        })
+    }
+
+    /// Open a rolled file. A restart inside the same period appends to it
+    /// (gzip members and zstd frames concatenate); Parquet starts over.
+    fn create_rolled(&self, path: &Path) -> io::Result<Box<dyn Write + Send>> {
+        let mut options = OpenOptions::new();
+        match self.format {
+            RecordFormat::Parquet => options.write(true).create(true).truncate(true),
+            RecordFormat::Text | RecordFormat::Ndjson => options.append(true).create(true),
+        };
+        Ok(Box::new(options.open(path)?))
+    }
+
+    /// Close the current rolled file and open the one for `period`
+    fn roll_over(&self, writer: &mut Records, rolling: &mut Rolling, period: u64, stats: &mut Stats, bytes: &Arc<AtomicU64>) -> io::Result<()> {
+        let path = rolling.roll.path(&rolling.base, period);
+        let next = self.records(self.create_rolled(&path)?, bytes)?;
+        std::mem::replace(writer, next).finish()?;
+        let (lines, duplicates) = (stats.candidates - rolling.candidates_before, stats.duplicates - rolling.duplicates_before);
+        self.write_manifest(&rolling.path, lines, duplicates, stats).map_err(io::Error::other)?;
+        crate::status!("  [roll] {} closed; writing {}", rolling.path.display(), path.display());
+        rolling.period = period;
+        if !stats.files.contains(&path) {
+            stats.files.push(path.clone());
+        }
+        rolling.path = path;
+        rolling.candidates_before = stats.candidates;
+        rolling.duplicates_before = stats.duplicates;
+        Ok(())
+    }
+
+    fn write_manifest(&self, path: &Path, lines: u64, duplicates: u64, stats: &mut Stats) -> Result<()> {
+        if let Some(parameters) = &self.manifest {
+            let sidecar = manifest::write(path, lines, duplicates, parameters.clone())?;
+            crate::status!("  [+] manifest written to {}", sidecar.display());
+            if !stats.files.contains(&sidecar) {
+                stats.files.push(sidecar);
+            }
+        }
+        Ok(())
+    }
+
+    /// Drain the channel into `writer`, skipping and checkpointing
+    /// against `resume` when appending
+    fn pump(
+        &self,
+        writer: &mut Records,
+        stats: &mut Stats,
+        bytes: &Arc<AtomicU64>,
+        started: Instant,
+        mut resume: Option<&mut Resume>,
+        mut rolling: Option<&mut Rolling>,
+    ) -> io::Result<()> {
+        let mut seen = self.unique.map(Seen::new);
+        let mut sorter = self.sort_memory.map(ExternalSorter::new);
+        let mut last_report = Instant::now();
+        let mut last_checkpoint = Instant::now();
+        let limit = self.limit.map(|n| n.saturating_sub(resume.as_ref().map_or(0, |resume| resume.checkpoint.lines)));
+
+        // Iterate over received batches
+        for batch in self.receiver.iter() {
+            // Stop between batches so the output ends on a whole record
+            // and the checkpoint below still gets saved
+            if crate::cli::exit::interrupted() {
+                break;
+            }
+            if let Some(rolling) = &mut rolling {
+                let period = rolling.roll.period(SystemTime::now());
+                if period != rolling.period {
+                    self.roll_over(writer, rolling, period, stats, bytes)?;
+                }
+            }
+            for candidate in &batch {
+                if let Some(resume) = &mut resume {
+                    resume.received += 1;
+                    // Already in the file; remember it so --unique drops repeats
+                    if resume.received <= resume.checkpoint.consumed {
+                        if let Some(seen) = &mut seen {
+                            seen.first_sighting(candidate);
+                        }
+                        continue;
+                    }
+                }
+                if self.filter.as_ref().is_some_and(|filter| !filter.accepts(candidate)) {
+                    stats.filtered += 1;
+                    continue;
+                }
+                if let Some(seen) = &mut seen {
+                    if !seen.first_sighting(candidate) {
+                        stats.duplicates += 1;
+                        continue;
+                    }
+                }
+                match &mut sorter {
+                    Some(sorter) => sorter.push(candidate.clone())?,
+                    None => {
+                        if limit.is_some_and(|limit| stats.candidates >= limit) {
+                            return Ok(());
+                        }
+                        stats.candidates += 1;
+                        self.write_record(writer, candidate)?;
+                    }
+                }
+            }
+            // Generators refill it; with no room in the channel it's dropped
+            if let Some(spent) = &self.recycle {
+                let _ = spent.try_send(batch);
+            }
+            // Keep filling while more batches are queued; drain once caught up
+            if self.receiver.is_empty() {
+                writer.drain()?;
+            }
+
+            if let Some(interval) = self.report_every {
+                if last_report.elapsed() >= interval {
+                    stats.bytes = bytes.load(Ordering::Relaxed);
+                    stats.elapsed = started.elapsed();
+                    eprintln!("  [stats] {}", stats);
+                    last_report = Instant::now();
+                }
+            }
+
+            if let Some(resume) = &resume {
+                if last_checkpoint.elapsed() >= CHECKPOINT_EVERY {
+                    writer.flush()?;
+                    resume.save(stats, bytes.load(Ordering::Relaxed))?;
+                    last_checkpoint = Instant::now();
+                }
+            }
+        }
+
+        if let Some(sorter) = sorter {
+            stats.duplicates += sorter.finish(|candidate| {
+                if limit.is_some_and(|limit| stats.candidates >= limit) {
+                    return Ok(());
+                }
+                stats.candidates += 1;
+                self.write_record(writer, candidate)
+            })?;
+        }
+        Ok(())
+    }
+
+    fn write_record(&self, writer: &mut Records, candidate: &[u8]) -> io::Result<()> {
+        let buffer = match writer {
+            Records::Parquet(rows) => return rows.push(candidate),
+            Records::Stream { buffer, .. } => buffer,
+        };
+        match self.format {
+            RecordFormat::Text | RecordFormat::Parquet => {
+                buffer.extend_from_slice(candidate);
+                buffer.extend_from_slice(self.line_ending.as_bytes());
+            }
+            RecordFormat::Ndjson => {
+                let record = match std::str::from_utf8(candidate) {
+                    Ok(text) => serde_json::json!({ "candidate": text }),
+                    Err(_) => serde_json::json!({ "candidate": hex(candidate), "encoding": "hex" }),
+                };
+                serde_json::to_writer(&mut *buffer, &record)?;
+                buffer.push(b'\n');
+            }
+        }
+        if buffer.len() >= DRAIN_AT {
+            writer.drain()?;
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("cmd");
+    cmd.args(["/C", command]);
+    cmd
+}
+
+#[cfg(not(windows))]
+fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.args(["-c", command]);
+    cmd
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn write_words(path: &Path, compression: Option<Compression>) {
+        let (sender, receiver) = bounded(4);
+        let mut writer = Writer::new(receiver, Output::File(path.to_path_buf()));
+        if let Some(c) = compression {
+            writer = writer.compression(c);
+        }
+        let handle = writer.start();
+        sender.send(vec![b"alpha".to_vec(), b"beta".to_vec()]).unwrap();
+        drop(sender);
+        handle.join().unwrap().unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_pipe_to_process() {
+        let path = std::env::temp_dir().join(format!("jigsaw_pipe_{}.txt", std::process::id()));
+        let (sender, receiver) = bounded(4);
+        let command = format!("tr a-z A-Z > '{}'", path.display());
+        let handle = Writer::new(receiver, Output::Process(command)).start();
+        sender.send(vec![b"alpha".to_vec(), b"beta".to_vec()]).unwrap();
+        drop(sender);
+        assert_eq!(handle.join().unwrap().unwrap().candidates, 2);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "ALPHA\nBETA\n");
+        let _ = std::fs::remove_file(path);
+
+        let (sender, receiver) = bounded(1);
+        let handle = Writer::new(receiver, Output::Process("exit 3".to_string())).start();
+        drop(sender);
+        assert!(handle.join().unwrap().is_err());
+
+        // A reader that quits early ends the thread with its status
+        let (sender, receiver) = bounded(4);
+        let handle = Writer::new(receiver, Output::Process("exit 5".to_string())).start();
+        let batch: Batch = vec![vec![b'x'; 1000]; 1000];
+        while sender.send(batch.clone()).is_ok() {}
+        let error = handle.join().unwrap().unwrap_err();
+        assert_eq!(error.downcast_ref::<WriteError>(), Some(&WriteError::ReaderClosed { code: 5 }));
+    }
+
+    #[test]
+    fn test_append_resume() {
+        let path = std::env::temp_dir().join(format!("jigsaw_append_{}.txt", std::process::id()));
+        // An interrupted --unique run: three candidates in (one a repeat),
+        // two whole lines checkpointed, then a torn third
+        std::fs::write(&path, "alpha\nbeta\ngam").unwrap();
+        Checkpoint { consumed: 3, lines: 2, bytes: 11 }.save(&path).unwrap();
+
+        let (sender, receiver) = bounded(4);
+        let handle = Writer::new(receiver, Output::File(path.clone())).unique(Unique::Exact).append().start();
+        sender.send(vec![b"alpha".to_vec(), b"beta".to_vec(), b"alpha".to_vec()]).unwrap();
+        sender.send(vec![b"gamma".to_vec(), b"beta".to_vec(), b"delta".to_vec()]).unwrap();
+        drop(sender);
+        let stats = handle.join().unwrap().unwrap();
+
+        assert_eq!((stats.candidates, stats.duplicates), (2, 1));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "alpha\nbeta\ngamma\ndelta\n");
+        assert_eq!(Checkpoint::load(&path), Some(Checkpoint { consumed: 6, lines: 4, bytes: 23 }));
+
+        // No sidecar: whole records already in the file are counted
+        let _ = std::fs::remove_file(checkpoint_path(&path));
+        std::fs::write(&path, "alpha\nbeta\ngam").unwrap();
+        let (file, checkpoint) = reopen(&path, RecordFormat::Text, LineEnding::Lf).unwrap();
+        drop(file);
+        assert_eq!(checkpoint, Checkpoint { consumed: 2, lines: 2, bytes: 11 });
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "alpha\nbeta\n");
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_limit() {
+        let path = std::env::temp_dir().join(format!("jigsaw_limit_{}.txt", std::process::id()));
+        let (sender, receiver) = bounded(4);
+        let handle = Writer::new(receiver, Output::File(path.clone())).unique(Unique::Exact).limit(3).start();
+        sender.send(vec![b"a".to_vec(), b"b".to_vec(), b"a".to_vec()]).unwrap();
+        sender.send(vec![b"c".to_vec(), b"d".to_vec(), b"e".to_vec()]).unwrap();
+        let stats = handle.join().unwrap().unwrap();
+
+        // Duplicates don't count toward the limit, and producers are cut off
+        assert_eq!((stats.candidates, stats.duplicates), (3, 1));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "a\nb\nc\n");
+        assert_eq!(stats.files, vec![path.clone()]);
+        assert!(sender.send(vec![b"f".to_vec()]).is_err());
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_filter() {
+        let path = std::env::temp_dir().join(format!("jigsaw_filter_{}.txt", std::process::id()));
+        let (sender, receiver) = bounded(4);
+        let mut filter = Filter::new();
+        filter.min_len = Some(2);
+        filter.require = vec![crate::engine::filter::Class::Digit];
+        let handle = Writer::new(receiver, Output::File(path.clone())).filter(filter).unique(Unique::Exact).start();
+        sender.send(vec![b"a1".to_vec(), b"1".to_vec(), b"ab".to_vec(), b"a1".to_vec(), b"b2".to_vec()]).unwrap();
+        drop(sender);
+        let stats = handle.join().unwrap().unwrap();
+
+        assert_eq!((stats.candidates, stats.filtered, stats.duplicates), (2, 2, 1));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "a1\nb2\n");
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_recycled_batches_come_back() {
+        let path = std::env::temp_dir().join(format!("jigsaw_recycled_{}.txt", std::process::id()));
+        let (sender, receiver) = channel(4);
+        let (spent, spares) = channel(4);
+        let handle = Writer::new(receiver, Output::File(path.clone())).recycle(spent).start();
+        let mut batcher = BatchSender::new(sender, 2).recycling(spares);
+        batcher.send(vec![b"ab".to_vec(), b"cd".to_vec()]).unwrap();
+
+        // The written batch returns whole, for `fill_batch` to overwrite
+        let start = Instant::now();
+        let spare = loop {
+            let spare = batcher.spare();
+            if !spare.is_empty() || start.elapsed() > Duration::from_secs(5) {
+                break spare;
+            }
+            thread::yield_now();
+        };
+        assert_eq!(spare, [b"ab".to_vec(), b"cd".to_vec()]);
+        assert!(batcher.spare().is_empty());
+        drop(batcher);
+        handle.join().unwrap().unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "ab\ncd\n");
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_sort_unique() {
+        let path = std::env::temp_dir().join(format!("jigsaw_sorted_{}.txt", std::process::id()));
+        let (sender, receiver) = bounded(4);
+        let handle = Writer::new(receiver, Output::File(path.clone())).sort_unique(64).start();
+        sender.send(vec![b"pear".to_vec(), b"apple".to_vec(), b"fig".to_vec()]).unwrap();
+        sender.send(vec![b"apple".to_vec(), b"kiwi".to_vec(), b"fig".to_vec()]).unwrap();
+        drop(sender);
+        let stats = handle.join().unwrap().unwrap();
+
+        assert_eq!((stats.candidates, stats.duplicates), (4, 2));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "apple\nfig\nkiwi\npear\n");
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_batch_sender() {
+        let (sender, receiver) = channel(8);
+        let mut batcher = BatchSender::new(sender, 2);
+        let mut worker = batcher.clone();
+        for word in ["a", "b", "c"] {
+            batcher.push(word.as_bytes().to_vec()).unwrap();
+        }
+        worker.push(b"d".to_vec()).unwrap();
+        drop(worker);
+        drop(batcher);
+        let batches: Vec<usize> = receiver.iter().map(|batch| batch.len()).collect();
+        assert_eq!(batches, vec![2, 1, 1]);
+
+        let (sender, receiver) = channel(1);
+        drop(receiver);
+        assert_eq!(BatchSender::new(sender, 1).push(b"x".to_vec()), Err(WriterClosed));
+    }
+
+    #[test]
+    fn test_line_endings() {
+        let path = std::env::temp_dir().join(format!("jigsaw_endings_{}.txt", std::process::id()));
+        for (ending, expected) in [
+            (LineEnding::CrLf, b"a\r\nb\nc\r\n".to_vec()),
+            (LineEnding::Nul, b"a\0b\nc\0".to_vec()),
+        ] {
+            let (sender, receiver) = bounded(4);
+            let handle = Writer::new(receiver, Output::File(path.clone())).line_ending(ending).start();
+            sender.send(vec![b"a".to_vec(), b"b\nc".to_vec()]).unwrap();
+            drop(sender);
+            handle.join().unwrap().unwrap();
+            assert_eq!(std::fs::read(&path).unwrap(), expected);
+        }
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_ndjson_records() {
+        let path = std::env::temp_dir().join(format!("jigsaw_ndjson_{}.jsonl", std::process::id()));
+        let (sender, receiver) = bounded(4);
+        let handle = Writer::new(receiver, Output::File(path.clone())).format(RecordFormat::Ndjson).start();
+        sender.send(vec![b"pass\"word".to_vec(), b"a\nb".to_vec(), vec![b'a', 0xff, 0x00]]).unwrap();
+        drop(sender);
+        handle.join().unwrap().unwrap();
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(path);
+        let records: Vec<serde_json::Value> = text.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0]["candidate"], "pass\"word");
+        assert_eq!(records[1]["candidate"], "a\nb");
+        assert!(records[1].get("encoding").is_none());
+        assert_eq!(records[2]["candidate"], "61ff00");
+        assert_eq!(records[2]["encoding"], "hex");
+    }
+
+    #[test]
+    fn test_unique_across_batches() {
+        let path = std::env::temp_dir().join(format!("jigsaw_unique_{}.txt", std::process::id()));
+        for unique in [Unique::Exact, Unique::Bloom { expected: Some(100), fp_rate: 0.0001 }] {
+            let (sender, receiver) = bounded(4);
+            let handle = Writer::new(receiver, Output::File(path.clone())).unique(unique).start();
+            sender.send(vec![b"a".to_vec(), b"b".to_vec(), b"a".to_vec()]).unwrap();
+            sender.send(vec![b"b".to_vec(), b"c".to_vec()]).unwrap();
+            drop(sender);
+            let stats = handle.join().unwrap().unwrap();
+            assert_eq!(std::fs::read_to_string(&path).unwrap(), "a\nb\nc\n", "{:?}", unique);
+            assert_eq!((stats.candidates, stats.duplicates, stats.bytes), (3, 2, 6));
+        }
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_roll_paths() {
+        // 2024-02-29 13:45:00 UTC
+        let time = UNIX_EPOCH + Duration::from_secs(1_709_214_300);
+        let base = Path::new("/lists/words.txt.gz");
+        let hour = Roll::Hourly.period(time);
+        assert_eq!(Roll::Hourly.path(base, hour), Path::new("/lists/words-2024-02-29T13.txt.gz"));
+        assert_eq!(Roll::Hourly.path(base, hour + 11), Path::new("/lists/words-2024-03-01T00.txt.gz"));
+        assert_eq!(Roll::Daily.path(base, Roll::Daily.period(time)), Path::new("/lists/words-2024-02-29.txt.gz"));
+        assert_eq!(Roll::Daily.path(Path::new(".hidden"), 0), Path::new(".hidden-1970-01-01"));
+        assert_eq!(civil_date(10_956), (1999, 12, 31));
+        assert_eq!(Compression::from_path(&Roll::Daily.path(base, 0)), Compression::Gzip);
+    }
+
+    #[test]
+    fn test_compressed_output() {
+        let dir = std::env::temp_dir();
+        let id = std::process::id();
+
+        let gz = dir.join(format!("jigsaw_writer_{}.txt.gz", id));
+        write_words(&gz, None);
+        let mut text = String::new();
+        flate2::read::GzDecoder::new(File::open(&gz).unwrap()).read_to_string(&mut text).unwrap();
+        assert_eq!(text, "alpha\nbeta\n");
+
+        let zst = dir.join(format!("jigsaw_writer_{}.lst", id));
+        write_words(&zst, Some(Compression::Zstd));
+        let bytes = zstd::decode_all(File::open(&zst).unwrap()).unwrap();
+        assert_eq!(bytes, b"alpha\nbeta\n");
+
+        let _ = std::fs::remove_file(gz);
+        let _ = std::fs::remove_file(zst);
+    }
 }
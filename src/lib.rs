@@ -1,3 +1,69 @@
+//! Candidate generators behind the `jigsaw` command, for use from Rust.
+//!
+//! The engines are plain synchronous code with no server or terminal
+//! attached: build a generator, then iterate it or hand it a callback.
+//! The modules re-exported at the top level are the stable surface;
+//! `engine`, `io` and `cli` hold the rest of the binary's internals and
+//! may change between releases.
+//!
+//! `engine` needs no default features: build with `--no-default-features`
+//! for just the generators (they compile for `wasm32-unknown-unknown`),
+//! and add `--features wasm` for the browser bindings in `wasm`.
+//!
+//! - [`mask`]: hashcat-style masks (`?l?l?d?d`), iterated in order or by index
+//! - [`rules`]: hashcat-style rule chains (`c$1$!`) applied to candidates
+//! - [`markov`]: character-level Markov models, trained and sampled
+//! - [`personal`]: wordlists built from what is known about a target
+//! - [`memorable`]: passphrase-style passwords
+//!
+//! ```
+//! use std::str::FromStr;
+//! use jigsaw::{Mask, RuleSet};
+//!
+//! let mask = Mask::from_str("pw?d").unwrap();
+//! assert_eq!(mask.search_space_size(), 10);
+//! assert_eq!(mask.nth_candidate(7).unwrap(), b"pw7");
+//!
+//! let rules = RuleSet::from_str("u$!").unwrap();
+//! let mut word = b"secret".to_vec();
+//! rules.apply(&mut word);
+//! assert_eq!(word, b"SECRET!");
+//! ```
+//!
+//! ```
+//! use jigsaw::{Dedup, MarkovModel, MemorableConfig, Profile};
+//!
+//! let mut model = MarkovModel::new(2);
+//! model.train_reader("password\npassport\npassage\n".as_bytes()).unwrap();
+//! let word = model.generate(&mut jigsaw::memorable::seeded_rng(7), 6, 10);
+//! assert!(model.log_probability(&word).is_some());
+//!
+//! let mut profile = Profile::new();
+//! profile.first_names.push("alice".to_string());
+//! profile.dates.push("1990".to_string());
+//! let mut first = Vec::new();
+//! profile.for_each_unique(Dedup::Exact, |candidate| {
+//!     first.push(candidate);
+//!     first.len() == 10 // stop after ten
+//! });
+//! assert_eq!(first.len(), 10);
+//!
+//! let config = MemorableConfig { count: 3, seed: Some(1), ..Default::default() };
+//! assert_eq!(jigsaw::memorable::generate_batch(&config).unwrap().len(), 3);
+//! ```
+
 pub mod engine;
+#[cfg(feature = "cli")]
 pub mod cli;
+#[cfg(feature = "cli")]
 pub mod io;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use engine::{mask, markov, memorable, personal, rules};
+
+pub use engine::mask::{Charset, Mask};
+pub use engine::markov::MarkovModel;
+pub use engine::memorable::MemorableConfig;
+pub use engine::personal::{Dedup, Profile};
+pub use engine::rules::{Rule, RuleSet};
@@ -1,107 +1,226 @@
-mod engine;
-mod io;
-mod cli;
 mod interactive;
 mod api;
+mod commands;
 
+use jigsaw::{cli, engine, invalid, io, status};
+
+use cli::args::{JigsawArgs, Commands, CompressFormat, LineEndingArg, OutputFormat, RollArg, DedupMode, MemStyle, MemCase, MemLanguage, MemWordPool, MnemonicCaseArg, NumForm, NumPosition, KeyboardLayout, WalkDirection, ModelType};
+use cli::exit;
+use cli::session::Session;
+use cli::summary::Summary;
 use clap::Parser;
-use cli::args::{JigsawArgs, Commands, OutputFormat, GenerationLevel, MemStyle, MemCase, NumPosition};
-use engine::mask::Mask;
-use engine::memorable::{MemorableConfig, MemorableStyle, CaseStyle, Position};
-use io::writer::{Writer, Output as WriterOutput};
-use std::str::FromStr;
+use engine::sample::Reservoir;
+use engine::personal::Dedup;
+use engine::walks::{Direction, Layout};
+use engine::memorable::{MemorableConfig, MemorableStyle, CaseStyle, Position, WordPool, Language, NumberForm};
+use io::writer::{BatchSender, Compression, LineEnding, RecordFormat, Roll, Unique, WriteError, Writer, Output as WriterOutput};
 use std::path::PathBuf;
-use crossbeam_channel::bounded;
 use rayon::prelude::*;
 
 #[actix_web::main]
-async fn main() -> anyhow::Result<()> {
-    let args = JigsawArgs::parse();
+async fn main() {
+    let code = match jigsaw_main().await {
+        Ok(code) => code,
+        // A reader that stopped early (`| head`, hashcat) isn't a failure
+        Err(e) => match e.downcast_ref::<WriteError>() {
+            Some(WriteError::ReaderClosed { code }) => *code,
+            None => {
+                eprintln!("Error: {:?}", e);
+                exit::code_for(&e)
+            }
+        },
+    };
+    std::process::exit(code);
+}
+
+/// Parse the command line and run it, returning the exit code
+async fn jigsaw_main() -> anyhow::Result<i32> {
+    let argv = cli::config::resolve(std::env::args_os().collect())?;
+    let mut args = JigsawArgs::parse_from(&argv);
 
     // Check for subcommands first
-    if let Some(Commands::Server { port }) = args.command {
-        return api::server::run_server(port).await.map_err(|e| anyhow::anyhow!(e));
+    let mut restored = None;
+    match &args.command {
+        Some(Commands::Server(server_args)) => {
+            api::server::run_server(server_args).await.map_err(|e| anyhow::anyhow!(e))?;
+            return Ok(exit::SUCCESS);
+        }
+        Some(Commands::Profile { action }) => return commands::profile::run(action).map(|_| exit::SUCCESS),
+        Some(Commands::Scrape(scrape_args)) => return commands::scrape::run(scrape_args).map(|_| exit::SUCCESS),
+        Some(Commands::Bench(bench_args)) => return commands::bench::run(bench_args).map(|_| exit::SUCCESS),
+        Some(Commands::Combine(combine_args)) => return commands::combine::run(combine_args).map(|_| exit::SUCCESS),
+        Some(Commands::Analyze(analyze_args)) => return commands::analyze::run(analyze_args).map(|_| exit::SUCCESS),
+        Some(Commands::Strength(strength_args)) => return commands::strength::run(strength_args).map(|_| exit::SUCCESS),
+        Some(Commands::Audit(audit_args)) => return commands::audit::run(audit_args).map(|_| exit::SUCCESS),
+        Some(Commands::PwnedFilter(filter_args)) => return commands::pwned::build(filter_args).map(|_| exit::SUCCESS),
+        Some(Commands::PwnedCheck(check_args)) => return commands::pwned::check(check_args),
+        Some(Commands::Wordlist { action }) => return commands::wordlist::run(action).map(|_| exit::SUCCESS),
+        Some(Commands::Dates(dates_args)) => return commands::dates::run(dates_args).map(|_| exit::SUCCESS),
+        Some(Commands::Worker(worker_args)) => return commands::worker::run(worker_args).map(|_| exit::SUCCESS),
+        Some(Commands::Rank(rank_args)) => return commands::rank::run(rank_args).map(|_| exit::SUCCESS),
+        Some(Commands::Mangle(mangle_args)) => return commands::mangle::run(mangle_args).map(|_| exit::SUCCESS),
+        Some(Commands::Leet(leet_args)) => return commands::leet::run(leet_args).map(|_| exit::SUCCESS),
+        Some(Commands::Case(case_args)) => return commands::case::run(case_args).map(|_| exit::SUCCESS),
+        Some(Commands::Rules { action }) => return commands::rules::run(action).map(|_| exit::SUCCESS),
+        Some(Commands::Tui { profile, output }) => return interactive::tui::run(profile, output).map(|_| exit::SUCCESS),
+        Some(Commands::Restore { name: None }) => return list_sessions().map(|_| exit::SUCCESS),
+        Some(Commands::Restore { name: Some(name) }) => restored = Some(Session::load(&cli::session::dir()?, name)?),
+        None => {}
     }
+    let argv: Vec<String> = match &restored {
+        Some(session) => {
+            if session.finished {
+                return Err(invalid!("session '{}' already finished ({} lines in {:?})", session.name, session.lines, session.output));
+            }
+            args = JigsawArgs::try_parse_from(&session.command)?;
+            session.command.clone()
+        }
+        None => argv.iter().map(|arg| arg.to_string_lossy().into_owned()).collect(),
+    };
 
     let final_args = if args.interactive {
-        interactive::run_wizard()?
+        if let Some(path) = &args.answers {
+            interactive::prompt::replay(path)?;
+        } else if let Some(path) = &args.record_answers {
+            interactive::prompt::record(path);
+        }
+        interactive::run_wizard(&args.profiles_dir)?
     } else {
         args
     };
+    cli::status::set_quiet(final_args.quiet);
+    // --copy leaves Ctrl-C to its default: quit now, keeping the clipboard
+    if !final_args.copy {
+        exit::catch_interrupts()?;
+    }
 
+    let stats_json = final_args.stats_json.clone();
+    let mut summary = Summary::new(mode_name(&final_args), manifest_parameters(&final_args));
+    let result = generate(final_args, argv, restored, &mut summary);
+    let code = match &result {
+        Ok(()) if exit::interrupted() => exit::INTERRUPTED,
+        Ok(()) => summary.exit_code,
+        Err(e) => exit::code_for(e),
+    };
+    summary.finish(code, result.as_ref().err());
+    if let Some(path) = stats_json {
+        if let Err(e) = summary.write(&path) {
+            eprintln!("  [!] --stats-json {:?}: {}", path, e);
+        }
+    }
+    result.map(|()| code)
+}
+
+/// A generation run, recorded as a session with --session
+fn generate(mut final_args: JigsawArgs, argv: Vec<String>, restored: Option<Session>, summary: &mut Summary) -> anyhow::Result<()> {
+    let Some(name) = final_args.session.clone() else {
+        return run(final_args, summary);
+    };
+    let dir = cli::session::dir()?;
+    let mut session = begin_session(&mut final_args, &dir, &name, argv, restored)?;
+    summary.parameters = session.parameters.clone();
+    let result = run(final_args, summary);
+    session.lines = session.progress();
+    session.finished = result.is_ok() && !exit::interrupted();
+    session.save(&dir)?;
+    if !session.finished {
+        eprintln!("  [!] session '{}' stopped at {} lines; resume it with `jigsaw restore {}`", name, session.lines, name);
+    }
+    result
+}
+
+/// Everything but the subcommands
+fn run(final_args: JigsawArgs, summary: &mut Summary) -> anyhow::Result<()> {
     // --- Markov Training Mode ---
     if let Some(train_path) = final_args.train {
         let start_time = std::time::Instant::now();
-        println!("Training Markov model from {:?}...", train_path);
-        let mut model = engine::markov::MarkovModel::new(3);
-        model.train(&train_path)?;
-        
+        if final_args.order == 0 {
+            return Err(invalid!("--order must be at least 1"));
+        }
         let valid_model_path = final_args.model.clone().unwrap_or_else(|| PathBuf::from("jigsaw.model"));
-        println!("Saving model to {:?}...", valid_model_path);
-        model.save(&valid_model_path)?;
-        println!("Training complete. Time taken: {}ms", start_time.elapsed().as_millis());
+        match final_args.model_type {
+            ModelType::Markov => {
+                status!("Training Markov model from {:?}...", train_path);
+                let mut model = engine::markov::MarkovModel::new(final_args.order);
+                model.train(&train_path)?;
+                status!("Saving model to {:?}...", valid_model_path);
+                model.save(&valid_model_path)?;
+            }
+            #[cfg(feature = "neural")]
+            ModelType::Neural => {
+                status!("Training neural model from {:?} ({} epochs)...", train_path, final_args.epochs);
+                let training = engine::neural::Training {
+                    hidden: final_args.hidden,
+                    epochs: final_args.epochs,
+                    seed: final_args.seed.unwrap_or(0),
+                };
+                let (model, losses) = engine::neural::NeuralModel::train(&train_path, &training)?;
+                for (epoch, loss) in losses.iter().enumerate() {
+                    status!("  epoch {}: loss {:.3}", epoch + 1, loss);
+                }
+                status!("Saving model to {:?}...", valid_model_path);
+                model.save(&valid_model_path)?;
+            }
+            #[cfg(not(feature = "neural"))]
+            ModelType::Neural => return Err(invalid!("--model-type neural needs jigsaw built with `--features neural`")),
+        }
+        summary.output(&valid_model_path);
+        status!("Training complete. Time taken: {}ms", start_time.elapsed().as_millis());
         return Ok(());
     }
 
     // --- Markov Generation Mode ---
     if final_args.markov {
         let start_time = std::time::Instant::now();
-        println!("JIGSAW Running in Markov Mode...");
+        status!("JIGSAW Running in Markov Mode...");
         let model_path = final_args.model.clone().unwrap_or_else(|| PathBuf::from("jigsaw.model"));
-        println!("Loading model from {:?}...", model_path);
+        status!("Loading model from {:?}...", model_path);
         
-        let model = engine::markov::MarkovModel::load(&model_path)?;
-        let model = std::sync::Arc::new(model);
+        let model = std::sync::Arc::new(CandidateModel::load(final_args.model_type, &model_path)?);
+        let (min_len, max_len) = (final_args.markov_min_len, final_args.markov_max_len);
+        if min_len == 0 || min_len > max_len {
+            return Err(invalid!("--markov-min-len must be between 1 and --markov-max-len (got {} and {})", min_len, max_len));
+        }
         
-        let count = final_args.count;
-        println!("Generating {} candidates...", count);
+        // Markov candidates are independent draws, so a sample is a shorter run
+        let count = final_args.sample.map_or(final_args.count, |n| final_args.count.min(n as usize));
+        status!("Generating {} candidates...", count);
+
+        // --append resumes by replaying the run, which needs a fixed sequence
+        let replay_seed = match (final_args.append, final_args.seed) {
+            (true, None) => return Err(invalid!("--append in Markov mode needs --seed so a resumed run replays the same candidates")),
+            (true, Some(seed)) => Some(seed),
+            (false, _) => None,
+        };
 
         if let Some(threads) = final_args.threads {
             rayon::ThreadPoolBuilder::new().num_threads(threads).build_global()?;
         }
 
-        let (sender, receiver) = bounded::<Vec<Vec<u8>>>(100);
-        let writer_output = match final_args.output {
-            Some(path) => WriterOutput::File(path),
-            None => WriterOutput::Stdout,
-        };
-        let writer_thread = Writer::new(receiver, writer_output).start();
-
-        struct MarkovBatcher {
-            buffer: Vec<Vec<u8>>,
-            sender: crossbeam_channel::Sender<Vec<Vec<u8>>>,
-            rng: rand::rngs::ThreadRng,
+        if final_args.estimate {
+            let (rate, mean_len) = measure((count as u64).min(ESTIMATE_SAMPLE), |_| model.generate(&mut rand::rng(), min_len, max_len).len());
+            print_estimate(&final_args, count as u128, "configured count", count as f64 * mean_len, rate);
+            return Ok(());
         }
 
-        impl Drop for MarkovBatcher {
-            fn drop(&mut self) {
-                if !self.buffer.is_empty() {
-                    let _ = self.sender.send(self.buffer.clone());
-                }
-            }
-        }
+        let (batcher, writer_thread) = spawn_writer(&final_args, Some(count as u64), 0)?;
 
-        (0..count).into_par_iter()
-            .for_each_init(
-                || MarkovBatcher {
-                    buffer: Vec::with_capacity(1000),
-                    sender: sender.clone(),
-                    rng: rand::rng(),
-                },
-                |batcher, _| {
-                    let candidate = model.generate(&mut batcher.rng, 6, 12);
-                    batcher.buffer.push(candidate.into_bytes());
-                    
-                    if batcher.buffer.len() >= 1000 {
-                        batcher.sender.send(batcher.buffer.clone()).expect("Channel closed");
-                        batcher.buffer.clear();
-                    }
-                }
-            );
-            
-         drop(sender);
-         writer_thread.join().expect("Writer panic")?;
-         println!("Done. Time taken: {}ms", start_time.elapsed().as_millis());
+        // A closed channel means the writer failed; joining it reports why
+        let _ = if let Some(seed) = replay_seed {
+            let mut rng = engine::memorable::seeded_rng(seed);
+            let mut batcher = batcher.clone();
+            (0..count).try_for_each(|_| batcher.push(model.generate(&mut rng, min_len, max_len).into_bytes()))
+        } else {
+            (0..count).into_par_iter()
+                .try_for_each_init(
+                    || (batcher.clone(), rand::rng()),
+                    |(batcher, rng), _| batcher.push(model.generate(rng, min_len, max_len).into_bytes()),
+                )
+        };
+
+        drop(batcher);
+        summary.add(&writer_thread.join().expect("Writer panic")?);
+         status!("Done. Time taken: {}ms", start_time.elapsed().as_millis());
          return Ok(());
     }
 
@@ -109,184 +228,814 @@ async fn main() -> anyhow::Result<()> {
     if final_args.memorable {
         let start_time = std::time::Instant::now();
         
-        let config = build_memorable_config(&final_args);
-        let passwords = engine::memorable::generate_batch(&config);
-        
+        let mut config = build_memorable_config(&final_args);
+        // Each password is a fresh draw, so a sample is just fewer of them
+        if let Some(n) = final_args.limit.or(final_args.sample) {
+            config.count = config.count.min(n as usize);
+        }
+        if let Some(template) = &config.template {
+            engine::memorable::compile_template(template)?;
+        }
+        if config.specials.as_ref().is_some_and(|set| set.trim().is_empty()) {
+            return Err(invalid!("--specials needs at least one character (use --no-special to omit symbols)"));
+        }
+        if let Some(path) = &final_args.wordlist_file {
+            config.custom_words = io::reader::read_words(path)?;
+            if config.custom_words.is_empty() {
+                return Err(invalid!("No words found in {:?}", path));
+            }
+        }
+        if let Some(path) = &final_args.language_file {
+            let text = std::fs::read_to_string(path)?;
+            config.custom_pools = Some(serde_json::from_str(&text)
+                .map_err(|e| invalid!("{:?}: {}", path, e))?);
+        }
+        engine::memorable::check_word_lengths(&config)?;
+        if let Some(spec) = &final_args.policy {
+            let policy = engine::memorable::Policy::parse(spec)?;
+            let requested = config.word_count;
+            engine::memorable::apply_policy(&mut config, policy)?;
+            if config.word_count < requested {
+                eprintln!("  [!] --policy: {} words won't fit in {} characters; using {} (~{:.1} bits)",
+                    requested, config.max_length, config.word_count, engine::memorable::entropy_bits(&config));
+            }
+        }
+        let entropy = match final_args.min_entropy {
+            Some(bits) => Some(engine::memorable::meet_min_entropy(&mut config, bits)?),
+            None => None,
+        };
+        if entropy.is_some() {
+            engine::memorable::check_policy(&config)?;
+        }
+        if final_args.estimate {
+            let sample = config.count.clamp(1, 1000);
+            let mut rng = rand::rng();
+            let start = std::time::Instant::now();
+            let mut bytes = 0;
+            for _ in 0..sample {
+                bytes += engine::memorable::generate_with_rng(&mut rng, &config)?.len();
+            }
+            let rate = sample as f64 / start.elapsed().as_secs_f64().max(1e-9);
+            let mean_len = bytes as f64 / sample as f64;
+            print_estimate(&final_args, config.count as u128, "configured count", config.count as f64 * mean_len, rate);
+            return Ok(());
+        }
+        let wants_qr = final_args.qr || final_args.qr_out.is_some();
+        if (final_args.copy || wants_qr) && config.count != 1 {
+            return Err(invalid!("--copy and --qr work with a single password; drop --mem-count"));
+        }
+        let mut passwords = engine::memorable::generate_batch(&config)?;
+        if final_args.unique.is_some() {
+            // Printed here rather than by the writer, so --unique is applied here too
+            let mut seen = std::collections::HashSet::new();
+            passwords.retain(|pw| seen.insert(pw.clone()));
+        }
+        summary.candidates = passwords.len() as u64;
+        let pwned = if final_args.check_pwned {
+            let mut client = io::pwned::Client::new();
+            Some(passwords.iter().map(|pw| client.breach_count(pw)).collect::<anyhow::Result<Vec<_>>>()?)
+        } else {
+            None
+        };
+        let breaches = |i: usize| pwned.as_ref().map(|counts| counts[i]);
+
+        if wants_qr {
+            if let Some(path) = &final_args.qr_out {
+                io::qr::write_png(&passwords[0], path)?;
+                summary.output(path);
+                status!("\n  QR code saved to {:?}", path);
+            }
+            if final_args.qr {
+                println!("\n{}", io::qr::render_terminal(&passwords[0])?);
+            }
+        }
+
+        if final_args.copy {
+            let password = &passwords[0];
+            io::clipboard::copy(password)?;
+            status!("\n  Password copied to clipboard (len: {}).", password.len());
+            if let Some(n @ 1..) = breaches(0) {
+                status!("  [!] It appears in {} known breaches; generate another.", n);
+            }
+            if final_args.clear_after > 0 {
+                status!("  Clearing in {}s; press Ctrl-C to keep it.\n", final_args.clear_after);
+                std::thread::sleep(std::time::Duration::from_secs(final_args.clear_after));
+                if io::clipboard::clear_if_unchanged(password)? {
+                    status!("  Clipboard cleared.\n");
+                }
+            }
+            return Ok(());
+        }
+
         match final_args.format {
+            OutputFormat::Parquet => return Err(invalid!("--format parquet is for wordlists (mask, Markov, personal)")),
+            OutputFormat::Ndjson => {
+                for (i, pw) in passwords.iter().enumerate() {
+                    let mut line = serde_json::json!({ "password": pw, "length": pw.len() });
+                    if let Some(n) = breaches(i) {
+                        line["pwned"] = n.into();
+                    }
+                    println!("{}", line);
+                }
+            }
             OutputFormat::Json => {
-                println!("{}", serde_json::to_string_pretty(&serde_json::json!({
+                let mut report = serde_json::json!({
                     "passwords": passwords,
                     "count": passwords.len(),
                     "style": format!("{:?}", config.style),
+                    "entropy_bits": engine::memorable::entropy_bits(&config),
                     "time_taken_ms": start_time.elapsed().as_millis(),
-                }))?);
+                });
+                if let Some(counts) = &pwned {
+                    report["pwned"] = serde_json::json!(counts);
+                }
+                println!("{}", serde_json::to_string_pretty(&report)?);
             }
             OutputFormat::Plain => {
                 println!("\n  ╔═══════════════════════════════════════════╗");
                 println!("  ║     JIGSAW Memorable Passwords            ║");
                 println!("  ╚═══════════════════════════════════════════╝\n");
+                if let Some(bits) = entropy {
+                    println!("  Entropy: ~{:.1} bits ({} words)\n", bits, config.word_count);
+                }
                 for (i, pw) in passwords.iter().enumerate() {
-                    println!("  {}. {} (len: {})", i + 1, pw, pw.len());
+                    match breaches(i) {
+                        Some(n @ 1..) => println!("  {}. {} (len: {}) [!] in {} known breaches", i + 1, pw, pw.len(), n),
+                        _ => println!("  {}. {} (len: {})", i + 1, pw, pw.len()),
+                    }
+                }
+                if pwned.is_some() {
+                    status!("\n  Checked against Have I Been Pwned (k-anonymity: only hash prefixes were sent)");
                 }
-                println!("\n  Generated {} password(s) in {}ms\n",
+                status!("\n  Generated {} password(s) in {}ms\n",
                     passwords.len(), start_time.elapsed().as_millis());
             }
         }
         return Ok(());
     }
 
+    // --- Mnemonic Password Mode ---
+    if let Some(sentence) = &final_args.mnemonic {
+        let config = engine::mnemonic::MnemonicConfig {
+            case: match final_args.mnemonic_case {
+                MnemonicCaseArg::Preserve => engine::mnemonic::MnemonicCase::Preserve,
+                MnemonicCaseArg::Lower => engine::mnemonic::MnemonicCase::Lower,
+                MnemonicCaseArg::Upper => engine::mnemonic::MnemonicCase::Upper,
+                MnemonicCaseArg::Alternating => engine::mnemonic::MnemonicCase::Alternating,
+            },
+            leet: final_args.mnemonic_leet,
+            number_words: final_args.number_words,
+            keep_punctuation: !final_args.no_punctuation,
+        };
+        let password = engine::mnemonic::acronym(sentence, &config);
+        if password.is_empty() {
+            return Err(invalid!("No words found in the sentence"));
+        }
+        let start_time = std::time::Instant::now();
+        let mut variants = engine::mnemonic::variants(sentence, &config);
+        if let Some(n) = final_args.limit {
+            variants.truncate(n as usize);
+        }
+        if let Some(n) = final_args.sample {
+            let mut reservoir = Reservoir::new(n as usize, sample_rng(&final_args));
+            variants.into_iter().for_each(|variant| reservoir.push(variant));
+            variants = reservoir.into_vec();
+        }
+        if final_args.estimate {
+            let rate = variants.len() as f64 / start_time.elapsed().as_secs_f64().max(1e-9);
+            let bytes = variants.iter().map(|v| v.len()).sum::<usize>();
+            print_estimate(&final_args, variants.len() as u128, "exact", bytes as f64, rate);
+            return Ok(());
+        }
+
+        summary.candidates = variants.len() as u64;
+        match final_args.format {
+            OutputFormat::Parquet => return Err(invalid!("--format parquet is for wordlists (mask, Markov, personal)")),
+            OutputFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(&serde_json::json!({
+                    "password": password,
+                    "length": password.len(),
+                    "variants": variants,
+                }))?);
+            }
+            OutputFormat::Ndjson => {
+                println!("{}", serde_json::json!({
+                    "password": password,
+                    "length": password.len(),
+                    "variants": variants,
+                }));
+            }
+            OutputFormat::Plain => {
+                println!("\n  ╔═══════════════════════════════════════════╗");
+                println!("  ║     JIGSAW Mnemonic Password              ║");
+                println!("  ╚═══════════════════════════════════════════╝\n");
+                println!("  {} (len: {})\n", password, password.len());
+                println!("  Variants:");
+                for variant in variants.iter().filter(|v| **v != password) {
+                    println!("    {}", variant);
+                }
+                println!();
+            }
+        }
+        return Ok(());
+    }
+
     // --- Personal Attack Mode ---
     if final_args.personal || final_args.profile.is_some() {
         let start_time = std::time::Instant::now();
-        println!("\n  ╔═══════════════════════════════════════════╗");
-        println!("  ║     JIGSAW Personal Attack Engine          ║");
-        println!("  ╚═══════════════════════════════════════════╝\n");
+        status!("\n  ╔═══════════════════════════════════════════╗");
+        status!("  ║     JIGSAW Personal Attack Engine          ║");
+        status!("  ╚═══════════════════════════════════════════╝\n");
         
-        let profile_path = final_args.profile
-            .ok_or_else(|| anyhow::anyhow!("Profile path required (use --profile <PATH>)"))?;
+        let profile_path = final_args.profile.clone()
+            .ok_or_else(|| invalid!("Profile path required (use --profile <PATH>)"))?;
             
-        println!("  Profile:  {:?}", profile_path);
-        println!("  Level:    {:?}", final_args.level);
+        status!("  Profile:  {:?}", profile_path);
+        status!("  Level:    {:?}", final_args.level);
         
-        let mut profile = engine::personal::Profile::load(&profile_path)?;
+        let profile_text = std::fs::read_to_string(&profile_path)?;
+        let diagnostics = engine::validate::validate_profile(&profile_text)?;
+        for diag in &diagnostics {
+            eprintln!("  [!] {:?} {}", profile_path, diag);
+        }
+        if final_args.strict && !diagnostics.is_empty() {
+            return Err(invalid!("{} profile warning(s) with --strict", diagnostics.len()));
+        }
+        let mut profile: engine::personal::Profile = serde_json::from_str(&profile_text)?;
         
         // Apply CLI length overrides
-        if let Some(min) = final_args.min_length {
+        if let Some(min) = final_args.filter.min_length {
             profile.min_length = Some(min);
         }
-        if let Some(max) = final_args.max_length {
+        if let Some(max) = final_args.filter.max_length {
             profile.max_length = Some(max);
         }
+        if final_args.typos {
+            profile.typos = true;
+        }
+        if final_args.walk_layout.is_some() {
+            profile.keyboard = walk_layout(final_args.walk_layout);
+        }
+        profile.skip.extend(final_args.skip.iter().map(|category| category.category()));
+        // Level-based pruning keeps the highest-weighted profile values, but
+        // only once the profile ranks its words
+        let weighted = !profile.field_weights.is_empty() || !profile.word_weights.is_empty();
+        if profile.word_limit.is_none() && weighted {
+            profile.word_limit = final_args.level.word_limit();
+        }
+        if let Some(path) = &final_args.nicknames {
+            for (name, nicks) in engine::nicknames::load_map(path)? {
+                profile.nickname_map.entry(name).or_default().extend(nicks);
+            }
+        }
+        if let Some(path) = &final_args.blend {
+            profile.blend.extend(io::reader::read_words(path)?);
+            status!("  Blend:    {} common words from {:?}", profile.blend.len(), path);
+        }
         
         if let Some(min) = profile.min_length {
-            println!("  Min Len:  {}", min);
+            status!("  Min Len:  {}", min);
         }
         if let Some(max) = profile.max_length {
-            println!("  Max Len:  {}", max);
+            status!("  Max Len:  {}", max);
         }
-        println!();
+        if let Some(limit) = profile.word_limit {
+            status!("  Words:    top {} by weight", limit);
+        }
+        status!();
         
         // Check Mode
         if let Some(target) = &final_args.check {
-            println!("  Checking for password: '{}'...", target);
+            status!("  Checking for password: '{}'...", target);
             if profile.check_password(target) {
                 println!("\n  [+] FOUND: Password exists in generated candidates!");
             } else {
                 println!("\n  [-] NOT FOUND: Password not in generated list.");
+                summary.exit_code = exit::NOT_FOUND;
             }
-            println!("  Time taken: {}ms", start_time.elapsed().as_millis());
+            status!("  Time taken: {}ms", start_time.elapsed().as_millis());
+            return Ok(());
+        }
+
+        // Estimate Mode
+        if final_args.estimate {
+            let estimate = profile.estimate();
+            let sample_start = std::time::Instant::now();
+            let sample = profile.sample(ESTIMATE_SAMPLE as usize).len();
+            let rate = sample as f64 / sample_start.elapsed().as_secs_f64().max(1e-9);
+            // `Estimate::bytes` counts a newline per candidate
+            let bytes = estimate.bytes - estimate.candidates;
+            print_estimate(&final_args, estimate.candidates as u128, "analytic, before dedup", bytes as f64, rate);
+            status!("  Time taken: {}ms", start_time.elapsed().as_millis());
+            return Ok(());
+        }
+
+        // Hashcat Rules Mode
+        if let Some(rule_path) = &final_args.emit_rules {
+            let (words, rules) = profile.hashcat_rules();
+            let mut rule_file = rules.join("\n");
+            rule_file.push('\n');
+            std::fs::write(rule_path, rule_file)?;
+            summary.output(rule_path);
+            status!("  Rules:    {} written to {:?}", rules.len(), rule_path);
+
+            let (mut batcher, writer_thread) = spawn_writer(&final_args, Some(words.len() as u64), 0)?;
+            status!("  Words:    {} base words", words.len());
+            let _ = words.into_iter().try_for_each(|word| batcher.push(word.into_bytes()));
+            drop(batcher);
+            summary.add(&writer_thread.join().expect("Writer panic")?);
+
+            status!("  Done. Time taken: {}ms\n", start_time.elapsed().as_millis());
             return Ok(());
         }
 
         // Generate
-        println!("  Generating candidates...");
-        let candidates = profile.generate();
-        println!("  Generated {} unique candidates.", candidates.len());
+        let dedup = match final_args.dedup {
+            DedupMode::Exact => Dedup::Exact,
+            DedupMode::Bloom => {
+                if !(final_args.fp_rate > 0.0 && final_args.fp_rate < 1.0) {
+                    return Err(invalid!("--fp-rate must be between 0 and 1 (got {})", final_args.fp_rate));
+                }
+                status!("  Dedup:    bloom (fp rate {})", final_args.fp_rate);
+                Dedup::Bloom { fp_rate: final_args.fp_rate }
+            }
+        };
+        status!("  Generating candidates...");
 
         match final_args.format {
             OutputFormat::Json => {
-                let strings: Vec<String> = candidates.iter()
-                    .map(|b| String::from_utf8_lossy(b).to_string())
-                    .collect();
-                let output_path = final_args.output;
+                // The writer isn't involved, so filter here; for_each_unique
+                // already drops every repeat, which is all --unique would do
+                let filter = final_args.filter.filter()?;
+                let mut strings: Vec<String> = Vec::new();
+                match (final_args.limit, final_args.sample) {
+                    (_, Some(n)) => {
+                        let mut reservoir = Reservoir::new(n as usize, sample_rng(&final_args));
+                        profile.for_each_unique(dedup, |s| {
+                            if filter.accepts(s.as_bytes()) {
+                                reservoir.push(s);
+                            }
+                            false
+                        });
+                        strings = reservoir.into_vec();
+                    }
+                    (Some(n), None) => profile.for_each_unique(dedup, |s| {
+                        if filter.accepts(s.as_bytes()) {
+                            strings.push(s);
+                        }
+                        strings.len() as u64 >= n
+                    }),
+                    (None, None) => profile.for_each_unique(dedup, |s| {
+                        if filter.accepts(s.as_bytes()) {
+                            strings.push(s);
+                        }
+                        false
+                    }),
+                }
+                status!("  Generated {} unique candidates.", strings.len());
+                summary.candidates = strings.len() as u64;
+                let output_path = final_args.output.clone();
                 let json = serde_json::to_string_pretty(&serde_json::json!({
                     "candidates": strings,
                     "total": strings.len(),
                     "time_taken_ms": start_time.elapsed().as_millis(),
                 }))?;
                 if let Some(path) = output_path {
-                    std::fs::write(&path, &json)?;
-                    println!("  Written to {:?}", path);
+                    match path.to_str() {
+                        Some(url) if io::upload::is_remote(url) => {
+                            if final_args.manifest {
+                                return Err(invalid!("--manifest needs a local --output file"));
+                            }
+                            io::upload::put(url, json.as_bytes())?
+                        }
+                        _ => {
+                            std::fs::write(&path, &json)?;
+                            summary.output(&path);
+                        }
+                    }
+                    status!("  Written to {:?}", path);
+                    if final_args.manifest {
+                        let sidecar = io::manifest::write(&path, strings.len() as u64, 0, manifest_parameters(&final_args))?;
+                        status!("  Manifest: {:?}", sidecar);
+                        summary.output(&sidecar);
+                    }
                 } else {
                     println!("{}", json);
                 }
             }
-            OutputFormat::Plain => {
-                // Setup Output via writer
-                let (sender, receiver) = bounded::<Vec<Vec<u8>>>(100);
-                let writer_output = match final_args.output {
-                    Some(path) => WriterOutput::File(path),
-                    None => WriterOutput::Stdout,
-                };
-                let writer_thread = Writer::new(receiver, writer_output).start();
-                
-                // Send in parallel batches
-                let chunk_size = 1000;
-                for chunk in candidates.chunks(chunk_size) {
-                    sender.send(chunk.to_vec()).expect("Channel closed");
+            OutputFormat::Plain | OutputFormat::Ndjson | OutputFormat::Parquet => {
+                // Only a Bloom filter needs sizing from the estimate
+                let expected = matches!(final_args.unique, Some(DedupMode::Bloom))
+                    .then(|| profile.estimate().candidates);
+                let (mut batcher, writer_thread) = spawn_writer(&final_args, expected, 0)?;
+
+                // Stream in batches as candidates come out of dedup; a closed
+                // channel means the writer hit --limit or failed
+                let mut total = 0usize;
+                if let Some(n) = final_args.sample {
+                    let mut reservoir = Reservoir::new(n as usize, sample_rng(&final_args));
+                    profile.for_each_unique(dedup, |s| { reservoir.push(s); false });
+                    let sample = reservoir.into_vec();
+                    total = sample.len();
+                    let _ = sample.into_iter().try_for_each(|s| batcher.push(s.into_bytes()));
+                } else {
+                    profile.for_each_unique(dedup, |s| {
+                        total += 1;
+                        batcher.push(s.into_bytes()).is_err()
+                    });
                 }
-                
-                drop(sender);
-                writer_thread.join().expect("Writer panic")?;
+
+                drop(batcher);
+                summary.add(&writer_thread.join().expect("Writer panic")?);
+                status!("  Generated {} unique candidates.", total);
             }
         }
         
-        println!("  Done. Time taken: {}ms\n", start_time.elapsed().as_millis());
+        status!("  Done. Time taken: {}ms\n", start_time.elapsed().as_millis());
+        return Ok(());
+    }
+
+    // --- PRINCE Mode ---
+    if let Some(wordlist) = &final_args.prince {
+        let start_time = std::time::Instant::now();
+        status!("JIGSAW Running in PRINCE Mode...");
+        let (min_len, max_len) = (final_args.prince_min_len, final_args.prince_max_len);
+        if min_len == 0 || min_len > max_len {
+            return Err(invalid!("--prince-min-len must be between 1 and --prince-max-len (got {} and {})", min_len, max_len));
+        }
+        let (min_elems, max_elems) = (final_args.prince_min_elems, final_args.prince_max_elems);
+        if min_elems == 0 || min_elems > max_elems {
+            return Err(invalid!("--prince-min-elems must be between 1 and --prince-max-elems (got {} and {})", min_elems, max_elems));
+        }
+        let prince = engine::prince::Prince::new(io::reader::read_words(wordlist)?, min_len, max_len, min_elems, max_elems);
+        if prince.elements().is_empty() {
+            return Err(invalid!("{:?} has no elements of at most {} characters", wordlist, max_len));
+        }
+        let keyspace = prince.keyspace();
+        status!("Elements: {} from {:?}", prince.elements().len(), wordlist);
+        status!("Keyspace: {}", keyspace);
+
+        if final_args.estimate {
+            let mut sampled = 0u64;
+            let mut bytes = 0usize;
+            prince.for_each(|chain| {
+                sampled += 1;
+                bytes += chain.len();
+                sampled >= ESTIMATE_SAMPLE
+            });
+            let rate = sampled as f64 / start_time.elapsed().as_secs_f64().max(1e-9);
+            let mean_len = bytes as f64 / sampled.max(1) as f64;
+            print_estimate(&final_args, keyspace, "exact", keyspace as f64 * mean_len, rate);
+            return Ok(());
+        }
+
+        let (mut batcher, writer_thread) = spawn_writer(&final_args, u64::try_from(keyspace).ok(), 0)?;
+
+        // Chains come out best first, so a closed channel (--limit, or a
+        // failed writer) just ends the run early
+        if let Some(n) = final_args.sample {
+            let mut reservoir = Reservoir::new(n as usize, sample_rng(&final_args));
+            prince.for_each(|chain| { reservoir.push(chain.to_vec()); false });
+            let _ = reservoir.into_vec().into_iter().try_for_each(|chain| batcher.push(chain));
+        } else {
+            prince.for_each(|chain| batcher.push(chain.to_vec()).is_err());
+        }
+
+        drop(batcher);
+        summary.add(&writer_thread.join().expect("Writer panic")?);
+        status!("Done. Time taken: {}ms", start_time.elapsed().as_millis());
+        return Ok(());
+    }
+
+    // --- Keyboard Walk Mode ---
+    if final_args.walks {
+        let start_time = std::time::Instant::now();
+        status!("JIGSAW Running in Keyboard Walk Mode...");
+        let (min_len, max_len) = (final_args.walk_min_len, final_args.walk_max_len);
+        if min_len < 2 || min_len > max_len {
+            return Err(invalid!("--walk-min-len must be between 2 and --walk-max-len (got {} and {})", min_len, max_len));
+        }
+        let directions: Vec<Direction> = final_args.walk_directions.iter()
+            .flat_map(|direction| match direction {
+                WalkDirection::Right => &[Direction::Right][..],
+                WalkDirection::Left => &[Direction::Left][..],
+                WalkDirection::Down => &[Direction::DownLeft, Direction::DownRight][..],
+                WalkDirection::Up => &[Direction::UpLeft, Direction::UpRight][..],
+            })
+            .copied()
+            .collect();
+        let layout = walk_layout(final_args.walk_layout);
+        let walks = engine::walks::Walks::new(layout, min_len, max_len, final_args.walk_turns, &directions, final_args.walk_shift);
+        let keyspace = walks.keyspace();
+        status!("Layout: {:?}", layout);
+        status!("Keyspace: {}", keyspace);
+
+        if final_args.estimate {
+            let mut bytes = 0usize;
+            walks.for_each(|walk| { bytes += walk.len(); false });
+            let rate = keyspace as f64 / start_time.elapsed().as_secs_f64().max(1e-9);
+            print_estimate(&final_args, keyspace as u128, "exact", bytes as f64, rate);
+            return Ok(());
+        }
+
+        let (mut batcher, writer_thread) = spawn_writer(&final_args, Some(keyspace), 0)?;
+        if let Some(n) = final_args.sample {
+            let mut reservoir = Reservoir::new(n as usize, sample_rng(&final_args));
+            walks.for_each(|walk| { reservoir.push(walk.as_bytes().to_vec()); false });
+            let _ = reservoir.into_vec().into_iter().try_for_each(|walk| batcher.push(walk));
+        } else {
+            walks.for_each(|walk| batcher.push(walk.as_bytes().to_vec()).is_err());
+        }
+
+        drop(batcher);
+        summary.add(&writer_thread.join().expect("Writer panic")?);
+        status!("Done. Time taken: {}ms", start_time.elapsed().as_millis());
         return Ok(());
     }
 
     // --- Mask Mode ---
     if final_args.mask.is_none() {
-        println!("Error: No mode specified. Use --interactive, --personal, --memorable, --mask, or --markov.");
-        println!("Try: jigsaw --help");
+        eprintln!("Error: No mode specified. Use --interactive, --personal, --memorable, --mask, --markov, --prince, or --walks.");
+        eprintln!("Try: jigsaw --help");
+        summary.exit_code = exit::INVALID_INPUT;
         return Ok(());
     }
 
-    let mask_str = final_args.mask.unwrap();
+    let mask_str = final_args.mask.clone().unwrap();
     let start_time = std::time::Instant::now();
-    println!("JIGSAW Running...");
-    println!("Mask: {}", mask_str);
+    status!("JIGSAW Running...");
+    status!("Mask: {}", mask_str);
 
-    let mask = Mask::from_str(&mask_str)?;
-    println!("Search space: {}", mask.search_space_size());
+    let mask = final_args.charsets.mask(&mask_str)?;
+    status!("Search space: {}", mask.search_space_size());
 
     if let Some(threads) = final_args.threads {
         rayon::ThreadPoolBuilder::new().num_threads(threads).build_global()?;
     }
 
-    let (sender, receiver) = bounded::<Vec<Vec<u8>>>(100);
-    
-    let writer_output = match final_args.output {
-        Some(path) => WriterOutput::File(path),
-        None => WriterOutput::Stdout,
+    if final_args.estimate {
+        let space = mask.search_space_size();
+        let sample = u64::try_from(space).unwrap_or(u64::MAX).min(ESTIMATE_SAMPLE);
+        let (rate, mean_len) = measure(sample, |i| mask.nth_candidate(i as u128).map_or(0, |c| c.len()));
+        print_estimate(&final_args, space, "exact", space as f64 * mean_len, rate);
+        return Ok(());
+    }
+
+    // Masks are indexed, so a resumed run seeks to its checkpoint instead of replaying up to it
+    let resume_from = match (&final_args.output, final_args.append && final_args.sample.is_none()) {
+        (Some(path), true) => io::writer::Checkpoint::load(path).map_or(0, |checkpoint| checkpoint.consumed),
+        _ => 0,
+    };
+    let (batcher, writer_thread) = spawn_writer(&final_args, u64::try_from(mask.search_space_size()).ok(), resume_from)?;
+
+    // A closed channel means the writer failed; joining it reports why
+    let _ = if let Some(n) = final_args.sample {
+        // Masks are indexed, so draw the sample's positions directly
+        let indices = engine::sample::distinct_indices(mask.search_space_size(), n, &mut sample_rng(&final_args));
+        let mut batcher = batcher.clone();
+        indices.into_iter().try_for_each(|i| batcher.push(mask.nth_candidate(i).expect("index is in the keyspace")))
+    } else if final_args.append || final_args.limit.is_some() {
+        // Sequential, so a resumed run replays candidates in the same order
+        // and --limit keeps the start of the keyspace
+        let mut sending = batcher.clone();
+        mask.batches_from(resume_from as u128, final_args.batch_size, || batcher.spare())
+            .try_for_each(|batch| sending.send(batch))
+    } else {
+        mask.par_batches(final_args.batch_size, || batcher.spare()).try_for_each_init(|| batcher.clone(), |batcher, batch| batcher.send(batch))
     };
 
-    let writer_thread = Writer::new(receiver, writer_output).start();
+    drop(batcher);
+    summary.add(&writer_thread.join().expect("Writer thread panicked")?);
     
-    struct BatchSender {
-        buffer: Vec<Vec<u8>>,
-        sender: crossbeam_channel::Sender<Vec<Vec<u8>>>,
+    status!("Done. Time taken: {}ms", start_time.elapsed().as_millis());
+    Ok(())
+}
+
+/// A trained model for --markov, `rank` and `audit`, of the kind --model-type names
+enum CandidateModel {
+    Markov(engine::markov::MarkovModel),
+    #[cfg(feature = "neural")]
+    Neural(engine::neural::NeuralModel),
+}
+
+impl CandidateModel {
+    fn load(model_type: ModelType, path: &std::path::Path) -> anyhow::Result<Self> {
+        match model_type {
+            ModelType::Markov => Ok(Self::Markov(engine::markov::MarkovModel::load(path)?)),
+            #[cfg(feature = "neural")]
+            ModelType::Neural => Ok(Self::Neural(engine::neural::NeuralModel::load(path)?)),
+            #[cfg(not(feature = "neural"))]
+            ModelType::Neural => Err(invalid!("--model-type neural needs jigsaw built with `--features neural`")),
+        }
     }
-    
-    impl Drop for BatchSender {
-        fn drop(&mut self) {
-            if !self.buffer.is_empty() {
-                let _ = self.sender.send(self.buffer.clone());
-            }
+
+    fn generate(&self, rng: &mut impl rand::Rng, min_len: usize, max_len: usize) -> String {
+        match self {
+            Self::Markov(model) => model.generate(rng, min_len, max_len),
+            #[cfg(feature = "neural")]
+            Self::Neural(model) => model.generate(rng, min_len, max_len),
         }
     }
-    
-    mask.par_iter().for_each_init(
-        || BatchSender {
-            buffer: Vec::with_capacity(1000),
-            sender: sender.clone(),
-        },
-        |batcher, candidate| {
-            batcher.buffer.push(candidate);
-            if batcher.buffer.len() >= 1000 {
-                batcher.sender.send(batcher.buffer.clone()).expect("Writer channel closed");
-                batcher.buffer.clear();
+
+    fn log_probability(&self, word: &str) -> Option<f64> {
+        match self {
+            Self::Markov(model) => model.log_probability(word),
+            #[cfg(feature = "neural")]
+            Self::Neural(model) => model.log_probability(word),
+        }
+    }
+}
+
+/// Set up `--session NAME`: a new session starts its output afresh and pins
+/// a Markov seed so the run can be replayed; a restored one carries on.
+/// Either way the run appends, so the output keeps a checkpoint.
+fn begin_session(args: &mut JigsawArgs, dir: &std::path::Path, name: &str, argv: Vec<String>, restored: Option<Session>) -> anyhow::Result<Session> {
+    let streams = !matches!(args.format, OutputFormat::Json | OutputFormat::Parquet);
+    let other_mode = args.train.is_some() || args.memorable || args.mnemonic.is_some();
+    let no_output = args.estimate || args.check.is_some() || args.emit_rules.is_some();
+    let wordlist = args.markov || args.mask.is_some() || args.prince.is_some() || args.walks || args.personal || args.profile.is_some();
+    if !streams || other_mode || no_output || !wordlist {
+        return Err(invalid!("--session resumes mask, Markov, PRINCE, keyboard-walk and personal wordlists written as plain text or NDJSON"));
+    }
+    let output = args.output.clone().ok_or_else(|| invalid!("--session needs --output"))?;
+    if output.to_str().is_some_and(io::upload::is_remote) {
+        return Err(invalid!("--session needs a local --output file"));
+    }
+
+    let mut session = match restored {
+        Some(session) => {
+            status!("  [session] restoring '{}': {} run, {} lines in {:?}", name, session.mode, session.progress(), output);
+            session
+        }
+        None => {
+            if let Ok(existing) = Session::load(dir, name) {
+                if !existing.finished {
+                    return Err(invalid!("session '{}' is unfinished; resume it with `jigsaw restore {}` or pick another name", name, name));
+                }
+            }
+            let mut command = argv;
+            if args.markov && args.seed.is_none() {
+                let seed = rand::random();
+                args.seed = Some(seed);
+                command.push(format!("--seed={}", seed));
             }
+            if !args.append {
+                // Like any run without --append, start the output over
+                let _ = std::fs::remove_file(io::writer::checkpoint_path(&output));
+                std::fs::File::create(&output)?;
+            }
+            Session::new(name, mode_name(args), output, command, manifest_parameters(args))?
         }
-    );
-    
-    drop(sender);
-    writer_thread.join().expect("Writer thread panicked")?;
-    
-    println!("Done. Time taken: {}ms", start_time.elapsed().as_millis());
+    };
+    args.append = true;
+    session.save(dir)?;
+    Ok(session)
+}
+
+/// `jigsaw restore` without a name
+fn list_sessions() -> anyhow::Result<()> {
+    let sessions = Session::list(&cli::session::dir()?)?;
+    if sessions.is_empty() {
+        println!("  No sessions in {:?}", cli::session::dir()?);
+    }
+    for session in sessions {
+        let state = if session.finished { "finished" } else { "unfinished" };
+        println!("  {:<20} {:<8} {:<10} {} lines in {:?}", session.name, session.mode, state, session.progress(), session.output);
+    }
     Ok(())
 }
 
+/// Engine layout for --walk-layout, QWERTY when unset
+fn walk_layout(layout: Option<KeyboardLayout>) -> Layout {
+    match layout {
+        None | Some(KeyboardLayout::Qwerty) => Layout::Qwerty,
+        Some(KeyboardLayout::Azerty) => Layout::Azerty,
+        Some(KeyboardLayout::Qwertz) => Layout::Qwertz,
+    }
+}
+
+/// The generator a run uses, checked in the order `run` does
+fn mode_name(args: &JigsawArgs) -> &'static str {
+    if args.train.is_some() {
+        "train"
+    } else if args.markov {
+        "markov"
+    } else if args.memorable {
+        "memorable"
+    } else if args.mnemonic.is_some() {
+        "mnemonic"
+    } else if args.personal || args.profile.is_some() {
+        "personal"
+    } else if args.prince.is_some() {
+        "prince"
+    } else if args.walks {
+        "walks"
+    } else {
+        "mask"
+    }
+}
+
+/// Generation settings recorded by --manifest, enough to rerun the list
+fn manifest_parameters(args: &JigsawArgs) -> serde_json::Value {
+    use clap::ValueEnum;
+    fn name<T: ValueEnum>(value: &T) -> Option<String> {
+        value.to_possible_value().map(|v| v.get_name().to_string())
+    }
+    let mode = match mode_name(args) {
+        "personal" => serde_json::json!({
+            "mode": "personal",
+            "profile": args.profile,
+            "level": name(&args.level),
+            "min_length": args.filter.min_length,
+            "max_length": args.filter.max_length,
+            "skip": args.skip.iter().filter_map(name).collect::<Vec<_>>(),
+            "typos": args.typos,
+            "walk_layout": args.walk_layout.as_ref().and_then(name),
+            "blend": args.blend,
+            "dedup": name(&args.dedup),
+        }),
+        "markov" => serde_json::json!({
+            "mode": "markov",
+            "model": args.model,
+            "model_type": name(&args.model_type),
+            "count": args.count,
+            "min_len": args.markov_min_len,
+            "max_len": args.markov_max_len,
+            "seed": args.seed,
+        }),
+        "prince" => serde_json::json!({
+            "mode": "prince",
+            "wordlist": args.prince,
+            "min_len": args.prince_min_len,
+            "max_len": args.prince_max_len,
+            "min_elems": args.prince_min_elems,
+            "max_elems": args.prince_max_elems,
+        }),
+        "walks" => serde_json::json!({
+            "mode": "walks",
+            "layout": name(&args.walk_layout.unwrap_or(KeyboardLayout::Qwerty)),
+            "min_len": args.walk_min_len,
+            "max_len": args.walk_max_len,
+            "turns": args.walk_turns,
+            "directions": args.walk_directions.iter().filter_map(name).collect::<Vec<_>>(),
+            "shift": args.walk_shift,
+        }),
+        "train" => match args.model_type {
+            ModelType::Markov => serde_json::json!({ "mode": "train", "train": args.train, "order": args.order, "model": args.model }),
+            ModelType::Neural => serde_json::json!({
+                "mode": "train",
+                "model_type": "neural",
+                "train": args.train,
+                "order": args.order,
+                "hidden": args.hidden,
+                "epochs": args.epochs,
+                "seed": args.seed.unwrap_or(0),
+                "model": args.model,
+            }),
+        },
+        // Passwords, not lists: record their shape, never the sentence or the output
+        "memorable" => serde_json::json!({
+            "mode": "memorable",
+            "style": name(&args.mem_style),
+            "words": args.words,
+            "case": name(&args.mem_case),
+            "language": name(&args.language),
+            "count": args.mem_count,
+            "template": args.template,
+            "policy": args.policy,
+            "min_entropy": args.min_entropy,
+        }),
+        "mnemonic" => serde_json::json!({
+            "mode": "mnemonic",
+            "case": name(&args.mnemonic_case),
+            "leet": args.mnemonic_leet,
+            "number_words": args.number_words,
+        }),
+        _ => serde_json::json!({
+            "mode": "mask",
+            "mask": args.mask,
+            "custom_charsets": [
+                &args.charsets.custom_charset1, &args.charsets.custom_charset2,
+                &args.charsets.custom_charset3, &args.charsets.custom_charset4,
+            ],
+            "frequency_order": args.charsets.frequency_order,
+        }),
+    };
+    let mut parameters = mode;
+    parameters["format"] = name(&args.format).into();
+    parameters["line_ending"] = name(&args.line_ending).into();
+    parameters["unique"] = args.unique.as_ref().and_then(name).into();
+    parameters["compress"] = args.compress.as_ref().and_then(name).into();
+    parameters["filters"] = serde_json::json!({
+        "min_length": args.filter.min_length,
+        "max_length": args.filter.max_length,
+        "require": args.filter.require.iter().filter_map(name).collect::<Vec<_>>(),
+        "forbid": args.filter.forbid.iter().filter_map(name).collect::<Vec<_>>(),
+        "allowed_chars": args.filter.allowed_chars,
+    });
+    parameters
+}
+
 /// Build MemorableConfig from CLI args
 fn build_memorable_config(args: &JigsawArgs) -> MemorableConfig {
     MemorableConfig {
@@ -321,5 +1070,187 @@ fn build_memorable_config(args: &JigsawArgs) -> MemorableConfig {
         count: args.mem_count,
         min_length: args.mem_min_len,
         max_length: args.mem_max_len,
+        word_pool: match args.word_pool {
+            MemWordPool::Builtin => WordPool::Builtin,
+            MemWordPool::EffLong => WordPool::EffLong,
+            MemWordPool::EffShort => WordPool::EffShort,
+        },
+        custom_words: Vec::new(),
+        language: language(args.language),
+        custom_pools: None,
+        template: args.template.clone(),
+        policy: None,
+        leet_probability: if args.mem_leet { args.leet_prob.clamp(0.0, 1.0) } else { 0.0 },
+        seed: args.seed,
+        specials: args.specials.clone(),
+        min_word_len: args.min_word_len,
+        max_word_len: args.max_word_len,
+        number_form: match args.num_form {
+            NumForm::Digits => NumberForm::Digits,
+            NumForm::Words => NumberForm::Words,
+            NumForm::Both => NumberForm::WordsAndDigits,
+        },
+    }
+}
+
+/// Engine language for --language and --locales
+fn language(language: MemLanguage) -> Language {
+    match language {
+        MemLanguage::En => Language::English,
+        MemLanguage::De => Language::German,
+        MemLanguage::Es => Language::Spanish,
+        MemLanguage::Fr => Language::French,
+        MemLanguage::It => Language::Italian,
+        MemLanguage::Pt => Language::Portuguese,
+    }
+}
+
+/// Writer thread for streamed output, honouring --output/--pipe-to, --format ndjson,
+/// --line-ending, --compress, --unique and --stats. `expected` sizes a bloom filter when the count is known;
+/// `resume_from` is where an appending engine starts itself (see `Writer::resume_from`).
+fn spawn_writer(
+    args: &JigsawArgs,
+    expected: Option<u64>,
+    resume_from: u64,
+) -> anyhow::Result<(BatchSender, std::thread::JoinHandle<anyhow::Result<io::writer::Stats>>)> {
+    let (sender, receiver) = io::writer::channel(args.channel_capacity);
+    // Written batches come back to be refilled rather than reallocated
+    let (spent, spares) = io::writer::channel(args.channel_capacity);
+    let output = match (&args.pipe_to, &args.output) {
+        (Some(command), _) => WriterOutput::Process(command.clone()),
+        (None, Some(path)) if path.to_str().is_some_and(io::upload::is_remote) => {
+            WriterOutput::Upload(path.to_string_lossy().into_owned())
+        }
+        (None, Some(path)) => WriterOutput::File(path.clone()),
+        (None, None) => WriterOutput::Stdout,
+    };
+    let mut writer = Writer::new(receiver, output).recycle(spent).line_ending(match args.line_ending {
+        LineEndingArg::Lf => LineEnding::Lf,
+        LineEndingArg::Crlf => LineEnding::CrLf,
+        LineEndingArg::Nul => LineEnding::Nul,
+    });
+    match args.compress {
+        Some(CompressFormat::Gz) => writer = writer.compression(Compression::Gzip),
+        Some(CompressFormat::Zst) => writer = writer.compression(Compression::Zstd),
+        None => {}
+    }
+    match args.format {
+        OutputFormat::Ndjson => writer = writer.format(RecordFormat::Ndjson),
+        OutputFormat::Parquet => writer = writer.format(RecordFormat::Parquet).category(mode_name(args)),
+        OutputFormat::Plain | OutputFormat::Json => {}
+    }
+    if args.append {
+        writer = writer.append().resume_from(resume_from);
+    }
+    if let Some(n) = args.limit {
+        writer = writer.limit(n);
+    }
+    if args.sort_unique {
+        writer = writer.sort_unique(args.sort_memory.max(1) * 1024 * 1024);
+    }
+    if args.manifest {
+        writer = writer.manifest(manifest_parameters(args));
+    }
+    match args.roll {
+        Some(RollArg::Hourly) => writer = writer.roll(Roll::Hourly),
+        Some(RollArg::Daily) => writer = writer.roll(Roll::Daily),
+        None => {}
+    }
+    if args.stats {
+        writer = writer.report_every(std::time::Duration::from_secs(args.stats_interval.max(1)));
+    }
+    match args.unique {
+        Some(DedupMode::Exact) => writer = writer.unique(Unique::Exact),
+        Some(DedupMode::Bloom) => writer = writer.unique(Unique::Bloom { expected, fp_rate: args.fp_rate }),
+        None => {}
+    }
+    // --emit-rules writes base words for hashcat to mangle; filters are for finished candidates
+    let filter = args.filter.filter()?;
+    if args.emit_rules.is_none() {
+        writer = writer.filter(filter);
+    }
+    Ok((BatchSender::new(sender, args.batch_size).recycling(spares), writer.start()))
+}
+
+/// RNG for --sample: repeatable with --seed
+fn sample_rng(args: &JigsawArgs) -> chacha20::ChaCha20Rng {
+    engine::memorable::seeded_rng(args.seed.unwrap_or_else(rand::random))
+}
+
+/// Candidates generated to time a run for --estimate
+const ESTIMATE_SAMPLE: u64 = 100_000;
+
+/// Generate `sample` candidates in parallel, as a real run does, and
+/// return candidates per second and their mean length. Only generation is
+/// timed: dedup, the writer and the disk can make a real run slower.
+fn measure(sample: u64, generate: impl Fn(u64) -> usize + Sync + Send) -> (f64, f64) {
+    let start = std::time::Instant::now();
+    let bytes: usize = (0..sample).into_par_iter().map(generate).sum();
+    let rate = sample as f64 / start.elapsed().as_secs_f64().max(1e-9);
+    (rate, bytes as f64 / sample.max(1) as f64)
+}
+
+/// Print the --estimate report. `bytes` is the candidates' own length;
+/// record framing for the chosen --format is added here.
+fn print_estimate(args: &JigsawArgs, candidates: u128, basis: &str, bytes: f64, rate: f64) {
+    let framing = match args.format {
+        // {"candidate":""} and a newline
+        OutputFormat::Ndjson => 17,
+        // Four spaces of indent, quotes, comma and newline
+        OutputFormat::Json => 8,
+        OutputFormat::Plain | OutputFormat::Parquet => match args.line_ending {
+            LineEndingArg::Crlf => 2,
+            LineEndingArg::Lf | LineEndingArg::Nul => 1,
+        },
+    };
+    println!("  Keyspace: {} candidates ({})", candidates, basis);
+    // A sample is taken from the whole run, but only the cap is written
+    let (candidates, bytes) = match args.limit.or(args.sample).map(u128::from) {
+        Some(cap) if cap < candidates => {
+            println!("  Output:   {} candidates ({})", cap, if args.limit.is_some() { "--limit" } else { "--sample" });
+            (cap, bytes * cap as f64 / candidates as f64)
+        }
+        _ => (candidates, bytes),
+    };
+    let size = bytes + candidates as f64 * framing as f64;
+    let compressed = if args.compress.is_some() || matches!(args.format, OutputFormat::Parquet) { ", before compression" } else { "" };
+    let size = if size < u64::MAX as f64 { human_bytes(size as u64) } else { format!("{:.1e} B", size) };
+    println!("  Size:     ~{}{}", size, compressed);
+    let runtime = human_duration(candidates as f64 / rate);
+    let about = if runtime.starts_with('<') { "" } else { "~" };
+    println!("  Runtime:  {}{} at {:.0} candidates/s (measured)", about, runtime, rate);
+}
+
+fn human_duration(secs: f64) -> String {
+    const UNITS: [(&str, f64); 5] = [("y", 31_557_600.0), ("d", 86_400.0), ("h", 3_600.0), ("m", 60.0), ("s", 1.0)];
+    if secs < 1.0 {
+        return "<1s".to_string();
+    }
+    if secs >= 1000.0 * UNITS[0].1 {
+        return format!("{:.1e} years", secs / UNITS[0].1);
+    }
+    let first = UNITS.iter().position(|(_, unit)| secs >= *unit).unwrap_or(UNITS.len() - 1);
+    let (name, unit) = UNITS[first];
+    let whole = (secs / unit).floor();
+    match UNITS.get(first + 1) {
+        Some((next_name, next_unit)) => {
+            format!("{}{} {}{}", whole, name, ((secs - whole * unit) / next_unit).floor(), next_name)
+        }
+        _ => format!("{}{}", whole, name),
+    }
+}
+
+fn human_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 7] = ["B", "KB", "MB", "GB", "TB", "PB", "EB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
     }
 }
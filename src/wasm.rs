@@ -0,0 +1,64 @@
+use crate::engine::memorable::{self, MemorableConfig};
+use crate::engine::personal::{Dedup, Profile};
+use crate::engine::strength::Estimator;
+use serde_json::{json, Value};
+use wasm_bindgen::prelude::*;
+
+// ═══════════════════════════════════════════════════════════════
+// BROWSER BINDINGS
+// ═══════════════════════════════════════════════════════════════
+//
+// The memorable generator, profile preview and strength meter for a
+// page that never sends what it's given anywhere. Build with
+//
+//   cargo rustc --lib --release --target wasm32-unknown-unknown \
+//       --no-default-features --features wasm --crate-type cdylib
+//   wasm-bindgen --target web target/wasm32-unknown-unknown/release/jigsaw.wasm --out-dir pkg
+//
+// Values cross as JSON strings, in the same shapes the REST API uses.
+
+fn js_error(e: impl std::fmt::Display) -> JsError {
+    JsError::new(&e.to_string())
+}
+
+/// Memorable passwords as a JSON array. `config` is a JSON object of
+/// MemorableConfig fields; any left out keep their defaults.
+#[wasm_bindgen]
+pub fn memorable(config: &str) -> Result<String, JsError> {
+    let mut merged = serde_json::to_value(MemorableConfig::default()).map_err(js_error)?;
+    let Value::Object(fields) = serde_json::from_str(config).map_err(js_error)? else {
+        return Err(JsError::new("config must be a JSON object"));
+    };
+    for (field, value) in fields {
+        merged[field] = value;
+    }
+    let config: MemorableConfig = serde_json::from_value(merged).map_err(js_error)?;
+    let passwords = memorable::generate_batch(&config).map_err(js_error)?;
+    serde_json::to_string(&passwords).map_err(js_error)
+}
+
+/// The first `limit` candidates of a profile (JSON, as for `--profile`)
+/// and the size of the whole run: `{"candidates": [...], "total": n, "bytes": n}`
+#[wasm_bindgen(js_name = profilePreview)]
+pub fn profile_preview(profile: &str, limit: usize) -> Result<String, JsError> {
+    let profile: Profile = serde_json::from_str(profile).map_err(js_error)?;
+    let estimate = profile.estimate();
+    let mut candidates = Vec::new();
+    if limit > 0 {
+        profile.for_each_unique(Dedup::Exact, |candidate| {
+            candidates.push(candidate);
+            candidates.len() >= limit
+        });
+    }
+    Ok(json!({ "candidates": candidates, "total": estimate.candidates, "bytes": estimate.bytes }).to_string())
+}
+
+thread_local! {
+    static ESTIMATOR: Estimator = Estimator::new();
+}
+
+/// Guesses, score and matched patterns for one password, as JSON
+#[wasm_bindgen]
+pub fn strength(password: &str) -> Result<String, JsError> {
+    ESTIMATOR.with(|estimator| serde_json::to_string(&estimator.estimate(password)).map_err(js_error))
+}
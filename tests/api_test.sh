@@ -26,7 +26,19 @@ cat <<EOF > profile.json
 }
 EOF
 
-curl -s -X POST -H "Content-Type: application/json" -d @profile.json "$BASE_URL/personal/generate" | jq '.[:5]' # Show first 5
+curl -s -X POST -H "Content-Type: application/json" -d @profile.json "$BASE_URL/personal/generate?limit=5" | jq . # First page of 5
+echo ""
+
+echo "Testing Streamed Wordlist Generation..."
+curl -s -N -X POST -H "Content-Type: application/json" -d @profile.json "$BASE_URL/personal/stream" | head -n 5
+echo ""
+
+echo "Testing Job Queue..."
+JOB_ID=$(curl -s -X POST -H "Content-Type: application/json" -d '{"kind": "mask", "mask": "?d?d?d"}' "$BASE_URL/jobs" | jq -r .id)
+sleep 1
+curl -s "$BASE_URL/jobs/$JOB_ID" | jq .
+curl -s "$BASE_URL/jobs/$JOB_ID/result" | head -n 5
+curl -s -X DELETE "$BASE_URL/jobs/$JOB_ID"
 echo ""
 
 echo "Testing Password Check (Found)..."
@@ -64,4 +76,15 @@ EOF
 curl -s -X POST -H "Content-Type: application/json" -d @check_not_found.json "$BASE_URL/check-password" | jq .
 echo ""
 
-rm profile.json check_found.json check_not_found.json
+echo "Testing Markov Train, Generate and Score..."
+printf 'password\nsunshine\nshadow12\nmonkey99\ndragonfly\n' > corpus.txt
+MODEL=$(curl -s -F corpus=@corpus.txt "$BASE_URL/markov/train?order=2" | jq -r .model)
+curl -s -X POST -H "Content-Type: application/json" -d "{\"model\": \"$MODEL\", \"count\": 5}" "$BASE_URL/markov/generate" | jq .
+curl -s -X POST -H "Content-Type: application/json" -d "{\"model\": \"$MODEL\", \"words\": [\"password\", \"qwerty\"]}" "$BASE_URL/markov/score" | jq .
+echo ""
+
+echo "Testing Pwned Lookup..."
+curl -s "$BASE_URL/pwned?password=password" | jq .
+echo ""
+
+rm profile.json check_found.json check_not_found.json corpus.txt
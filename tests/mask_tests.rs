@@ -1,4 +1,4 @@
-use jigsaw::engine::mask::{Mask, Charset};
+use jigsaw::engine::mask::Mask;
 use std::str::FromStr;
 
 #[test]